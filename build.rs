@@ -0,0 +1,51 @@
+fn main() {
+    // The C header is only generated for the `ffi` feature; other builds need no build step.
+    #[cfg(feature = "ffi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("set by cargo");
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        cbindgen::generate(&crate_dir)
+            .expect("failed to generate the C header")
+            .write_to_file(format!("{}/include/mapradar.h", crate_dir));
+    }
+
+    // gRPC service stubs for the `grpc` feature. The messages are written by hand in
+    // src/grpc/proto.rs, mirroring proto/mapradar.proto, so no protoc is needed.
+    #[cfg(feature = "grpc")]
+    {
+        use tonic_build::manual::{Builder, Method, Service};
+
+        let method = |name: &str, route: &str, input: &str, output: &str| {
+            Method::builder()
+                .name(name)
+                .route_name(route)
+                .input_type(format!("crate::grpc::proto::{}", input))
+                .output_type(format!("crate::grpc::proto::{}", output))
+                .codec_path("tonic_prost::ProstCodec")
+        };
+        let service = Service::builder()
+            .name("Mapradar")
+            .package("mapradar.v1")
+            .method(method("geocode", "Geocode", "GeocodeRequest", "Location").build())
+            .method(
+                method(
+                    "reverse_geocode",
+                    "ReverseGeocode",
+                    "ReverseGeocodeRequest",
+                    "Location",
+                )
+                .build(),
+            )
+            .method(method("nearby", "Nearby", "NearbyRequest", "NearbyResponse").build())
+            .method(
+                method("stream_nearby", "StreamNearby", "NearbyRequest", "Service")
+                    .server_streaming()
+                    .build(),
+            )
+            .method(method("route", "Route", "RouteRequest", "Route").build())
+            .build();
+        println!("cargo:rerun-if-changed=build.rs");
+        Builder::new().build_client(false).compile(&[service]);
+    }
+}
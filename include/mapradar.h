@@ -0,0 +1,106 @@
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#ifndef MAPRADAR_H
+#define MAPRADAR_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of an FFI call.
+typedef enum MapradarStatus {
+  MAPRADAR_STATUS_OK = 0,
+  // A pointer was null, a string was not UTF-8, or an argument was out of range.
+  MAPRADAR_STATUS_INVALID_ARGUMENT = 1,
+  MAPRADAR_STATUS_AUTH_FAILED = 2,
+  // The address or location could not be resolved, or the search found nothing.
+  MAPRADAR_STATUS_NOT_FOUND = 3,
+  MAPRADAR_STATUS_QUOTA_EXCEEDED = 4,
+  MAPRADAR_STATUS_NETWORK = 5,
+  // The provider does not offer the requested capability.
+  MAPRADAR_STATUS_UNSUPPORTED = 6,
+  // The provider returned an error, or something unexpected failed.
+  MAPRADAR_STATUS_INTERNAL = 7,
+} MapradarStatus;
+
+// Opaque client handle.
+typedef struct MapradarClient MapradarClient;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates a client and writes its handle to `out_client`.
+//
+// `api_key` may be null to read `MAPRADAR_API_KEY` from the environment. `provider` may be
+// null for Google, or name a provider: `google`, `nominatim`, or `offline`.
+//
+// # Safety
+//
+// `api_key` and `provider` must be null or NUL-terminated strings, and `out_client` must be
+// valid for writes.
+enum MapradarStatus mapradar_client_new(const char *api_key,
+                                        const char *provider,
+                                        struct MapradarClient **out_client);
+
+// Releases a client. Null is ignored.
+//
+// # Safety
+//
+// `client` must be null or come from `mapradar_client_new`, and must not be used afterwards.
+void mapradar_client_free(struct MapradarClient *client);
+
+// Geocodes an address, writing the location as JSON.
+//
+// # Safety
+//
+// `client` must come from `mapradar_client_new`, `address` must be a NUL-terminated string,
+// and `out_json` must be valid for writes.
+enum MapradarStatus mapradar_geocode(const struct MapradarClient *client,
+                                     const char *address,
+                                     char **out_json);
+
+// Reverse geocodes a point, writing the location as JSON.
+//
+// # Safety
+//
+// `client` must come from `mapradar_client_new`, and `out_json` must be valid for writes.
+enum MapradarStatus mapradar_reverse_geocode(const struct MapradarClient *client,
+                                             double latitude,
+                                             double longitude,
+                                             char **out_json);
+
+// Finds services near a query, writing the location intelligence as JSON.
+//
+// `query` is an address, "lat,lng", or a JSON object as accepted by batch input.
+// `service_types` is a comma-separated list such as "bank,hospital".
+//
+// # Safety
+//
+// `client` must come from `mapradar_client_new`, `query` and `service_types` must be
+// NUL-terminated strings, and `out_json` must be valid for writes.
+enum MapradarStatus mapradar_nearby(const struct MapradarClient *client,
+                                    const char *query,
+                                    const char *service_types,
+                                    double radius_km,
+                                    uintptr_t max_results_per_type,
+                                    char **out_json);
+
+// Releases a string returned through `out_json`. Null is ignored.
+//
+// # Safety
+//
+// `value` must be null or a string returned by this library, and must not be used afterwards.
+void mapradar_string_free(char *value);
+
+// Message for the last failed call on this thread, or null when none has failed.
+//
+// The pointer stays valid until the next failing call on the same thread.
+const char *mapradar_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MAPRADAR_H */
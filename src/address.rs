@@ -0,0 +1,327 @@
+//! Offline parsing and normalization of free-form, comma-separated addresses.
+//!
+//! The parser is heuristic: it recognizes the common "number street, locality, city, region
+//! postcode, country" layouts used in North America, Europe, and much of Africa and Asia, and
+//! leaves fields it cannot place as `None` rather than guessing.
+
+use crate::models::AddressComponents;
+
+/// Country names and common aliases with their ISO 3166-1 alpha-2 codes. Bare two-letter
+/// codes are not matched, since they are indistinguishable from US states and provinces.
+const COUNTRIES: &[(&str, &str)] = &[
+    ("argentina", "AR"),
+    ("australia", "AU"),
+    ("austria", "AT"),
+    ("belgium", "BE"),
+    ("brazil", "BR"),
+    ("cameroon", "CM"),
+    ("canada", "CA"),
+    ("chile", "CL"),
+    ("china", "CN"),
+    ("colombia", "CO"),
+    ("czechia", "CZ"),
+    ("czech republic", "CZ"),
+    ("denmark", "DK"),
+    ("egypt", "EG"),
+    ("ethiopia", "ET"),
+    ("finland", "FI"),
+    ("france", "FR"),
+    ("germany", "DE"),
+    ("deutschland", "DE"),
+    ("ghana", "GH"),
+    ("greece", "GR"),
+    ("india", "IN"),
+    ("indonesia", "ID"),
+    ("ireland", "IE"),
+    ("israel", "IL"),
+    ("italy", "IT"),
+    ("japan", "JP"),
+    ("kenya", "KE"),
+    ("mexico", "MX"),
+    ("morocco", "MA"),
+    ("netherlands", "NL"),
+    ("the netherlands", "NL"),
+    ("new zealand", "NZ"),
+    ("nigeria", "NG"),
+    ("norway", "NO"),
+    ("pakistan", "PK"),
+    ("philippines", "PH"),
+    ("poland", "PL"),
+    ("portugal", "PT"),
+    ("rwanda", "RW"),
+    ("saudi arabia", "SA"),
+    ("senegal", "SN"),
+    ("singapore", "SG"),
+    ("south africa", "ZA"),
+    ("south korea", "KR"),
+    ("spain", "ES"),
+    ("sweden", "SE"),
+    ("switzerland", "CH"),
+    ("tanzania", "TZ"),
+    ("turkey", "TR"),
+    ("uganda", "UG"),
+    ("ukraine", "UA"),
+    ("united arab emirates", "AE"),
+    ("uae", "AE"),
+    ("united kingdom", "GB"),
+    ("uk", "GB"),
+    ("great britain", "GB"),
+    ("england", "GB"),
+    ("scotland", "GB"),
+    ("wales", "GB"),
+    ("united states", "US"),
+    ("united states of america", "US"),
+    ("usa", "US"),
+    ("u.s.a.", "US"),
+    ("vietnam", "VN"),
+];
+
+/// Street-type abbreviations expanded during normalization.
+const STREET_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("ave", "Avenue"),
+    ("blvd", "Boulevard"),
+    ("cl", "Close"),
+    ("cres", "Crescent"),
+    ("ct", "Court"),
+    ("dr", "Drive"),
+    ("hwy", "Highway"),
+    ("ln", "Lane"),
+    ("pkwy", "Parkway"),
+    ("pl", "Place"),
+    ("rd", "Road"),
+    ("sq", "Square"),
+    ("st", "Street"),
+    ("ter", "Terrace"),
+];
+
+/// Full street-type words, used to recognize a street without a house number.
+const STREET_SUFFIXES: &[&str] = &[
+    "avenue",
+    "boulevard",
+    "close",
+    "court",
+    "crescent",
+    "drive",
+    "highway",
+    "lane",
+    "parkway",
+    "place",
+    "road",
+    "square",
+    "street",
+    "terrace",
+    "way",
+];
+
+/// Splits a free-form address into its components without any network request.
+pub fn parse_address(input: &str) -> AddressComponents {
+    let mut components = AddressComponents::default();
+    let mut parts: Vec<String> = input
+        .split(',')
+        .map(|part| part.split_whitespace().collect::<Vec<_>>().join(" "))
+        .map(|part| part.trim_end_matches('.').to_string())
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    if let Some(code) = parts.last().and_then(|part| country_code(part)) {
+        components.country_code = Some(code.to_string());
+        parts.pop();
+    }
+
+    // The postcode sits in one of the last two parts, alone or beside a city or state. The
+    // first part is skipped when there are others, since it usually starts with a house number.
+    let first_candidate = parts
+        .len()
+        .saturating_sub(2)
+        .max(usize::from(parts.len() > 1));
+    for index in (first_candidate..parts.len()).rev() {
+        if let Some((postcode, rest)) = split_postcode(&parts[index]) {
+            components.postcode = Some(postcode);
+            if rest.is_empty() {
+                parts.remove(index);
+            } else {
+                parts[index] = rest;
+            }
+            break;
+        }
+    }
+
+    if parts.len() > 1 && parts.last().is_some_and(|part| is_region_code(part)) {
+        components.state = parts.pop();
+    }
+
+    let mut locality = parts.into_iter();
+    let mut first = locality.next();
+    if let Some(number) = first.as_deref().filter(|part| is_house_number(part)) {
+        components.house_number = Some(number.to_string());
+        first = locality.next();
+    }
+    if let Some(part) = first {
+        match split_street(&part) {
+            Some((number, street)) => {
+                components.house_number = components.house_number.or(number);
+                components.street = Some(street);
+            }
+            None if components.house_number.is_some() => components.street = Some(part),
+            None => {
+                // Not a street; put it back so it is read as a place name.
+                let rest: Vec<String> = locality.collect();
+                return place_names(components, std::iter::once(part).chain(rest).collect());
+            }
+        }
+    }
+    place_names(components, locality.collect())
+}
+
+/// Upper-cases a postcode and drops its spaces and dashes, so "sw1a 2aa" and "SW1A2AA" compare
+/// equal.
+pub fn normalize_postcode(postcode: &str) -> String {
+    postcode
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Whether two postcodes name the same area. A postcode also matches a longer one it starts,
+/// so a UK outward code ("SW1A") matches the full code and a ZIP matches its ZIP+4.
+pub fn postcodes_match(a: &str, b: &str) -> bool {
+    let (a, b) = (normalize_postcode(a), normalize_postcode(b));
+    !a.is_empty() && !b.is_empty() && (a.starts_with(&b) || b.starts_with(&a))
+}
+
+/// Assigns the remaining parts to suburb, city, and state, working back from the end.
+fn place_names(mut components: AddressComponents, mut parts: Vec<String>) -> AddressComponents {
+    if parts.len() >= 3 && components.state.is_none() {
+        components.state = parts.pop();
+    }
+    components.city = parts.pop();
+    components.suburb = parts.pop();
+    components
+}
+
+fn country_code(part: &str) -> Option<&'static str> {
+    let part = part.to_lowercase();
+    COUNTRIES
+        .iter()
+        .find(|(name, _)| *name == part)
+        .map(|(_, code)| *code)
+}
+
+/// Two or three uppercase letters, such as `CA` or `NSW`.
+fn is_region_code(part: &str) -> bool {
+    (2..=3).contains(&part.len()) && part.chars().all(|c| c.is_ascii_uppercase())
+}
+
+/// A number optionally followed by a letter or a range, such as `221B` or `12-14`.
+fn is_house_number(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_digit())
+        && token.len() <= 8
+        && token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '/')
+        && token.chars().filter(|c| c.is_ascii_alphabetic()).count() <= 1
+}
+
+/// Finds a postcode at the start or end of a part, returning it and the rest of the part.
+fn split_postcode(part: &str) -> Option<(String, String)> {
+    let tokens: Vec<&str> = part.split(' ').collect();
+    let join = |tokens: &[&str]| tokens.join(" ");
+
+    // Two-token UK ("NW1 6XE") and Canadian ("M5V 3L9") postcodes.
+    if tokens.len() >= 2 {
+        let (head, tail) = tokens.split_at(tokens.len() - 2);
+        if is_outward_code(tail[0]) && is_inward_code(tail[1]) {
+            return Some((join(tail).to_uppercase(), join(head)));
+        }
+    }
+    if let Some((last, head)) = tokens.split_last()
+        && is_postcode_token(last)
+    {
+        return Some((last.to_uppercase(), join(head)));
+    }
+    // Leading numeric postcodes, as in "10117 Berlin".
+    if let Some((first, rest)) = tokens.split_first()
+        && !rest.is_empty()
+        && (4..=6).contains(&first.len())
+        && first.chars().all(|c| c.is_ascii_digit())
+    {
+        return Some((first.to_string(), join(rest)));
+    }
+    None
+}
+
+/// US ZIP (`94043`, `94043-1351`), 4-6 digit numeric, or unspaced UK/Canadian postcodes.
+fn is_postcode_token(token: &str) -> bool {
+    let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    match token.split_once('-') {
+        Some((zip, plus4)) => zip.len() == 5 && digits(zip) && plus4.len() == 4 && digits(plus4),
+        None if digits(token) => (4..=6).contains(&token.len()),
+        None => {
+            (5..=7).contains(&token.len())
+                && token.is_char_boundary(token.len() - 3)
+                && is_outward_code(&token[..token.len() - 3])
+                && is_inward_code(&token[token.len() - 3..])
+        }
+    }
+}
+
+/// First half of a UK or Canadian postcode: letters then digits, e.g. `NW1`, `SW1A`, `M5V`.
+fn is_outward_code(token: &str) -> bool {
+    (2..=4).contains(&token.len())
+        && token.chars().all(|c| c.is_ascii_alphanumeric())
+        && token.starts_with(|c: char| c.is_ascii_alphabetic())
+        && token.chars().any(|c| c.is_ascii_digit())
+}
+
+/// Second half of a UK (`6XE`) or Canadian (`3L9`) postcode.
+fn is_inward_code(token: &str) -> bool {
+    let chars: Vec<char> = token.chars().collect();
+    matches!(chars[..], [a, b, c]
+        if a.is_ascii_digit() && b.is_ascii_alphabetic() && c.is_ascii_alphanumeric())
+}
+
+/// Recognizes "221B Baker St" and "Unter den Linden 77", returning the number and the street
+/// with its type expanded.
+fn split_street(part: &str) -> Option<(Option<String>, String)> {
+    let tokens: Vec<&str> = part.split(' ').collect();
+    if tokens.len() < 2 {
+        return None;
+    }
+    let (number, street) = if is_house_number(tokens[0]) {
+        (Some(tokens[0].to_string()), &tokens[1..])
+    } else if is_house_number(tokens[tokens.len() - 1]) {
+        (
+            Some(tokens[tokens.len() - 1].to_string()),
+            &tokens[..tokens.len() - 1],
+        )
+    } else if is_street_name(&tokens) {
+        (None, &tokens[..])
+    } else {
+        return None;
+    };
+    Some((number, expand_street_type(street)))
+}
+
+fn is_street_name(tokens: &[&str]) -> bool {
+    let last = tokens[tokens.len() - 1]
+        .trim_end_matches('.')
+        .to_lowercase();
+    STREET_SUFFIXES.contains(&last.as_str())
+        || STREET_ABBREVIATIONS.iter().any(|(abbr, _)| *abbr == last)
+        || ["strasse", "straße", "weg", "platz"]
+            .iter()
+            .any(|suffix| last.ends_with(suffix))
+}
+
+/// Replaces an abbreviated trailing street type ("St.", "Rd") with its full word.
+pub(crate) fn expand_street_type(tokens: &[&str]) -> String {
+    let mut words: Vec<String> = tokens.iter().map(|t| t.to_string()).collect();
+    if let Some(last) = words.last_mut() {
+        let key = last.trim_end_matches('.').to_lowercase();
+        if let Some((_, full)) = STREET_ABBREVIATIONS.iter().find(|(abbr, _)| *abbr == key) {
+            *last = full.to_string();
+        }
+    }
+    words.join(" ")
+}
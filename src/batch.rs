@@ -0,0 +1,487 @@
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    coordinates::Coordinates,
+    error::MapradarError,
+    models::{GeoLocation, SearchQuery},
+};
+
+const DEFAULT_ADDRESS_COLUMN: &str = "address";
+
+/// Outcome of geocoding a single row of a batch job.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchRecord {
+    pub row: usize,
+    pub input: String,
+    pub location: Option<GeoLocation>,
+    pub error: Option<String>,
+}
+
+impl BatchRecord {
+    pub fn new(row: usize, input: String, result: Result<GeoLocation, MapradarError>) -> Self {
+        match result {
+            Ok(location) => Self {
+                row,
+                input,
+                location: Some(location),
+                error: None,
+            },
+            Err(err) => Self {
+                row,
+                input,
+                location: None,
+                error: Some(err.to_string()),
+            },
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+#[cfg(feature = "python")]
+crate::python::data_model!(BatchRecord);
+
+/// Rows of a batch job that are finished, kept as NDJSON records in a file so an interrupted job
+/// can resume without looking them up again.
+///
+/// A row is finished when it was geocoded, or when it failed in a way a retry would not change
+/// (no match, or invalid input). Rows that failed on the network, a quota, or credentials are
+/// left out, so resuming tries them again.
+pub struct Checkpoint {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    completed: BTreeMap<usize, BatchRecord>,
+}
+
+impl Checkpoint {
+    /// Starts a new checkpoint at `path`. Fails when one with finished rows is already there,
+    /// rather than throwing away the progress of an unfinished job.
+    pub fn create(path: impl Into<PathBuf>) -> Result<Self, MapradarError> {
+        let path = path.into();
+        let existing = Self::load(&path)?;
+        if !existing.is_empty() {
+            return Err(MapradarError::InvalidInput(format!(
+                "Checkpoint {} already holds {} finished rows; resume it, or delete it to start over",
+                path.display(),
+                existing.len()
+            )));
+        }
+        Self::write(path, BTreeMap::new())
+    }
+
+    /// Opens the checkpoint at `path` to carry on with its job, or starts a new one when there is
+    /// none. A record cut short by a crash is dropped, and its row runs again.
+    pub fn resume(path: impl Into<PathBuf>) -> Result<Self, MapradarError> {
+        let path = path.into();
+        let completed = Self::load(&path)?;
+        Self::write(path, completed)
+    }
+
+    /// Finished rows read from `path`; none when the file does not exist.
+    fn load(path: &Path) -> Result<BTreeMap<usize, BatchRecord>, MapradarError> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let lines: Vec<&str> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        let mut completed = BTreeMap::new();
+        for (index, line) in lines.iter().enumerate() {
+            match serde_json::from_str::<BatchRecord>(line) {
+                Ok(record) => {
+                    completed.insert(record.row, record);
+                }
+                // Only the last write can have been interrupted.
+                Err(_) if index + 1 == lines.len() => {}
+                Err(err) => {
+                    return Err(MapradarError::InvalidInput(format!(
+                        "Line {} of checkpoint {} is not a batch record: {}",
+                        index + 1,
+                        path.display(),
+                        err
+                    )));
+                }
+            }
+        }
+        Ok(completed)
+    }
+
+    /// Rewrites `path` with the `completed` rows, ready for more to be appended.
+    fn write(
+        path: PathBuf,
+        completed: BTreeMap<usize, BatchRecord>,
+    ) -> Result<Self, MapradarError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&path)?;
+        let mut checkpoint = Self {
+            path,
+            writer: BufWriter::new(file),
+            completed: BTreeMap::new(),
+        };
+        for record in completed.into_values() {
+            checkpoint.append(record)?;
+        }
+        Ok(checkpoint)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of finished rows, including those finished by earlier runs.
+    pub fn completed(&self) -> usize {
+        self.completed.len()
+    }
+
+    /// Deletes the checkpoint file, once its job's results are safely written elsewhere.
+    pub fn remove(self) -> Result<(), MapradarError> {
+        let Self { path, writer, .. } = self;
+        drop(writer);
+        Ok(std::fs::remove_file(path)?)
+    }
+
+    /// The finished record of `row`, checking it was made from the same `input`.
+    pub(crate) fn get(
+        &self,
+        row: usize,
+        input: &str,
+    ) -> Result<Option<&BatchRecord>, MapradarError> {
+        match self.completed.get(&row) {
+            Some(record) if record.input != input => Err(MapradarError::InvalidInput(format!(
+                "Checkpoint {} belongs to another input: its row {} is '{}', not '{}'",
+                self.path.display(),
+                row,
+                record.input,
+                input
+            ))),
+            record => Ok(record),
+        }
+    }
+
+    /// Turns a row's result into its record, kept in the checkpoint when the row is finished.
+    pub(crate) fn record(
+        &mut self,
+        row: usize,
+        input: String,
+        result: Result<GeoLocation, MapradarError>,
+    ) -> Result<BatchRecord, MapradarError> {
+        let finished = matches!(
+            result,
+            Ok(_)
+                | Err(MapradarError::AddressNotFound { .. }
+                    | MapradarError::ZeroResults
+                    | MapradarError::InvalidCoordinates { .. }
+                    | MapradarError::InvalidInput(_))
+        );
+        let record = BatchRecord::new(row, input, result);
+        if finished {
+            self.append(record.clone())?;
+        }
+        Ok(record)
+    }
+
+    fn append(&mut self, record: BatchRecord) -> Result<(), MapradarError> {
+        serde_json::to_writer(&mut self.writer, &record)?;
+        self.writer.write_all(b"\n")?;
+        // Flushed per row, so a crash loses at most the row being written.
+        self.writer.flush()?;
+        self.completed.insert(record.row, record);
+        Ok(())
+    }
+
+    /// Fails when the checkpoint has rows beyond the input's `rows`, as the input has changed.
+    pub(crate) fn check_len(&self, rows: usize) -> Result<(), MapradarError> {
+        match self.completed.keys().next_back() {
+            Some(&last) if last > rows => Err(MapradarError::InvalidInput(format!(
+                "Checkpoint {} belongs to another input: it has row {}, but the input has {} rows",
+                self.path.display(),
+                last,
+                rows
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Reads addresses from a CSV file (with header) or a newline-delimited file; a path of "-"
+/// reads stdin.
+///
+/// CSV input is detected by the `.csv` extension or an explicit `column`. Blank rows are skipped.
+pub fn read_addresses(path: &Path, column: Option<&str>) -> Result<Vec<String>, MapradarError> {
+    let is_csv = column.is_some()
+        || path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    let reader: Box<dyn std::io::Read> = if path.as_os_str() == "-" {
+        Box::new(std::io::stdin().lock())
+    } else {
+        Box::new(std::fs::File::open(path)?)
+    };
+
+    if is_csv {
+        read_csv_column(reader, path, column.unwrap_or(DEFAULT_ADDRESS_COLUMN))
+    } else {
+        let mut addresses = Vec::new();
+        for line in std::io::BufReader::new(reader).lines() {
+            let line = line?;
+            let line = line.trim();
+            if !line.is_empty() {
+                addresses.push(line.to_string());
+            }
+        }
+        Ok(addresses)
+    }
+}
+
+/// Reads "lat,lng" pairs from a CSV file (with header), or from lines of "lat,lng" text or
+/// NDJSON objects; a path of "-" reads stdin.
+///
+/// CSV input is detected by the `.csv` extension, and its coordinates come from the
+/// `latitude`/`lat` and `longitude`/`lng`/`lon` columns. Blank and `#` lines are skipped, and a
+/// line that holds no coordinates fails the whole read so its row number can be reported.
+pub fn read_coordinates(path: &Path) -> Result<Vec<Coordinates>, MapradarError> {
+    let reader: Box<dyn std::io::Read> = if path.as_os_str() == "-" {
+        Box::new(std::io::stdin().lock())
+    } else {
+        Box::new(std::fs::File::open(path)?)
+    };
+
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+    {
+        return read_csv_coordinates(reader, path);
+    }
+
+    read_queries(std::io::BufReader::new(reader))?
+        .into_iter()
+        .map(|input| match input.query? {
+            SearchQuery::Coordinates {
+                latitude,
+                longitude,
+            } => Ok(Coordinates {
+                lat: latitude,
+                lon: longitude,
+            }),
+            _ => Err(MapradarError::InvalidInput(format!(
+                "Line {} has no latitude/longitude",
+                input.row
+            ))),
+        })
+        .collect()
+}
+
+fn read_csv_coordinates(
+    reader: impl std::io::Read,
+    path: &Path,
+) -> Result<Vec<Coordinates>, MapradarError> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = reader.headers().map_err(std::io::Error::from)?;
+    let column = |names: &[&str]| {
+        headers
+            .iter()
+            .position(|h| names.iter().any(|name| h.trim().eq_ignore_ascii_case(name)))
+            .ok_or_else(|| {
+                MapradarError::InvalidInput(format!(
+                    "Column '{}' not found in {}",
+                    names[0],
+                    path.display()
+                ))
+            })
+    };
+    let lat_index = column(&["latitude", "lat"])?;
+    let lng_index = column(&["longitude", "lng", "lon"])?;
+
+    let mut coordinates = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record.map_err(std::io::Error::from)?;
+        if record.iter().all(|value| value.trim().is_empty()) {
+            continue;
+        }
+        let number = |i: usize| record.get(i).and_then(|value| value.trim().parse().ok());
+        let point = number(lat_index)
+            .zip(number(lng_index))
+            .and_then(|(lat, lng)| Coordinates::new(lat, lng).ok());
+        match point {
+            Some(point) => coordinates.push(point),
+            None => {
+                return Err(MapradarError::InvalidInput(format!(
+                    "Row {} of {} has no valid latitude/longitude",
+                    index + 1,
+                    path.display()
+                )));
+            }
+        }
+    }
+
+    Ok(coordinates)
+}
+
+fn read_csv_column(
+    reader: impl std::io::Read,
+    path: &Path,
+    column: &str,
+) -> Result<Vec<String>, MapradarError> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = reader.headers().map_err(std::io::Error::from)?;
+    let index = headers
+        .iter()
+        .position(|h| h.trim().eq_ignore_ascii_case(column))
+        .ok_or_else(|| {
+            MapradarError::InvalidInput(format!(
+                "Column '{}' not found in {}",
+                column,
+                path.display()
+            ))
+        })?;
+
+    let mut addresses = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(std::io::Error::from)?;
+        if let Some(value) = record.get(index).map(str::trim)
+            && !value.is_empty()
+        {
+            addresses.push(value.to_string());
+        }
+    }
+
+    Ok(addresses)
+}
+
+/// A query read from one line of bulk input.
+#[derive(Debug)]
+pub struct QueryInput {
+    /// 1-based position among the non-blank input lines.
+    pub row: usize,
+    /// The line's `id` field, echoed back so results can be matched to inputs.
+    pub id: Option<Value>,
+    /// The query, or why the line could not be read as one.
+    pub query: Result<SearchQuery, MapradarError>,
+}
+
+/// Parses one line of bulk input, which is either a JSON object or plain text.
+///
+/// Objects carry an optional `id` and either `address`, `latitude`/`longitude` (`lat`, `lng`,
+/// and `lon` also work), or `ip`; a serialized [`SearchQuery`] is accepted too. Plain text is
+/// read as "lat,lng" when it parses as coordinates and as an address otherwise; numbers out of
+/// range fail the line.
+pub fn parse_query_line(row: usize, line: &str) -> QueryInput {
+    let line = line.trim();
+    if !line.starts_with('{') {
+        let query = match line.parse::<Coordinates>() {
+            Ok(point) => Ok(SearchQuery::at(point)),
+            Err(err @ MapradarError::InvalidCoordinates { .. }) => Err(err),
+            Err(_) => Ok(SearchQuery::from_address(line.to_string())),
+        };
+        return QueryInput {
+            row,
+            id: None,
+            query,
+        };
+    }
+
+    let object: Value = match serde_json::from_str(line) {
+        Ok(object) => object,
+        Err(err) => {
+            return QueryInput {
+                row,
+                id: None,
+                query: Err(err.into()),
+            };
+        }
+    };
+    QueryInput {
+        row,
+        id: object.get("id").filter(|id| !id.is_null()).cloned(),
+        query: query_from_object(row, &object),
+    }
+}
+
+fn query_from_object(row: usize, object: &Value) -> Result<SearchQuery, MapradarError> {
+    let number = |keys: &[&str]| keys.iter().find_map(|key| object[*key].as_f64());
+    if let Some(address) = object["address"].as_str() {
+        return Ok(SearchQuery::from_address(address.to_string()));
+    }
+    if let (Some(latitude), Some(longitude)) = (
+        number(&["latitude", "lat"]),
+        number(&["longitude", "lng", "lon"]),
+    ) {
+        return SearchQuery::from_coordinates(latitude, longitude);
+    }
+    if let Some(ip) = object["ip"].as_str() {
+        let ip = ip
+            .parse()
+            .map_err(|_| MapradarError::InvalidInput(format!("Invalid IP address '{}'", ip)))?;
+        return Ok(SearchQuery::Ip { ip: Some(ip) });
+    }
+    SearchQuery::deserialize(object).map_err(|_| {
+        MapradarError::InvalidInput(format!(
+            "Line {} has no address, latitude/longitude, or ip",
+            row
+        ))
+    })
+}
+
+/// Reads one query per non-blank line, skipping `#` comments. Lines that fail to parse keep
+/// their row with an error, so the rest of the input can still be processed.
+pub fn read_queries(reader: impl BufRead) -> Result<Vec<QueryInput>, MapradarError> {
+    let mut queries = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        queries.push(parse_query_line(queries.len() + 1, line));
+    }
+    Ok(queries)
+}
+
+/// Outcome of one line of bulk input: the result, or the error that replaced it.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryOutcome<T> {
+    pub row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl<T> QueryOutcome<T> {
+    pub fn new(row: usize, id: Option<Value>, result: Result<T, MapradarError>) -> Self {
+        match result {
+            Ok(result) => Self {
+                row,
+                id,
+                result: Some(result),
+                error: None,
+            },
+            Err(err) => Self {
+                row,
+                id,
+                result: None,
+                error: Some(err.to_string()),
+            },
+        }
+    }
+}
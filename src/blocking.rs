@@ -0,0 +1,96 @@
+//! Blocking wrapper around the async [`MapradarClient`](crate::client::MapradarClient).
+//!
+//! Each client owns a single-threaded Tokio runtime and drives the async implementation to
+//! completion on it. Calling these methods from inside an async runtime panics; use the async
+//! client there instead.
+
+use std::sync::Arc;
+
+use tokio::runtime::{Builder, Runtime};
+
+use crate::{
+    client,
+    coordinates::Coordinates,
+    error::MapradarError,
+    models::{
+        GeoLocation, LocationIntelligence, NearbyService, PlaceCategory, PlaceDetails, PlacePhoto,
+        SearchQuery,
+    },
+};
+
+/// Synchronous client exposing the same operations as the async client.
+#[derive(Clone)]
+pub struct MapradarClient {
+    inner: client::MapradarClient,
+    runtime: Arc<Runtime>,
+}
+
+impl MapradarClient {
+    /// Creates a Google-backed client with default settings.
+    pub fn new(api_key: String) -> Result<Self, MapradarError> {
+        Self::from_async(client::MapradarClient::_new(api_key))
+    }
+
+    /// Wraps an async client configured through [`client::MapradarClientBuilder`].
+    pub fn from_async(inner: client::MapradarClient) -> Result<Self, MapradarError> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Returns the wrapped async client.
+    pub fn as_async(&self) -> &client::MapradarClient {
+        &self.inner
+    }
+
+    pub fn geocode(&self, address: &str) -> Result<GeoLocation, MapradarError> {
+        self.runtime.block_on(self.inner.geocode_async(address))
+    }
+
+    pub fn reverse_geocode(&self, point: Coordinates) -> Result<GeoLocation, MapradarError> {
+        self.runtime
+            .block_on(self.inner.reverse_geocode_async(point))
+    }
+
+    pub fn search_nearby(
+        &self,
+        point: Coordinates,
+        category: impl Into<PlaceCategory>,
+        radius_meters: f64,
+        max_results: usize,
+    ) -> Result<Vec<NearbyService>, MapradarError> {
+        self.runtime.block_on(self.inner.search_nearby_async(
+            point,
+            category,
+            radius_meters,
+            max_results,
+        ))
+    }
+
+    pub fn place_details(&self, place_id: &str) -> Result<PlaceDetails, MapradarError> {
+        self.runtime
+            .block_on(self.inner.place_details_async(place_id))
+    }
+
+    pub fn photos(&self, place_id: &str, limit: usize) -> Result<Vec<PlacePhoto>, MapradarError> {
+        self.runtime
+            .block_on(self.inner.photos_async(place_id, limit))
+    }
+
+    pub fn fetch_intelligence(
+        &self,
+        query: SearchQuery,
+        service_types: impl IntoIterator<Item = impl Into<PlaceCategory>>,
+        radius_km: f64,
+        max_results_per_type: usize,
+    ) -> Result<LocationIntelligence, MapradarError> {
+        self.runtime.block_on(self.inner.fetch_intelligence_async(
+            query,
+            service_types,
+            radius_km,
+            max_results_per_type,
+        ))
+    }
+}
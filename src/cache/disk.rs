@@ -0,0 +1,144 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::error::MapradarError;
+
+pub const DEFAULT_DISK_TTL_SECS: u64 = 86_400;
+
+/// SQLite-backed response cache that persists across process restarts.
+#[derive(Clone)]
+pub struct DiskCache {
+    conn: Arc<Mutex<Connection>>,
+    ttl: Duration,
+}
+
+/// Summary of the on-disk cache contents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub path: String,
+    pub total_entries: usize,
+    pub expired_entries: usize,
+    pub entries_by_namespace: Vec<(String, usize)>,
+    pub size_bytes: u64,
+}
+
+impl DiskCache {
+    /// Opens (or creates) the cache database at `path`.
+    pub fn open(path: &Path, ttl: Duration) -> Result<Self, MapradarError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS entries (
+                namespace TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (namespace, key)
+            );",
+        )?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            ttl,
+        })
+    }
+
+    /// Default location: `$XDG_CACHE_HOME/mapradar/cache.sqlite`, falling back to `~/.cache`.
+    pub fn default_path() -> PathBuf {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+            .unwrap_or_else(std::env::temp_dir);
+        base.join("mapradar").join("cache.sqlite")
+    }
+
+    fn now_secs() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default()
+    }
+
+    fn cutoff(&self) -> i64 {
+        Self::now_secs() - self.ttl.as_secs() as i64
+    }
+
+    /// Returns a cached value if present and younger than the TTL.
+    pub fn get<T: DeserializeOwned>(&self, namespace: &str, key: &str) -> Option<T> {
+        let conn = self.conn.lock().ok()?;
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM entries WHERE namespace = ?1 AND key = ?2 AND created_at >= ?3",
+                params![namespace, key, self.cutoff()],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()?;
+
+        value.and_then(|v| serde_json::from_str(&v).ok())
+    }
+
+    /// Stores a value, replacing any previous entry for the same key.
+    pub fn set<T: Serialize>(&self, namespace: &str, key: &str, value: &T) {
+        let Ok(json) = serde_json::to_string(value) else {
+            return;
+        };
+        if let Ok(conn) = self.conn.lock() {
+            let _ = conn.execute(
+                "INSERT OR REPLACE INTO entries (namespace, key, value, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![namespace, key, json, Self::now_secs()],
+            );
+        }
+    }
+
+    /// Removes every entry, returning how many were deleted.
+    pub fn clear(&self) -> Result<usize, MapradarError> {
+        let conn = self.lock()?;
+        let deleted = conn.execute("DELETE FROM entries", [])?;
+        conn.execute_batch("VACUUM;")?;
+        Ok(deleted)
+    }
+
+    pub fn stats(&self) -> Result<CacheStats, MapradarError> {
+        let conn = self.lock()?;
+        let total_entries: i64 =
+            conn.query_row("SELECT COUNT(*) FROM entries", [], |row| row.get(0))?;
+        let expired_entries: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM entries WHERE created_at < ?1",
+            params![self.cutoff()],
+            |row| row.get(0),
+        )?;
+
+        let mut statement = conn.prepare(
+            "SELECT namespace, COUNT(*) FROM entries GROUP BY namespace ORDER BY namespace",
+        )?;
+        let entries_by_namespace = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))?
+            .collect::<Result<Vec<(String, usize)>, _>>()?;
+
+        let path = conn.path().unwrap_or_default().to_string();
+        let size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+        Ok(CacheStats {
+            path,
+            total_entries: total_entries as usize,
+            expired_entries: expired_entries as usize,
+            entries_by_namespace,
+            size_bytes,
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, MapradarError> {
+        self.conn
+            .lock()
+            .map_err(|_| MapradarError::Unknown("Cache connection lock poisoned".to_string()))
+    }
+}
@@ -0,0 +1,314 @@
+use moka::future::Cache;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::time::Duration;
+
+use crate::health::{CacheHealth, DiskHealth};
+use crate::metrics::Metrics;
+use crate::models::{GeoLocation, NearbyService, PlaceCategory, SearchRegion};
+use crate::usage::UsageLedger;
+
+pub mod disk;
+
+pub use disk::{CacheStats, DiskCache};
+
+const GEOCODE_TTL_SECS: u64 = 3600;
+const PLACES_TTL_SECS: u64 = 900;
+const MAX_GEOCODE_ENTRIES: u64 = 10_000;
+const MAX_PLACES_ENTRIES: u64 = 50_000;
+
+/// Nearby results as stored, and whether they are every result the provider had.
+#[derive(Clone, Serialize, Deserialize)]
+struct CachedNearby {
+    services: Vec<NearbyService>,
+    exhaustive: bool,
+}
+
+#[derive(Clone)]
+pub struct GeoCache {
+    geocode: Cache<String, GeoLocation>,
+    reverse_geocode: Cache<String, GeoLocation>,
+    nearby: Cache<String, CachedNearby>,
+    disk: Option<DiskCache>,
+    ledger: Option<UsageLedger>,
+    metrics: Option<Metrics>,
+    provider: Option<&'static str>,
+    language: Option<String>,
+    enabled: bool,
+}
+
+impl Default for GeoCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GeoCache {
+    pub fn new() -> Self {
+        Self {
+            geocode: Cache::builder()
+                .max_capacity(MAX_GEOCODE_ENTRIES)
+                .time_to_live(Duration::from_secs(GEOCODE_TTL_SECS))
+                .build(),
+            reverse_geocode: Cache::builder()
+                .max_capacity(MAX_GEOCODE_ENTRIES)
+                .time_to_live(Duration::from_secs(GEOCODE_TTL_SECS))
+                .build(),
+            nearby: Cache::builder()
+                .max_capacity(MAX_PLACES_ENTRIES)
+                .time_to_live(Duration::from_secs(PLACES_TTL_SECS))
+                .build(),
+            disk: None,
+            ledger: None,
+            metrics: None,
+            provider: None,
+            language: None,
+            enabled: true,
+        }
+    }
+
+    /// Creates a cache that also persists entries to disk.
+    pub fn with_disk(disk: DiskCache) -> Self {
+        Self {
+            disk: Some(disk),
+            ..Self::new()
+        }
+    }
+
+    /// Creates a cache that never stores or returns entries.
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::new()
+        }
+    }
+
+    /// Records every hit in `ledger` as a saved upstream call.
+    pub fn with_ledger(mut self, ledger: UsageLedger) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// Counts hits and misses in `metrics`.
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Keys entries by the `provider` name as well, so one provider's results (and place IDs)
+    /// never answer requests made to another.
+    pub fn with_provider(mut self, provider: &'static str) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
+    /// Keys entries by `language` as well, so localized results never answer requests made in
+    /// another language.
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Entry counts per tier, and whether the disk tier answers queries.
+    pub fn health(&self) -> CacheHealth {
+        let memory_entries = self.geocode.entry_count()
+            + self.reverse_geocode.entry_count()
+            + self.nearby.entry_count();
+        let disk = self.disk.as_ref().map(|disk| match disk.stats() {
+            Ok(stats) => DiskHealth {
+                healthy: true,
+                path: Some(stats.path),
+                entries: Some(stats.total_entries),
+                error: None,
+            },
+            Err(err) => DiskHealth {
+                healthy: false,
+                path: None,
+                entries: None,
+                error: Some(err.to_string()),
+            },
+        });
+        CacheHealth {
+            enabled: self.enabled,
+            memory_entries,
+            disk,
+        }
+    }
+
+    /// Prefixes `key` with the provider and the language, when they were set.
+    fn scoped(&self, key: String) -> String {
+        let key = match &self.language {
+            Some(language) => format!("{}:{}", language, key),
+            None => key,
+        };
+        match self.provider {
+            Some(provider) => format!("{}:{}", provider, key),
+            None => key,
+        }
+    }
+
+    /// Generates cache key for geocoding requests; constrained lookups add their region.
+    fn geocode_key(&self, address: &str, region: &SearchRegion) -> String {
+        let address = address.to_lowercase().trim().to_string();
+        self.scoped(if region.is_empty() {
+            address
+        } else {
+            format!("{}|{}", address, region)
+        })
+    }
+
+    /// Generates cache key for reverse geocoding requests.
+    fn reverse_geocode_key(&self, lat: f64, lng: f64) -> String {
+        self.scoped(format!("{:.6},{:.6}", lat, lng))
+    }
+
+    /// Generates cache key for nearby search requests.
+    fn nearby_key(
+        &self,
+        lat: f64,
+        lng: f64,
+        category: &PlaceCategory,
+        radius_meters: f64,
+    ) -> String {
+        self.scoped(format!(
+            "{:.4},{:.4}:{}:{:.0}",
+            lat, lng, category, radius_meters
+        ))
+    }
+
+    /// Checks the in-memory tier, then the disk tier, promoting disk hits into memory. Entries
+    /// `usable` rejects count as misses.
+    async fn lookup<T>(
+        &self,
+        memory: &Cache<String, T>,
+        namespace: &str,
+        key: String,
+        usable: impl Fn(&T) -> bool,
+    ) -> Option<T>
+    where
+        T: Clone + DeserializeOwned + Send + Sync + 'static,
+    {
+        if !self.enabled {
+            return None;
+        }
+        if let Some(hit) = memory.get(&key).await.filter(|hit| usable(hit)) {
+            tracing::debug!(namespace, key = %key, tier = "memory", "cache hit");
+            self.record_hit(namespace);
+            return Some(hit);
+        }
+        let Some(hit) = self
+            .disk
+            .as_ref()
+            .and_then(|disk| disk.get::<T>(namespace, &key))
+            .filter(|hit| usable(hit))
+        else {
+            tracing::debug!(namespace, key = %key, "cache miss");
+            if let Some(metrics) = &self.metrics {
+                metrics.record_cache_lookup(namespace, false);
+            }
+            return None;
+        };
+        tracing::debug!(namespace, key = %key, tier = "disk", "cache hit");
+        self.record_hit(namespace);
+        memory.insert(key, hit.clone()).await;
+        Some(hit)
+    }
+
+    fn record_hit(&self, namespace: &str) {
+        if let Some(ledger) = &self.ledger {
+            ledger.record_cache_hit(namespace);
+        }
+        if let Some(metrics) = &self.metrics {
+            metrics.record_cache_lookup(namespace, true);
+        }
+    }
+
+    /// Gets cached geocode result.
+    pub async fn get_geocode(&self, address: &str, region: &SearchRegion) -> Option<GeoLocation> {
+        self.lookup(
+            &self.geocode,
+            "geocode",
+            self.geocode_key(address, region),
+            |_| true,
+        )
+        .await
+    }
+
+    /// Stores geocode result in cache.
+    pub async fn set_geocode(&self, address: &str, region: &SearchRegion, location: GeoLocation) {
+        if !self.enabled {
+            return;
+        }
+        let key = self.geocode_key(address, region);
+        if let Some(disk) = &self.disk {
+            disk.set("geocode", &key, &location);
+        }
+        self.geocode.insert(key, location).await;
+    }
+
+    /// Gets cached reverse geocode result.
+    pub async fn get_reverse_geocode(&self, lat: f64, lng: f64) -> Option<GeoLocation> {
+        self.lookup(
+            &self.reverse_geocode,
+            "reverse",
+            self.reverse_geocode_key(lat, lng),
+            |_| true,
+        )
+        .await
+    }
+
+    /// Stores reverse geocode result in cache.
+    pub async fn set_reverse_geocode(&self, lat: f64, lng: f64, location: GeoLocation) {
+        if !self.enabled {
+            return;
+        }
+        let key = self.reverse_geocode_key(lat, lng);
+        if let Some(disk) = &self.disk {
+            disk.set("reverse", &key, &location);
+        }
+        self.reverse_geocode.insert(key, location).await;
+    }
+
+    /// Gets up to `max_results` cached nearby results, missing when fewer were cached and more
+    /// may exist upstream.
+    pub async fn get_nearby(
+        &self,
+        lat: f64,
+        lng: f64,
+        category: &PlaceCategory,
+        radius_meters: f64,
+        max_results: usize,
+    ) -> Option<Vec<NearbyService>> {
+        self.lookup(
+            &self.nearby,
+            "nearby",
+            self.nearby_key(lat, lng, category, radius_meters),
+            |cached: &CachedNearby| cached.exhaustive || cached.services.len() >= max_results,
+        )
+        .await
+        .map(|cached| cached.services.into_iter().take(max_results).collect())
+    }
+
+    /// Stores nearby search result in cache; `exhaustive` when the provider had no more.
+    pub async fn set_nearby(
+        &self,
+        lat: f64,
+        lng: f64,
+        category: &PlaceCategory,
+        radius_meters: f64,
+        services: Vec<NearbyService>,
+        exhaustive: bool,
+    ) {
+        if !self.enabled {
+            return;
+        }
+        let key = self.nearby_key(lat, lng, category, radius_meters);
+        let cached = CachedNearby {
+            services,
+            exhaustive,
+        };
+        if let Some(disk) = &self.disk {
+            disk.set("nearby", &key, &cached);
+        }
+        self.nearby.insert(key, cached).await;
+    }
+}
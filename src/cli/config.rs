@@ -0,0 +1,312 @@
+//! Persistent CLI defaults read from `~/.config/mapradar/config.toml`.
+//!
+//! Command-line flags and environment variables take precedence over the file, which in turn
+//! overrides the built-in defaults.
+
+use std::{collections::BTreeMap, fs, path::Path, path::PathBuf};
+
+use clap::ValueEnum;
+use mapradar::{
+    error::MapradarError,
+    models::PlaceCategory,
+    providers::{KeyRotation, ProviderKind},
+    transport::RateLimit,
+    units::Units,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::OutputFormat;
+
+/// Keys accepted by `mapradar config`.
+pub const KEYS: [&str; 12] = [
+    "api_key",
+    "api_keys",
+    "key_rotation",
+    "provider",
+    "format",
+    "radius",
+    "units",
+    "types",
+    "cache_ttl",
+    "no_cache",
+    "rate_limit",
+    "max_calls_per_day",
+];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Comma-separated keys rotated by the Google provider.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_keys: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_rotation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radius: Option<f64>,
+    /// Units for --radius and displayed distances; `radius` above is always meters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub units: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub types: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_ttl: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_cache: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_calls_per_day: Option<u64>,
+    /// Saved `nearby` arguments by name, applied with `mapradar run`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub searches: BTreeMap<String, Vec<String>>,
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/mapradar/config.toml`, falling back to `~/.config`.
+    pub fn default_path() -> PathBuf {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .unwrap_or_else(std::env::temp_dir);
+        base.join("mapradar").join("config.toml")
+    }
+
+    /// Reads the config at `path`; a missing file yields an empty config.
+    pub fn load(path: &Path) -> Result<Self, MapradarError> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+        let config: Self = toml::from_str(&text)
+            .map_err(|err| MapradarError::Config(format!("{}: {}", path.display(), err)))?;
+
+        // Catch hand edits that parse as TOML but hold invalid values.
+        for (key, value) in config.entries() {
+            Self::default()
+                .set(key, &value)
+                .map_err(|err| MapradarError::Config(format!("{}: {}", path.display(), err)))?;
+        }
+        Ok(config)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), MapradarError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let text = toml::to_string_pretty(self)
+            .map_err(|err| MapradarError::Config(format!("Failed to write config: {}", err)))?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    /// Validates `value` for `key` and stores it.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), MapradarError> {
+        let invalid = |expected: &str| {
+            MapradarError::InvalidInput(format!(
+                "Invalid value '{}' for {} (expected {})",
+                value, key, expected
+            ))
+        };
+
+        match key {
+            "api_key" => self.api_key = Some(value.to_string()),
+            "api_keys" => {
+                if value.split(',').any(|key| key.trim().is_empty()) {
+                    return Err(invalid("comma-separated API keys"));
+                }
+                self.api_keys = Some(value.to_string());
+            }
+            "key_rotation" => {
+                value.parse::<KeyRotation>()?;
+                self.key_rotation = Some(value.to_string());
+            }
+            "provider" => {
+                value.parse::<ProviderKind>()?;
+                self.provider = Some(value.to_string());
+            }
+            "format" => {
+                OutputFormat::from_str(value, true).map_err(|_| {
+                    invalid("json, geojson, csv, tsv, table, kml, gpx, html, or parquet")
+                })?;
+                self.format = Some(value.to_string());
+            }
+            "radius" => {
+                let radius: f64 = value
+                    .parse()
+                    .ok()
+                    .filter(|r: &f64| *r > 0.0)
+                    .ok_or_else(|| invalid("a positive number of meters"))?;
+                self.radius = Some(radius);
+            }
+            "units" => {
+                value.parse::<Units>()?;
+                self.units = Some(value.to_string());
+            }
+            "types" => {
+                for category in value.split(',') {
+                    category.parse::<PlaceCategory>()?;
+                }
+                self.types = Some(value.to_string());
+            }
+            "cache_ttl" => {
+                self.cache_ttl = Some(value.parse().map_err(|_| invalid("seconds"))?);
+            }
+            "no_cache" => {
+                self.no_cache = Some(value.parse().map_err(|_| invalid("true or false"))?);
+            }
+            "rate_limit" => {
+                value.parse::<RateLimit>()?;
+                self.rate_limit = Some(value.to_string());
+            }
+            "max_calls_per_day" => {
+                self.max_calls_per_day =
+                    Some(value.parse().map_err(|_| invalid("a number of calls"))?);
+            }
+            other => return Err(unknown_key(other)),
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<String>, MapradarError> {
+        if !KEYS.contains(&key) {
+            return Err(unknown_key(key));
+        }
+        Ok(self
+            .entries()
+            .into_iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value))
+    }
+
+    pub fn unset(&mut self, key: &str) -> Result<(), MapradarError> {
+        match key {
+            "api_key" => self.api_key = None,
+            "api_keys" => self.api_keys = None,
+            "key_rotation" => self.key_rotation = None,
+            "provider" => self.provider = None,
+            "format" => self.format = None,
+            "radius" => self.radius = None,
+            "units" => self.units = None,
+            "types" => self.types = None,
+            "cache_ttl" => self.cache_ttl = None,
+            "no_cache" => self.no_cache = None,
+            "rate_limit" => self.rate_limit = None,
+            "max_calls_per_day" => self.max_calls_per_day = None,
+            other => return Err(unknown_key(other)),
+        }
+        Ok(())
+    }
+
+    /// Every key that has a value, in [`KEYS`] order.
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let values = [
+            self.api_key.clone(),
+            self.api_keys.clone(),
+            self.key_rotation.clone(),
+            self.provider.clone(),
+            self.format.clone(),
+            self.radius.map(|r| r.to_string()),
+            self.units.clone(),
+            self.types.clone(),
+            self.cache_ttl.map(|t| t.to_string()),
+            self.no_cache.map(|b| b.to_string()),
+            self.rate_limit.clone(),
+            self.max_calls_per_day.map(|n| n.to_string()),
+        ];
+        KEYS.iter()
+            .zip(values)
+            .filter_map(|(key, value)| Some((*key, value?)))
+            .collect()
+    }
+
+    /// The keys of `api_keys`, or `api_key` alone when that is all that is set.
+    pub fn api_keys(&self) -> Option<Vec<String>> {
+        match (&self.api_keys, &self.api_key) {
+            (Some(keys), _) => Some(keys.split(',').map(|key| key.trim().to_string()).collect()),
+            (None, key) => key.clone().map(|key| vec![key]),
+        }
+    }
+
+    pub fn key_rotation(&self) -> Option<KeyRotation> {
+        self.key_rotation.as_deref().and_then(|r| r.parse().ok())
+    }
+
+    pub fn provider(&self) -> Option<ProviderKind> {
+        self.provider.as_deref().and_then(|p| p.parse().ok())
+    }
+
+    pub fn format(&self) -> Option<OutputFormat> {
+        self.format
+            .as_deref()
+            .and_then(|f| OutputFormat::from_str(f, true).ok())
+    }
+
+    pub fn units(&self) -> Option<Units> {
+        self.units.as_deref().and_then(|u| u.parse().ok())
+    }
+
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit.as_deref().and_then(|r| r.parse().ok())
+    }
+
+    /// Stores `args` under `name`, returning whether a search of that name was replaced.
+    pub fn save_search(&mut self, name: &str, args: &[String]) -> Result<bool, MapradarError> {
+        if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace) {
+            return Err(MapradarError::InvalidInput(format!(
+                "Invalid search name '{}' (expected a word such as \"family\" or \"commuter\")",
+                name
+            )));
+        }
+        Ok(self
+            .searches
+            .insert(name.to_string(), args.to_vec())
+            .is_some())
+    }
+
+    /// The arguments saved under `name`.
+    pub fn search(&self, name: &str) -> Result<&[String], MapradarError> {
+        self.searches
+            .get(name)
+            .map(Vec::as_slice)
+            .ok_or_else(|| unknown_search(self, name))
+    }
+
+    pub fn remove_search(&mut self, name: &str) -> Result<(), MapradarError> {
+        self.searches
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| unknown_search(self, name))
+    }
+}
+
+fn unknown_key(key: &str) -> MapradarError {
+    MapradarError::InvalidInput(format!(
+        "Unknown config key '{}' (expected one of: {})",
+        key,
+        KEYS.join(", ")
+    ))
+}
+
+fn unknown_search(config: &Config, name: &str) -> MapradarError {
+    let saved = if config.searches.is_empty() {
+        "none saved yet; add one with `mapradar save-search`".to_string()
+    } else {
+        format!(
+            "saved: {}",
+            config
+                .searches
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+    MapradarError::InvalidInput(format!("No saved search '{}' ({})", name, saved))
+}
@@ -0,0 +1,9 @@
+//! Command-line front-end modules that are not part of the library API.
+
+pub mod config;
+pub mod progress;
+pub mod select;
+pub mod template;
+
+#[cfg(feature = "tui")]
+pub mod tui;
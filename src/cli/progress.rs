@@ -0,0 +1,101 @@
+//! Progress bars and spinners drawn on stderr while long commands run.
+//!
+//! Batch geocoding shows one bar counting rows; a nearby search shows a spinner per service type
+//! while its search runs. Everything is cleared once the command has its results, so only the
+//! results stay on screen.
+
+use std::{
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use mapradar::progress::{ProgressObserver, ProgressTask};
+
+const TICK: Duration = Duration::from_millis(100);
+
+/// Draws client progress with indicatif, which shows nothing when stderr is not a terminal.
+#[derive(Default)]
+pub struct ProgressBars {
+    bars: MultiProgress,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Row counter of a batch call.
+    rows: Option<ProgressBar>,
+    /// Spinners of the service-type searches, kept showing their outcome once finished.
+    spinners: Vec<(String, ProgressBar)>,
+}
+
+impl ProgressBars {
+    /// The bars, still usable after a panic elsewhere poisoned the lock; they only draw.
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl ProgressObserver for ProgressBars {
+    fn started(&self, task: ProgressTask, steps: usize) {
+        let unit = match task {
+            ProgressTask::BatchGeocode => "geocoded",
+            ProgressTask::BatchReverse => "reverse geocoded",
+            ProgressTask::Nearby => return,
+        };
+        let style = ProgressStyle::with_template(&format!(
+            "{{bar:40.cyan/blue}} {{pos}}/{{len}} {} ({{eta}} left) {{wide_msg}}",
+            unit
+        ))
+        .expect("valid progress template")
+        .progress_chars("##-");
+        let rows = self
+            .bars
+            .add(ProgressBar::new(steps as u64).with_style(style));
+        rows.enable_steady_tick(TICK);
+        self.state().rows = Some(rows);
+    }
+
+    fn step_started(&self, label: &str) {
+        let mut state = self.state();
+        if state.rows.is_some() {
+            return;
+        }
+        let style =
+            ProgressStyle::with_template("{spinner:.cyan} {msg}").expect("valid progress template");
+        let spinner = self.bars.add(
+            ProgressBar::new_spinner()
+                .with_style(style)
+                .with_message(format!("searching {}", label)),
+        );
+        spinner.enable_steady_tick(TICK);
+        state.spinners.push((label.to_string(), spinner));
+    }
+
+    fn step_finished(&self, label: &str, ok: bool) {
+        let state = self.state();
+        if let Some(rows) = &state.rows {
+            rows.set_message(label.to_string());
+            rows.inc(1);
+            return;
+        }
+        if let Some((_, spinner)) = state
+            .spinners
+            .iter()
+            .find(|(name, spinner)| name == label && !spinner.is_finished())
+        {
+            let outcome = if ok { "done" } else { "failed" };
+            spinner.finish_with_message(format!("{} {}", label, outcome));
+        }
+    }
+
+    fn finished(&self) {
+        let mut state = self.state();
+        if let Some(rows) = state.rows.take() {
+            rows.finish_and_clear();
+        }
+        for (_, spinner) in state.spinners.drain(..) {
+            spinner.finish_and_clear();
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! Parts of JSON results picked out with `--fields` or `--query`.
+//!
+//! `--fields` keeps some fields of each place, or of a result without places, by name or dotted
+//! path. `--query` runs a jq filter (through [jaq]) against the whole result.
+//!
+//! [jaq]: https://github.com/01mf02/jaq
+
+use std::str::FromStr;
+
+use jaq_core::{
+    Compiler, Ctx, Filter, Native, RcIter,
+    load::{Arena, File, Loader},
+};
+use jaq_json::Val;
+use mapradar::error::MapradarError;
+use serde::{Serialize, Serializer, ser::SerializeMap};
+use serde_json::{Map, Value};
+
+/// Fields holding a result's places or rows, which `--fields` and `--template` go through one
+/// item at a time.
+const ITEM_FIELDS: [&str; 5] = [
+    "nearby_services",
+    "amenities",
+    "clusters",
+    "cells",
+    "features",
+];
+
+/// The items of a list result, or of a result holding places or rows (such as a nearby search's
+/// `nearby_services`) with the result's other fields, like `location`, added to each item that
+/// lacks them; `None` for any other result.
+pub fn items(result: &Value) -> Option<Vec<Value>> {
+    let fields = match result {
+        Value::Array(items) => return Some(items.clone()),
+        Value::Object(fields) => fields,
+        _ => return None,
+    };
+    let (list, items) = ITEM_FIELDS
+        .iter()
+        .find_map(|field| Some((*field, fields.get(*field)?.as_array()?)))?;
+    Some(
+        items
+            .iter()
+            .map(|item| {
+                let mut item = match item {
+                    Value::Object(item) => item.clone(),
+                    other => Map::from_iter([("value".to_string(), other.clone())]),
+                };
+                for (field, value) in fields {
+                    if field != list && !item.contains_key(field) {
+                        item.insert(field.clone(), value.clone());
+                    }
+                }
+                Value::Object(item)
+            })
+            .collect(),
+    )
+}
+
+/// What `--fields` or `--query` keeps of a result.
+#[derive(Debug, Clone)]
+pub enum Selection {
+    /// Field names or dotted paths, such as `name` or `location.address`.
+    Fields(Vec<String>),
+    Query(Query),
+}
+
+impl Selection {
+    /// The values left of `result`: one for `--fields`, a list when the result has items, and
+    /// every output of a `--query` filter.
+    pub fn apply(&self, result: &Value) -> Result<Vec<Value>, MapradarError> {
+        match self {
+            Self::Fields(fields) => Ok(vec![match items(result) {
+                Some(items) => items.iter().map(|item| pick(item, fields)).collect(),
+                None => pick(result, fields),
+            }]),
+            Self::Query(query) => query.run(result),
+        }
+    }
+}
+
+/// An object of `fields` from `value`, keyed as given; missing fields are `null`.
+fn pick(value: &Value, fields: &[String]) -> Value {
+    fields
+        .iter()
+        .map(|field| {
+            let picked = field
+                .split('.')
+                .try_fold(value, |value, key| value.get(key))
+                .cloned()
+                .unwrap_or(Value::Null);
+            (field.clone(), picked)
+        })
+        .collect::<Map<_, _>>()
+        .into()
+}
+
+/// Serializes a value with the keys of its objects in the order of `--fields`, as JSON objects
+/// otherwise list them alphabetically.
+pub struct InFieldOrder<'a>(pub &'a Value, pub &'a [String]);
+
+impl Serialize for InFieldOrder<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Self(value, fields) = *self;
+        match value {
+            Value::Array(items) => {
+                serializer.collect_seq(items.iter().map(|item| InFieldOrder(item, fields)))
+            }
+            Value::Object(object) => {
+                let mut map = serializer.serialize_map(Some(object.len()))?;
+                for field in fields {
+                    if let Some(value) = object.get(field) {
+                        map.serialize_entry(field, value)?;
+                    }
+                }
+                for (key, value) in object {
+                    if !fields.contains(key) {
+                        map.serialize_entry(key, value)?;
+                    }
+                }
+                map.end()
+            }
+            other => other.serialize(serializer),
+        }
+    }
+}
+
+/// A jq filter given with `--query`, compiled once when parsed to catch errors early.
+#[derive(Debug, Clone)]
+pub struct Query(String);
+
+impl FromStr for Query {
+    type Err = MapradarError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        let query = Self(code.to_string());
+        query.compile()?;
+        Ok(query)
+    }
+}
+
+impl Query {
+    fn compile(&self) -> Result<Filter<Native<Val>>, MapradarError> {
+        let arena = Arena::default();
+        let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+        let file = File {
+            code: self.0.as_str(),
+            path: (),
+        };
+        let modules = loader.load(&arena, file).map_err(|errors| {
+            let reasons = errors.into_iter().flat_map(|(_, error)| match error {
+                jaq_core::load::Error::Io(errors) => errors
+                    .into_iter()
+                    .map(|(path, error)| format!("{}: {}", path, error))
+                    .collect::<Vec<_>>(),
+                jaq_core::load::Error::Lex(errors) => errors
+                    .into_iter()
+                    .map(|(expected, at)| unexpected(expected.as_str(), at))
+                    .collect(),
+                jaq_core::load::Error::Parse(errors) => errors
+                    .into_iter()
+                    .map(|(expected, at)| unexpected(expected.as_str(), at))
+                    .collect(),
+            });
+            invalid(reasons.collect())
+        })?;
+        Compiler::default()
+            .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+            .compile(modules)
+            .map_err(|errors| {
+                invalid(
+                    errors
+                        .into_iter()
+                        .flat_map(|(_, undefined)| undefined)
+                        .map(|(name, kind)| format!("undefined {} '{}'", kind.as_str(), name))
+                        .collect(),
+                )
+            })
+    }
+
+    /// Every output of the filter run against `result`.
+    pub fn run(&self, result: &Value) -> Result<Vec<Value>, MapradarError> {
+        let filter = self.compile()?;
+        let inputs = RcIter::new(std::iter::empty());
+        filter
+            .run((Ctx::new([], &inputs), Val::from(result.clone())))
+            .map(|output| {
+                output
+                    .map(Value::from)
+                    .map_err(|err| MapradarError::InvalidInput(format!("Query failed: {}", err)))
+            })
+            .collect()
+    }
+}
+
+fn unexpected(expected: &str, at: &str) -> String {
+    match at.chars().take(20).collect::<String>() {
+        rest if rest.is_empty() => format!("expected {} at the end", expected),
+        rest => format!("expected {} at \"{}\"", expected, rest),
+    }
+}
+
+fn invalid(reasons: Vec<String>) -> MapradarError {
+    MapradarError::InvalidInput(format!("Invalid query: {}", reasons.join("; ")))
+}
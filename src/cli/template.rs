@@ -0,0 +1,75 @@
+//! Plain-text output rendered from JSON results with `--template`.
+//!
+//! Templates use [TinyTemplate] syntax: `{name}` prints a field, `{location.address}` a nested
+//! one, `{distance_km | round}` a number to two decimal places, and `{{ if rating }}...{{ endif }}`
+//! or `{{ for s in nearby_services }}...{{ endfor }}` add conditions and loops. Literal braces are
+//! written `\{` and `\}`.
+//!
+//! [TinyTemplate]: https://docs.rs/tinytemplate
+
+use std::str::FromStr;
+
+use mapradar::error::MapradarError;
+use serde_json::Value;
+use tinytemplate::TinyTemplate;
+
+use super::select::items;
+
+const NAME: &str = "template";
+
+/// A `--template` string, compiled once when parsed to catch syntax errors early.
+#[derive(Debug, Clone)]
+pub struct Template(String);
+
+impl FromStr for Template {
+    type Err = MapradarError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let template = Self(text.to_string());
+        template.engine()?;
+        Ok(template)
+    }
+}
+
+impl Template {
+    fn engine(&self) -> Result<TinyTemplate<'_>, MapradarError> {
+        let mut engine = TinyTemplate::new();
+        engine.set_default_formatter(&format_value);
+        engine.add_formatter("round", format_rounded);
+        engine.add_template(NAME, &self.0).map_err(invalid)?;
+        Ok(engine)
+    }
+
+    /// Renders `result` as one line per item (see [`items`]), or as one line when it has none.
+    pub fn render(&self, result: &Value) -> Result<String, MapradarError> {
+        let engine = self.engine()?;
+        let mut text = String::new();
+        for context in items(result).unwrap_or_else(|| vec![result.clone()]) {
+            text.push_str(&engine.render(NAME, &context).map_err(invalid)?);
+            text.push('\n');
+        }
+        Ok(text)
+    }
+}
+
+/// Prints strings as they are, nulls as nothing, and lists and objects as compact JSON.
+fn format_value(value: &Value, output: &mut String) -> tinytemplate::error::Result<()> {
+    match value {
+        Value::Null => {}
+        Value::String(text) => output.push_str(text),
+        other => output.push_str(&other.to_string()),
+    }
+    Ok(())
+}
+
+fn format_rounded(value: &Value, output: &mut String) -> tinytemplate::error::Result<()> {
+    match value.as_f64() {
+        Some(number) => output.push_str(&format!("{:.2}", number)),
+        None => format_value(value, output)?,
+    }
+    Ok(())
+}
+
+fn invalid(err: tinytemplate::error::Error) -> MapradarError {
+    MapradarError::InvalidInput(format!("Invalid template: {}", err))
+}
@@ -0,0 +1,359 @@
+//! Interactive terminal UI: type an address, pick service types, and browse nearby results.
+
+use std::{io, time::Duration};
+
+use mapradar::{
+    client::MapradarClient,
+    error::MapradarError,
+    models::{LocationIntelligence, NearbyService, SearchQuery, ServiceType},
+};
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, Paragraph, Wrap},
+};
+use tokio::sync::mpsc;
+
+const RADIUS_STEP_KM: f64 = 0.5;
+const MAX_RESULTS_PER_TYPE: usize = 10;
+
+/// Pane that receives key presses.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Input,
+    Types,
+    Results,
+}
+
+impl Focus {
+    fn next(self) -> Self {
+        match self {
+            Self::Input => Self::Types,
+            Self::Types => Self::Results,
+            Self::Results => Self::Input,
+        }
+    }
+}
+
+struct App {
+    client: MapradarClient,
+    input: String,
+    focus: Focus,
+    selected_types: Vec<bool>,
+    types_state: ListState,
+    radius_km: f64,
+    intel: Option<LocationIntelligence>,
+    results_state: ListState,
+    status: String,
+    loading: bool,
+    results_tx: mpsc::UnboundedSender<Result<LocationIntelligence, MapradarError>>,
+    results_rx: mpsc::UnboundedReceiver<Result<LocationIntelligence, MapradarError>>,
+}
+
+impl App {
+    fn new(client: MapradarClient) -> Self {
+        let (results_tx, results_rx) = mpsc::unbounded_channel();
+        let mut selected_types = vec![false; ServiceType::ALL.len()];
+        selected_types[ServiceType::ALL
+            .iter()
+            .position(|t| *t == ServiceType::Bank)
+            .unwrap_or_default()] = true;
+
+        Self {
+            client,
+            input: String::new(),
+            focus: Focus::Input,
+            selected_types,
+            types_state: ListState::default().with_selected(Some(0)),
+            radius_km: 1.0,
+            intel: None,
+            results_state: ListState::default(),
+            status: "Type an address and press Enter".to_string(),
+            loading: false,
+            results_tx,
+            results_rx,
+        }
+    }
+
+    fn service_types(&self) -> Vec<ServiceType> {
+        ServiceType::ALL
+            .iter()
+            .zip(&self.selected_types)
+            .filter(|(_, selected)| **selected)
+            .map(|(service_type, _)| *service_type)
+            .collect()
+    }
+
+    fn selected_service(&self) -> Option<&NearbyService> {
+        let intel = self.intel.as_ref()?;
+        intel.nearby_services.get(self.results_state.selected()?)
+    }
+
+    /// Starts a search in the background; the result arrives on `results_rx`.
+    fn search(&mut self) {
+        let address = self.input.trim().to_string();
+        if address.is_empty() {
+            self.status = "Enter an address first".to_string();
+            return;
+        }
+        let service_types = self.service_types();
+        if service_types.is_empty() {
+            self.status = "Select at least one service type".to_string();
+            return;
+        }
+
+        self.loading = true;
+        self.status = format!(
+            "Searching within {:.1} km of '{}'...",
+            self.radius_km, address
+        );
+
+        let client = self.client.clone();
+        let radius_km = self.radius_km;
+        let tx = self.results_tx.clone();
+        tokio::spawn(async move {
+            let result = client
+                .fetch_intelligence_async(
+                    SearchQuery::from_address(address),
+                    service_types,
+                    radius_km,
+                    MAX_RESULTS_PER_TYPE,
+                )
+                .await;
+            let _ = tx.send(result);
+        });
+    }
+
+    fn receive_results(&mut self) {
+        while let Ok(result) = self.results_rx.try_recv() {
+            self.loading = false;
+            match result {
+                Ok(intel) => {
+                    self.status = format!(
+                        "{} ({:.5}, {:.5}) - {} services found",
+                        intel.location.address,
+                        intel.location.latitude,
+                        intel.location.longitude,
+                        intel.total_services_found
+                    );
+                    self.results_state
+                        .select((!intel.nearby_services.is_empty()).then_some(0));
+                    self.intel = Some(intel);
+                }
+                Err(err) => self.status = format!("Error: {}", err),
+            }
+        }
+    }
+
+    /// Handles a key press, returning `false` when the user asked to quit.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return false;
+        }
+
+        match (self.focus, key.code) {
+            (_, KeyCode::Tab) => self.focus = self.focus.next(),
+            (Focus::Input, KeyCode::Esc) => return false,
+            (Focus::Input, KeyCode::Enter) => self.search(),
+            (Focus::Input, KeyCode::Backspace) => {
+                self.input.pop();
+            }
+            (Focus::Input, KeyCode::Char(c)) => self.input.push(c),
+            (_, KeyCode::Char('q') | KeyCode::Esc) => return false,
+            (_, KeyCode::Char('+') | KeyCode::Char('=')) => {
+                self.radius_km += RADIUS_STEP_KM;
+            }
+            (_, KeyCode::Char('-')) => {
+                self.radius_km = (self.radius_km - RADIUS_STEP_KM).max(RADIUS_STEP_KM);
+            }
+            (_, KeyCode::Char('r')) | (_, KeyCode::Enter) if self.focus != Focus::Types => {
+                self.search()
+            }
+            (Focus::Types, KeyCode::Up) => self.types_state.select_previous(),
+            (Focus::Types, KeyCode::Down) => self.types_state.select_next(),
+            (Focus::Types, KeyCode::Char(' ') | KeyCode::Enter) => {
+                if let Some(selected) = self.types_state.selected()
+                    && let Some(flag) = self.selected_types.get_mut(selected)
+                {
+                    *flag = !*flag;
+                }
+            }
+            (Focus::Results, KeyCode::Up) => self.results_state.select_previous(),
+            (Focus::Results, KeyCode::Down) => self.results_state.select_next(),
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Runs the TUI until the user quits, restoring the terminal afterwards.
+pub async fn run(client: MapradarClient) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, App::new(client)).await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(terminal: &mut DefaultTerminal, mut app: App) -> io::Result<()> {
+    loop {
+        app.receive_results();
+        terminal.draw(|frame| draw(frame, &mut app))?;
+
+        if event::poll(Duration::from_millis(50))?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && !app.handle_key(key)
+        {
+            return Ok(());
+        }
+        tokio::task::yield_now().await;
+    }
+}
+
+fn pane(title: &str, focused: bool) -> Block<'_> {
+    let border = if focused {
+        Color::Cyan
+    } else {
+        Color::DarkGray
+    };
+    Block::bordered()
+        .title(title)
+        .border_style(Style::new().fg(border))
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let [input_area, body, status_area, help_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(5),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [types_area, results_area, details_area] = Layout::horizontal([
+        Constraint::Length(26),
+        Constraint::Percentage(45),
+        Constraint::Min(20),
+    ])
+    .areas(body);
+
+    let input_title = format!(" Address · radius {:.1} km ", app.radius_km);
+    let input =
+        Paragraph::new(app.input.as_str()).block(pane(&input_title, app.focus == Focus::Input));
+    frame.render_widget(input, input_area);
+    if app.focus == Focus::Input {
+        let cursor_x = input_area.x + 1 + app.input.chars().count() as u16;
+        frame.set_cursor_position((cursor_x.min(input_area.right() - 2), input_area.y + 1));
+    }
+
+    draw_types(frame, app, types_area);
+    draw_results(frame, app, results_area);
+    draw_details(frame, app, details_area);
+
+    let status_style = if app.status.starts_with("Error") {
+        Style::new().fg(Color::Red)
+    } else if app.loading {
+        Style::new().fg(Color::Yellow)
+    } else {
+        Style::new().fg(Color::Green)
+    };
+    frame.render_widget(
+        Paragraph::new(app.status.as_str()).style(status_style),
+        status_area,
+    );
+    frame.render_widget(
+        Paragraph::new(
+            "Tab switch pane · Enter search · Space toggle type · +/- radius · ↑/↓ move · q quit",
+        )
+        .dark_gray(),
+        help_area,
+    );
+}
+
+fn draw_types(frame: &mut Frame, app: &mut App, area: Rect) {
+    let items: Vec<ListItem> = ServiceType::ALL
+        .iter()
+        .zip(&app.selected_types)
+        .map(|(service_type, selected)| {
+            let mark = if *selected { "[x] " } else { "[ ] " };
+            ListItem::new(format!("{}{}", mark, service_type))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(pane(" Service types ", app.focus == Focus::Types))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, area, &mut app.types_state);
+}
+
+fn draw_results(frame: &mut Frame, app: &mut App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .intel
+        .as_ref()
+        .map(|intel| {
+            intel
+                .nearby_services
+                .iter()
+                .map(|service| {
+                    ListItem::new(Line::from(vec![
+                        Span::styled(
+                            format!("{:>7.2} km  ", service.distance_km),
+                            Style::new().fg(Color::Yellow),
+                        ),
+                        Span::raw(service.name.clone()),
+                    ]))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let list = List::new(items)
+        .block(pane(" Nearby ", app.focus == Focus::Results))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, area, &mut app.results_state);
+}
+
+fn draw_details(frame: &mut Frame, app: &App, area: Rect) {
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{}: ", label), Style::new().bold()),
+            Span::raw(value),
+        ])
+    };
+    let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+
+    let lines = match app.selected_service() {
+        Some(service) => vec![
+            Line::from(service.name.clone().cyan().bold()),
+            field("Type", service.service_type.to_string()),
+            field("Distance", format!("{:.2} km", service.distance_km)),
+            field("Address", or_dash(service.address.clone())),
+            field(
+                "Rating",
+                or_dash(service.rating.map(|r| format!("{:.1}", r))),
+            ),
+            field("Phone", or_dash(service.phone_number.clone())),
+            field(
+                "Open now",
+                or_dash(
+                    service
+                        .open_now
+                        .map(|open| if open { "yes" } else { "no" }.to_string()),
+                ),
+            ),
+            field(
+                "Coordinates",
+                format!("{:.5}, {:.5}", service.latitude, service.longitude),
+            ),
+            field("Place ID", or_dash(service.place_id.clone())),
+        ],
+        None => vec![Line::from("No result selected".dark_gray())],
+    };
+
+    let details = Paragraph::new(lines)
+        .wrap(Wrap { trim: true })
+        .block(pane(" Details ", false));
+    frame.render_widget(details, area);
+}
@@ -1,7 +1,16 @@
 #[cfg(feature = "python")]
-use crate::models::TravelParameters;
-use crate::models::{SearchQuery, ServiceType};
+use crate::coordinates::{Coordinates, Latitude, Longitude};
+#[cfg(feature = "python")]
+use crate::models::{
+    AutocompleteBias, GeoLocation, LocationIntelligence, MeetingStrategy, SearchRegion, TravelMode,
+    TravelParameters,
+};
+use crate::models::{PlaceCategory, SearchQuery};
 
+#[cfg(feature = "python")]
+use crate::staticmap::StaticMap;
+#[cfg(feature = "python")]
+use crate::transport::RateLimiter;
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
 
@@ -9,29 +18,304 @@ use pyo3::prelude::*;
 #[pymethods]
 impl super::MapradarClient {
     #[new]
-    pub fn new(api_key: String) -> Self {
-        Self::_new(api_key)
+    #[pyo3(signature = (api_key=None, provider="google", rate_limit=None, language=None))]
+    pub fn new(
+        api_key: Option<String>,
+        provider: &str,
+        rate_limit: Option<&str>,
+        language: Option<String>,
+    ) -> PyResult<Self> {
+        let mut builder = Self::builder().provider(provider.parse()?);
+        if let Some(api_key) = api_key {
+            builder = builder.api_key(api_key);
+        }
+        if let Some(rate_limit) = rate_limit {
+            builder = builder.rate_limiter(RateLimiter::new(rate_limit.parse()?));
+        }
+        if let Some(language) = language {
+            builder = builder.language(language);
+        }
+        Ok(builder.build()?)
     }
 
-    /// Converts an address string into a geographic location.
-    pub fn geocode<'py>(&self, py: Python<'py>, address: String) -> PyResult<Bound<'py, PyAny>> {
+    /// Converts an address string into a geographic location, optionally within a
+    /// `SearchRegion`.
+    #[pyo3(signature = (address, region=None))]
+    pub fn geocode<'py>(
+        &self,
+        py: Python<'py>,
+        address: String,
+        region: Option<SearchRegion>,
+    ) -> PyResult<Bound<'py, PyAny>> {
         let client = self.clone();
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let location = client.geocode_async(&address).await?;
+            let location = client
+                .geocode_in_async(&address, &region.unwrap_or_default())
+                .await?;
             Ok(location)
         })
     }
 
+    /// Returns every location matching an address, most confident first, optionally within a
+    /// `SearchRegion`.
+    #[pyo3(signature = (address, region=None))]
+    pub fn geocode_all<'py>(
+        &self,
+        py: Python<'py>,
+        address: String,
+        region: Option<SearchRegion>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let candidates = client
+                .geocode_all_in_async(&address, &region.unwrap_or_default())
+                .await?;
+            Ok(candidates)
+        })
+    }
+
+    /// Returns the centre, bounding box, and covered localities of a postcode in a country.
+    pub fn postcode<'py>(
+        &self,
+        py: Python<'py>,
+        postcode: String,
+        country: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let area = client.postcode_async(&postcode, &country).await?;
+            Ok(area)
+        })
+    }
+
+    /// Geocodes an address and checks that the postcode written in it matches the location.
+    pub fn check_postcode<'py>(
+        &self,
+        py: Python<'py>,
+        address: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let check = client.check_postcode_async(&address).await?;
+            Ok(check)
+        })
+    }
+
+    /// Geocodes an address, reverse-geocodes the match, and compares their components with
+    /// the address as written.
+    pub fn verify_address<'py>(
+        &self,
+        py: Python<'py>,
+        address: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let verification = client.verify_address_async(&address).await?;
+            Ok(verification)
+        })
+    }
+
+    /// Geocodes a list of addresses concurrently, returning one record per input address.
+    #[pyo3(signature = (addresses, concurrency=5))]
+    pub fn batch_geocode<'py>(
+        &self,
+        py: Python<'py>,
+        addresses: Vec<String>,
+        concurrency: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            Ok(client.batch_geocode_async(addresses, concurrency).await)
+        })
+    }
+
+    /// Reverse geocodes a list of (latitude, longitude) pairs concurrently, returning one record
+    /// per input point.
+    #[pyo3(signature = (coordinates, concurrency=5))]
+    pub fn batch_reverse_geocode<'py>(
+        &self,
+        py: Python<'py>,
+        coordinates: Vec<(Latitude, Longitude)>,
+        concurrency: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let coordinates = coordinates
+            .into_iter()
+            .map(|(lat, lon)| Coordinates { lat, lon })
+            .collect();
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            Ok(client
+                .batch_reverse_geocode_async(coordinates, concurrency)
+                .await)
+        })
+    }
+
+    /// Returns the time zone, UTC offset, and DST status at a point.
+    pub fn timezone<'py>(
+        &self,
+        py: Python<'py>,
+        latitude: Latitude,
+        longitude: Longitude,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let point = Coordinates {
+            lat: latitude,
+            lon: longitude,
+        };
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let timezone = client.timezone_async(point).await?;
+            Ok(timezone)
+        })
+    }
+
+    /// Returns the administrative areas containing a point, country first, with boundary
+    /// polygons when `with_geometry` is set.
+    #[pyo3(signature = (latitude, longitude, with_geometry=false))]
+    pub fn admin_lookup<'py>(
+        &self,
+        py: Python<'py>,
+        latitude: Latitude,
+        longitude: Longitude,
+        with_geometry: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let point = Coordinates {
+            lat: latitude,
+            lon: longitude,
+        };
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let hierarchy = client.admin_lookup_async(point, with_geometry).await?;
+            Ok(hierarchy)
+        })
+    }
+
+    /// Returns ground elevation in meters above sea level at a point.
+    pub fn elevation<'py>(
+        &self,
+        py: Python<'py>,
+        latitude: Latitude,
+        longitude: Longitude,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let point = Coordinates {
+            lat: latitude,
+            lon: longitude,
+        };
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let elevation = client.elevation_async(point).await?;
+            Ok(elevation)
+        })
+    }
+
+    /// Returns an elevation profile along a path of `(lat, lng)` points, optionally resampled
+    /// to `samples` evenly spaced points.
+    #[pyo3(signature = (path, samples=None))]
+    pub fn elevation_profile<'py>(
+        &self,
+        py: Python<'py>,
+        path: Vec<(f64, f64)>,
+        samples: Option<usize>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let profile = client.elevation_profile_async(&path, samples).await?;
+            Ok(profile)
+        })
+    }
+
+    /// Corrects noisy GPS `(lat, lng)` points onto the nearest road, with the distance each
+    /// point moved.
+    pub fn snap_to_road<'py>(
+        &self,
+        py: Python<'py>,
+        points: Vec<(f64, f64)>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let snapped = client.snap_to_road_async(&points).await?;
+            Ok(snapped)
+        })
+    }
+
+    /// Finds amenities of the given types within `radius_m` of a path of `(lat, lng)` points,
+    /// searching every `spacing_m` along it (by default, every `radius_m`).
+    #[pyo3(signature = (path, service_types, radius_m=500.0, spacing_m=None, max_results=10))]
+    pub fn enrich_track<'py>(
+        &self,
+        py: Python<'py>,
+        path: Vec<(f64, f64)>,
+        service_types: Vec<PlaceCategory>,
+        radius_m: f64,
+        spacing_m: Option<f64>,
+        max_results: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let enrichment = client
+                .enrich_track_async(
+                    &path,
+                    service_types,
+                    radius_m,
+                    spacing_m.unwrap_or(radius_m) / 1000.0,
+                    max_results,
+                )
+                .await?;
+            Ok(enrichment)
+        })
+    }
+
+    /// Suggests addresses completing a partial input, optionally restricted to countries
+    /// ("NG,GH") and biased toward a `(min_lon, min_lat, max_lon, max_lat)` box or a
+    /// `(lat, lng)` point.
+    #[pyo3(signature = (partial, limit=5, country=None, bounds=None, around=None))]
+    pub fn autocomplete<'py>(
+        &self,
+        py: Python<'py>,
+        partial: String,
+        limit: usize,
+        country: Option<String>,
+        bounds: Option<(f64, f64, f64, f64)>,
+        around: Option<(f64, f64)>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        let bias = AutocompleteBias {
+            countries: country
+                .map(|countries| countries.split(',').map(str::to_string).collect())
+                .unwrap_or_default(),
+            around: around
+                .map(|(latitude, longitude)| Coordinates::new(latitude, longitude))
+                .transpose()?,
+            bounds: bounds.map(|(min_lng, min_lat, max_lng, max_lat)| {
+                crate::geo::Geometry::BoundingBox {
+                    min_lng,
+                    min_lat,
+                    max_lng,
+                    max_lat,
+                }
+            }),
+        };
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let suggestions = client
+                .autocomplete_with_bias_async(&partial, limit, &bias)
+                .await?;
+            Ok(suggestions)
+        })
+    }
+
     /// Converts geographic coordinates into a human-readable address.
     pub fn reverse_geocode<'py>(
         &self,
         py: Python<'py>,
-        latitude: f64,
-        longitude: f64,
+        latitude: Latitude,
+        longitude: Longitude,
     ) -> PyResult<Bound<'py, PyAny>> {
+        let point = Coordinates {
+            lat: latitude,
+            lon: longitude,
+        };
         let client = self.clone();
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let location = client.reverse_geocode_async(latitude, longitude).await?;
+            let location = client.reverse_geocode_async(point).await?;
             Ok(location)
         })
     }
@@ -40,21 +324,50 @@ impl super::MapradarClient {
     pub fn search_nearby<'py>(
         &self,
         py: Python<'py>,
-        lat: f64,
-        lng: f64,
-        service_type: ServiceType,
+        lat: Latitude,
+        lng: Longitude,
+        service_type: PlaceCategory,
         radius_meters: f64,
         max_results: usize,
     ) -> PyResult<Bound<'py, PyAny>> {
+        let point = Coordinates { lat, lon: lng };
         let client = self.clone();
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
             let services = client
-                .search_nearby_async(lat, lng, service_type, radius_meters, max_results)
+                .search_nearby_async(point, service_type, radius_meters, max_results)
                 .await?;
             Ok(services)
         })
     }
 
+    /// Looks up phone, website, hours, photos, and review count for a place ID.
+    pub fn place_details<'py>(
+        &self,
+        py: Python<'py>,
+        place_id: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let details = client.place_details_async(&place_id).await?;
+            Ok(details)
+        })
+    }
+
+    /// Up to `limit` photos of a place, each with a `url` that needs no API key.
+    #[pyo3(signature = (place_id, limit=5))]
+    pub fn photos<'py>(
+        &self,
+        py: Python<'py>,
+        place_id: String,
+        limit: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let photos = client.photos_async(&place_id, limit).await?;
+            Ok(photos)
+        })
+    }
+
     /// Fetches travel distance between two geographic points.
     pub fn calculate_travel_distance<'py>(
         &self,
@@ -70,13 +383,161 @@ impl super::MapradarClient {
         })
     }
 
+    /// Renders the searched location and its nearby services as a PNG map, returned as bytes,
+    /// optionally with a circle of `radius_m` around the location.
+    #[pyo3(signature = (intel, width=640, height=640, radius_m=None))]
+    pub fn render_map<'py>(
+        &self,
+        py: Python<'py>,
+        intel: LocationIntelligence,
+        width: u32,
+        height: u32,
+        radius_m: Option<f64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        let mut map = StaticMap::from_intelligence(&intel, width, height);
+        if let Some(radius_m) = radius_m {
+            map = map.with_circle(intel.location.latitude, intel.location.longitude, radius_m);
+        }
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let png = client.static_map_async(&map).await?;
+            Ok(std::borrow::Cow::<[u8]>::Owned(png))
+        })
+    }
+
+    /// Computes a travel route between two addresses or coordinates.
+    #[pyo3(signature = (origin, destination, mode=TravelMode::Driving))]
+    pub fn route<'py>(
+        &self,
+        py: Python<'py>,
+        origin: SearchQuery,
+        destination: SearchQuery,
+        mode: TravelMode,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let route = client.route_async(origin, destination, mode).await?;
+            Ok(route)
+        })
+    }
+
+    /// Finds amenities within `corridor_m` of the route between two places, ordered by
+    /// distance along the route.
+    #[pyo3(signature = (origin, destination, service_types, corridor_m=1000.0, mode=TravelMode::Driving, max_results=10))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn search_along_route<'py>(
+        &self,
+        py: Python<'py>,
+        origin: SearchQuery,
+        destination: SearchQuery,
+        service_types: Vec<PlaceCategory>,
+        corridor_m: f64,
+        mode: TravelMode,
+        max_results: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let enrichment = client
+                .search_along_route_async(
+                    origin,
+                    destination,
+                    mode,
+                    service_types,
+                    corridor_m,
+                    max_results,
+                )
+                .await?;
+            Ok(enrichment)
+        })
+    }
+
+    /// Finds a meeting point among several addresses or coordinates and the amenities around it.
+    #[pyo3(signature = (participants, service_types, strategy=MeetingStrategy::Centroid, radius_km=1.0, max_results_per_type=5))]
+    pub fn meeting_point<'py>(
+        &self,
+        py: Python<'py>,
+        participants: Vec<SearchQuery>,
+        service_types: Vec<PlaceCategory>,
+        strategy: MeetingStrategy,
+        radius_km: f64,
+        max_results_per_type: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let meeting = client
+                .meeting_point_async(
+                    participants,
+                    strategy,
+                    service_types,
+                    radius_km,
+                    max_results_per_type,
+                )
+                .await?;
+            Ok(meeting)
+        })
+    }
+
+    /// Returns a copy of `intel` with each service's `travel_time_min` from the searched location.
+    #[pyo3(signature = (intel, mode=TravelMode::Driving))]
+    pub fn attach_travel_times<'py>(
+        &self,
+        py: Python<'py>,
+        mut intel: LocationIntelligence,
+        mode: TravelMode,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            client.attach_travel_times_async(&mut intel, mode).await?;
+            Ok(intel)
+        })
+    }
+
+    /// Computes distances between every origin and destination (straight-line when `mode` is None).
+    #[pyo3(signature = (origins, destinations, mode=None))]
+    pub fn distance_matrix<'py>(
+        &self,
+        py: Python<'py>,
+        origins: Vec<SearchQuery>,
+        destinations: Vec<SearchQuery>,
+        mode: Option<TravelMode>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let matrix = client
+                .distance_matrix_async(origins, destinations, mode)
+                .await?;
+            Ok(matrix)
+        })
+    }
+
+    /// Approximates the area reachable from a point within a travel-time budget.
+    #[pyo3(signature = (latitude, longitude, minutes, mode=TravelMode::Driving))]
+    pub fn isochrone<'py>(
+        &self,
+        py: Python<'py>,
+        latitude: Latitude,
+        longitude: Longitude,
+        minutes: f64,
+        mode: TravelMode,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let center = Coordinates {
+            lat: latitude,
+            lon: longitude,
+        };
+        let client = self.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let isochrone = client.isochrone_async(center, minutes, mode).await?;
+            Ok(isochrone)
+        })
+    }
+
     /// Fetches comprehensive location intelligence, including multiple types of amenities in parallel.
     #[pyo3(signature = (query, service_types, radius_km=5.0, max_results_per_type=5))]
     pub fn fetch_intelligence<'py>(
         &self,
         py: Python<'py>,
         query: SearchQuery,
-        service_types: Vec<ServiceType>,
+        service_types: Vec<PlaceCategory>,
         radius_km: f64,
         max_results_per_type: usize,
     ) -> PyResult<Bound<'py, PyAny>> {
@@ -89,6 +550,57 @@ impl super::MapradarClient {
         })
     }
 
+    /// Blocking variant of `geocode` for callers outside an asyncio event loop.
+    #[pyo3(signature = (address, region=None))]
+    pub fn geocode_sync(
+        &self,
+        py: Python<'_>,
+        address: String,
+        region: Option<SearchRegion>,
+    ) -> PyResult<GeoLocation> {
+        let client = self.clone();
+        Ok(block_on(py, async move {
+            client
+                .geocode_in_async(&address, &region.unwrap_or_default())
+                .await
+        })?)
+    }
+
+    /// Blocking variant of `reverse_geocode`.
+    pub fn reverse_geocode_sync(
+        &self,
+        py: Python<'_>,
+        latitude: Latitude,
+        longitude: Longitude,
+    ) -> PyResult<GeoLocation> {
+        let point = Coordinates {
+            lat: latitude,
+            lon: longitude,
+        };
+        let client = self.clone();
+        Ok(block_on(py, async move {
+            client.reverse_geocode_async(point).await
+        })?)
+    }
+
+    /// Blocking variant of `fetch_intelligence`.
+    #[pyo3(signature = (query, service_types, radius_km=5.0, max_results_per_type=5))]
+    pub fn fetch_intelligence_sync(
+        &self,
+        py: Python<'_>,
+        query: SearchQuery,
+        service_types: Vec<PlaceCategory>,
+        radius_km: f64,
+        max_results_per_type: usize,
+    ) -> PyResult<LocationIntelligence> {
+        let client = self.clone();
+        Ok(block_on(py, async move {
+            client
+                .fetch_intelligence_async(query, service_types, radius_km, max_results_per_type)
+                .await
+        })?)
+    }
+
     /// Fetches geocode information in JSON-RPC 2.0 format.
     #[pyo3(signature = (address, id="1".to_string()))]
     pub fn geocode_rpc<'py>(
@@ -115,7 +627,10 @@ impl super::MapradarClient {
     ) -> PyResult<Bound<'py, PyAny>> {
         let client = self.clone();
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let result = client.reverse_geocode_async(latitude, longitude).await;
+            let result = match Coordinates::new(latitude, longitude) {
+                Ok(point) => client.reverse_geocode_async(point).await,
+                Err(err) => Err(err),
+            };
             Ok(client.rpc_response(id, result))
         })
     }
@@ -128,16 +643,21 @@ impl super::MapradarClient {
         py: Python<'py>,
         lat: f64,
         lng: f64,
-        service_type: ServiceType,
+        service_type: PlaceCategory,
         radius_meters: f64,
         max_results: usize,
         id: String,
     ) -> PyResult<Bound<'py, PyAny>> {
         let client = self.clone();
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let result = client
-                .search_nearby_async(lat, lng, service_type, radius_meters, max_results)
-                .await;
+            let result = match Coordinates::new(lat, lng) {
+                Ok(point) => {
+                    client
+                        .search_nearby_async(point, service_type, radius_meters, max_results)
+                        .await
+                }
+                Err(err) => Err(err),
+            };
             Ok(client.rpc_response(id, result))
         })
     }
@@ -148,7 +668,7 @@ impl super::MapradarClient {
         &self,
         py: Python<'py>,
         query: SearchQuery,
-        service_types: Vec<ServiceType>,
+        service_types: Vec<PlaceCategory>,
         radius_km: f64,
         max_results_per_type: usize,
         id: String,
@@ -177,3 +697,13 @@ impl super::MapradarClient {
         })
     }
 }
+
+/// Runs a client future to completion on the shared Tokio runtime with the GIL released.
+#[cfg(feature = "python")]
+fn block_on<F, T>(py: Python<'_>, future: F) -> T
+where
+    F: std::future::Future<Output = T> + Send,
+    T: Send,
+{
+    py.detach(|| pyo3_async_runtimes::tokio::get_runtime().block_on(future))
+}
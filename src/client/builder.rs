@@ -0,0 +1,462 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use reqwest::{Certificate, Proxy, Url};
+
+use super::{DEFAULT_CONCURRENCY, DEFAULT_MAX_PAGES, MapradarClient};
+#[cfg(feature = "gtfs")]
+use crate::transit::GtfsIndex;
+use crate::{
+    cache::{DiskCache, GeoCache},
+    error::MapradarError,
+    metrics::Metrics,
+    plan::RequestRecorder,
+    progress::ProgressObserver,
+    providers::{
+        GeocodingProvider, GoogleProvider, KeyPool, KeyRotation, NominatimProvider, ProviderKind,
+    },
+    testing::{FixtureMode, Fixtures},
+    transport::{CancellationToken, RateLimiter, RequestHook, RetryPolicy, Transport},
+    usage::UsageLedger,
+};
+
+const USER_AGENT: &str = concat!("mapradar/", env!("CARGO_PKG_VERSION"));
+
+/// Builder for [`MapradarClient`].
+///
+/// Cloning a builder shares its disk cache and rate limiter with every client built from it.
+#[derive(Clone, Default)]
+pub struct MapradarClientBuilder {
+    api_keys: Vec<String>,
+    key_rotation: KeyRotation,
+    provider_kind: ProviderKind,
+    custom_provider: Option<Arc<dyn GeocodingProvider>>,
+    disk_cache: Option<DiskCache>,
+    no_cache: bool,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    dry_run: bool,
+    usage_ledger: Option<UsageLedger>,
+    metrics: Option<Metrics>,
+    middleware: Vec<Arc<dyn RequestHook>>,
+    progress: Option<Arc<dyn ProgressObserver>>,
+    cancellation: Option<CancellationToken>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    base_url: Option<String>,
+    proxy: Option<String>,
+    user_agent: Option<String>,
+    language: Option<String>,
+    root_certificates: Vec<Vec<u8>>,
+    accept_invalid_certs: bool,
+    fixtures: Option<Fixtures>,
+    offline_index: Option<PathBuf>,
+    #[cfg(feature = "gtfs")]
+    transit_index: Option<GtfsIndex>,
+    max_pages: Option<usize>,
+    concurrency: Option<usize>,
+}
+
+impl MapradarClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the API key used by providers that require one (e.g., Google), replacing any keys
+    /// set with [`Self::api_keys`].
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_keys = vec![api_key.into()];
+        self
+    }
+
+    /// Spreads requests over several API keys, moving on to the next key when one is rejected or
+    /// out of quota; see [`KeyPool`].
+    pub fn api_keys<I, S>(mut self, api_keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.api_keys = api_keys.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the order in which several [`Self::api_keys`] are used (default round-robin).
+    pub fn key_rotation(mut self, rotation: KeyRotation) -> Self {
+        self.key_rotation = rotation;
+        self
+    }
+
+    /// Selects one of the built-in providers.
+    pub fn provider(mut self, kind: ProviderKind) -> Self {
+        self.provider_kind = kind;
+        self
+    }
+
+    /// Uses a custom provider implementation, overriding [`Self::provider`].
+    pub fn custom_provider(mut self, provider: impl GeocodingProvider + 'static) -> Self {
+        self.custom_provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// Persists responses in an on-disk cache in addition to the in-memory one.
+    pub fn disk_cache(mut self, disk_cache: DiskCache) -> Self {
+        self.disk_cache = Some(disk_cache);
+        self
+    }
+
+    /// Sets how transient upstream failures are retried.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Throttles upstream requests; the limiter is shared by all clones of the client.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Caps the result pages fetched per nearby search when `max_results` exceeds one page
+    /// (default [`DEFAULT_MAX_PAGES`]).
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// Caps how many service types a multi-type search queries at once (default
+    /// [`DEFAULT_CONCURRENCY`]).
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency);
+        self
+    }
+
+    /// Records upstream requests instead of sending them; read them back with
+    /// [`MapradarClient::request_plan`]. Has no effect on custom providers.
+    pub fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Counts upstream calls and cache hits in `ledger`, enforcing its daily budget. Has no
+    /// effect on custom providers.
+    pub fn usage_ledger(mut self, ledger: UsageLedger) -> Self {
+        self.usage_ledger = Some(ledger);
+        self
+    }
+
+    /// Records upstream latency, rate-limit waits, and cache hits in `metrics`, which servers
+    /// expose at `/metrics`.
+    pub fn metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Passes every upstream request and response through `hook`, after any hooks added
+    /// before it, e.g. to add gateway headers or sign requests; see
+    /// [`crate::transport::middleware`]. Has no effect on custom providers.
+    pub fn middleware(mut self, hook: impl RequestHook + 'static) -> Self {
+        self.middleware.push(Arc::new(hook));
+        self
+    }
+
+    /// Reports the rows of batch calls and the service-type searches of nearby calls to
+    /// `observer`, e.g. to draw progress bars; see [`crate::progress`].
+    pub fn progress(mut self, observer: impl ProgressObserver + 'static) -> Self {
+        self.progress = Some(Arc::new(observer));
+        self
+    }
+
+    /// Abandons in-flight upstream requests once `token` is cancelled, e.g. on Ctrl-C; they fail
+    /// with [`MapradarError::Cancelled`], and batch calls return the rows finished so far.
+    /// Requests of custom providers run to completion, but batches still stop.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Limits how long establishing a connection may take.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Limits how long the client waits between reads of a response.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Limits each request attempt as a whole, from connecting to the end of the body. Retries
+    /// get a fresh timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends requests to `base_url` instead of the provider's public server, e.g. a self-hosted
+    /// Nominatim or a mock server. See [`GoogleProvider::with_base_url`] and
+    /// [`NominatimProvider::with_base_url`] for what each provider redirects.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Routes all requests through an `http://`, `https://`, `socks5://`, or `socks5h://` proxy.
+    /// Without one, the standard `HTTP(S)_PROXY` environment variables apply.
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    /// Replaces the default `mapradar/<version>` User-Agent. Public OpenStreetMap services ask
+    /// for one that identifies your application.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Asks the provider for addresses and place names in `language`, a code such as "fr" or
+    /// "pt-BR"; results record it in [`GeoLocation::language`](crate::models::GeoLocation).
+    /// Has no effect on custom providers or [`ProviderKind::Offline`].
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Trusts an extra PEM-encoded root certificate, e.g. for a corporate proxy.
+    pub fn root_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(pem.into());
+        self
+    }
+
+    /// Skips TLS certificate validation. Only for local test servers.
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    /// Records upstream responses to, or replays them from, a fixtures directory; see
+    /// [`crate::testing`]. Replaying needs no API key.
+    pub fn fixtures(mut self, fixtures: Fixtures) -> Self {
+        self.fixtures = Some(fixtures);
+        self
+    }
+
+    /// Index used by [`ProviderKind::Offline`], instead of the default
+    /// `~/.local/share/mapradar/osm.sqlite`.
+    pub fn offline_index(mut self, path: impl Into<PathBuf>) -> Self {
+        self.offline_index = Some(path.into());
+        self
+    }
+
+    /// Completes bus stop and train station searches with the stops of GTFS feeds imported
+    /// into `index`, with the routes serving them, wherever a feed spans the searched point.
+    #[cfg(feature = "gtfs")]
+    pub fn transit_index(mut self, index: GtfsIndex) -> Self {
+        self.transit_index = Some(index);
+        self
+    }
+
+    /// Disables all response caching.
+    pub fn no_cache(mut self) -> Self {
+        self.no_cache = true;
+        self
+    }
+
+    pub fn build(self) -> Result<MapradarClient, MapradarError> {
+        if let Some(language) = &self.language
+            && !is_language_tag(language)
+        {
+            return Err(MapradarError::Config(format!(
+                "Invalid language '{}': expected a code such as \"fr\" or \"pt-BR\"",
+                language
+            )));
+        }
+        let http_client = self.http_client()?;
+        let max_pages = self.max_pages.unwrap_or(DEFAULT_MAX_PAGES).max(1);
+        let concurrency = self.concurrency.unwrap_or(DEFAULT_CONCURRENCY).max(1);
+        let cache = match (self.no_cache, self.disk_cache) {
+            (true, _) => GeoCache::disabled(),
+            (false, Some(disk)) => GeoCache::with_disk(disk),
+            (false, None) => GeoCache::new(),
+        };
+        let cache = match &self.metrics {
+            Some(metrics) => cache.with_metrics(metrics.clone()),
+            None => cache,
+        };
+
+        if let Some(provider) = self.custom_provider {
+            let mut client = MapradarClient::from_parts(provider, cache);
+            client.max_pages = max_pages;
+            client.concurrency = concurrency;
+            client.metrics = self.metrics;
+            client.progress = self.progress;
+            client.cancellation = self.cancellation;
+            #[cfg(feature = "gtfs")]
+            {
+                client.transit_index = self.transit_index;
+            }
+            return Ok(client);
+        }
+        let cache = match &self.usage_ledger {
+            Some(ledger) => cache.with_ledger(ledger.clone().provider(self.provider_kind)),
+            None => cache,
+        };
+        let cache = match &self.language {
+            Some(language) => cache.with_language(language),
+            None => cache,
+        };
+
+        let mut transport = Transport::new(http_client, self.retry_policy);
+        if let Some(rate_limiter) = self.rate_limiter {
+            transport = transport.with_rate_limiter(rate_limiter);
+        }
+        if let Some(ledger) = self.usage_ledger {
+            transport = transport.with_ledger(ledger);
+        }
+        if let Some(metrics) = &self.metrics {
+            transport = transport.with_metrics(metrics.clone());
+        }
+        for hook in self.middleware {
+            transport = transport.with_middleware(hook);
+        }
+        if let Some(token) = &self.cancellation {
+            transport = transport.with_cancellation(token.clone());
+        }
+        let replaying = self
+            .fixtures
+            .as_ref()
+            .is_some_and(|fixtures| fixtures.mode() == FixtureMode::Replay);
+        if let Some(fixtures) = self.fixtures {
+            transport = transport.with_fixtures(fixtures);
+        }
+        let recorder = self.dry_run.then(RequestRecorder::new);
+        if let Some(recorder) = &recorder {
+            transport = transport.with_recorder(recorder.clone());
+        }
+
+        let provider: Arc<dyn GeocodingProvider> = match self.provider_kind {
+            ProviderKind::Google => {
+                let mut api_keys = self.api_keys;
+                // Fixture lookups ignore credentials, so replay works without a real key.
+                if api_keys.is_empty() && replaying {
+                    api_keys.push(String::new());
+                }
+                let mut providers: Vec<GoogleProvider> = api_keys
+                    .into_iter()
+                    .map(|api_key| {
+                        let provider = GoogleProvider::new(api_key, transport.clone());
+                        let provider = match &self.language {
+                            Some(language) => provider.with_language(language),
+                            None => provider,
+                        };
+                        match &self.base_url {
+                            Some(base_url) => provider.with_base_url(base_url),
+                            None => provider,
+                        }
+                    })
+                    .collect();
+                match providers.len() {
+                    0 => {
+                        return Err(MapradarError::Config(
+                            "An API key is required for the Google provider".into(),
+                        ));
+                    }
+                    1 => Arc::new(providers.remove(0)),
+                    _ => Arc::new(KeyPool::new(providers, self.key_rotation)),
+                }
+            }
+            ProviderKind::Nominatim => {
+                let provider = NominatimProvider::new(transport);
+                let provider = match self.language {
+                    Some(language) => provider.with_language(language),
+                    None => provider,
+                };
+                Arc::new(match self.base_url {
+                    Some(base_url) => provider.with_base_url(base_url),
+                    None => provider,
+                })
+            }
+            ProviderKind::Offline => offline_provider(self.offline_index)?,
+        };
+
+        let mut client = MapradarClient::from_parts(provider, cache);
+        client.recorder = recorder;
+        client.max_pages = max_pages;
+        client.concurrency = concurrency;
+        client.metrics = self.metrics;
+        client.progress = self.progress;
+        client.cancellation = self.cancellation;
+        #[cfg(feature = "gtfs")]
+        {
+            client.transit_index = self.transit_index;
+        }
+        Ok(client)
+    }
+
+    /// HTTP client with the configured timeouts, proxy, User-Agent, and TLS settings.
+    fn http_client(&self) -> Result<reqwest::Client, MapradarError> {
+        if let Some(base_url) = &self.base_url {
+            let parsed = Url::parse(base_url).map_err(|err| {
+                MapradarError::Config(format!("Invalid base URL '{}': {}", base_url, err))
+            })?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(MapradarError::Config(format!(
+                    "Base URL '{}' must use http or https",
+                    base_url
+                )));
+            }
+        }
+
+        let mut builder = reqwest::Client::builder()
+            .user_agent(self.user_agent.as_deref().unwrap_or(USER_AGENT))
+            .danger_accept_invalid_certs(self.accept_invalid_certs);
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.read_timeout {
+            builder = builder.read_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            let proxy = Proxy::all(proxy.as_str()).map_err(|err| {
+                MapradarError::Config(format!("Invalid proxy '{}': {}", proxy, err))
+            })?;
+            builder = builder.proxy(proxy);
+        }
+        for pem in &self.root_certificates {
+            let certificate = Certificate::from_pem(pem).map_err(|err| {
+                MapradarError::Config(format!("Invalid root certificate: {}", err))
+            })?;
+            builder = builder.add_root_certificate(certificate);
+        }
+        Ok(builder.build()?)
+    }
+}
+
+/// Whether `tag` looks like a BCP 47 language tag: letter-and-digit subtags of up to eight
+/// characters, joined by hyphens.
+fn is_language_tag(tag: &str) -> bool {
+    tag.split('-').all(|part| {
+        (1..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+    })
+}
+
+#[cfg(feature = "offline")]
+fn offline_provider(path: Option<PathBuf>) -> Result<Arc<dyn GeocodingProvider>, MapradarError> {
+    use crate::{osm::OsmIndex, providers::OfflineProvider};
+
+    let path = path.unwrap_or_else(OsmIndex::default_path);
+    Ok(Arc::new(OfflineProvider::new(OsmIndex::open_existing(
+        &path,
+    )?)))
+}
+
+#[cfg(not(feature = "offline"))]
+fn offline_provider(_path: Option<PathBuf>) -> Result<Arc<dyn GeocodingProvider>, MapradarError> {
+    Err(MapradarError::Unsupported(
+        "the offline provider requires the `offline` feature".to_string(),
+    ))
+}
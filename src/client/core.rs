@@ -1,16 +1,43 @@
 use crate::{
-    error::GeoError,
+    batch::{BatchRecord, Checkpoint},
+    coordinates::Coordinates,
+    error::MapradarError,
+    geo::{
+        Geometry, centroid, destination_point, haversine_distance, minimax_center, nearest_on_path,
+        sample_path,
+    },
+    health::{ProviderHealth, Readiness},
+    heatmap::Heatmap,
     models::{
-        GeoLocation, JsonRpcError, JsonRpcResponse, LocationIntelligence, NearbyService,
-        SearchQuery, ServiceType, TravelParameters,
+        AdminHierarchy, AutocompleteBias, DistanceMatrix, ElevationSample, FailedServiceType,
+        GeoLocation, GeocodeCandidate, Isochrone, JsonRpcError, JsonRpcResponse,
+        LocationIntelligence, MatchType, MatrixElement, MeetingPoint, MeetingStrategy,
+        NearbyService, PlaceCategory, PlaceDetails, PlacePhoto, PostcodeArea, PostcodeCheck, Route,
+        SearchQuery, SearchRegion, SnappedPoint, Suggestion, TimeZoneInfo, TrackAmenity,
+        TrackEnrichment, TravelMode, TravelParameters, country_code, merge_duplicates,
     },
-    utils::{calculate_distance, parse_address_components},
+    plus_code::{self, CodeArea},
+    progress::ProgressTask,
+    staticmap::StaticMap,
+    utils::{decode_polyline, validate_coordinates},
+    verify::AddressVerification,
 };
 
-use serde_json::Value;
+use std::collections::BTreeMap;
+
+const ISOCHRONE_BEARINGS: usize = 16;
+/// Width in pixels requested for place photos.
+const PHOTO_MAX_WIDTH: u32 = 800;
+const ISOCHRONE_RINGS: usize = 4;
+
+use futures::{
+    StreamExt,
+    stream::{self, BoxStream},
+};
 
 impl super::MapradarClient {
     #[cfg(not(feature = "python"))]
+    #[deprecated(note = "use MapradarClient::builder().api_key(key).build()")]
     pub fn new(api_key: String) -> Self {
         Self::_new(api_key)
     }
@@ -18,7 +45,7 @@ impl super::MapradarClient {
     pub fn rpc_response<T: serde::Serialize>(
         &self,
         id: String,
-        result: Result<T, GeoError>,
+        result: Result<T, MapradarError>,
     ) -> JsonRpcResponse {
         match result {
             Ok(data) => {
@@ -32,102 +59,364 @@ impl super::MapradarClient {
         }
     }
 
-    pub async fn geocode_async(&self, address: &str) -> Result<GeoLocation, GeoError> {
-        if let Some(cached) = self.cache.get_geocode(address).await {
+    /// Geocodes an address, caching the result.
+    ///
+    /// Plus codes are decoded locally: a full code such as "6FR5GC9M+7V", or a short code
+    /// followed by its locality, such as "GC9M+7V Lagos".
+    pub async fn geocode_async(&self, address: &str) -> Result<GeoLocation, MapradarError> {
+        self.geocode_in_async(address, &SearchRegion::default())
+            .await
+    }
+
+    /// Like [`geocode_async`](Self::geocode_async), restricted to `region.countries` and
+    /// preferring matches near `region.bias_around`. A full plus code is exact and ignores the
+    /// region; a short code's locality is looked up within it.
+    pub async fn geocode_in_async(
+        &self,
+        address: &str,
+        region: &SearchRegion,
+    ) -> Result<GeoLocation, MapradarError> {
+        if let Some((code, locality)) = plus_code::split_locality(address) {
+            return self
+                .geocode_plus_code_async(&code, locality, region)
+                .await
+                .map(|(location, _)| location);
+        }
+        self.geocode_address_async(address, region).await
+    }
+
+    async fn geocode_address_async(
+        &self,
+        address: &str,
+        region: &SearchRegion,
+    ) -> Result<GeoLocation, MapradarError> {
+        if let Some(cached) = self.cache.get_geocode(address, region).await {
             return Ok(cached);
         }
 
-        let url = "https://maps.googleapis.com/maps/api/geocode/json";
-        let response = self
-            .http_client
-            .get(url)
-            .query(&[("address", address), ("key", &self.api_key)])
-            .send()
-            .await?;
+        let location = if region.is_empty() {
+            self.provider.geocode(address).await?
+        } else {
+            self.provider.geocode_in(address, region).await?
+        };
 
-        let data: Value = response.json().await?;
-        let status = data["status"].as_str().unwrap_or("UNKNOWN");
+        self.cache
+            .set_geocode(address, region, location.clone())
+            .await;
+        Ok(location)
+    }
 
-        if status != "OK" {
-            if status == "ZERO_RESULTS" {
-                return Err(GeoError::ZeroResults);
+    /// Locates the centre of a plus code, completing a short code from `locality`, and reverse
+    /// geocodes it for an address. Codes in places without one keep the code as their address.
+    async fn geocode_plus_code_async(
+        &self,
+        code: &str,
+        locality: Option<&str>,
+        region: &SearchRegion,
+    ) -> Result<(GeoLocation, CodeArea), MapradarError> {
+        let code = match locality {
+            _ if plus_code::is_full(code) => code.to_string(),
+            Some(locality) => {
+                let reference = self
+                    .geocode_address_async(locality, region)
+                    .await?
+                    .coordinates()?;
+                plus_code::recover_nearest(code, reference)?
             }
-            return Err(GeoError::ApiError {
-                status: status.to_string(),
-                message: data["error_message"]
-                    .as_str()
-                    .unwrap_or("Geocoding failed")
-                    .to_string(),
-            });
+            None => {
+                return Err(MapradarError::InvalidInput(format!(
+                    "Short plus code '{}' needs a locality, e.g. \"{} Lagos\"",
+                    code, code
+                )));
+            }
+        };
+        let area = plus_code::decode(&code)?;
+        let center = area.center();
+        let mut location = match self.reverse_geocode_async(center).await {
+            Ok(location) => location,
+            Err(MapradarError::AddressNotFound { .. } | MapradarError::ZeroResults) => {
+                GeoLocation {
+                    address: code,
+                    latitude: 0.0,
+                    longitude: 0.0,
+                    city: None,
+                    state: None,
+                    country: String::new(),
+                    address_components: None,
+                    timezone: None,
+                    elevation_m: None,
+                    language: None,
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        (location.latitude, location.longitude) = center.as_tuple();
+        Ok((location, area))
+    }
+
+    /// Returns every location matching `address`, most confident first.
+    ///
+    /// Unlike [`geocode_async`](Self::geocode_async), results are not cached, so ambiguity is
+    /// always reported as the provider currently sees it. A plus code has one exact match.
+    pub async fn geocode_all_async(
+        &self,
+        address: &str,
+    ) -> Result<Vec<GeocodeCandidate>, MapradarError> {
+        self.geocode_all_in_async(address, &SearchRegion::default())
+            .await
+    }
+
+    /// Like [`geocode_all_async`](Self::geocode_all_async), keeping matches in
+    /// `region.countries` and preferring those near `region.bias_around`.
+    pub async fn geocode_all_in_async(
+        &self,
+        address: &str,
+        region: &SearchRegion,
+    ) -> Result<Vec<GeocodeCandidate>, MapradarError> {
+        if let Some((code, locality)) = plus_code::split_locality(address) {
+            let (location, area) = self
+                .geocode_plus_code_async(&code, locality, region)
+                .await?;
+            return Ok(vec![GeocodeCandidate {
+                location,
+                confidence: 1.0,
+                match_type: MatchType::Exact,
+                bounding_box: Some((area.west, area.south, area.east, area.north)),
+            }]);
         }
+        let mut candidates = if region.is_empty() {
+            self.provider.geocode_all(address).await?
+        } else {
+            self.provider.geocode_all_in(address, region).await?
+        };
+        candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        Ok(candidates)
+    }
 
-        let result = &data["results"][0];
-        let geometry = &result["geometry"]["location"];
-        let (city, state, country) = parse_address_components(&result["address_components"])?;
-
-        let location = GeoLocation {
-            address: result["formatted_address"]
-                .as_str()
-                .unwrap_or_default()
-                .to_string(),
-            latitude: geometry["lat"].as_f64().unwrap_or_default(),
-            longitude: geometry["lng"].as_f64().unwrap_or_default(),
-            city,
-            state,
-            country,
+    /// Returns the centre, extent, and covered localities of a postal code in `country`.
+    pub async fn postcode_async(
+        &self,
+        postcode: &str,
+        country: &str,
+    ) -> Result<PostcodeArea, MapradarError> {
+        self.provider
+            .postcode_area(&crate::models::postcode(postcode)?, &country_code(country)?)
+            .await
+    }
+
+    /// Geocodes `address` and reports whether the postcode written in it agrees with the
+    /// postcode of the location it resolves to.
+    pub async fn check_postcode_async(
+        &self,
+        address: &str,
+    ) -> Result<PostcodeCheck, MapradarError> {
+        let location = self.geocode_async(address).await?;
+        Ok(PostcodeCheck::new(address, location))
+    }
+
+    /// Verifies an address for delivery: geocodes it, reverse-geocodes the best match, and
+    /// compares the components written in the address with both.
+    ///
+    /// An address with no match is reported with the `not_found` verdict rather than as an error.
+    pub async fn verify_address_async(
+        &self,
+        address: &str,
+    ) -> Result<AddressVerification, MapradarError> {
+        let candidates = match self.geocode_all_async(address).await {
+            Ok(candidates) => candidates,
+            Err(MapradarError::AddressNotFound { .. } | MapradarError::ZeroResults) => Vec::new(),
+            Err(e) => return Err(e),
         };
+        let reverse = match candidates.first() {
+            Some(best) => match self
+                .reverse_geocode_async(best.location.coordinates()?)
+                .await
+            {
+                Ok(location) => Some(location),
+                Err(MapradarError::AddressNotFound { .. } | MapradarError::ZeroResults) => None,
+                Err(e) => return Err(e),
+            },
+            None => None,
+        };
+        Ok(AddressVerification::compare(
+            address,
+            candidates,
+            reverse.as_ref(),
+        ))
+    }
 
-        self.cache.set_geocode(address, location.clone()).await;
-        Ok(location)
+    /// Geocodes many addresses concurrently, keeping input order and reporting failures per row.
+    /// Once the client is [cancelled](super::MapradarClientBuilder::cancellation), returns the
+    /// rows finished so far.
+    pub async fn batch_geocode_async(
+        &self,
+        addresses: Vec<String>,
+        concurrency: usize,
+    ) -> Vec<BatchRecord> {
+        self.batch_async(
+            ProgressTask::BatchGeocode,
+            addresses,
+            concurrency,
+            None,
+            |address: String| async move { self.geocode_async(&address).await },
+        )
+        .await
+        .unwrap_or_default()
     }
 
-    pub async fn reverse_geocode_async(&self, lat: f64, lng: f64) -> Result<GeoLocation, GeoError> {
-        if let Some(cached) = self.cache.get_reverse_geocode(lat, lng).await {
-            return Ok(cached);
-        }
+    /// Like [`batch_geocode_async`](Self::batch_geocode_async), but skips the rows `checkpoint`
+    /// already holds and records each newly finished row in it as soon as it completes. Fails
+    /// when the checkpoint cannot be written, or was made from a different input.
+    pub async fn batch_geocode_resumable_async(
+        &self,
+        addresses: Vec<String>,
+        concurrency: usize,
+        checkpoint: &mut Checkpoint,
+    ) -> Result<Vec<BatchRecord>, MapradarError> {
+        self.batch_async(
+            ProgressTask::BatchGeocode,
+            addresses,
+            concurrency,
+            Some(checkpoint),
+            |address: String| async move { self.geocode_async(&address).await },
+        )
+        .await
+    }
 
-        let url = "https://maps.googleapis.com/maps/api/geocode/json";
-        let response = self
-            .http_client
-            .get(url)
-            .query(&[
-                ("latlng", format!("{},{}", lat, lng)),
-                ("key", self.api_key.clone()),
-            ])
-            .send()
-            .await?;
+    /// Reverse geocodes many points concurrently, keeping input order and reporting failures
+    /// per row. Each record's input is the point as "lat,lng".
+    pub async fn batch_reverse_geocode_async(
+        &self,
+        coordinates: Vec<Coordinates>,
+        concurrency: usize,
+    ) -> Vec<BatchRecord> {
+        self.batch_async(
+            ProgressTask::BatchReverse,
+            coordinates,
+            concurrency,
+            None,
+            |point| self.reverse_geocode_async(point),
+        )
+        .await
+        .unwrap_or_default()
+    }
 
-        let data: Value = response.json().await?;
-        let status = data["status"].as_str().unwrap_or("UNKNOWN");
+    /// Like [`batch_reverse_geocode_async`](Self::batch_reverse_geocode_async), resuming from
+    /// and recording into `checkpoint` as
+    /// [`batch_geocode_resumable_async`](Self::batch_geocode_resumable_async) does.
+    pub async fn batch_reverse_geocode_resumable_async(
+        &self,
+        coordinates: Vec<Coordinates>,
+        concurrency: usize,
+        checkpoint: &mut Checkpoint,
+    ) -> Result<Vec<BatchRecord>, MapradarError> {
+        self.batch_async(
+            ProgressTask::BatchReverse,
+            coordinates,
+            concurrency,
+            Some(checkpoint),
+            |point| self.reverse_geocode_async(point),
+        )
+        .await
+    }
 
-        if status != "OK" {
-            if status == "ZERO_RESULTS" {
-                return Err(GeoError::ZeroResults);
+    /// Looks up the inputs not already finished in `checkpoint`, `concurrency` at a time, and
+    /// returns every row's record in input order. Only checkpoint errors fail the batch; when
+    /// the client is cancelled, rows that did not finish are missing from the records.
+    async fn batch_async<I, F>(
+        &self,
+        task: ProgressTask,
+        inputs: Vec<I>,
+        concurrency: usize,
+        mut checkpoint: Option<&mut Checkpoint>,
+        lookup: impl Fn(I) -> F,
+    ) -> Result<Vec<BatchRecord>, MapradarError>
+    where
+        I: std::fmt::Display,
+        F: Future<Output = Result<GeoLocation, MapradarError>>,
+    {
+        let mut records = BTreeMap::new();
+        let mut pending = Vec::new();
+        if let Some(checkpoint) = &checkpoint {
+            checkpoint.check_len(inputs.len())?;
+        }
+        for (index, input) in inputs.into_iter().enumerate() {
+            let (row, label) = (index + 1, input.to_string());
+            match &checkpoint {
+                Some(checkpoint) if let Some(record) = checkpoint.get(row, &label)? => {
+                    records.insert(row, record.clone());
+                }
+                _ => pending.push((row, label, input)),
             }
-            return Err(GeoError::ApiError {
-                status: status.to_string(),
-                message: data["error_message"]
-                    .as_str()
-                    .unwrap_or("Reverse geocoding failed")
-                    .to_string(),
-            });
         }
 
-        let result = &data["results"][0];
-        let geometry = &result["geometry"]["location"];
-        let (city, state, country) = parse_address_components(&result["address_components"])?;
-
-        let location = GeoLocation {
-            address: result["formatted_address"]
-                .as_str()
-                .unwrap_or_default()
-                .to_string(),
-            latitude: geometry["lat"].as_f64().unwrap_or_default(),
-            longitude: geometry["lng"].as_f64().unwrap_or_default(),
-            city,
-            state,
-            country,
+        self.progress_started(task, pending.len());
+        let lookup = &lookup;
+        // Once cancelled, rows not yet started are left out rather than failed.
+        let mut results = stream::iter(pending)
+            .take_while(|_| std::future::ready(!self.is_cancelled()))
+            .map(|(row, label, input)| async move {
+                let result = self.observe_step(&label, lookup(input)).await;
+                (row, label, result)
+            })
+            .buffered(concurrency.max(1));
+        while let Some((row, label, result)) = results.next().await {
+            if matches!(result, Err(MapradarError::Cancelled)) {
+                continue;
+            }
+            let record = match checkpoint.as_deref_mut() {
+                Some(checkpoint) => match checkpoint.record(row, label, result) {
+                    Ok(record) => record,
+                    Err(err) => {
+                        self.progress_finished();
+                        return Err(err);
+                    }
+                },
+                None => BatchRecord::new(row, label, result),
+            };
+            records.insert(row, record);
+        }
+        self.progress_finished();
+        Ok(records.into_values().collect())
+    }
+
+    fn progress_started(&self, task: ProgressTask, steps: usize) {
+        if let Some(progress) = &self.progress {
+            progress.started(task, steps);
+        }
+    }
+
+    fn progress_finished(&self) {
+        if let Some(progress) = &self.progress {
+            progress.finished();
+        }
+    }
+
+    /// Runs one step of a batch or multi-type call, reporting it to the progress observer.
+    async fn observe_step<T>(
+        &self,
+        label: &str,
+        step: impl Future<Output = Result<T, MapradarError>>,
+    ) -> Result<T, MapradarError> {
+        let Some(progress) = &self.progress else {
+            return step.await;
         };
+        progress.step_started(label);
+        let result = step.await;
+        progress.step_finished(label, result.is_ok());
+        result
+    }
+
+    pub async fn reverse_geocode_async(
+        &self,
+        point: Coordinates,
+    ) -> Result<GeoLocation, MapradarError> {
+        let (lat, lng) = point.as_tuple();
+        if let Some(cached) = self.cache.get_reverse_geocode(lat, lng).await {
+            return Ok(cached);
+        }
+
+        let location = self.provider.reverse_geocode(lat, lng).await?;
 
         self.cache
             .set_reverse_geocode(lat, lng, location.clone())
@@ -137,136 +426,134 @@ impl super::MapradarClient {
 
     pub async fn search_nearby_async(
         &self,
-        lat: f64,
-        lng: f64,
-        service_type: ServiceType,
+        point: Coordinates,
+        category: impl Into<PlaceCategory>,
         radius_meters: f64,
         max_results: usize,
-    ) -> Result<Vec<NearbyService>, GeoError> {
+    ) -> Result<Vec<NearbyService>, MapradarError> {
+        let (lat, lng) = point.as_tuple();
+        let category = category.into();
         if let Some(cached) = self
             .cache
-            .get_nearby(lat, lng, service_type, radius_meters)
+            .get_nearby(lat, lng, &category, radius_meters, max_results)
             .await
         {
-            return Ok(cached.into_iter().take(max_results).collect());
-        }
-
-        let url = "https://maps.googleapis.com/maps/api/place/nearbysearch/json";
-        let google_type = match service_type {
-            ServiceType::BusStop => "bus_station",
-            ServiceType::Market => "supermarket",
-            ServiceType::School => "school",
-            ServiceType::Mall => "shopping_mall",
-            ServiceType::Hospital => "hospital",
-            ServiceType::Bank => "bank",
-            ServiceType::Restaurant => "restaurant",
-            ServiceType::FuelStation => "gas_station",
-            ServiceType::TrainStation => "train_station",
-            ServiceType::TaxiStand => "taxi_stand",
-            ServiceType::Landmark => "tourist_attraction",
-        };
-
-        let response = self
-            .http_client
-            .get(url)
-            .query(&[
-                ("location", format!("{},{}", lat, lng)),
-                ("radius", radius_meters.to_string()),
-                ("type", google_type.to_string()),
-                ("key", self.api_key.clone()),
-            ])
-            .send()
-            .await?;
-
-        let data: Value = response.json().await?;
-        let status = data["status"].as_str().unwrap_or("UNKNOWN");
-
-        if status != "OK" && status != "ZERO_RESULTS" {
-            return Err(GeoError::ApiError {
-                status: status.to_string(),
-                message: data["error_message"]
-                    .as_str()
-                    .unwrap_or("Places API search failed")
-                    .to_string(),
-            });
+            return Ok(cached);
         }
 
+        // Follow page tokens until there are enough results, the provider runs out, or the page
+        // limit is reached. A failed follow-up page ends the search with the results so far.
         let mut services = Vec::new();
-        if let Some(results) = data["results"].as_array() {
-            for place in results.iter().take(max_results) {
-                let loc = &place["geometry"]["location"];
-                let p_lat = loc["lat"].as_f64().unwrap_or_default();
-                let p_lng = loc["lng"].as_f64().unwrap_or_default();
-
-                services.push(NearbyService {
-                    name: place["name"].as_str().unwrap_or("Unknown").to_string(),
-                    service_type,
-                    latitude: p_lat,
-                    longitude: p_lng,
-                    distance_km: calculate_distance(lat, lng, p_lat, p_lng),
-                    address: place
-                        .get("vicinity")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string()),
-                    rating: place
-                        .get("rating")
-                        .and_then(|r| r.as_f64())
-                        .map(|f| f as f32),
-                    place_id: place
-                        .get("place_id")
-                        .and_then(|p| p.as_str())
-                        .map(|s| s.to_string()),
-                    phone_number: place
-                        .get("international_phone_number")
-                        .and_then(|p| p.as_str())
-                        .map(|s| s.to_string()),
-                    open_now: place
-                        .get("opening_hours")
-                        .and_then(|p| p.get("open_now"))
-                        .and_then(|p| p.as_bool()),
-                });
+        let mut page_token: Option<String> = None;
+        for page_number in 1..=self.max_pages {
+            let remaining = max_results - services.len();
+            let page = match self
+                .provider
+                .nearby_search_page(
+                    lat,
+                    lng,
+                    &category,
+                    radius_meters,
+                    remaining,
+                    page_token.as_deref(),
+                )
+                .await
+            {
+                Ok(page) => page,
+                Err(err) if page_number > 1 => {
+                    tracing::warn!(%category, page = page_number, error = %err, "nearby page failed");
+                    break;
+                }
+                Err(err) => return Err(err),
+            };
+            services.extend(page.services.into_iter().take(remaining));
+            page_token = page.next_page_token;
+            if page_token.is_none() || services.len() >= max_results {
+                break;
             }
         }
+        // Providers may report distance differently (or not at all); measure every result the same way.
+        for service in &mut services {
+            service.distance_km = haversine_distance(lat, lng, service.latitude, service.longitude);
+        }
 
+        let exhaustive = page_token.is_none() && services.len() < max_results;
         self.cache
-            .set_nearby(lat, lng, service_type, radius_meters, services.clone())
+            .set_nearby(
+                lat,
+                lng,
+                &category,
+                radius_meters,
+                services.clone(),
+                exhaustive,
+            )
             .await;
         Ok(services)
     }
 
+    /// Resolves the query and searches every service type, up to the client's concurrency
+    /// limit at a time. A failed search becomes a `warnings` entry rather than failing the
+    /// call, unless every search fails; it is also listed in `failed_service_types`.
     pub async fn fetch_intelligence_async(
         &self,
         query: SearchQuery,
-        service_types: Vec<ServiceType>,
+        service_types: impl IntoIterator<Item = impl Into<PlaceCategory>>,
         radius_km: f64,
         max_results_per_type: usize,
-    ) -> Result<LocationIntelligence, GeoError> {
-        let location = match query {
-            SearchQuery::Address { address } => self.geocode_async(&address).await?,
-            SearchQuery::Coordinates {
-                latitude,
-                longitude,
-            } => self.reverse_geocode_async(latitude, longitude).await?,
-        };
-
+    ) -> Result<LocationIntelligence, MapradarError> {
+        let location = self.locate_async(query).await?;
+        let point = location.coordinates()?;
         let radius_meters = radius_km * 1000.0;
-        let mut futures = Vec::new();
+        let categories: Vec<PlaceCategory> = service_types.into_iter().map(Into::into).collect();
+        let searched = categories.len();
 
-        for &service_type in &service_types {
-            futures.push(self.search_nearby_async(
-                location.latitude,
-                location.longitude,
-                service_type,
-                radius_meters,
-                max_results_per_type,
-            ));
+        self.progress_started(ProgressTask::Nearby, searched);
+        let results: Vec<(PlaceCategory, Result<Vec<NearbyService>, MapradarError>)> =
+            stream::iter(categories.clone())
+                .map(|category| async move {
+                    let label = category.to_string();
+                    let search = self.search_nearby_async(
+                        point,
+                        category.clone(),
+                        radius_meters,
+                        max_results_per_type,
+                    );
+                    (category, self.observe_step(&label, search).await)
+                })
+                .buffer_unordered(self.concurrency)
+                .collect()
+                .await;
+        self.progress_finished();
+        // Searches cut short are not failures to report as warnings; the whole call is.
+        if self.is_cancelled() {
+            return Err(MapradarError::Cancelled);
         }
 
-        let results = futures::future::join_all(futures).await;
-
         let mut all_services = Vec::new();
-        for services in results.into_iter().flatten() {
-            all_services.extend(services);
+        let mut failures = Vec::new();
+        for (category, result) in results {
+            match result {
+                Ok(services) => all_services.extend(services),
+                // A dry run plans every search; none of them is a failure.
+                Err(MapradarError::DryRun) => {}
+                Err(err) => failures.push((category, err)),
+            }
+        }
+        if searched > 0 && failures.len() == searched {
+            return Err(failures.swap_remove(0).1);
+        }
+        #[cfg(feature = "gtfs")]
+        if let Some(index) = &self.transit_index
+            && self.recorder.is_none()
+        {
+            index.merge_into(
+                point.latitude(),
+                point.longitude(),
+                &categories,
+                radius_km,
+                max_results_per_type,
+                &mut all_services,
+            )?;
         }
 
         all_services.sort_by(|a, b| {
@@ -275,36 +562,110 @@ impl super::MapradarClient {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        Ok(LocationIntelligence::new(location, all_services))
+        let mut intel = LocationIntelligence::new(location, all_services);
+        for (category, err) in failures {
+            intel
+                .warnings
+                .push(format!("{} search failed: {}", category, err));
+            intel.failed_service_types.push(FailedServiceType {
+                service_type: category,
+                error: err.to_string(),
+            });
+        }
+        Ok(intel)
+    }
+
+    /// Resolves the query, then yields nearby services as each service-type search completes.
+    ///
+    /// Unlike [`fetch_intelligence_async`](Self::fetch_intelligence_async), results arrive in
+    /// completion order rather than sorted by distance, and failed searches surface as `Err` items.
+    pub async fn fetch_intelligence_stream(
+        &self,
+        query: SearchQuery,
+        service_types: impl IntoIterator<Item = impl Into<PlaceCategory>>,
+        radius_km: f64,
+        max_results_per_type: usize,
+    ) -> Result<
+        (
+            GeoLocation,
+            BoxStream<'_, Result<NearbyService, MapradarError>>,
+        ),
+        MapradarError,
+    > {
+        let location = self.locate_async(query).await?;
+        let point = location.coordinates()?;
+        let radius_meters = radius_km * 1000.0;
+        let categories: Vec<PlaceCategory> = service_types.into_iter().map(Into::into).collect();
+
+        let services = stream::iter(categories)
+            .map(move |service_type| {
+                self.search_nearby_async(point, service_type, radius_meters, max_results_per_type)
+            })
+            .buffer_unordered(self.concurrency)
+            .flat_map(|result| {
+                let items = match result {
+                    Ok(services) => services.into_iter().map(Ok).collect(),
+                    Err(err) => vec![Err(err)],
+                };
+                stream::iter(items)
+            })
+            .boxed();
+
+        Ok((location, services))
+    }
+
+    /// Geocodes an address query, reverse geocodes a coordinate query, or locates an IP query.
+    pub async fn locate_async(&self, query: SearchQuery) -> Result<GeoLocation, MapradarError> {
+        let (result, fallback) = match query {
+            SearchQuery::Address { address, region } => {
+                (self.geocode_in_async(&address, &region).await, (0.0, 0.0))
+            }
+            SearchQuery::Coordinates {
+                latitude,
+                longitude,
+            } => (
+                self.reverse_geocode_async(Coordinates {
+                    lat: latitude,
+                    lon: longitude,
+                })
+                .await,
+                (latitude.degrees(), longitude.degrees()),
+            ),
+            SearchQuery::Ip { ip } => (self.provider.ip_location(ip).await, (0.0, 0.0)),
+        };
+        match result {
+            Err(MapradarError::DryRun) => Ok(planned_location(fallback.0, fallback.1)),
+            result => result,
+        }
     }
 
     pub async fn calculate_travel_distance_async(
         &self,
         travel_distance_params: TravelParameters,
-    ) -> Result<f64, GeoError> {
+    ) -> Result<f64, MapradarError> {
         let (origin_latitude, origin_longitude) = match (
             travel_distance_params.origin_latitude,
             travel_distance_params.origin_longitude,
         ) {
-            (Some(lat), Some(lng)) => (lat, lng),
+            (Some(lat), Some(lng)) => {
+                validate_coordinates(lat, lng)?;
+                (lat, lng)
+            }
             (None, None) => {
                 if let Some(origin_addr) = travel_distance_params.origin_address {
                     let location = self.geocode_async(&origin_addr).await?;
                     (location.latitude, location.longitude)
                 } else {
-                    return Err(GeoError::ApiError {
-                        status: "INVALID_ORIGIN_PARAMETERS".to_string(),
-                        message: "Origin address or coordinates are required".to_string(),
-                    });
+                    return Err(MapradarError::InvalidInput(
+                        "Origin address or coordinates are required".to_string(),
+                    ));
                 }
             }
             _ => {
-                return Err(GeoError::ApiError {
-                    status: "INVALID_ORIGIN_PARAMETERS".to_string(),
-                    message:
-                        "Both origin latitude and longitude are required when using coordinates"
-                            .to_string(),
-                });
+                return Err(MapradarError::InvalidInput(
+                    "Both origin latitude and longitude are required when using coordinates"
+                        .to_string(),
+                ));
             }
         };
 
@@ -312,27 +673,29 @@ impl super::MapradarClient {
             travel_distance_params.destination_latitude,
             travel_distance_params.destination_longitude,
         ) {
-            (Some(lat), Some(lng)) => (lat, lng),
+            (Some(lat), Some(lng)) => {
+                validate_coordinates(lat, lng)?;
+                (lat, lng)
+            }
             (None, None) => {
                 if let Some(destination_addr) = travel_distance_params.destination_address {
                     let location = self.geocode_async(&destination_addr).await?;
                     (location.latitude, location.longitude)
                 } else {
-                    return Err(GeoError::ApiError {
-                        status: "INVALID_DESTINATION_PARAMETERS".to_string(),
-                        message: "Destination address or coordinates are required".to_string(),
-                    });
+                    return Err(MapradarError::InvalidInput(
+                        "Destination address or coordinates are required".to_string(),
+                    ));
                 }
             }
             _ => {
-                return Err(GeoError::ApiError {
-                    status: "INVALID_DESTINATION_PARAMETERS".to_string(),
-                    message: "Both destination latitude and longitude are required when using coordinates".to_string(),
-                });
+                return Err(MapradarError::InvalidInput(
+                    "Both destination latitude and longitude are required when using coordinates"
+                        .to_string(),
+                ));
             }
         };
 
-        let distance_km = calculate_distance(
+        let distance_km = haversine_distance(
             origin_latitude,
             origin_longitude,
             destination_latitude,
@@ -341,4 +704,800 @@ impl super::MapradarClient {
 
         Ok(distance_km)
     }
+
+    /// Returns the IANA time zone, UTC offset, and DST status in effect at a point.
+    ///
+    /// With the `offline-tz` feature the lookup uses bundled boundary data and makes no request;
+    /// otherwise it is delegated to the provider.
+    pub async fn timezone_async(&self, point: Coordinates) -> Result<TimeZoneInfo, MapradarError> {
+        let (lat, lng) = point.as_tuple();
+
+        #[cfg(feature = "offline-tz")]
+        return crate::timezone::lookup(lat, lng);
+
+        #[cfg(not(feature = "offline-tz"))]
+        self.provider.timezone(lat, lng).await
+    }
+
+    /// Fills in `location.timezone` from its coordinates.
+    pub async fn attach_timezone_async(
+        &self,
+        mut location: GeoLocation,
+    ) -> Result<GeoLocation, MapradarError> {
+        location.timezone = Some(self.timezone_async(location.coordinates()?).await?);
+        Ok(location)
+    }
+
+    /// Returns the administrative areas containing a point (country, state, county or LGA,
+    /// ward), broadest first, with boundary polygons when `with_geometry` is set and the provider
+    /// has them.
+    pub async fn admin_lookup_async(
+        &self,
+        point: Coordinates,
+        with_geometry: bool,
+    ) -> Result<AdminHierarchy, MapradarError> {
+        let (latitude, longitude) = point.as_tuple();
+        let mut areas = self
+            .provider
+            .admin_areas(latitude, longitude, with_geometry)
+            .await?;
+        if areas.is_empty() {
+            return Err(MapradarError::ZeroResults);
+        }
+        areas.sort_by_key(|area| area.admin_level);
+        Ok(AdminHierarchy {
+            latitude,
+            longitude,
+            areas,
+        })
+    }
+
+    /// Ground elevation in meters above sea level at a point.
+    pub async fn elevation_async(&self, point: Coordinates) -> Result<f64, MapradarError> {
+        let elevations = self.batch_elevation_async(&[point.as_tuple()]).await?;
+        Ok(elevations[0])
+    }
+
+    /// Ground elevation in meters for each `(lat, lng)` point, in input order.
+    pub async fn batch_elevation_async(
+        &self,
+        points: &[(f64, f64)],
+    ) -> Result<Vec<f64>, MapradarError> {
+        for (lat, lng) in points {
+            validate_coordinates(*lat, *lng)?;
+        }
+        if points.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let elevations = self.provider.elevation(points).await?;
+        if elevations.len() != points.len() {
+            return Err(MapradarError::Api {
+                status: "INCOMPLETE".to_string(),
+                message: format!(
+                    "Requested elevation for {} points but received {}",
+                    points.len(),
+                    elevations.len()
+                ),
+            });
+        }
+        Ok(elevations)
+    }
+
+    /// Elevation profile along a path of `(lat, lng)` vertices.
+    ///
+    /// With `samples`, that many points are spaced evenly along the path; otherwise each vertex
+    /// is sampled.
+    pub async fn elevation_profile_async(
+        &self,
+        path: &[(f64, f64)],
+        samples: Option<usize>,
+    ) -> Result<Vec<ElevationSample>, MapradarError> {
+        let points: Vec<(f64, f64, f64)> = match samples {
+            Some(samples) => sample_path(path, samples),
+            None => {
+                let mut distance_km = 0.0;
+                path.iter()
+                    .enumerate()
+                    .map(|(i, &(lat, lng))| {
+                        if i > 0 {
+                            let (prev_lat, prev_lng) = path[i - 1];
+                            distance_km += haversine_distance(prev_lat, prev_lng, lat, lng);
+                        }
+                        (lat, lng, distance_km)
+                    })
+                    .collect()
+            }
+        };
+
+        let coordinates: Vec<(f64, f64)> = points.iter().map(|p| (p.0, p.1)).collect();
+        let elevations = self.batch_elevation_async(&coordinates).await?;
+
+        Ok(points
+            .into_iter()
+            .zip(elevations)
+            .map(
+                |((latitude, longitude, distance_km), elevation_m)| ElevationSample {
+                    latitude,
+                    longitude,
+                    distance_km,
+                    elevation_m,
+                },
+            )
+            .collect())
+    }
+
+    /// Corrects noisy GPS `(lat, lng)` points onto the nearest road, in input order, with the
+    /// distance each point moved. Points with no road nearby are returned where they were.
+    pub async fn snap_to_road_async(
+        &self,
+        points: &[(f64, f64)],
+    ) -> Result<Vec<SnappedPoint>, MapradarError> {
+        for (lat, lng) in points {
+            validate_coordinates(*lat, *lng)?;
+        }
+        if points.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let snapped = self.provider.snap_to_road(points).await?;
+        if snapped.len() != points.len() {
+            return Err(MapradarError::Api {
+                status: "INCOMPLETE".to_string(),
+                message: format!(
+                    "Requested {} points snapped to roads but received {}",
+                    points.len(),
+                    snapped.len()
+                ),
+            });
+        }
+        Ok(snapped)
+    }
+
+    /// Finds amenities along a path of `(lat, lng)` vertices, such as a recorded GPX track.
+    ///
+    /// Every category is searched within `radius_meters` of points spaced about `spacing_km`
+    /// apart along the path, so the search circles overlap when the spacing is under twice the
+    /// radius. Places found from several points are merged, and each is measured against the
+    /// nearest point of the path. As with
+    /// [`fetch_intelligence_async`](Self::fetch_intelligence_async), a failed search becomes a
+    /// warning unless every search fails.
+    pub async fn enrich_track_async(
+        &self,
+        path: &[(f64, f64)],
+        service_types: impl IntoIterator<Item = impl Into<PlaceCategory>>,
+        radius_meters: f64,
+        spacing_km: f64,
+        max_results_per_search: usize,
+    ) -> Result<TrackEnrichment, MapradarError> {
+        if path.is_empty() {
+            return Err(MapradarError::InvalidInput(
+                "The track has no points".to_string(),
+            ));
+        }
+        if !spacing_km.is_finite() || spacing_km <= 0.0 {
+            return Err(MapradarError::InvalidInput(
+                "Sample spacing must be positive".to_string(),
+            ));
+        }
+        for &(lat, lng) in path {
+            validate_coordinates(lat, lng)?;
+        }
+
+        let distance_km: f64 = path
+            .windows(2)
+            .map(|pair| haversine_distance(pair[0].0, pair[0].1, pair[1].0, pair[1].1))
+            .sum();
+        let samples = sample_path(path, (distance_km / spacing_km).ceil() as usize + 1);
+        let categories: Vec<PlaceCategory> = service_types.into_iter().map(Into::into).collect();
+        let points = samples
+            .iter()
+            .map(|&(lat, lng, _)| Coordinates::new(lat, lng))
+            .collect::<Result<Vec<_>, _>>()?;
+        let searches: Vec<(Coordinates, PlaceCategory)> = points
+            .iter()
+            .flat_map(|&point| categories.iter().map(move |c| (point, c.clone())))
+            .collect();
+        let searched = searches.len();
+
+        let results: Vec<(PlaceCategory, Result<Vec<NearbyService>, MapradarError>)> =
+            stream::iter(searches)
+                .map(|(point, category)| async move {
+                    let result = self
+                        .search_nearby_async(
+                            point,
+                            category.clone(),
+                            radius_meters,
+                            max_results_per_search,
+                        )
+                        .await;
+                    (category, result)
+                })
+                .buffered(self.concurrency)
+                .collect()
+                .await;
+
+        let mut found = Vec::new();
+        let mut failures = Vec::new();
+        for (category, result) in results {
+            match result {
+                Ok(services) => found.extend(services),
+                Err(MapradarError::DryRun) => {}
+                Err(err) => failures.push((category, err)),
+            }
+        }
+        if searched > 0 && failures.len() == searched {
+            return Err(failures.swap_remove(0).1);
+        }
+
+        let mut amenities: Vec<TrackAmenity> = merge_duplicates(found)
+            .into_iter()
+            .map(|mut service| {
+                let (offset_km, along_km) =
+                    nearest_on_path(path, service.latitude, service.longitude).unwrap_or_default();
+                service.distance_km = offset_km;
+                TrackAmenity {
+                    service,
+                    distance_along_km: along_km,
+                }
+            })
+            .collect();
+        amenities.sort_by(|a, b| a.distance_along_km.total_cmp(&b.distance_along_km));
+
+        let mut warnings: Vec<String> = Vec::new();
+        for (category, err) in failures {
+            let warning = format!("{} search failed: {}", category, err);
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        }
+
+        Ok(TrackEnrichment {
+            distance_km,
+            samples: samples.len(),
+            amenities,
+            warnings,
+            route: None,
+        })
+    }
+
+    /// Routes from `origin` to `destination`, then finds amenities within `corridor_meters` of
+    /// the route with [`enrich_track_async`](Self::enrich_track_async). Results are ordered by
+    /// distance along the route, and the route itself is returned with them.
+    pub async fn search_along_route_async(
+        &self,
+        origin: SearchQuery,
+        destination: SearchQuery,
+        mode: TravelMode,
+        service_types: impl IntoIterator<Item = impl Into<PlaceCategory>>,
+        corridor_meters: f64,
+        max_results_per_search: usize,
+    ) -> Result<TrackEnrichment, MapradarError> {
+        let route = self.route_async(origin, destination, mode).await?;
+        let mut enrichment = self
+            .enrich_track_async(
+                &decode_polyline(&route.polyline),
+                service_types,
+                corridor_meters,
+                corridor_meters / 1000.0,
+                max_results_per_search,
+            )
+            .await?;
+        enrichment.route = Some(route);
+        Ok(enrichment)
+    }
+
+    /// Tiles `area` into cells about `cell_meters` square and counts the amenities in each, with
+    /// one search per cell and type covering the whole cell. Places found by neighbouring
+    /// searches are counted once, and places outside the area are dropped.
+    ///
+    /// A search that returns `max_results_per_search` results may have missed some, so its cell
+    /// may be undercounted; this is reported in `warnings` along with failed searches.
+    pub async fn heatmap_async(
+        &self,
+        area: &Geometry,
+        service_types: impl IntoIterator<Item = impl Into<PlaceCategory>>,
+        cell_meters: f64,
+        max_results_per_search: usize,
+    ) -> Result<Heatmap, MapradarError> {
+        let mut heatmap = Heatmap::new(area, cell_meters)?;
+        let categories: Vec<PlaceCategory> = service_types.into_iter().map(Into::into).collect();
+        let searches: Vec<(Coordinates, f64, PlaceCategory)> = heatmap
+            .cells
+            .iter()
+            .flat_map(|cell| {
+                let center = cell.center();
+                let (lat, lng) = center.as_tuple();
+                let radius_meters = haversine_distance(lat, lng, cell.north, cell.east) * 1000.0;
+                categories
+                    .iter()
+                    .map(move |c| (center, radius_meters, c.clone()))
+            })
+            .collect();
+        let searched = searches.len();
+
+        let results: Vec<(PlaceCategory, Result<Vec<NearbyService>, MapradarError>)> =
+            stream::iter(searches)
+                .map(|(point, radius_meters, category)| async move {
+                    let result = self
+                        .search_nearby_async(
+                            point,
+                            category.clone(),
+                            radius_meters,
+                            max_results_per_search,
+                        )
+                        .await;
+                    (category, result)
+                })
+                .buffer_unordered(self.concurrency)
+                .collect()
+                .await;
+
+        let mut found = Vec::new();
+        let mut failures = Vec::new();
+        let mut saturated = 0;
+        for (category, result) in results {
+            match result {
+                Ok(services) => {
+                    if services.len() >= max_results_per_search {
+                        saturated += 1;
+                    }
+                    found.extend(services);
+                }
+                Err(MapradarError::DryRun) => {}
+                Err(err) => failures.push((category, err)),
+            }
+        }
+        if searched > 0 && failures.len() == searched {
+            return Err(failures.swap_remove(0).1);
+        }
+
+        heatmap.add(&merge_duplicates(found));
+        for (category, err) in failures {
+            let warning = format!("{} search failed: {}", category, err);
+            if !heatmap.warnings.contains(&warning) {
+                heatmap.warnings.push(warning);
+            }
+        }
+        if saturated > 0 {
+            heatmap.warnings.push(format!(
+                "{} of {} searches reached the result limit, so their cells may be undercounted",
+                saturated, searched
+            ));
+        }
+        Ok(heatmap)
+    }
+
+    /// Locates every participant, places a meeting point among them with `strategy`, and
+    /// searches around it like [`fetch_intelligence_async`](Self::fetch_intelligence_async).
+    /// Distances are measured to the searched location, the address found at the meeting point.
+    /// At least two participants are needed; any that cannot be located fails the call.
+    pub async fn meeting_point_async(
+        &self,
+        participants: Vec<SearchQuery>,
+        strategy: MeetingStrategy,
+        service_types: impl IntoIterator<Item = impl Into<PlaceCategory>>,
+        radius_km: f64,
+        max_results_per_type: usize,
+    ) -> Result<MeetingPoint, MapradarError> {
+        if participants.len() < 2 {
+            return Err(MapradarError::InvalidInput(
+                "A meeting point needs at least two participants".to_string(),
+            ));
+        }
+        // Participants given as points stay where they are, not at the address found for them.
+        let participants: Vec<GeoLocation> = stream::iter(participants)
+            .map(|query| async move {
+                let point = query.coordinates();
+                let mut location = self.locate_async(query).await?;
+                if let Some(point) = point {
+                    (location.latitude, location.longitude) = point.as_tuple();
+                }
+                Ok::<_, MapradarError>(location)
+            })
+            .buffered(self.concurrency)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<_, _>>()?;
+
+        let points: Vec<(f64, f64)> = participants
+            .iter()
+            .map(|location| (location.latitude, location.longitude))
+            .collect();
+        let center = match strategy {
+            MeetingStrategy::Centroid => centroid(&points),
+            MeetingStrategy::Minimax => minimax_center(&points),
+        }
+        .ok_or_else(|| {
+            MapradarError::InvalidInput(
+                "The participants are spread around the globe with no point between them"
+                    .to_string(),
+            )
+        })?;
+
+        let intelligence = self
+            .fetch_intelligence_async(
+                SearchQuery::at(Coordinates::try_from(center)?),
+                service_types,
+                radius_km,
+                max_results_per_type,
+            )
+            .await?;
+        let (lat, lng) = (
+            intelligence.location.latitude,
+            intelligence.location.longitude,
+        );
+        let distances_km: Vec<f64> = points
+            .iter()
+            .map(|&(p_lat, p_lng)| haversine_distance(lat, lng, p_lat, p_lng))
+            .collect();
+        Ok(MeetingPoint {
+            strategy,
+            participants,
+            max_distance_km: distances_km.iter().copied().fold(0.0, f64::max),
+            distances_km,
+            intelligence,
+        })
+    }
+
+    /// Fills in each service's `travel_time_min` from the searched location by `mode`, with the
+    /// provider's distance matrix. Places the provider cannot route to are left without one.
+    pub async fn attach_travel_times_async(
+        &self,
+        intel: &mut LocationIntelligence,
+        mode: TravelMode,
+    ) -> Result<(), MapradarError> {
+        if intel.nearby_services.is_empty() {
+            return Ok(());
+        }
+        let origin = intel.location.coordinates()?.as_tuple();
+        let destinations: Vec<(f64, f64)> = intel
+            .nearby_services
+            .iter()
+            .map(|service| (service.latitude, service.longitude))
+            .collect();
+        let rows = match self
+            .provider
+            .distance_matrix(&[origin], &destinations, mode)
+            .await
+        {
+            // A dry run plans the request; there are no times to attach.
+            Err(MapradarError::DryRun) => return Ok(()),
+            result => result?,
+        };
+        let elements = rows.into_iter().next().unwrap_or_default();
+        for (service, element) in intel.nearby_services.iter_mut().zip(elements) {
+            service.travel_time_min = element.duration_secs.map(|secs| secs as f64 / 60.0);
+        }
+        Ok(())
+    }
+
+    /// Fills in `location.elevation_m` from its coordinates.
+    pub async fn attach_elevation_async(
+        &self,
+        mut location: GeoLocation,
+    ) -> Result<GeoLocation, MapradarError> {
+        location.elevation_m = Some(self.elevation_async(location.coordinates()?).await?);
+        Ok(location)
+    }
+
+    /// Suggests up to `limit` addresses completing `partial`, best match first.
+    pub async fn autocomplete_async(
+        &self,
+        partial: &str,
+        limit: usize,
+    ) -> Result<Vec<Suggestion>, MapradarError> {
+        self.autocomplete_with_bias_async(partial, limit, &AutocompleteBias::default())
+            .await
+    }
+
+    /// Like [`autocomplete_async`](Self::autocomplete_async), restricted to `bias.country` and
+    /// preferring suggestions inside `bias.bounds`.
+    pub async fn autocomplete_with_bias_async(
+        &self,
+        partial: &str,
+        limit: usize,
+        bias: &AutocompleteBias,
+    ) -> Result<Vec<Suggestion>, MapradarError> {
+        let partial = partial.trim();
+        if partial.is_empty() {
+            return Err(MapradarError::InvalidInput(
+                "Autocomplete input must not be empty".to_string(),
+            ));
+        }
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        for country in &bias.countries {
+            country_code(country)?;
+        }
+
+        self.provider.autocomplete(partial, limit, bias).await
+    }
+
+    /// Looks up phone, website, opening hours, photos, and review count for a place ID taken
+    /// from [`NearbyService::place_id`].
+    pub async fn place_details_async(&self, place_id: &str) -> Result<PlaceDetails, MapradarError> {
+        let place_id = place_id.trim();
+        if place_id.is_empty() {
+            return Err(MapradarError::InvalidInput(
+                "Place ID must not be empty".to_string(),
+            ));
+        }
+
+        self.provider.place_details(place_id).await
+    }
+
+    /// Up to `limit` photos of a place, each with a `url` that needs no API key.
+    pub async fn photos_async(
+        &self,
+        place_id: &str,
+        limit: usize,
+    ) -> Result<Vec<PlacePhoto>, MapradarError> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let mut photos = self.place_details_async(place_id).await?.photos;
+        photos.truncate(limit);
+        self.resolve_photo_urls_async(&mut photos).await?;
+        Ok(photos)
+    }
+
+    /// Fills in `url` on each photo that lacks one.
+    pub async fn resolve_photo_urls_async(
+        &self,
+        photos: &mut [PlacePhoto],
+    ) -> Result<(), MapradarError> {
+        for photo in photos.iter_mut().filter(|photo| photo.url.is_none()) {
+            photo.url = Some(
+                self.provider
+                    .photo_url(&photo.reference, PHOTO_MAX_WIDTH)
+                    .await?,
+            );
+        }
+        Ok(())
+    }
+
+    /// Sets `photos` on every service with a place ID to up to `limit` of its photos.
+    pub async fn attach_photos_async(
+        &self,
+        services: &mut [NearbyService],
+        limit: usize,
+    ) -> Result<(), MapradarError> {
+        for service in services.iter_mut() {
+            if let Some(place_id) = &service.place_id {
+                service.photos = self.photos_async(place_id, limit).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Saves each photo with a `url` into `dir` as `<name>-<n>.jpg` and records its `path`.
+    pub async fn download_photos_async(
+        &self,
+        photos: &mut [PlacePhoto],
+        dir: &std::path::Path,
+        name: &str,
+    ) -> Result<(), MapradarError> {
+        let name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        tokio::fs::create_dir_all(dir).await?;
+        for (index, photo) in photos.iter_mut().enumerate() {
+            let Some(url) = &photo.url else {
+                continue;
+            };
+            let bytes = self.provider.download_photo(url).await?;
+            let path = dir.join(format!("{}-{}.jpg", name, index + 1));
+            tokio::fs::write(&path, bytes).await?;
+            photo.path = Some(path);
+        }
+        Ok(())
+    }
+
+    /// Renders a static map as PNG bytes.
+    pub async fn static_map_async(&self, map: &StaticMap) -> Result<Vec<u8>, MapradarError> {
+        self.provider.static_map(map).await
+    }
+
+    /// Resolves a query to `(lat, lng)`, geocoding addresses and passing coordinates through.
+    pub async fn resolve_coordinates_async(
+        &self,
+        query: &SearchQuery,
+    ) -> Result<(f64, f64), MapradarError> {
+        let planned = |result: Result<GeoLocation, MapradarError>| match result {
+            Ok(location) => Ok((location.latitude, location.longitude)),
+            Err(MapradarError::DryRun) => Ok((0.0, 0.0)),
+            Err(e) => Err(e),
+        };
+        match query {
+            SearchQuery::Address { address, region } => {
+                planned(self.geocode_in_async(address, region).await)
+            }
+            SearchQuery::Coordinates {
+                latitude,
+                longitude,
+            } => Ok((latitude.degrees(), longitude.degrees())),
+            SearchQuery::Ip { ip } => planned(self.provider.ip_location(*ip).await),
+        }
+    }
+
+    /// Computes a travel route between two addresses or coordinates.
+    pub async fn route_async(
+        &self,
+        origin: SearchQuery,
+        destination: SearchQuery,
+        mode: TravelMode,
+    ) -> Result<Route, MapradarError> {
+        let (origin, destination) = futures::try_join!(
+            self.resolve_coordinates_async(&origin),
+            self.resolve_coordinates_async(&destination)
+        )?;
+
+        self.provider.route(origin, destination, mode).await
+    }
+
+    /// Computes an NxM matrix between every origin and destination.
+    ///
+    /// With `mode` set, travel distance and duration come from the provider; with `None`,
+    /// straight-line (haversine) distances are computed locally without durations.
+    pub async fn distance_matrix_async(
+        &self,
+        origins: Vec<SearchQuery>,
+        destinations: Vec<SearchQuery>,
+        mode: Option<TravelMode>,
+    ) -> Result<DistanceMatrix, MapradarError> {
+        let origins = futures::future::try_join_all(
+            origins.iter().map(|q| self.resolve_coordinates_async(q)),
+        )
+        .await?;
+        let destinations = futures::future::try_join_all(
+            destinations
+                .iter()
+                .map(|q| self.resolve_coordinates_async(q)),
+        )
+        .await?;
+
+        let rows = match mode {
+            Some(mode) => {
+                self.provider
+                    .distance_matrix(&origins, &destinations, mode)
+                    .await?
+            }
+            None => origins
+                .iter()
+                .map(|&(o_lat, o_lng)| {
+                    destinations
+                        .iter()
+                        .map(|&(d_lat, d_lng)| {
+                            MatrixElement::ok(haversine_distance(o_lat, o_lng, d_lat, d_lng), None)
+                        })
+                        .collect()
+                })
+                .collect(),
+        };
+
+        Ok(DistanceMatrix {
+            mode,
+            origins,
+            destinations,
+            rows,
+        })
+    }
+
+    /// Approximates the area reachable within `minutes` by sampling travel times on rays.
+    ///
+    /// Candidate points are placed on evenly spaced bearings at increasing distances, timed with
+    /// the provider's distance matrix, and the reachable distance along each ray is interpolated.
+    pub async fn isochrone_async(
+        &self,
+        center: Coordinates,
+        minutes: f64,
+        mode: TravelMode,
+    ) -> Result<Isochrone, MapradarError> {
+        let (lat, lng) = center.as_tuple();
+        if minutes <= 0.0 {
+            return Err(MapradarError::InvalidInput(
+                "Isochrone time budget must be positive".to_string(),
+            ));
+        }
+
+        let max_speed_kmh = match mode {
+            TravelMode::Driving => 60.0,
+            TravelMode::Walking => 5.0,
+            TravelMode::Cycling => 18.0,
+        };
+        let max_km = max_speed_kmh * minutes / 60.0;
+        let budget_secs = minutes * 60.0;
+        let ring_km = |ring: usize| max_km * (ring + 1) as f64 / ISOCHRONE_RINGS as f64;
+        let bearing = |index: usize| 360.0 * index as f64 / ISOCHRONE_BEARINGS as f64;
+
+        let candidates: Vec<(f64, f64)> = (0..ISOCHRONE_BEARINGS)
+            .flat_map(|b| (0..ISOCHRONE_RINGS).map(move |r| (b, r)))
+            .map(|(b, r)| destination_point(lat, lng, bearing(b), ring_km(r)))
+            .collect();
+
+        let matrix = self
+            .provider
+            .distance_matrix(&[(lat, lng)], &candidates, mode)
+            .await?;
+        let durations = matrix.into_iter().next().unwrap_or_default();
+
+        let mut polygon: Vec<(f64, f64)> = (0..ISOCHRONE_BEARINGS)
+            .map(|b| {
+                let (mut reach_km, mut reach_secs) = (0.0, 0.0);
+                for r in 0..ISOCHRONE_RINGS {
+                    let duration = durations
+                        .get(b * ISOCHRONE_RINGS + r)
+                        .and_then(|element| element.duration_secs);
+                    match duration {
+                        Some(secs) if (secs as f64) <= budget_secs => {
+                            (reach_km, reach_secs) = (ring_km(r), secs as f64);
+                        }
+                        Some(secs) => {
+                            let fraction = (budget_secs - reach_secs) / (secs as f64 - reach_secs);
+                            reach_km += (ring_km(r) - reach_km) * fraction.clamp(0.0, 1.0);
+                            break;
+                        }
+                        None => break,
+                    }
+                }
+                destination_point(lat, lng, bearing(b), reach_km)
+            })
+            .collect();
+
+        if let Some(&first) = polygon.first() {
+            polygon.push(first);
+        }
+
+        Ok(Isochrone {
+            latitude: lat,
+            longitude: lng,
+            minutes,
+            mode,
+            polygon,
+        })
+    }
+
+    /// Checks whether the client can serve requests: the cache tiers always, and the provider
+    /// with one uncached reverse geocode of 0,0 when `probe_upstream` is set.
+    pub async fn readiness_async(&self, probe_upstream: bool) -> Readiness {
+        if !probe_upstream {
+            return self.readiness_with(ProviderHealth::not_probed(self.provider_name()));
+        }
+        let started = std::time::Instant::now();
+        let result = self.provider.reverse_geocode(0.0, 0.0).await;
+        let provider = ProviderHealth::from_probe(self.provider_name(), result, started.elapsed());
+        self.readiness_with(provider)
+    }
+
+    /// Readiness given an earlier provider probe, checking only the cache again.
+    pub(crate) fn readiness_with(&self, provider: ProviderHealth) -> Readiness {
+        Readiness::new(provider, self.cache.health())
+    }
+}
+
+/// Stand-in for a lookup that a dry run did not send, so requests depending on it can still be
+/// planned.
+fn planned_location(latitude: f64, longitude: f64) -> GeoLocation {
+    GeoLocation {
+        address: "(dry run)".to_string(),
+        latitude,
+        longitude,
+        city: None,
+        state: None,
+        country: String::new(),
+        address_components: None,
+        timezone: None,
+        elevation_m: None,
+        language: None,
+    }
 }
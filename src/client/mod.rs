@@ -1,27 +1,93 @@
+use std::sync::Arc;
+
 use crate::cache::GeoCache;
+use crate::metrics::Metrics;
+use crate::plan::{RequestPlan, RequestRecorder};
+use crate::progress::ProgressObserver;
+use crate::providers::{GeocodingProvider, GoogleProvider};
+use crate::transport::{CancellationToken, Transport};
 
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
 
-/// Client for interacting with Google Maps APIs with built-in caching.
+/// Result pages fetched per nearby search by default; Google serves at most three.
+pub const DEFAULT_MAX_PAGES: usize = 3;
+
+/// Service-type searches run at once by default.
+pub const DEFAULT_CONCURRENCY: usize = 5;
+
+/// Client for interacting with geocoding and places providers with built-in caching.
 #[cfg_attr(feature = "python", pyclass)]
 #[derive(Clone)]
 pub struct MapradarClient {
-    api_key: String,
-    http_client: reqwest::Client,
+    provider: Arc<dyn GeocodingProvider>,
     cache: GeoCache,
+    recorder: Option<RequestRecorder>,
+    metrics: Option<Metrics>,
+    progress: Option<Arc<dyn ProgressObserver>>,
+    cancellation: Option<CancellationToken>,
+    max_pages: usize,
+    concurrency: usize,
+    #[cfg(feature = "gtfs")]
+    transit_index: Option<crate::transit::GtfsIndex>,
 }
 
 impl MapradarClient {
     pub fn _new(api_key: String) -> Self {
+        Self::from_provider(Arc::new(GoogleProvider::new(api_key, Transport::default())))
+    }
+
+    pub(crate) fn from_provider(provider: Arc<dyn GeocodingProvider>) -> Self {
+        Self::from_parts(provider, GeoCache::new())
+    }
+
+    pub(crate) fn from_parts(provider: Arc<dyn GeocodingProvider>, cache: GeoCache) -> Self {
+        let cache = cache.with_provider(provider.name());
         Self {
-            api_key,
-            http_client: reqwest::Client::new(),
-            cache: GeoCache::new(),
+            provider,
+            cache,
+            recorder: None,
+            metrics: None,
+            progress: None,
+            cancellation: None,
+            max_pages: DEFAULT_MAX_PAGES,
+            concurrency: DEFAULT_CONCURRENCY,
+            #[cfg(feature = "gtfs")]
+            transit_index: None,
         }
     }
+
+    /// Returns a builder for configuring the provider and other client options.
+    pub fn builder() -> MapradarClientBuilder {
+        MapradarClientBuilder::new()
+    }
+
+    /// Requests recorded so far by a dry-run client, or `None` when requests are really sent.
+    pub fn request_plan(&self) -> Option<RequestPlan> {
+        self.recorder.as_ref().map(RequestRecorder::plan)
+    }
+
+    /// Metrics registry attached with [`MapradarClientBuilder::metrics`], if any.
+    pub fn metrics(&self) -> Option<&Metrics> {
+        self.metrics.as_ref()
+    }
+
+    /// Whether the token given to [`MapradarClientBuilder::cancellation`] has been cancelled.
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Name of the upstream provider serving this client.
+    pub fn provider_name(&self) -> &'static str {
+        self.provider.name()
+    }
 }
 
 #[cfg(feature = "python")]
 pub mod bindings;
+pub mod builder;
 pub mod core;
+
+pub use builder::MapradarClientBuilder;
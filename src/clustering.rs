@@ -0,0 +1,131 @@
+//! Density-based clustering (DBSCAN) of nearby results, for finding commercial hubs.
+//!
+//! A place with at least `min_points` results (itself included) within `eps_m` metres is a core
+//! of a cluster; clusters grow through neighbouring cores and take in the places next to them.
+//! Places near no core are left unclustered, so isolated results do not blur the hubs.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use rstar::{RTree, primitives::GeomWithData};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    geo::{centroid, haversine_distance},
+    models::NearbyService,
+    spatial::{chord_squared, to_unit_vector},
+};
+
+/// A group of nearby results lying close together.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceCluster {
+    /// 1 for the largest cluster, 2 for the next, and so on.
+    pub id: usize,
+    /// Centroid of the members.
+    pub latitude: f64,
+    pub longitude: f64,
+    pub count: usize,
+    /// Distance from the centroid to the farthest member.
+    pub radius_m: f64,
+    /// Members per service type.
+    pub by_type: BTreeMap<String, usize>,
+    pub services: Vec<NearbyService>,
+}
+
+#[cfg(feature = "python")]
+crate::python::data_model!(ServiceCluster);
+
+/// Clusters `services` with DBSCAN, largest cluster first. Unclustered services are left out.
+pub fn dbscan(services: &[NearbyService], eps_m: f64, min_points: usize) -> Vec<ServiceCluster> {
+    let tree = RTree::bulk_load(
+        services
+            .iter()
+            .enumerate()
+            .map(|(index, service)| {
+                GeomWithData::new(to_unit_vector(service.latitude, service.longitude), index)
+            })
+            .collect(),
+    );
+    let max_distance = chord_squared(eps_m / 1000.0);
+    let region = |index: usize| -> Vec<usize> {
+        let service = &services[index];
+        tree.locate_within_distance(
+            to_unit_vector(service.latitude, service.longitude),
+            max_distance,
+        )
+        .map(|entry| entry.data)
+        .collect()
+    };
+
+    let mut labels: Vec<Option<usize>> = vec![None; services.len()];
+    let mut visited = vec![false; services.len()];
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for start in 0..services.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let neighbours = region(start);
+        if neighbours.len() < min_points {
+            continue;
+        }
+
+        let label = groups.len();
+        let mut members = vec![start];
+        labels[start] = Some(label);
+        let mut queue = neighbours;
+        while let Some(index) = queue.pop() {
+            if !visited[index] {
+                visited[index] = true;
+                let neighbours = region(index);
+                if neighbours.len() >= min_points {
+                    queue.extend(neighbours);
+                }
+            }
+            if labels[index].is_none() {
+                labels[index] = Some(label);
+                members.push(index);
+            }
+        }
+        groups.push(members);
+    }
+
+    let mut clusters: Vec<ServiceCluster> = groups
+        .into_iter()
+        .map(|members| {
+            let members: Vec<NearbyService> =
+                members.into_iter().map(|i| services[i].clone()).collect();
+            summarize(members)
+        })
+        .collect();
+    clusters.sort_by_key(|cluster| std::cmp::Reverse(cluster.count));
+    for (index, cluster) in clusters.iter_mut().enumerate() {
+        cluster.id = index + 1;
+    }
+    clusters
+}
+
+/// Builds a cluster from its members, averaging them on the sphere for the centroid.
+fn summarize(services: Vec<NearbyService>) -> ServiceCluster {
+    let points: Vec<(f64, f64)> = services.iter().map(|s| (s.latitude, s.longitude)).collect();
+    let (latitude, longitude) = centroid(&points).unwrap_or(points[0]);
+
+    let radius_m = services
+        .iter()
+        .map(|s| haversine_distance(latitude, longitude, s.latitude, s.longitude) * 1000.0)
+        .fold(0.0, f64::max);
+    let mut by_type = BTreeMap::new();
+    for service in &services {
+        *by_type.entry(service.service_type.to_string()).or_insert(0) += 1;
+    }
+    ServiceCluster {
+        id: 0,
+        latitude,
+        longitude,
+        count: services.len(),
+        radius_m,
+        by_type,
+        services,
+    }
+}
@@ -0,0 +1,63 @@
+//! Parquet output for batch geocoding, behind the `parquet` feature.
+//!
+//! Columns match the CSV and TSV batch output, so files load straight into pandas, Polars,
+//! or DuckDB with proper types and nulls for failed rows.
+
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+
+use crate::{batch::BatchRecord, error::MapradarError, tabular::BatchRow};
+
+/// Encodes batch records as a Snappy-compressed Parquet file, one row per record.
+pub fn to_parquet(records: &[BatchRecord]) -> Result<Vec<u8>, MapradarError> {
+    let rows: Vec<BatchRow> = records.iter().map(BatchRow::from).collect();
+    let text = |field: for<'r> fn(&'r BatchRow<'r>) -> Option<&'r str>| -> ArrayRef {
+        Arc::new(rows.iter().map(field).collect::<StringArray>())
+    };
+    let number = |field: fn(&BatchRow) -> Option<f64>| -> ArrayRef {
+        Arc::new(rows.iter().map(field).collect::<Float64Array>())
+    };
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("row", DataType::UInt64, false),
+        Field::new("input", DataType::Utf8, false),
+        Field::new("address", DataType::Utf8, true),
+        Field::new("latitude", DataType::Float64, true),
+        Field::new("longitude", DataType::Float64, true),
+        Field::new("city", DataType::Utf8, true),
+        Field::new("state", DataType::Utf8, true),
+        Field::new("country", DataType::Utf8, true),
+        Field::new("error", DataType::Utf8, true),
+    ]));
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from_iter_values(
+            rows.iter().map(|row| row.row as u64),
+        )),
+        text(|row| Some(row.input)),
+        text(|row| row.address),
+        number(|row| row.latitude),
+        number(|row| row.longitude),
+        text(|row| row.city),
+        text(|row| row.state),
+        text(|row| row.country),
+        text(|row| row.error),
+    ];
+    let batch = RecordBatch::try_new(schema.clone(), columns).map_err(parquet_error)?;
+
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut buffer = Vec::new();
+    let mut writer =
+        ArrowWriter::try_new(&mut buffer, schema, Some(properties)).map_err(parquet_error)?;
+    writer.write(&batch).map_err(parquet_error)?;
+    writer.close().map_err(parquet_error)?;
+    Ok(buffer)
+}
+
+fn parquet_error(err: impl std::fmt::Display) -> MapradarError {
+    MapradarError::Unknown(format!("Failed to write Parquet: {}", err))
+}
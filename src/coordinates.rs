@@ -0,0 +1,278 @@
+//! Validated latitude, longitude, and coordinate pairs.
+//!
+//! A [`Latitude`] is always within ±90 degrees and a [`Longitude`] within ±180, so functions that
+//! take [`Coordinates`] never see out-of-range or non-finite values. Invalid input is rejected
+//! where it is parsed or deserialized, with [`MapradarError::InvalidCoordinates`] or
+//! [`MapradarError::InvalidInput`].
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+use crate::error::MapradarError;
+
+/// Degrees north (positive) or south (negative) of the equator, within ±90.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct Latitude(f64);
+
+/// Degrees east (positive) or west (negative) of the prime meridian, within ±180.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct Longitude(f64);
+
+impl Latitude {
+    pub fn new(degrees: f64) -> Result<Self, MapradarError> {
+        if (-90.0..=90.0).contains(&degrees) {
+            Ok(Self(degrees))
+        } else {
+            Err(MapradarError::InvalidInput(format!(
+                "Latitude {} is outside ±90",
+                degrees
+            )))
+        }
+    }
+
+    pub fn degrees(self) -> f64 {
+        self.0
+    }
+}
+
+impl Longitude {
+    pub fn new(degrees: f64) -> Result<Self, MapradarError> {
+        if (-180.0..=180.0).contains(&degrees) {
+            Ok(Self(degrees))
+        } else {
+            Err(MapradarError::InvalidInput(format!(
+                "Longitude {} is outside ±180",
+                degrees
+            )))
+        }
+    }
+
+    pub fn degrees(self) -> f64 {
+        self.0
+    }
+}
+
+macro_rules! degrees_conversions {
+    ($ty:ident) => {
+        impl TryFrom<f64> for $ty {
+            type Error = MapradarError;
+
+            fn try_from(degrees: f64) -> Result<Self, Self::Error> {
+                Self::new(degrees)
+            }
+        }
+
+        impl From<$ty> for f64 {
+            fn from(value: $ty) -> f64 {
+                value.0
+            }
+        }
+
+        impl FromStr for $ty {
+            type Err = MapradarError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let degrees = s.trim().parse().map_err(|_| {
+                    MapradarError::InvalidInput(format!(
+                        "Invalid {} '{}'",
+                        stringify!($ty).to_lowercase(),
+                        s
+                    ))
+                })?;
+                Self::new(degrees)
+            }
+        }
+
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        /// Converted to and from a Python `float`, raising on values out of range.
+        #[cfg(feature = "python")]
+        impl<'py> pyo3::IntoPyObject<'py> for $ty {
+            type Target = pyo3::types::PyFloat;
+            type Output = pyo3::Bound<'py, pyo3::types::PyFloat>;
+            type Error = std::convert::Infallible;
+
+            fn into_pyobject(self, py: pyo3::Python<'py>) -> Result<Self::Output, Self::Error> {
+                self.0.into_pyobject(py)
+            }
+        }
+
+        #[cfg(feature = "python")]
+        impl<'a, 'py> pyo3::FromPyObject<'a, 'py> for $ty {
+            type Error = pyo3::PyErr;
+
+            fn extract(obj: pyo3::Borrowed<'a, 'py, pyo3::PyAny>) -> pyo3::PyResult<Self> {
+                Ok(Self::new(obj.extract::<f64>()?)?)
+            }
+        }
+    };
+}
+
+degrees_conversions!(Latitude);
+degrees_conversions!(Longitude);
+
+/// A validated point, parsed from and displayed as "lat,lng".
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Coordinates {
+    #[serde(rename = "latitude")]
+    pub lat: Latitude,
+    #[serde(rename = "longitude")]
+    pub lon: Longitude,
+}
+
+impl Coordinates {
+    /// Validates a point, failing with [`MapradarError::InvalidCoordinates`] when either value
+    /// is out of range.
+    pub fn new(latitude: f64, longitude: f64) -> Result<Self, MapradarError> {
+        match (Latitude::new(latitude), Longitude::new(longitude)) {
+            (Ok(lat), Ok(lon)) => Ok(Self { lat, lon }),
+            _ => Err(MapradarError::InvalidCoordinates {
+                latitude,
+                longitude,
+            }),
+        }
+    }
+
+    pub fn latitude(self) -> f64 {
+        self.lat.0
+    }
+
+    pub fn longitude(self) -> f64 {
+        self.lon.0
+    }
+
+    /// The point as `(lat, lng)`.
+    pub fn as_tuple(self) -> (f64, f64) {
+        (self.lat.0, self.lon.0)
+    }
+}
+
+impl TryFrom<(f64, f64)> for Coordinates {
+    type Error = MapradarError;
+
+    fn try_from((latitude, longitude): (f64, f64)) -> Result<Self, Self::Error> {
+        Self::new(latitude, longitude)
+    }
+}
+
+impl From<Coordinates> for (f64, f64) {
+    fn from(coordinates: Coordinates) -> Self {
+        coordinates.as_tuple()
+    }
+}
+
+/// Parses "lat,lng", e.g. "6.5244, 3.3792" as copied from Google Maps, or degrees, minutes, and
+/// seconds with hemispheres, e.g. `6°31'28"N 3°22'45"E`. Text that is neither fails with
+/// [`MapradarError::InvalidInput`]; numbers out of range fail with
+/// [`MapradarError::InvalidCoordinates`].
+impl FromStr for Coordinates {
+    type Err = MapradarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            MapradarError::InvalidInput(format!(
+                "Invalid point '{}' (expected lat,lng or DMS such as 6°31'28\"N 3°22'45\"E)",
+                s
+            ))
+        };
+        if let Some((lat, lng)) = s.split_once(',')
+            && let (Ok(lat), Ok(lng)) = (lat.trim().parse(), lng.trim().parse())
+        {
+            return Self::new(lat, lng);
+        }
+        let (lat, lng) = parse_dms(s).ok_or_else(invalid)?;
+        Self::new(lat, lng)
+    }
+}
+
+/// Reads a point written with hemisphere letters, in either order and with the letters before
+/// or after each angle: `6°31'28"N 3°22'45"E`, `N 6°31.47' E 3°22.75'`, or `6.5244N, 3.3792E`.
+fn parse_dms(s: &str) -> Option<(f64, f64)> {
+    let s = s
+        .replace(['′', '’'], "'")
+        .replace(['″', '”'], "\"")
+        .replace("''", "\"")
+        .replace('º', "°");
+    let letters: Vec<(usize, char)> = s
+        .char_indices()
+        .filter(|(_, c)| c.is_alphabetic())
+        .map(|(i, c)| (i, c.to_ascii_uppercase()))
+        .collect();
+    let [(first_at, first), (second_at, second)] = letters[..] else {
+        return None;
+    };
+    if !"NSEW".contains(first) || !"NSEW".contains(second) {
+        return None;
+    }
+
+    // With leading letters each angle runs up to the next letter; otherwise each ends at one.
+    let (first_angle, second_angle) = if s[..first_at].trim().is_empty() {
+        (&s[first_at + 1..second_at], &s[second_at + 1..])
+    } else if s[second_at + 1..].trim().is_empty() {
+        (&s[..first_at], &s[first_at + 1..second_at])
+    } else {
+        return None;
+    };
+    let first = signed(first, dms_angle(first_angle)?)?;
+    let second = signed(second, dms_angle(second_angle)?)?;
+    match (first, second) {
+        (Hemisphere::Latitude(lat), Hemisphere::Longitude(lng))
+        | (Hemisphere::Longitude(lng), Hemisphere::Latitude(lat)) => Some((lat, lng)),
+        _ => None,
+    }
+}
+
+enum Hemisphere {
+    Latitude(f64),
+    Longitude(f64),
+}
+
+fn signed(letter: char, degrees: f64) -> Option<Hemisphere> {
+    match letter {
+        'N' => Some(Hemisphere::Latitude(degrees)),
+        'S' => Some(Hemisphere::Latitude(-degrees)),
+        'E' => Some(Hemisphere::Longitude(degrees)),
+        'W' => Some(Hemisphere::Longitude(-degrees)),
+        _ => None,
+    }
+}
+
+/// Degrees from "D°M'S\"", "D°M.m'", or "D.d°", the symbols optional between space-separated
+/// parts.
+fn dms_angle(angle: &str) -> Option<f64> {
+    let parts: Vec<f64> = angle
+        .trim_matches(|c: char| c == ',' || c.is_whitespace())
+        .split(|c: char| matches!(c, '°' | '\'' | '"') || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite() && *n >= 0.0)
+        })
+        .collect::<Option<_>>()?;
+    let (degrees, minutes, seconds) = match parts[..] {
+        [d] => (d, 0.0, 0.0),
+        [d, m] => (d, m, 0.0),
+        [d, m, s] => (d, m, s),
+        _ => return None,
+    };
+    if parts.len() > 1 && (degrees.fract() != 0.0 || minutes >= 60.0 || seconds >= 60.0) {
+        return None;
+    }
+    if parts.len() > 2 && minutes.fract() != 0.0 {
+        return None;
+    }
+    Some(degrees + minutes / 60.0 + seconds / 3600.0)
+}
+
+impl fmt::Display for Coordinates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.lat, self.lon)
+    }
+}
@@ -0,0 +1,53 @@
+//! API key storage in the operating system's credential store: the macOS Keychain, Windows
+//! Credential Manager, or the Secret Service (GNOME Keyring, KWallet) on Linux.
+//!
+//! A stored key never appears in environment variables, config files, or shell history. The CLI
+//! stores one with `mapradar auth login` and prefers it over `MAPRADAR_API_KEY` and `--api-key`.
+
+use keyring::Entry;
+
+use crate::error::MapradarError;
+
+/// Service name the key is stored under.
+pub const SERVICE: &str = "mapradar";
+
+/// Account name the key is stored under.
+pub const ACCOUNT: &str = "api_key";
+
+/// Saves `api_key`, replacing any stored key.
+pub fn store_api_key(api_key: &str) -> Result<(), MapradarError> {
+    let api_key = api_key.to_string();
+    with_entry(move |entry| entry.set_password(&api_key))
+}
+
+/// The stored key, or `None` when there is none.
+pub fn load_api_key() -> Result<Option<String>, MapradarError> {
+    with_entry(|entry| match entry.get_password() {
+        Ok(api_key) => Ok(Some(api_key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(err),
+    })
+}
+
+/// Removes the stored key. Returns false when there was none.
+pub fn delete_api_key() -> Result<bool, MapradarError> {
+    with_entry(|entry| match entry.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(err) => Err(err),
+    })
+}
+
+/// Runs `op` on its own thread: the Secret Service backend blocks on a runtime of its own, which
+/// cannot be started from inside the caller's.
+fn with_entry<T: Send>(
+    op: impl FnOnce(&Entry) -> Result<T, keyring::Error> + Send,
+) -> Result<T, MapradarError> {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| Entry::new(SERVICE, ACCOUNT).and_then(|entry| op(&entry)))
+            .join()
+            .map_err(|_| MapradarError::Config("OS keyring access panicked".to_string()))?
+            .map_err(|err| MapradarError::Config(format!("OS keyring: {}", err)))
+    })
+}
@@ -0,0 +1,150 @@
+//! DataFrame conversion for the Python bindings.
+//!
+//! Columns are built in Rust and handed to `polars.DataFrame` or `pandas.DataFrame`, whichever
+//! the caller asks for, so neither library is needed until a conversion is requested.
+
+use pyo3::{prelude::*, types::PyDict};
+
+use crate::{
+    error::MapradarError,
+    models::{GeoLocation, LocationIntelligence},
+    tabular::ServiceRow,
+};
+
+/// Builds a DataFrame from `columns` with the named backend, `polars` or `pandas`.
+fn to_frame<'py>(
+    py: Python<'py>,
+    columns: Bound<'py, PyDict>,
+    backend: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    match backend {
+        "polars" | "pandas" => py.import(backend)?.call_method1("DataFrame", (columns,)),
+        other => Err(MapradarError::InvalidInput(format!(
+            "Unknown DataFrame backend '{}': expected polars or pandas",
+            other
+        ))
+        .into()),
+    }
+}
+
+/// One row per nearby service, with the same columns as CSV output plus place and contact details.
+pub(crate) fn services_frame<'py>(
+    py: Python<'py>,
+    intel: &LocationIntelligence,
+    backend: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let services = &intel.nearby_services;
+    let rows: Vec<ServiceRow> = services.iter().map(ServiceRow::from).collect();
+    let columns = PyDict::new(py);
+    columns.set_item("name", rows.iter().map(|r| r.name).collect::<Vec<_>>())?;
+    columns.set_item(
+        "type",
+        rows.iter()
+            .map(|r| r.service_type.as_str())
+            .collect::<Vec<_>>(),
+    )?;
+    columns.set_item(
+        "latitude",
+        rows.iter().map(|r| r.latitude).collect::<Vec<_>>(),
+    )?;
+    columns.set_item(
+        "longitude",
+        rows.iter().map(|r| r.longitude).collect::<Vec<_>>(),
+    )?;
+    columns.set_item(
+        "distance_km",
+        rows.iter().map(|r| r.distance_km).collect::<Vec<_>>(),
+    )?;
+    columns.set_item("rating", rows.iter().map(|r| r.rating).collect::<Vec<_>>())?;
+    columns.set_item(
+        "address",
+        rows.iter().map(|r| r.address).collect::<Vec<_>>(),
+    )?;
+    columns.set_item(
+        "place_id",
+        services
+            .iter()
+            .map(|s| s.place_id.as_deref())
+            .collect::<Vec<_>>(),
+    )?;
+    columns.set_item(
+        "phone_number",
+        services
+            .iter()
+            .map(|s| s.phone_number.as_deref())
+            .collect::<Vec<_>>(),
+    )?;
+    columns.set_item(
+        "open_now",
+        services.iter().map(|s| s.open_now).collect::<Vec<_>>(),
+    )?;
+    to_frame(py, columns, backend)
+}
+
+/// One row per location.
+pub(crate) fn locations_frame<'py>(
+    py: Python<'py>,
+    locations: &[GeoLocation],
+    backend: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let columns = PyDict::new(py);
+    columns.set_item(
+        "address",
+        locations
+            .iter()
+            .map(|l| l.address.as_str())
+            .collect::<Vec<_>>(),
+    )?;
+    columns.set_item(
+        "latitude",
+        locations.iter().map(|l| l.latitude).collect::<Vec<_>>(),
+    )?;
+    columns.set_item(
+        "longitude",
+        locations.iter().map(|l| l.longitude).collect::<Vec<_>>(),
+    )?;
+    columns.set_item(
+        "city",
+        locations
+            .iter()
+            .map(|l| l.city.as_deref())
+            .collect::<Vec<_>>(),
+    )?;
+    columns.set_item(
+        "state",
+        locations
+            .iter()
+            .map(|l| l.state.as_deref())
+            .collect::<Vec<_>>(),
+    )?;
+    columns.set_item(
+        "country",
+        locations
+            .iter()
+            .map(|l| l.country.as_str())
+            .collect::<Vec<_>>(),
+    )?;
+    columns.set_item(
+        "timezone",
+        locations
+            .iter()
+            .map(|l| l.timezone.as_ref().map(|tz| tz.id.as_str()))
+            .collect::<Vec<_>>(),
+    )?;
+    columns.set_item(
+        "elevation_m",
+        locations.iter().map(|l| l.elevation_m).collect::<Vec<_>>(),
+    )?;
+    to_frame(py, columns, backend)
+}
+
+/// Converts a list of locations, such as geocoding results, to a DataFrame with one row each.
+#[pyfunction]
+#[pyo3(signature = (locations, backend="polars"))]
+pub fn locations_to_dataframe<'py>(
+    py: Python<'py>,
+    locations: Vec<GeoLocation>,
+    backend: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    locations_frame(py, &locations, backend)
+}
@@ -0,0 +1,275 @@
+//! Differences between two saved [`LocationIntelligence`] snapshots, as a report or a JSON Patch.
+//!
+//! Services are paired the way [`LocationIntelligence::deduplicated`] pairs duplicates: by place
+//! ID, or by name within 50 m when either lacks one. The report compares paired services field
+//! by field, skipping `distance_km`, which follows from the searched point, and `departures`,
+//! which follow from the time of the search. The patch ([RFC 6902]) covers every field, so
+//! applying it to the older snapshot gives the newer one.
+//!
+//! [RFC 6902]: https://www.rfc-editor.org/rfc/rfc6902
+
+use std::path::Path;
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::{
+    error::MapradarError,
+    geo::haversine_distance,
+    models::{LocationIntelligence, NearbyService},
+};
+
+/// Service fields left out of the report.
+const UNREPORTED_FIELDS: &[&str] = &["distance_km", "departures"];
+
+/// A service field whose value differs between snapshots.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    /// `None` when the field is absent from the older snapshot.
+    pub before: Option<Value>,
+    /// `None` when the field is absent from the newer snapshot.
+    pub after: Option<Value>,
+}
+
+/// A service found in both snapshots with different details.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedService {
+    /// The service as in the newer snapshot.
+    pub service: NearbyService,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Services added, removed, and changed from one snapshot to the next.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    /// Distance between the two snapshots' searched locations.
+    pub location_moved_km: f64,
+    pub added: Vec<NearbyService>,
+    pub removed: Vec<NearbyService>,
+    pub changed: Vec<ChangedService>,
+    pub unchanged: usize,
+}
+
+impl SnapshotDiff {
+    /// Whether no service was added, removed, or changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Reads a snapshot saved from `nearby` JSON output.
+pub fn read_snapshot(path: &Path) -> Result<LocationIntelligence, MapradarError> {
+    let file = std::fs::File::open(path)?;
+    serde_json::from_reader(std::io::BufReader::new(file)).map_err(|err| {
+        MapradarError::InvalidInput(format!(
+            "{} is not a saved nearby result: {}",
+            path.display(),
+            err
+        ))
+    })
+}
+
+/// Lists the services added, removed, and changed between `before` and `after`, in the order
+/// each snapshot lists them.
+pub fn compare(before: &LocationIntelligence, after: &LocationIntelligence) -> SnapshotDiff {
+    let pairs = pair_services(&before.nearby_services, &after.nearby_services);
+    let mut diff = SnapshotDiff {
+        location_moved_km: haversine_distance(
+            before.location.latitude,
+            before.location.longitude,
+            after.location.latitude,
+            after.location.longitude,
+        ),
+        added: Vec::new(),
+        removed: before
+            .nearby_services
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !pairs.contains(&Some(*index)))
+            .map(|(_, service)| service.clone())
+            .collect(),
+        changed: Vec::new(),
+        unchanged: 0,
+    };
+    for (service, pair) in after.nearby_services.iter().zip(&pairs) {
+        let Some(index) = *pair else {
+            diff.added.push(service.clone());
+            continue;
+        };
+        let changes = field_changes(
+            &to_value(&before.nearby_services[index]),
+            &to_value(service),
+        )
+        .into_iter()
+        .filter(|change| {
+            // A field left out when empty is no different from one written as `null`.
+            !UNREPORTED_FIELDS.contains(&change.field.as_str())
+                && change.before.as_ref().unwrap_or(&Value::Null)
+                    != change.after.as_ref().unwrap_or(&Value::Null)
+        })
+        .collect::<Vec<_>>();
+        if changes.is_empty() {
+            diff.unchanged += 1;
+        } else {
+            diff.changed.push(ChangedService {
+                service: service.clone(),
+                changes,
+            });
+        }
+    }
+    diff
+}
+
+/// JSON Patch operations turning `before` into `after`: removals, then moves and additions that
+/// put the services in `after`'s order, then changes to paired services and to the other fields.
+pub fn json_patch(before: &LocationIntelligence, after: &LocationIntelligence) -> Vec<Value> {
+    let pairs = pair_services(&before.nearby_services, &after.nearby_services);
+    let mut patch = Vec::new();
+
+    // Positions in `before` of the services currently in the patched list.
+    let mut current: Vec<Option<usize>> = (0..before.nearby_services.len()).map(Some).collect();
+    for index in (0..before.nearby_services.len()).rev() {
+        if !pairs.contains(&Some(index)) {
+            patch.push(json!({"op": "remove", "path": service_path(index)}));
+            current.remove(index);
+        }
+    }
+    for (target, (service, pair)) in after.nearby_services.iter().zip(&pairs).enumerate() {
+        match pair {
+            Some(index) => {
+                let position = current
+                    .iter()
+                    .position(|entry| entry == &Some(*index))
+                    .unwrap_or(target);
+                if position != target {
+                    patch.push(json!({
+                        "op": "move",
+                        "from": service_path(position),
+                        "path": service_path(target),
+                    }));
+                    let entry = current.remove(position);
+                    current.insert(target, entry);
+                }
+            }
+            None => {
+                patch.push(json!({
+                    "op": "add",
+                    "path": service_path(target),
+                    "value": to_value(service),
+                }));
+                current.insert(target, None);
+            }
+        }
+    }
+    for (target, (service, pair)) in after.nearby_services.iter().zip(&pairs).enumerate() {
+        if let Some(index) = pair {
+            let path = service_path(target);
+            let changes = field_changes(
+                &to_value(&before.nearby_services[*index]),
+                &to_value(service),
+            );
+            patch.extend(changes.into_iter().map(|change| operation(&path, change)));
+        }
+    }
+
+    let (mut before, mut after) = (to_value(before), to_value(after));
+    for document in [&mut before, &mut after] {
+        if let Value::Object(fields) = document {
+            fields.remove("nearby_services");
+        }
+    }
+    patch.extend(
+        field_changes(&before, &after)
+            .into_iter()
+            .map(|change| operation("", change)),
+    );
+    patch
+}
+
+/// For each service in `after`, the index of the same place in `before`, each paired once.
+fn pair_services(before: &[NearbyService], after: &[NearbyService]) -> Vec<Option<usize>> {
+    let mut taken = vec![false; before.len()];
+    after
+        .iter()
+        .map(|service| {
+            let index = before
+                .iter()
+                .enumerate()
+                .position(|(index, old)| !taken[index] && old.is_same_place(service))?;
+            taken[index] = true;
+            Some(index)
+        })
+        .collect()
+}
+
+/// Top-level fields of two JSON objects whose values differ, in `after`'s order followed by
+/// those only in `before`.
+fn field_changes(before: &Value, after: &Value) -> Vec<FieldChange> {
+    let empty = Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+    let mut changes: Vec<FieldChange> = after
+        .iter()
+        .filter(|(field, value)| before.get(*field) != Some(value))
+        .map(|(field, value)| FieldChange {
+            field: field.clone(),
+            before: before.get(field).cloned(),
+            after: Some(value.clone()),
+        })
+        .collect();
+    changes.extend(
+        before
+            .iter()
+            .filter(|(field, _)| !after.contains_key(*field))
+            .map(|(field, value)| FieldChange {
+                field: field.clone(),
+                before: Some(value.clone()),
+                after: None,
+            }),
+    );
+    changes
+}
+
+/// The patch operation applying a field change to the object at `path`.
+fn operation(path: &str, change: FieldChange) -> Value {
+    let path = format!(
+        "{}/{}",
+        path,
+        change.field.replace('~', "~0").replace('/', "~1")
+    );
+    match (change.before, change.after) {
+        (_, None) => json!({"op": "remove", "path": path}),
+        (None, Some(value)) => json!({"op": "add", "path": path, "value": value}),
+        (Some(_), Some(value)) => json!({"op": "replace", "path": path, "value": value}),
+    }
+}
+
+fn service_path(index: usize) -> String {
+    format!("/nearby_services/{}", index)
+}
+
+fn to_value(value: &impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// Compares two nearby results, returning the added, removed, and changed services as a dict,
+/// or, with `patch=True`, the list of JSON Patch operations turning `before` into `after`.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (before, after, patch=false))]
+pub fn diff_snapshots<'py>(
+    py: Python<'py>,
+    before: LocationIntelligence,
+    after: LocationIntelligence,
+    patch: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let value = if patch {
+        Value::Array(json_patch(&before, &after))
+    } else {
+        to_value(&compare(&before, &after))
+    };
+    crate::python::json_to_py(py, &value)
+}
@@ -1,58 +1,269 @@
 #[cfg(feature = "python")]
 use pyo3::prelude::PyErr;
+use serde_json::json;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
-pub enum GeoError {
+pub enum MapradarError {
     /// Errors related to network requests (e.g., timeout, no internet).
-    #[error("API request failed: {0}")]
-    RequestError(#[from] reqwest::Error),
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
 
-    /// Errors related to JSON parsing (e.g., Google changed their response format).
-    #[error("JSON parsing failed: {0}")]
-    ParseError(#[from] serde_json::Error),
+    /// Errors related to JSON parsing (e.g., the provider changed its response format).
+    #[error("Failed to deserialize response: {0}")]
+    Deserialization(#[from] serde_json::Error),
+
+    /// Errors reading or writing local files (e.g., batch input).
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Errors from the on-disk response cache.
+    #[error("Cache error: {0}")]
+    Cache(#[from] rusqlite::Error),
 
     /// Configuration errors (e.g., missing API key).
     #[error("Configuration error: {0}")]
-    ConfigError(String),
+    Config(String),
+
+    /// The provider rejected the credentials (invalid, missing, or restricted API key).
+    #[error("Authentication with {provider} failed: {message}")]
+    AuthFailed { provider: String, message: String },
+
+    /// The provider's request quota or rate limit was exhausted.
+    #[error("Quota exceeded for {provider}: {message}")]
+    QuotaExceeded { provider: String, message: String },
+
+    /// Latitude or longitude outside the valid range (±90, ±180).
+    #[error(
+        "Invalid coordinates ({latitude}, {longitude}): latitude must be within ±90 and longitude within ±180"
+    )]
+    InvalidCoordinates { latitude: f64, longitude: f64 },
+
+    /// The address or location could not be resolved.
+    #[error("No location found for '{query}'")]
+    AddressNotFound { query: String },
+
+    /// Invalid or incomplete arguments supplied by the caller.
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    /// Errors returned by the provider API itself (e.g., INVALID_REQUEST).
+    #[error("API error: {status} - {message}")]
+    Api { status: String, message: String },
 
-    /// Errors returned by the Google Maps API itself (e.g., INVALID_REQUEST).
-    #[error("Google API error: {status} - {message}")]
-    ApiError { status: String, message: String },
+    /// The selected provider does not offer the requested capability.
+    #[error("Not supported: {0}")]
+    Unsupported(String),
 
     /// Case where no results were found for the query.
     #[error("No results found for the given query")]
     ZeroResults,
 
+    /// The request was recorded by a dry run instead of being sent.
+    #[error("Dry run: request recorded but not sent")]
+    DryRun,
+
+    /// The client's cancellation token was cancelled (e.g., on Ctrl-C) before the request
+    /// finished.
+    #[error("Interrupted before the request finished")]
+    Cancelled,
+
     /// Catch-all for unexpected errors.
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
 
-impl GeoError {
+/// Former name of [`MapradarError`].
+#[deprecated(note = "renamed to MapradarError")]
+pub type GeoError = MapradarError;
+
+impl MapradarError {
     pub fn json_rpc_code(&self) -> i32 {
         match self {
-            GeoError::RequestError(_) => -32001, // Custom Server Error
-            GeoError::ParseError(_) => -32700,   // Parse error
-            GeoError::ConfigError(_) => -32002,  // Custom Server Error
-            GeoError::ApiError { .. } => -32003, // Custom Server Error
-            GeoError::ZeroResults => -32602,     // Invalid params (effectively)
-            GeoError::Unknown(_) => -32603,      // Internal error
+            MapradarError::Network(_) => -32001, // Custom Server Error
+            MapradarError::Deserialization(_) => -32700, // Parse error
+            MapradarError::Config(_) => -32002,  // Custom Server Error
+            MapradarError::Api { .. } => -32003, // Custom Server Error
+            MapradarError::Io(_) => -32004,      // Custom Server Error
+            MapradarError::Cache(_) => -32005,   // Custom Server Error
+            MapradarError::AuthFailed { .. } => -32006, // Custom Server Error
+            MapradarError::QuotaExceeded { .. } => -32007, // Custom Server Error
+            MapradarError::InvalidCoordinates { .. } => -32602, // Invalid params
+            MapradarError::InvalidInput(_) => -32602, // Invalid params
+            MapradarError::AddressNotFound { .. } => -32602, // Invalid params (effectively)
+            MapradarError::ZeroResults => -32602, // Invalid params (effectively)
+            MapradarError::Unsupported(_) => -32601, // Method not found
+            MapradarError::DryRun => -32008,     // Custom Server Error
+            MapradarError::Cancelled => -32009,  // Custom Server Error
+            MapradarError::Unknown(_) => -32603, // Internal error
+        }
+    }
+
+    /// Name of the variant, e.g. `QuotaExceeded`, for labelling metrics.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            MapradarError::Network(_) => "Network",
+            MapradarError::Deserialization(_) => "Deserialization",
+            MapradarError::Io(_) => "Io",
+            MapradarError::Cache(_) => "Cache",
+            MapradarError::Config(_) => "Config",
+            MapradarError::AuthFailed { .. } => "AuthFailed",
+            MapradarError::QuotaExceeded { .. } => "QuotaExceeded",
+            MapradarError::InvalidCoordinates { .. } => "InvalidCoordinates",
+            MapradarError::AddressNotFound { .. } => "AddressNotFound",
+            MapradarError::InvalidInput(_) => "InvalidInput",
+            MapradarError::Api { .. } => "Api",
+            MapradarError::Unsupported(_) => "Unsupported",
+            MapradarError::ZeroResults => "ZeroResults",
+            MapradarError::DryRun => "DryRun",
+            MapradarError::Cancelled => "Cancelled",
+            MapradarError::Unknown(_) => "Unknown",
+        }
+    }
+
+    /// Structured form of the error, as printed by the CLI with `--error-format json`: the
+    /// [variant name](Self::variant_name) as `kind`, the message, the exit code, and the
+    /// variant's fields as `details` (`null` for variants without any).
+    pub fn to_json(&self) -> serde_json::Value {
+        let details = match self {
+            MapradarError::AuthFailed { provider, message }
+            | MapradarError::QuotaExceeded { provider, message } => {
+                json!({"provider": provider, "message": message})
+            }
+            MapradarError::InvalidCoordinates {
+                latitude,
+                longitude,
+            } => json!({"latitude": latitude, "longitude": longitude}),
+            MapradarError::AddressNotFound { query } => json!({"query": query}),
+            MapradarError::Api { status, message } => json!({"status": status, "message": message}),
+            MapradarError::Network(err) => json!({
+                "url": err.url().map(|url| crate::transport::redact_url(url).to_string()),
+                "status": err.status().map(|status| status.as_u16()),
+                "timeout": err.is_timeout(),
+            }),
+            _ => serde_json::Value::Null,
+        };
+        json!({
+            "error": {
+                "kind": self.variant_name(),
+                "message": self.to_string(),
+                "exit_code": self.exit_code(),
+                "details": details,
+            }
+        })
+    }
+
+    /// Process exit code used by the CLI for this class of failure.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            MapradarError::AuthFailed { .. } => 2,
+            MapradarError::AddressNotFound { .. } | MapradarError::ZeroResults => 3,
+            MapradarError::QuotaExceeded { .. } => 4,
+            MapradarError::Network(_) => 5,
+            MapradarError::InvalidCoordinates { .. }
+            | MapradarError::InvalidInput(_)
+            | MapradarError::Config(_) => 64,
+            // 128 + SIGINT, as shells report a process stopped by Ctrl-C.
+            MapradarError::Cancelled => 130,
+            _ => 1,
         }
     }
 }
 
+#[cfg(feature = "python")]
+pub mod exceptions {
+    use pyo3::create_exception;
+    use pyo3::exceptions::PyException;
+
+    create_exception!(
+        mapradar,
+        MapradarError,
+        PyException,
+        "Base class for Mapradar errors."
+    );
+    create_exception!(
+        mapradar,
+        AuthFailedError,
+        MapradarError,
+        "The provider rejected the API key."
+    );
+    create_exception!(
+        mapradar,
+        QuotaExceededError,
+        MapradarError,
+        "The provider quota or rate limit was exhausted."
+    );
+    create_exception!(
+        mapradar,
+        InvalidCoordinatesError,
+        MapradarError,
+        "Latitude or longitude is out of range."
+    );
+    create_exception!(
+        mapradar,
+        AddressNotFoundError,
+        MapradarError,
+        "No location matched the query."
+    );
+    create_exception!(
+        mapradar,
+        InvalidInputError,
+        MapradarError,
+        "Invalid or incomplete arguments."
+    );
+    create_exception!(
+        mapradar,
+        NetworkError,
+        MapradarError,
+        "The request could not reach the provider."
+    );
+    create_exception!(
+        mapradar,
+        DeserializationError,
+        MapradarError,
+        "The provider response could not be parsed."
+    );
+    create_exception!(
+        mapradar,
+        ProviderError,
+        MapradarError,
+        "The provider returned an error status."
+    );
+    create_exception!(
+        mapradar,
+        UnsupportedError,
+        MapradarError,
+        "The provider does not support this operation."
+    );
+}
+
 /// Convention to translate Rust errors into Python-native exceptions.
 #[cfg(feature = "python")]
-impl From<GeoError> for PyErr {
-    fn from(err: GeoError) -> PyErr {
+impl From<MapradarError> for PyErr {
+    fn from(err: MapradarError) -> PyErr {
+        use exceptions::{
+            AddressNotFoundError, AuthFailedError, DeserializationError, InvalidCoordinatesError,
+            InvalidInputError, MapradarError as PyMapradarError, NetworkError, ProviderError,
+            QuotaExceededError, UnsupportedError,
+        };
+
+        let message = err.to_string();
         match err {
-            GeoError::ConfigError(msg) => pyo3::exceptions::PyValueError::new_err(msg),
-            GeoError::ZeroResults => pyo3::exceptions::PyValueError::new_err("No results found"),
-            GeoError::ApiError { status, message } => {
-                pyo3::exceptions::PyRuntimeError::new_err(format!("{}: {}", status, message))
+            MapradarError::Config(msg) => pyo3::exceptions::PyValueError::new_err(msg),
+            MapradarError::AuthFailed { .. } => AuthFailedError::new_err(message),
+            MapradarError::QuotaExceeded { .. } => QuotaExceededError::new_err(message),
+            MapradarError::InvalidCoordinates { .. } => InvalidCoordinatesError::new_err(message),
+            MapradarError::AddressNotFound { .. } | MapradarError::ZeroResults => {
+                AddressNotFoundError::new_err(message)
+            }
+            MapradarError::InvalidInput(_) => InvalidInputError::new_err(message),
+            MapradarError::Network(_) => NetworkError::new_err(message),
+            MapradarError::Deserialization(_) => DeserializationError::new_err(message),
+            MapradarError::Api { status, message } => {
+                ProviderError::new_err(format!("{}: {}", status, message))
             }
-            _ => pyo3::exceptions::PyRuntimeError::new_err(err.to_string()),
+            MapradarError::Unsupported(_) => UnsupportedError::new_err(message),
+            _ => PyMapradarError::new_err(message),
         }
     }
 }
@@ -0,0 +1,160 @@
+//! SQLite export of nearby-search results, for querying accumulated runs with SQL.
+//!
+//! The schema is created on first use and later exports append to it. Each export adds one
+//! row to `queries` (what was searched, when, and how), one to `locations` (where the query
+//! resolved to), and one row per result to `services`, all joined on `query_id`.
+
+use rusqlite::{Connection, params};
+use std::path::Path;
+
+use crate::{
+    error::MapradarError,
+    models::{LocationIntelligence, PlaceCategory, SearchQuery},
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS queries (
+    id INTEGER PRIMARY KEY,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    query TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    service_types TEXT NOT NULL,
+    radius_m REAL NOT NULL,
+    total_services INTEGER NOT NULL,
+    score REAL,
+    warnings TEXT
+);
+CREATE TABLE IF NOT EXISTS locations (
+    query_id INTEGER PRIMARY KEY REFERENCES queries(id),
+    address TEXT NOT NULL,
+    latitude REAL NOT NULL,
+    longitude REAL NOT NULL,
+    city TEXT,
+    state TEXT,
+    country TEXT NOT NULL,
+    timezone TEXT,
+    elevation_m REAL
+);
+CREATE TABLE IF NOT EXISTS services (
+    id INTEGER PRIMARY KEY,
+    query_id INTEGER NOT NULL REFERENCES queries(id),
+    name TEXT NOT NULL,
+    service_type TEXT NOT NULL,
+    latitude REAL NOT NULL,
+    longitude REAL NOT NULL,
+    distance_km REAL NOT NULL,
+    address TEXT,
+    rating REAL,
+    place_id TEXT,
+    phone_number TEXT,
+    open_now INTEGER
+);
+CREATE INDEX IF NOT EXISTS services_query_id ON services(query_id);
+CREATE INDEX IF NOT EXISTS services_place_id ON services(place_id);
+";
+
+/// How a set of results was produced, stored alongside them in `queries`.
+#[derive(Debug, Clone)]
+pub struct ExportMetadata {
+    pub query: SearchQuery,
+    pub service_types: Vec<PlaceCategory>,
+    /// Search radius in meters.
+    pub radius_m: f64,
+    /// Provider name, e.g. `google`.
+    pub provider: String,
+}
+
+/// Database that nearby-search results are appended to.
+pub struct SqliteExport {
+    conn: Connection,
+}
+
+impl SqliteExport {
+    /// Opens (or creates) the database at `path`, creating any missing tables.
+    pub fn open(path: &Path) -> Result<Self, MapradarError> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Appends one search and its results in a single transaction, returning the new query ID.
+    pub fn write(
+        &mut self,
+        intel: &LocationIntelligence,
+        metadata: &ExportMetadata,
+    ) -> Result<i64, MapradarError> {
+        let service_types: Vec<String> = metadata
+            .service_types
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let warnings = (!intel.warnings.is_empty()).then(|| intel.warnings.join("\n"));
+
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO queries (query, provider, service_types, radius_m, total_services, score, warnings)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                serde_json::to_string(&metadata.query)?,
+                metadata.provider,
+                service_types.join(","),
+                metadata.radius_m,
+                intel.nearby_services.len() as i64,
+                intel.score.as_ref().map(|score| score.score),
+                warnings,
+            ],
+        )?;
+        let query_id = tx.last_insert_rowid();
+
+        let location = &intel.location;
+        tx.execute(
+            "INSERT INTO locations (query_id, address, latitude, longitude, city, state, country, timezone, elevation_m)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                query_id,
+                location.address,
+                location.latitude,
+                location.longitude,
+                location.city,
+                location.state,
+                location.country,
+                location.timezone.as_ref().map(|tz| tz.id.as_str()),
+                location.elevation_m,
+            ],
+        )?;
+
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO services (query_id, name, service_type, latitude, longitude, distance_km, address, rating, place_id, phone_number, open_now)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )?;
+            for service in &intel.nearby_services {
+                insert.execute(params![
+                    query_id,
+                    service.name,
+                    service.service_type.to_string(),
+                    service.latitude,
+                    service.longitude,
+                    service.distance_km,
+                    service.address,
+                    service.rating,
+                    service.place_id,
+                    service.phone_number,
+                    service.open_now,
+                ])?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(query_id)
+    }
+}
+
+/// Whether `path` names a SQLite database (`.sqlite`, `.sqlite3`, or `.db`).
+pub fn is_sqlite_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        ["sqlite", "sqlite3", "db"]
+            .iter()
+            .any(|known| ext.eq_ignore_ascii_case(known))
+    })
+}
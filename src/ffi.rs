@@ -0,0 +1,276 @@
+//! C ABI for embedding mapradar in C, C++, Go, and other languages (`ffi` feature).
+//!
+//! A client is an opaque handle created by `mapradar_client_new` and released with
+//! `mapradar_client_free`. Every call returns a [`MapradarStatus`]; on success the result is
+//! written to `*out_json` as a JSON string owned by the caller, who releases it with
+//! `mapradar_string_free`. On failure `mapradar_last_error` describes what went wrong.
+//!
+//! The header is generated into `include/mapradar.h` when building with the feature.
+
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString, c_char},
+    ptr,
+};
+
+use serde::Serialize;
+
+use crate::{
+    batch::parse_query_line,
+    blocking::MapradarClient,
+    client,
+    coordinates::Coordinates,
+    error::MapradarError,
+    models::PlaceCategory,
+};
+
+/// Result of an FFI call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapradarStatus {
+    Ok = 0,
+    /// A pointer was null, a string was not UTF-8, or an argument was out of range.
+    InvalidArgument = 1,
+    AuthFailed = 2,
+    /// The address or location could not be resolved, or the search found nothing.
+    NotFound = 3,
+    QuotaExceeded = 4,
+    Network = 5,
+    /// The provider does not offer the requested capability.
+    Unsupported = 6,
+    /// The provider returned an error, or something unexpected failed.
+    Internal = 7,
+}
+
+impl From<&MapradarError> for MapradarStatus {
+    fn from(err: &MapradarError) -> Self {
+        match err {
+            MapradarError::InvalidInput(_)
+            | MapradarError::InvalidCoordinates { .. }
+            | MapradarError::Config(_) => Self::InvalidArgument,
+            MapradarError::AuthFailed { .. } => Self::AuthFailed,
+            MapradarError::AddressNotFound { .. } | MapradarError::ZeroResults => Self::NotFound,
+            MapradarError::QuotaExceeded { .. } => Self::QuotaExceeded,
+            MapradarError::Network(_) => Self::Network,
+            MapradarError::Unsupported(_) => Self::Unsupported,
+            _ => Self::Internal,
+        }
+    }
+}
+
+/// Opaque client handle.
+pub struct MapradarHandle {
+    client: MapradarClient,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn fail(err: MapradarError) -> MapradarStatus {
+    let status = MapradarStatus::from(&err);
+    let message = CString::new(err.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+    status
+}
+
+fn invalid(message: &str) -> MapradarError {
+    MapradarError::InvalidInput(message.to_string())
+}
+
+/// Reads a required C string argument.
+///
+/// # Safety
+///
+/// `value` must be null or point to a NUL-terminated string.
+unsafe fn read_str<'a>(value: *const c_char, name: &str) -> Result<&'a str, MapradarError> {
+    if value.is_null() {
+        return Err(invalid(&format!("{} must not be null", name)));
+    }
+    unsafe { CStr::from_ptr(value) }
+        .to_str()
+        .map_err(|_| invalid(&format!("{} must be UTF-8", name)))
+}
+
+/// Reads an optional C string argument; null means absent.
+///
+/// # Safety
+///
+/// `value` must be null or point to a NUL-terminated string.
+unsafe fn read_optional_str<'a>(
+    value: *const c_char,
+    name: &str,
+) -> Result<Option<&'a str>, MapradarError> {
+    if value.is_null() {
+        Ok(None)
+    } else {
+        unsafe { read_str(value, name) }.map(Some)
+    }
+}
+
+/// Runs `call` against the handle and writes its JSON result to `out_json`.
+///
+/// # Safety
+///
+/// `handle` must be null or come from `mapradar_client_new`, and `out_json` must be null or
+/// valid for writes.
+unsafe fn respond<T: Serialize>(
+    handle: *const MapradarHandle,
+    out_json: *mut *mut c_char,
+    call: impl FnOnce(&MapradarClient) -> Result<T, MapradarError>,
+) -> MapradarStatus {
+    if handle.is_null() || out_json.is_null() {
+        return fail(invalid("client and out_json must not be null"));
+    }
+    let handle = unsafe { &*handle };
+    let json = call(&handle.client).and_then(|result| Ok(serde_json::to_string(&result)?));
+    match json.and_then(|json| CString::new(json).map_err(|_| invalid("result contains NUL"))) {
+        Ok(json) => {
+            unsafe { *out_json = json.into_raw() };
+            MapradarStatus::Ok
+        }
+        Err(err) => fail(err),
+    }
+}
+
+/// Creates a client and writes its handle to `out_client`.
+///
+/// `api_key` may be null to read `MAPRADAR_API_KEY` from the environment. `provider` may be
+/// null for Google, or name a provider: `google`, `nominatim`, or `offline`.
+///
+/// # Safety
+///
+/// `api_key` and `provider` must be null or NUL-terminated strings, and `out_client` must be
+/// valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mapradar_client_new(
+    api_key: *const c_char,
+    provider: *const c_char,
+    out_client: *mut *mut MapradarHandle,
+) -> MapradarStatus {
+    if out_client.is_null() {
+        return fail(invalid("out_client must not be null"));
+    }
+    let built = (|| {
+        let mut builder = client::MapradarClient::builder();
+        if let Some(provider) = unsafe { read_optional_str(provider, "provider") }? {
+            builder = builder.provider(provider.parse()?);
+        }
+        let api_key = match unsafe { read_optional_str(api_key, "api_key") }? {
+            Some(api_key) => Some(api_key.to_string()),
+            None => std::env::var("MAPRADAR_API_KEY").ok(),
+        };
+        if let Some(api_key) = api_key {
+            builder = builder.api_key(api_key);
+        }
+        MapradarClient::from_async(builder.build()?)
+    })();
+    match built {
+        Ok(client) => {
+            let handle = Box::new(MapradarHandle { client });
+            unsafe { *out_client = Box::into_raw(handle) };
+            MapradarStatus::Ok
+        }
+        Err(err) => fail(err),
+    }
+}
+
+/// Releases a client. Null is ignored.
+///
+/// # Safety
+///
+/// `client` must be null or come from `mapradar_client_new`, and must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mapradar_client_free(client: *mut MapradarHandle) {
+    if !client.is_null() {
+        drop(unsafe { Box::from_raw(client) });
+    }
+}
+
+/// Geocodes an address, writing the location as JSON.
+///
+/// # Safety
+///
+/// `client` must come from `mapradar_client_new`, `address` must be a NUL-terminated string,
+/// and `out_json` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mapradar_geocode(
+    client: *const MapradarHandle,
+    address: *const c_char,
+    out_json: *mut *mut c_char,
+) -> MapradarStatus {
+    unsafe {
+        respond(client, out_json, |client| {
+            client.geocode(read_str(address, "address")?)
+        })
+    }
+}
+
+/// Reverse geocodes a point, writing the location as JSON.
+///
+/// # Safety
+///
+/// `client` must come from `mapradar_client_new`, and `out_json` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mapradar_reverse_geocode(
+    client: *const MapradarHandle,
+    latitude: f64,
+    longitude: f64,
+    out_json: *mut *mut c_char,
+) -> MapradarStatus {
+    unsafe {
+        respond(client, out_json, |client| {
+            Coordinates::new(latitude, longitude).and_then(|point| client.reverse_geocode(point))
+        })
+    }
+}
+
+/// Finds services near a query, writing the location intelligence as JSON.
+///
+/// `query` is an address, "lat,lng", or a JSON object as accepted by batch input.
+/// `service_types` is a comma-separated list such as "bank,hospital".
+///
+/// # Safety
+///
+/// `client` must come from `mapradar_client_new`, `query` and `service_types` must be
+/// NUL-terminated strings, and `out_json` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mapradar_nearby(
+    client: *const MapradarHandle,
+    query: *const c_char,
+    service_types: *const c_char,
+    radius_km: f64,
+    max_results_per_type: usize,
+    out_json: *mut *mut c_char,
+) -> MapradarStatus {
+    unsafe {
+        respond(client, out_json, |client| {
+            let query = parse_query_line(1, read_str(query, "query")?).query?;
+            let service_types = read_str(service_types, "service_types")?
+                .split(',')
+                .map(|t| t.trim().parse())
+                .collect::<Result<Vec<PlaceCategory>, _>>()?;
+            client.fetch_intelligence(query, service_types, radius_km, max_results_per_type)
+        })
+    }
+}
+
+/// Releases a string returned through `out_json`. Null is ignored.
+///
+/// # Safety
+///
+/// `value` must be null or a string returned by this library, and must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mapradar_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(unsafe { CString::from_raw(value) });
+    }
+}
+
+/// Message for the last failed call on this thread, or null when none has failed.
+///
+/// The pointer stays valid until the next failing call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn mapradar_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+}
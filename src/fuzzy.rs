@@ -0,0 +1,261 @@
+//! Fuzzy matching of a query against a local list of named places, without any API call.
+//!
+//! Names are compared after lower-casing and dropping punctuation. A name scores the better of
+//! its trigram similarity (Dice coefficient over padded three-letter windows, which tolerates
+//! reordered words) and its edit similarity (one minus the Levenshtein distance over the longer
+//! length, which tolerates typos). When a reference point is given, closeness to it is blended in
+//! so that, of two equally good names, the nearer place ranks first.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::Path,
+};
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{coordinates::Coordinates, error::MapradarError, geo::haversine_distance};
+
+/// Matches scoring below this are dropped unless another minimum is set.
+pub const DEFAULT_MIN_SCORE: f64 = 0.3;
+
+/// Share of the score given to distance when a reference point is set.
+pub const DEFAULT_DISTANCE_WEIGHT: f64 = 0.3;
+
+/// Distance at which a place's closeness counts half.
+pub const DEFAULT_DISTANCE_SCALE_KM: f64 = 5.0;
+
+/// A named place read from a local list.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalPlace {
+    /// 1-based data row in the file.
+    pub row: usize,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// The row's other columns, by header.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, String>,
+}
+
+/// A local place matching a query.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuzzyMatch {
+    pub place: LocalPlace,
+    /// Combined score from 0.0 to 1.0.
+    pub score: f64,
+    /// Name similarity alone, from 0.0 to 1.0.
+    pub name_score: f64,
+    /// Distance from the reference point, when one was given.
+    pub distance_km: Option<f64>,
+}
+
+#[cfg(feature = "python")]
+crate::python::data_model!(LocalPlace);
+#[cfg(feature = "python")]
+crate::python::data_model!(FuzzyMatch);
+
+/// How matches are scored and which are kept.
+#[derive(Debug, Clone, Copy)]
+pub struct FuzzyOptions {
+    /// Reference point whose closeness raises a match's score.
+    pub near: Option<Coordinates>,
+    /// Share of the score given to closeness to `near`, from 0.0 to 1.0.
+    pub distance_weight: f64,
+    /// Distance at which closeness counts half; closeness is `scale / (scale + distance)`.
+    pub distance_scale_km: f64,
+    pub min_score: f64,
+    pub limit: usize,
+}
+
+impl Default for FuzzyOptions {
+    fn default() -> Self {
+        Self {
+            near: None,
+            distance_weight: DEFAULT_DISTANCE_WEIGHT,
+            distance_scale_km: DEFAULT_DISTANCE_SCALE_KM,
+            min_score: DEFAULT_MIN_SCORE,
+            limit: 10,
+        }
+    }
+}
+
+/// Reads places from a CSV file with a header row: a `name` column and the coordinates in
+/// `latitude`/`lat` and `longitude`/`lng`/`lon`. Other columns are kept as properties; rows
+/// without a name are skipped.
+pub fn read_places(path: &Path) -> Result<Vec<LocalPlace>, MapradarError> {
+    let mut reader = csv::Reader::from_path(path).map_err(std::io::Error::from)?;
+    let headers = reader.headers().map_err(std::io::Error::from)?.clone();
+    let column = |names: &[&str]| {
+        headers
+            .iter()
+            .position(|h| names.iter().any(|name| h.trim().eq_ignore_ascii_case(name)))
+            .ok_or_else(|| {
+                MapradarError::InvalidInput(format!(
+                    "Column '{}' not found in {}",
+                    names[0],
+                    path.display()
+                ))
+            })
+    };
+    let name_index = column(&["name"])?;
+    let lat_index = column(&["latitude", "lat"])?;
+    let lng_index = column(&["longitude", "lng", "lon"])?;
+
+    let mut places = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record.map_err(std::io::Error::from)?;
+        let Some(name) = record
+            .get(name_index)
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+        else {
+            continue;
+        };
+        let number = |i: usize| record.get(i).and_then(|value| value.trim().parse().ok());
+        let Some(point) = number(lat_index)
+            .zip(number(lng_index))
+            .and_then(|(lat, lng)| Coordinates::new(lat, lng).ok())
+        else {
+            return Err(MapradarError::InvalidInput(format!(
+                "Row {} of {} has no valid latitude/longitude",
+                index + 1,
+                path.display()
+            )));
+        };
+        let properties = headers
+            .iter()
+            .zip(record.iter())
+            .enumerate()
+            .filter(|(i, (_, value))| {
+                ![name_index, lat_index, lng_index].contains(i) && !value.trim().is_empty()
+            })
+            .map(|(_, (header, value))| (header.trim().to_string(), value.trim().to_string()))
+            .collect();
+        places.push(LocalPlace {
+            row: index + 1,
+            name: name.to_string(),
+            latitude: point.latitude(),
+            longitude: point.longitude(),
+            properties,
+        });
+    }
+    Ok(places)
+}
+
+/// Ranks `places` against `query`, best first, keeping at most `options.limit` matches scoring
+/// at least `options.min_score`.
+pub fn rank(query: &str, places: &[LocalPlace], options: &FuzzyOptions) -> Vec<FuzzyMatch> {
+    let query = normalize(query);
+    let query_trigrams = trigrams(&query);
+    let weight = if options.near.is_some() {
+        options.distance_weight.clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    let mut matches: Vec<FuzzyMatch> = places
+        .iter()
+        .filter_map(|place| {
+            let name = normalize(&place.name);
+            let name_score =
+                dice(&query_trigrams, &trigrams(&name)).max(edit_similarity(&query, &name));
+            let distance_km = options.near.map(|near| {
+                haversine_distance(
+                    near.latitude(),
+                    near.longitude(),
+                    place.latitude,
+                    place.longitude,
+                )
+            });
+            let closeness = distance_km.map_or(0.0, |distance| {
+                options.distance_scale_km / (options.distance_scale_km + distance)
+            });
+            let score = name_score * (1.0 - weight) + closeness * weight;
+            (score >= options.min_score).then(|| FuzzyMatch {
+                place: place.clone(),
+                score,
+                name_score,
+                distance_km,
+            })
+        })
+        .collect();
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches.truncate(options.limit);
+    matches
+}
+
+/// Lower-cases and keeps letters and digits, with single spaces between words.
+fn normalize(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Three-character windows of each word, padded so that word starts and ends count.
+fn trigrams(text: &str) -> HashSet<[char; 3]> {
+    text.split(' ')
+        .flat_map(|word| {
+            let chars: Vec<char> = format!("  {} ", word).chars().collect();
+            chars
+                .windows(3)
+                .map(|w| [w[0], w[1], w[2]])
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Dice coefficient of two trigram sets.
+fn dice(a: &HashSet<[char; 3]>, b: &HashSet<[char; 3]>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    2.0 * a.intersection(b).count() as f64 / (a.len() + b.len()) as f64
+}
+
+/// One minus the Levenshtein distance over the longer string's length.
+fn edit_similarity(a: &str, b: &str) -> f64 {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 0.0;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    1.0 - previous[b.len()] as f64 / longest as f64
+}
+
+/// Ranks the places in a CSV file against `query`, best first, optionally favouring those
+/// near a `(lat, lng)` point.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (query, candidates, near=None, limit=10, min_score=DEFAULT_MIN_SCORE))]
+pub fn fuzzy_match(
+    query: &str,
+    candidates: std::path::PathBuf,
+    near: Option<(f64, f64)>,
+    limit: usize,
+    min_score: f64,
+) -> PyResult<Vec<FuzzyMatch>> {
+    let options = FuzzyOptions {
+        near: near
+            .map(|(lat, lng)| Coordinates::new(lat, lng))
+            .transpose()?,
+        min_score,
+        limit,
+        ..FuzzyOptions::default()
+    };
+    Ok(rank(query, &read_places(&candidates)?, &options))
+}
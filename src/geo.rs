@@ -0,0 +1,435 @@
+//! Great-circle geometry: distances, bearings, destination points, and area containment.
+//!
+//! Coordinates are decimal degrees and distances kilometres unless noted otherwise.
+
+use std::{fmt, str::FromStr};
+
+pub use crate::units::{KM_PER_MILE, km_to_miles};
+use crate::{
+    error::MapradarError,
+    spatial::{from_vector, to_unit_vector},
+};
+
+/// Mean Earth radius used by the spherical formulas.
+pub const EARTH_RADIUS_KM: f64 = 6371.0;
+
+// WGS-84 ellipsoid, used by Vincenty's formula.
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+const VINCENTY_MAX_ITERATIONS: usize = 200;
+/// Steps towards the farthest point taken by [`minimax_center`]; the enclosing radius is within
+/// about 0.1% of the smallest after this many.
+const MINIMAX_ITERATIONS: usize = 10_000;
+
+/// Formula used to measure the distance between two points.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DistanceMethod {
+    /// Spherical Earth; fast, and within about 0.5% of the ellipsoidal distance.
+    #[default]
+    Haversine,
+    /// WGS-84 ellipsoid; accurate to millimetres.
+    Vincenty,
+}
+
+impl DistanceMethod {
+    /// Distance in km between two points.
+    ///
+    /// Vincenty's iteration can fail to converge for nearly antipodal points; the haversine
+    /// distance is returned in that case.
+    pub fn distance(self, lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+        match self {
+            Self::Haversine => haversine_distance(lat1, lng1, lat2, lng2),
+            Self::Vincenty => vincenty_distance(lat1, lng1, lat2, lng2)
+                .unwrap_or_else(|| haversine_distance(lat1, lng1, lat2, lng2)),
+        }
+    }
+}
+
+impl FromStr for DistanceMethod {
+    type Err = MapradarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "haversine" => Ok(Self::Haversine),
+            "vincenty" => Ok(Self::Vincenty),
+            other => Err(MapradarError::InvalidInput(format!(
+                "Unknown distance method '{}' (expected haversine or vincenty)",
+                other
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for DistanceMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Haversine => write!(f, "haversine"),
+            Self::Vincenty => write!(f, "vincenty"),
+        }
+    }
+}
+
+/// Great-circle distance in km on a spherical Earth.
+pub fn haversine_distance(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    let lat1_rad = lat1.to_radians();
+    let lat2_rad = lat2.to_radians();
+    let latitude_difference = (lat2 - lat1).to_radians();
+    let longitude_difference = (lng2 - lng1).to_radians();
+
+    let a = (latitude_difference / 2.0).sin().powi(2)
+        + lat1_rad.cos() * lat2_rad.cos() * (longitude_difference / 2.0).sin().powi(2);
+    let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
+
+    EARTH_RADIUS_KM * c
+}
+
+/// Geodesic distance in km on the WGS-84 ellipsoid (Vincenty's inverse formula).
+///
+/// Returns `None` when the iteration does not converge, which only happens for nearly
+/// antipodal points.
+pub fn vincenty_distance(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> Option<f64> {
+    let b = (1.0 - WGS84_F) * WGS84_A;
+    let l = (lng2 - lng1).to_radians();
+    let u1 = ((1.0 - WGS84_F) * lat1.to_radians().tan()).atan();
+    let u2 = ((1.0 - WGS84_F) * lat2.to_radians().tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
+
+    let mut lambda = l;
+    for _ in 0..VINCENTY_MAX_ITERATIONS {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+            + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+        .sqrt();
+        if sin_sigma == 0.0 {
+            // Coincident points.
+            return Some(0.0);
+        }
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos_sq_alpha = 1.0 - sin_alpha.powi(2);
+        // Zero on equatorial lines.
+        let cos_2sigma_m = if cos_sq_alpha == 0.0 {
+            0.0
+        } else {
+            cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha
+        };
+        let c = WGS84_F / 16.0 * cos_sq_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos_sq_alpha));
+
+        let previous = lambda;
+        lambda = l
+            + (1.0 - c)
+                * WGS84_F
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
+
+        if (lambda - previous).abs() < 1e-12 {
+            let u_sq = cos_sq_alpha * (WGS84_A.powi(2) - b.powi(2)) / b.powi(2);
+            let big_a =
+                1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+            let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+            let delta_sigma = big_b
+                * sin_sigma
+                * (cos_2sigma_m
+                    + big_b / 4.0
+                        * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))
+                            - big_b / 6.0
+                                * cos_2sigma_m
+                                * (-3.0 + 4.0 * sin_sigma.powi(2))
+                                * (-3.0 + 4.0 * cos_2sigma_m.powi(2))));
+            return Some(b * big_a * (sigma - delta_sigma) / 1000.0);
+        }
+    }
+    None
+}
+
+/// Initial bearing (forward azimuth) from the first point toward the second, in degrees
+/// clockwise from north, within `[0, 360)`.
+pub fn initial_bearing(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    let lat1 = lat1.to_radians();
+    let lat2 = lat2.to_radians();
+    let longitude_difference = (lng2 - lng1).to_radians();
+
+    let y = longitude_difference.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * longitude_difference.cos();
+
+    (y.atan2(x).to_degrees() + 360.0) % 360.0
+}
+
+/// Eight-point compass direction ("N", "NE", ...) nearest to a bearing in degrees.
+pub fn compass_point(bearing_degrees: f64) -> &'static str {
+    const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    let index = ((bearing_degrees.rem_euclid(360.0) + 22.5) / 45.0) as usize % POINTS.len();
+    POINTS[index]
+}
+
+/// Point reached by travelling `distance_km` from an origin along an initial bearing (degrees).
+pub fn destination_point(
+    latitude: f64,
+    longitude: f64,
+    bearing_degrees: f64,
+    distance_km: f64,
+) -> (f64, f64) {
+    let angular_distance = distance_km / EARTH_RADIUS_KM;
+    let bearing = bearing_degrees.to_radians();
+    let lat1 = latitude.to_radians();
+    let lng1 = longitude.to_radians();
+
+    let lat2 = (lat1.sin() * angular_distance.cos()
+        + lat1.cos() * angular_distance.sin() * bearing.cos())
+    .asin();
+    let lng2 = lng1
+        + (bearing.sin() * angular_distance.sin() * lat1.cos())
+            .atan2(angular_distance.cos() - lat1.sin() * lat2.sin());
+
+    (
+        lat2.to_degrees(),
+        (lng2.to_degrees() + 540.0) % 360.0 - 180.0,
+    )
+}
+
+/// Geographic centroid of `(lat, lng)` points: their mean on the sphere, projected back to the
+/// surface. `None` without points, or when they balance out around the Earth's centre, such as
+/// two antipodes.
+pub fn centroid(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let sum = points.iter().fold([0.0; 3], |sum, &(lat, lng)| {
+        let v = to_unit_vector(lat, lng);
+        [sum[0] + v[0], sum[1] + v[1], sum[2] + v[2]]
+    });
+    let length = (sum[0] * sum[0] + sum[1] * sum[1] + sum[2] * sum[2]).sqrt();
+    (length > 1e-9 * points.len() as f64).then(|| from_vector(sum))
+}
+
+/// Point minimising the greatest great-circle distance to any of `(lat, lng)` points: the centre
+/// of the smallest circle enclosing them. Found by stepping from the [`centroid`] towards the
+/// farthest point by ever smaller fractions (Bădoiu-Clarkson), so it is approximate, and only
+/// meaningful for points within a hemisphere. `None` when [`centroid`] is.
+pub fn minimax_center(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let (lat, lng) = centroid(points)?;
+    let vectors: Vec<[f64; 3]> = points
+        .iter()
+        .map(|&(lat, lng)| to_unit_vector(lat, lng))
+        .collect();
+    let dot = |a: [f64; 3], b: [f64; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+
+    let mut center = to_unit_vector(lat, lng);
+    for step in 1..=MINIMAX_ITERATIONS {
+        let farthest = *vectors
+            .iter()
+            .min_by(|a, b| dot(center, **a).total_cmp(&dot(center, **b)))?;
+        let fraction = 1.0 / (step + 1) as f64;
+        let moved = [0, 1, 2].map(|i| center[i] + (farthest[i] - center[i]) * fraction);
+        let length = dot(moved, moved).sqrt();
+        if length > 0.0 {
+            center = moved.map(|c| c / length);
+        }
+    }
+    Some(from_vector(center))
+}
+
+/// Places `samples` points evenly along a path of `(lat, lng)` vertices, returning each as
+/// `(lat, lng, distance_km)` measured from the start.
+///
+/// Points are interpolated linearly within each segment, which is accurate for the short
+/// segments of a route or track. Fewer than two samples yields just the start.
+pub fn sample_path(path: &[(f64, f64)], samples: usize) -> Vec<(f64, f64, f64)> {
+    let Some(&(start_lat, start_lng)) = path.first() else {
+        return Vec::new();
+    };
+    let segments: Vec<f64> = path
+        .windows(2)
+        .map(|pair| haversine_distance(pair[0].0, pair[0].1, pair[1].0, pair[1].1))
+        .collect();
+    let total: f64 = segments.iter().sum();
+    if samples < 2 || total == 0.0 {
+        return vec![(start_lat, start_lng, 0.0)];
+    }
+
+    let mut points = Vec::with_capacity(samples);
+    let mut segment = 0;
+    let mut segment_start = 0.0;
+    for i in 0..samples {
+        let target = total * i as f64 / (samples - 1) as f64;
+        while segment + 1 < segments.len() && segment_start + segments[segment] < target {
+            segment_start += segments[segment];
+            segment += 1;
+        }
+        let (lat1, lng1) = path[segment];
+        let (lat2, lng2) = path[segment + 1];
+        let fraction = if segments[segment] > 0.0 {
+            ((target - segment_start) / segments[segment]).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        points.push((
+            lat1 + (lat2 - lat1) * fraction,
+            lng1 + (lng2 - lng1) * fraction,
+            target,
+        ));
+    }
+    points
+}
+
+/// Finds the point of a path of `(lat, lng)` vertices nearest to a location, returning
+/// `(distance_km, along_km)`: how far the location is from the path, and how far along the
+/// path that nearest point lies.
+///
+/// Each segment is treated as straight on a local equirectangular projection, which is
+/// accurate for the short segments of a route or track. Returns `None` for an empty path.
+pub fn nearest_on_path(path: &[(f64, f64)], lat: f64, lng: f64) -> Option<(f64, f64)> {
+    let &(first_lat, first_lng) = path.first()?;
+    let mut best = (haversine_distance(first_lat, first_lng, lat, lng), 0.0);
+    let mut along = 0.0;
+    for pair in path.windows(2) {
+        let ((lat1, lng1), (lat2, lng2)) = (pair[0], pair[1]);
+        let length = haversine_distance(lat1, lng1, lat2, lng2);
+        let scale = lat1.to_radians().cos();
+        let (dx, dy) = ((lng2 - lng1) * scale, lat2 - lat1);
+        let (px, py) = ((lng - lng1) * scale, lat - lat1);
+        let squared = dx * dx + dy * dy;
+        let fraction = if squared > 0.0 {
+            ((px * dx + py * dy) / squared).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let distance = haversine_distance(
+            lat1 + (lat2 - lat1) * fraction,
+            lng1 + (lng2 - lng1) * fraction,
+            lat,
+            lng,
+        );
+        if distance < best.0 {
+            best = (distance, along + length * fraction);
+        }
+        along += length;
+    }
+    Some(best)
+}
+
+/// Area used to keep or discard points.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    /// Box bounded by two longitudes and two latitudes. A box whose `min_lng` exceeds its
+    /// `max_lng` crosses the antimeridian.
+    BoundingBox {
+        min_lng: f64,
+        min_lat: f64,
+        max_lng: f64,
+        max_lat: f64,
+    },
+    /// One or more polygons, each an outer ring followed by any holes, as `(lat, lng)` vertices.
+    Polygons(Vec<Vec<Vec<(f64, f64)>>>),
+}
+
+impl Geometry {
+    /// Returns true if the point lies inside the area. Points on a polygon edge may fall either way.
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        match self {
+            Self::BoundingBox {
+                min_lng,
+                min_lat,
+                max_lng,
+                max_lat,
+            } => {
+                let within_lng = if min_lng <= max_lng {
+                    (*min_lng..=*max_lng).contains(&longitude)
+                } else {
+                    longitude >= *min_lng || longitude <= *max_lng
+                };
+                within_lng && (*min_lat..=*max_lat).contains(&latitude)
+            }
+            Self::Polygons(polygons) => polygons.iter().any(|rings| {
+                let mut rings = rings.iter();
+                rings
+                    .next()
+                    .is_some_and(|outer| ring_contains(outer, latitude, longitude))
+                    && !rings.any(|hole| ring_contains(hole, latitude, longitude))
+            }),
+        }
+    }
+
+    /// Smallest box enclosing the area, as `(min_lng, min_lat, max_lng, max_lat)`.
+    ///
+    /// Polygon envelopes never cross the antimeridian; `None` for polygons without vertices.
+    pub fn envelope(&self) -> Option<(f64, f64, f64, f64)> {
+        match self {
+            Self::BoundingBox {
+                min_lng,
+                min_lat,
+                max_lng,
+                max_lat,
+            } => Some((*min_lng, *min_lat, *max_lng, *max_lat)),
+            Self::Polygons(polygons) => {
+                polygons
+                    .iter()
+                    .flatten()
+                    .flatten()
+                    .fold(None, |envelope, &(lat, lng)| match envelope {
+                        None => Some((lng, lat, lng, lat)),
+                        Some((min_lng, min_lat, max_lng, max_lat)) => Some((
+                            f64::min(min_lng, lng),
+                            f64::min(min_lat, lat),
+                            f64::max(max_lng, lng),
+                            f64::max(max_lat, lat),
+                        )),
+                    })
+            }
+        }
+    }
+}
+
+impl FromStr for Geometry {
+    type Err = MapradarError;
+
+    /// Parses a bounding box written as `minLon,minLat,maxLon,maxLat`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            MapradarError::InvalidInput(format!(
+                "Invalid bounding box '{}' (expected minLon,minLat,maxLon,maxLat)",
+                s
+            ))
+        };
+        let values = s
+            .split(',')
+            .map(|v| v.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+        let [min_lng, min_lat, max_lng, max_lat] = values[..] else {
+            return Err(invalid());
+        };
+
+        crate::utils::validate_coordinates(min_lat, min_lng)?;
+        crate::utils::validate_coordinates(max_lat, max_lng)?;
+        if min_lat > max_lat {
+            return Err(invalid());
+        }
+        Ok(Self::BoundingBox {
+            min_lng,
+            min_lat,
+            max_lng,
+            max_lat,
+        })
+    }
+}
+
+/// Even-odd ray casting test against one ring of `(lat, lng)` vertices.
+fn ring_contains(ring: &[(f64, f64)], latitude: f64, longitude: f64) -> bool {
+    let mut inside = false;
+    let mut previous = match ring.last() {
+        Some(vertex) => *vertex,
+        None => return false,
+    };
+    for &(lat, lng) in ring {
+        let (prev_lat, prev_lng) = previous;
+        if (lat > latitude) != (prev_lat > latitude)
+            && longitude < (prev_lng - lng) * (latitude - lat) / (prev_lat - lat) + lng
+        {
+            inside = !inside;
+        }
+        previous = (lat, lng);
+    }
+    inside
+}
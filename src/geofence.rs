@@ -0,0 +1,154 @@
+//! Named geofences: circles and polygons tested for point containment.
+//!
+//! Fence sets are read from GeoJSON. Each `Polygon` or `MultiPolygon` feature becomes a polygon
+//! fence, and each `Point` feature with a `radius_m` property becomes a circle. A fence is named
+//! by its `name` property, falling back to the feature `id` and then to its position in the file.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::{error::MapradarError, geo::Geometry};
+
+/// Area covered by a [`Geofence`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum FenceShape {
+    /// Every point within `radius_m` meters (great-circle distance) of the center.
+    Circle {
+        latitude: f64,
+        longitude: f64,
+        radius_m: f64,
+    },
+    /// Polygons with holes, or a bounding box.
+    Polygon(Geometry),
+}
+
+/// A named area that points can be tested against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Geofence {
+    pub name: String,
+    pub shape: FenceShape,
+}
+
+impl Geofence {
+    pub fn circle(
+        name: impl Into<String>,
+        latitude: f64,
+        longitude: f64,
+        radius_m: f64,
+    ) -> Result<Self, MapradarError> {
+        crate::utils::validate_coordinates(latitude, longitude)?;
+        if !(radius_m > 0.0 && radius_m.is_finite()) {
+            return Err(MapradarError::InvalidInput(
+                "Fence radius must be a positive number of meters".to_string(),
+            ));
+        }
+        Ok(Self {
+            name: name.into(),
+            shape: FenceShape::Circle {
+                latitude,
+                longitude,
+                radius_m,
+            },
+        })
+    }
+
+    pub fn polygon(name: impl Into<String>, area: Geometry) -> Self {
+        Self {
+            name: name.into(),
+            shape: FenceShape::Polygon(area),
+        }
+    }
+
+    /// Returns true if the point lies inside the fence. Points on a polygon edge may fall either
+    /// way; points exactly on a circle's boundary are inside.
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        match &self.shape {
+            FenceShape::Circle {
+                latitude: center_lat,
+                longitude: center_lng,
+                radius_m,
+            } => {
+                crate::geo::haversine_distance(*center_lat, *center_lng, latitude, longitude)
+                    * 1000.0
+                    <= *radius_m
+            }
+            FenceShape::Polygon(area) => area.contains(latitude, longitude),
+        }
+    }
+
+    /// Reads every fence in a GeoJSON file.
+    pub fn load(path: &Path) -> Result<Vec<Self>, MapradarError> {
+        let text = std::fs::read_to_string(path)?;
+        let value: Value = serde_json::from_str(&text).map_err(|err| {
+            MapradarError::InvalidInput(format!("{} is not valid JSON: {}", path.display(), err))
+        })?;
+        Self::from_geojson(&value)
+    }
+
+    /// Reads the fences of a GeoJSON `FeatureCollection` or single `Feature`. Features with other
+    /// geometry types, or points without a `radius_m`, are skipped.
+    pub fn from_geojson(value: &Value) -> Result<Vec<Self>, MapradarError> {
+        let features = match value["type"].as_str() {
+            Some("FeatureCollection") => value["features"].as_array().cloned().unwrap_or_default(),
+            Some("Feature") => vec![value.clone()],
+            _ => {
+                return Err(MapradarError::InvalidInput(
+                    "Geofences must be a GeoJSON Feature or FeatureCollection".to_string(),
+                ));
+            }
+        };
+
+        let mut fences = Vec::new();
+        for (index, feature) in features.iter().enumerate() {
+            let properties = &feature["properties"];
+            let name = properties["name"]
+                .as_str()
+                .map(str::to_string)
+                .or_else(|| match &feature["id"] {
+                    Value::String(id) => Some(id.clone()),
+                    Value::Number(id) => Some(id.to_string()),
+                    _ => None,
+                })
+                .unwrap_or_else(|| format!("fence-{}", index + 1));
+
+            let geometry = &feature["geometry"];
+            match geometry["type"].as_str() {
+                Some("Polygon" | "MultiPolygon") => {
+                    fences.push(Self::polygon(name, Geometry::from_geojson(geometry)?));
+                }
+                Some("Point") => {
+                    let Some(radius_m) = properties["radius_m"].as_f64() else {
+                        continue;
+                    };
+                    let position = &geometry["coordinates"];
+                    let (Some(lng), Some(lat)) = (position[0].as_f64(), position[1].as_f64())
+                    else {
+                        return Err(MapradarError::InvalidInput(format!(
+                            "Malformed point coordinates for fence '{}'",
+                            name
+                        )));
+                    };
+                    fences.push(Self::circle(name, lat, lng, radius_m)?);
+                }
+                _ => {}
+            }
+        }
+
+        if fences.is_empty() {
+            return Err(MapradarError::InvalidInput(
+                "GeoJSON contains no polygon or circle fences".to_string(),
+            ));
+        }
+        Ok(fences)
+    }
+}
+
+/// Names of the fences containing a point, in the order they were defined.
+pub fn fences_containing(fences: &[Geofence], latitude: f64, longitude: f64) -> Vec<&str> {
+    fences
+        .iter()
+        .filter(|fence| fence.contains(latitude, longitude))
+        .map(|fence| fence.name.as_str())
+        .collect()
+}
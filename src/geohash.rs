@@ -0,0 +1,225 @@
+//! Geohashes, and grouping nearby results by geohash cell.
+//!
+//! A geohash names a rectangular cell; each character splits its parent into 32, so a longer hash
+//! is a smaller cell (precision 5 is about 4.9 by 4.9 km, 6 about 1.2 by 0.6 km, 7 about 153 by
+//! 153 m). Points sharing a prefix are in the same cell, which makes hashes handy for counting
+//! results per area.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{coordinates::Coordinates, error::MapradarError, models::NearbyService};
+
+const BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Longest hash accepted, about 3.7 by 1.9 cm.
+pub const MAX_PRECISION: usize = 12;
+
+/// Bounds of a geohash cell, in degrees.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeohashCell {
+    pub geohash: String,
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+}
+
+impl GeohashCell {
+    /// Centre of the cell.
+    pub fn center(&self) -> Coordinates {
+        Coordinates::new(
+            (self.south + self.north) / 2.0,
+            (self.west + self.east) / 2.0,
+        )
+        .expect("a geohash cell lies within range")
+    }
+
+    /// The cell as a closed `[lng, lat]` ring, counter-clockwise from the south-west corner.
+    pub fn ring(&self) -> Vec<[f64; 2]> {
+        vec![
+            [self.west, self.south],
+            [self.east, self.south],
+            [self.east, self.north],
+            [self.west, self.north],
+            [self.west, self.south],
+        ]
+    }
+}
+
+/// The eight cells around a geohash, at the same precision. Cells beyond a pole are `None`;
+/// cells across the antimeridian wrap around.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Neighbors {
+    pub north: Option<String>,
+    pub north_east: Option<String>,
+    pub east: String,
+    pub south_east: Option<String>,
+    pub south: Option<String>,
+    pub south_west: Option<String>,
+    pub west: String,
+    pub north_west: Option<String>,
+}
+
+/// Hashes `point` to `precision` characters, from 1 to [`MAX_PRECISION`].
+pub fn encode(point: Coordinates, precision: usize) -> Result<String, MapradarError> {
+    if !(1..=MAX_PRECISION).contains(&precision) {
+        return Err(MapradarError::InvalidInput(format!(
+            "Invalid geohash precision {} (expected 1 to {})",
+            precision, MAX_PRECISION
+        )));
+    }
+    Ok(encode_degrees(
+        point.latitude(),
+        point.longitude(),
+        precision,
+    ))
+}
+
+/// Hashes any latitude and longitude within range; bits alternate longitude first.
+fn encode_degrees(lat: f64, lng: f64, precision: usize) -> String {
+    let (mut lat_range, mut lng_range) = ((-90.0, 90.0), (-180.0, 180.0));
+    let mut hash = String::with_capacity(precision);
+    let mut bits = 0;
+    let mut value = 0;
+    let mut even = true;
+    while hash.len() < precision {
+        let (range, degrees) = if even {
+            (&mut lng_range, lng)
+        } else {
+            (&mut lat_range, lat)
+        };
+        let mid = (range.0 + range.1) / 2.0;
+        value <<= 1;
+        if degrees >= mid {
+            value |= 1;
+            range.0 = mid;
+        } else {
+            range.1 = mid;
+        }
+        even = !even;
+        bits += 1;
+        if bits == 5 {
+            hash.push(BASE32[value] as char);
+            bits = 0;
+            value = 0;
+        }
+    }
+    hash
+}
+
+/// Decodes a geohash, in either case, to the cell it names.
+pub fn decode(geohash: &str) -> Result<GeohashCell, MapradarError> {
+    let invalid = || MapradarError::InvalidInput(format!("Invalid geohash '{}'", geohash));
+    if geohash.is_empty() || geohash.len() > MAX_PRECISION {
+        return Err(invalid());
+    }
+    let (mut lat_range, mut lng_range) = ((-90.0, 90.0), (-180.0, 180.0));
+    let mut even = true;
+    for c in geohash.chars() {
+        let value = BASE32
+            .iter()
+            .position(|&digit| digit as char == c.to_ascii_lowercase())
+            .ok_or_else(invalid)?;
+        for bit in (0..5).rev() {
+            let range = if even { &mut lng_range } else { &mut lat_range };
+            let mid = (range.0 + range.1) / 2.0;
+            if value >> bit & 1 == 1 {
+                range.0 = mid;
+            } else {
+                range.1 = mid;
+            }
+            even = !even;
+        }
+    }
+    Ok(GeohashCell {
+        geohash: geohash.to_lowercase(),
+        south: lat_range.0,
+        west: lng_range.0,
+        north: lat_range.1,
+        east: lng_range.1,
+    })
+}
+
+/// The eight cells around `geohash`.
+pub fn neighbors(geohash: &str) -> Result<Neighbors, MapradarError> {
+    let cell = decode(geohash)?;
+    let precision = cell.geohash.len();
+    let height = cell.north - cell.south;
+    let width = cell.east - cell.west;
+    let (lat, lng) = cell.center().as_tuple();
+    let at = |rows: f64, columns: f64| {
+        let lat = lat + rows * height;
+        let lng = (lng + columns * width + 180.0).rem_euclid(360.0) - 180.0;
+        (-90.0..=90.0)
+            .contains(&lat)
+            .then(|| encode_degrees(lat, lng, precision))
+    };
+    let beside = |columns: f64| at(0.0, columns).expect("the centre row is within range");
+    Ok(Neighbors {
+        north: at(1.0, 0.0),
+        north_east: at(1.0, 1.0),
+        east: beside(1.0),
+        south_east: at(-1.0, 1.0),
+        south: at(-1.0, 0.0),
+        south_west: at(-1.0, -1.0),
+        west: beside(-1.0),
+        north_west: at(1.0, -1.0),
+    })
+}
+
+/// Nearby results sharing one geohash cell.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeohashCluster {
+    pub geohash: String,
+    /// Centre of the cell.
+    pub latitude: f64,
+    pub longitude: f64,
+    pub count: usize,
+    /// Results per service type.
+    pub by_type: BTreeMap<String, usize>,
+    pub services: Vec<NearbyService>,
+}
+
+/// Groups `services` by their geohash at `precision`, densest cell first. Services at invalid
+/// coordinates are left out.
+pub fn cluster(
+    services: &[NearbyService],
+    precision: usize,
+) -> Result<Vec<GeohashCluster>, MapradarError> {
+    let mut cells: HashMap<String, Vec<&NearbyService>> = HashMap::new();
+    for service in services {
+        let Ok(point) = Coordinates::new(service.latitude, service.longitude) else {
+            continue;
+        };
+        cells
+            .entry(encode(point, precision)?)
+            .or_default()
+            .push(service);
+    }
+
+    let mut clusters = cells
+        .into_iter()
+        .map(|(geohash, services)| {
+            let (latitude, longitude) = decode(&geohash)?.center().as_tuple();
+            let mut by_type = BTreeMap::new();
+            for service in &services {
+                *by_type.entry(service.service_type.to_string()).or_insert(0) += 1;
+            }
+            Ok(GeohashCluster {
+                geohash,
+                latitude,
+                longitude,
+                count: services.len(),
+                by_type,
+                services: services.into_iter().cloned().collect(),
+            })
+        })
+        .collect::<Result<Vec<_>, MapradarError>>()?;
+    clusters.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.geohash.cmp(&b.geohash))
+    });
+    Ok(clusters)
+}
@@ -0,0 +1,427 @@
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+
+use crate::{
+    clustering::ServiceCluster,
+    error::MapradarError,
+    geo::Geometry,
+    geohash::{self, GeohashCluster},
+    gpx::GpxTrack,
+    heatmap::{Heatmap, HeatmapCell},
+    models::{
+        AdminArea, AdminHierarchy, GeoLocation, GeocodeCandidate, Isochrone, LocationIntelligence,
+        MeetingPoint, NearbyService, Route, SnappedPoint, TrackAmenity, TrackEnrichment,
+    },
+    utils::decode_polyline,
+};
+
+/// Conversion of a located model into a GeoJSON `Feature`.
+pub trait ToGeoJson {
+    /// Returns a GeoJSON `Feature` carrying the model's fields as properties.
+    fn to_feature(&self) -> Value;
+}
+
+/// Builds a `Point` feature, moving every serialized field except the coordinates into `properties`.
+fn point_feature<T: Serialize>(item: &T, latitude: f64, longitude: f64) -> Value {
+    let mut properties = match serde_json::to_value(item) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    };
+    properties.remove("latitude");
+    properties.remove("longitude");
+
+    json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "Point",
+            "coordinates": [longitude, latitude],
+        },
+        "properties": properties,
+    })
+}
+
+/// Wraps features into a GeoJSON `FeatureCollection`.
+pub fn feature_collection(features: Vec<Value>) -> Value {
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+impl ToGeoJson for GeoLocation {
+    fn to_feature(&self) -> Value {
+        point_feature(self, self.latitude, self.longitude)
+    }
+}
+
+impl ToGeoJson for GeocodeCandidate {
+    /// Returns the location as a `Point` feature with its confidence and match type, and the
+    /// candidate's extent as the feature `bbox`.
+    fn to_feature(&self) -> Value {
+        let mut feature = self.location.to_feature();
+        feature["properties"]["confidence"] = json!(self.confidence);
+        feature["properties"]["match_type"] = json!(self.match_type);
+        if let Some((min_lng, min_lat, max_lng, max_lat)) = self.bounding_box {
+            feature["bbox"] = json!([min_lng, min_lat, max_lng, max_lat]);
+        }
+        feature
+    }
+}
+
+impl ToGeoJson for NearbyService {
+    fn to_feature(&self) -> Value {
+        point_feature(self, self.latitude, self.longitude)
+    }
+}
+
+impl ToGeoJson for SnappedPoint {
+    /// Returns a `Point` feature at the corrected position, with the original position and snap
+    /// distance as properties.
+    fn to_feature(&self) -> Value {
+        point_feature(self, self.latitude, self.longitude)
+    }
+}
+
+impl LocationIntelligence {
+    /// Returns a `FeatureCollection` with the searched location first, followed by every nearby service.
+    pub fn to_feature_collection(&self) -> Value {
+        let mut origin = self.location.to_feature();
+        origin["properties"]["role"] = json!("origin");
+
+        let mut features = vec![origin];
+        features.extend(self.nearby_services.iter().map(|service| {
+            let mut feature = service.to_feature();
+            feature["properties"]["role"] = json!("service");
+            feature
+        }));
+
+        feature_collection(features)
+    }
+}
+
+impl MeetingPoint {
+    /// Returns a `FeatureCollection` with the meeting point first, then each participant with
+    /// its `distance_km` to the meeting point, then every nearby service.
+    pub fn to_feature_collection(&self) -> Value {
+        let mut meeting = self.intelligence.location.to_feature();
+        meeting["properties"]["role"] = json!("meeting_point");
+        meeting["properties"]["max_distance_km"] = json!(self.max_distance_km);
+
+        let mut features = vec![meeting];
+        features.extend(self.participants.iter().zip(&self.distances_km).map(
+            |(participant, distance_km)| {
+                let mut feature = participant.to_feature();
+                feature["properties"]["role"] = json!("participant");
+                feature["properties"]["distance_km"] = json!(distance_km);
+                feature
+            },
+        ));
+        features.extend(self.intelligence.nearby_services.iter().map(|service| {
+            let mut feature = service.to_feature();
+            feature["properties"]["role"] = json!("service");
+            feature
+        }));
+        feature_collection(features)
+    }
+}
+
+impl ToGeoJson for Route {
+    /// Returns a `LineString` feature built from the decoded route polyline.
+    fn to_feature(&self) -> Value {
+        let coordinates: Vec<[f64; 2]> = decode_polyline(&self.polyline)
+            .into_iter()
+            .map(|(lat, lng)| [lng, lat])
+            .collect();
+
+        json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "LineString",
+                "coordinates": coordinates,
+            },
+            "properties": {
+                "mode": self.mode,
+                "distance_km": self.distance_km,
+                "duration_secs": self.duration_secs,
+                "steps": self.steps,
+            },
+        })
+    }
+}
+
+impl ToGeoJson for TrackAmenity {
+    fn to_feature(&self) -> Value {
+        point_feature(self, self.service.latitude, self.service.longitude)
+    }
+}
+
+impl ToGeoJson for GpxTrack {
+    /// Returns a `LineString` feature through the track points, named after the track.
+    fn to_feature(&self) -> Value {
+        let coordinates: Vec<[f64; 2]> = self
+            .points
+            .iter()
+            .map(|point| [point.longitude, point.latitude])
+            .collect();
+
+        json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "LineString",
+                "coordinates": coordinates,
+            },
+            "properties": {
+                "name": self.name,
+            },
+        })
+    }
+}
+
+impl GpxTrack {
+    /// Returns a `FeatureCollection` with the track first, followed by every amenity found
+    /// along it.
+    pub fn to_feature_collection(&self, enrichment: &TrackEnrichment) -> Value {
+        let mut track = self.to_feature();
+        track["properties"]["role"] = json!("track");
+        track["properties"]["distance_km"] = json!(enrichment.distance_km);
+
+        let mut features = vec![track];
+        features.extend(enrichment.amenities.iter().map(ToGeoJson::to_feature));
+        feature_collection(features)
+    }
+}
+
+impl TrackEnrichment {
+    /// Returns a `FeatureCollection` with the searched route first, when there is one,
+    /// followed by every amenity found along it.
+    pub fn to_feature_collection(&self) -> Value {
+        let mut features = Vec::with_capacity(self.amenities.len() + 1);
+        if let Some(route) = &self.route {
+            let mut route = route.to_feature();
+            route["properties"]["role"] = json!("route");
+            features.push(route);
+        }
+        features.extend(self.amenities.iter().map(ToGeoJson::to_feature));
+        feature_collection(features)
+    }
+}
+
+impl ToGeoJson for GeohashCluster {
+    /// Returns a `Polygon` feature outlining the cell, with its counts as properties.
+    fn to_feature(&self) -> Value {
+        let Ok(cell) = geohash::decode(&self.geohash) else {
+            return point_feature(self, self.latitude, self.longitude);
+        };
+        json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [cell.ring()],
+            },
+            "properties": {
+                "geohash": self.geohash,
+                "latitude": self.latitude,
+                "longitude": self.longitude,
+                "count": self.count,
+                "by_type": self.by_type,
+            },
+        })
+    }
+}
+
+impl ToGeoJson for ServiceCluster {
+    /// Returns a `Point` feature at the centroid, with the cluster's size and counts as
+    /// properties and its members' names.
+    fn to_feature(&self) -> Value {
+        json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Point",
+                "coordinates": [self.longitude, self.latitude],
+            },
+            "properties": {
+                "id": self.id,
+                "count": self.count,
+                "radius_m": self.radius_m,
+                "by_type": self.by_type,
+                "names": self.services.iter().map(|s| &s.name).collect::<Vec<_>>(),
+            },
+        })
+    }
+}
+
+impl ToGeoJson for HeatmapCell {
+    /// Returns a `Polygon` feature outlining the cell, with its position and counts as
+    /// properties.
+    fn to_feature(&self) -> Value {
+        json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [self.ring()],
+            },
+            "properties": {
+                "row": self.row,
+                "column": self.column,
+                "count": self.count,
+                "by_type": self.by_type,
+            },
+        })
+    }
+}
+
+impl Heatmap {
+    /// Returns a `FeatureCollection` with one polygon per cell, empty cells included. Each
+    /// cell's `intensity` is its count relative to the densest cell, from 0 to 1.
+    pub fn to_feature_collection(&self) -> Value {
+        let features = self
+            .cells
+            .iter()
+            .map(|cell| {
+                let mut feature = cell.to_feature();
+                feature["properties"]["intensity"] = json!(if self.max_count == 0 {
+                    0.0
+                } else {
+                    cell.count as f64 / self.max_count as f64
+                });
+                feature
+            })
+            .collect();
+        let mut collection = feature_collection(features);
+        collection["bbox"] = json!([self.min_lng, self.min_lat, self.max_lng, self.max_lat]);
+        collection
+    }
+}
+
+impl ToGeoJson for Isochrone {
+    /// Returns a `Polygon` feature outlining the reachable area.
+    fn to_feature(&self) -> Value {
+        let ring: Vec<[f64; 2]> = self.polygon.iter().map(|&(lat, lng)| [lng, lat]).collect();
+
+        json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [ring],
+            },
+            "properties": {
+                "latitude": self.latitude,
+                "longitude": self.longitude,
+                "minutes": self.minutes,
+                "mode": self.mode,
+            },
+        })
+    }
+}
+
+impl ToGeoJson for AdminArea {
+    /// Returns a `MultiPolygon` feature outlining the area, or a feature with a null geometry
+    /// when the boundary was not fetched.
+    fn to_feature(&self) -> Value {
+        let geometry = match &self.geometry {
+            Some(polygons) => json!({
+                "type": "MultiPolygon",
+                "coordinates": polygons
+                    .iter()
+                    .map(|rings| {
+                        rings
+                            .iter()
+                            .map(|ring| ring.iter().map(|&(lat, lng)| [lng, lat]).collect())
+                            .collect::<Vec<Vec<[f64; 2]>>>()
+                    })
+                    .collect::<Vec<_>>(),
+            }),
+            None => Value::Null,
+        };
+        json!({
+            "type": "Feature",
+            "geometry": geometry,
+            "properties": {
+                "id": self.id,
+                "name": self.name,
+                "admin_level": self.admin_level,
+                "code": self.code,
+            },
+        })
+    }
+}
+
+impl AdminHierarchy {
+    /// Returns a `FeatureCollection` with the queried point first, followed by each area from
+    /// the country down.
+    pub fn to_feature_collection(&self) -> Value {
+        let mut features = vec![json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Point",
+                "coordinates": [self.longitude, self.latitude],
+            },
+            "properties": { "role": "point" },
+        })];
+        features.extend(self.areas.iter().map(ToGeoJson::to_feature));
+        feature_collection(features)
+    }
+}
+
+impl Geometry {
+    /// Reads the polygons of a GeoJSON `Polygon`, `MultiPolygon`, `Feature`, or
+    /// `FeatureCollection`; other geometry types are ignored.
+    pub fn from_geojson(value: &Value) -> Result<Self, MapradarError> {
+        let mut polygons = Vec::new();
+        collect_polygons(value, &mut polygons)?;
+        if polygons.is_empty() {
+            return Err(MapradarError::InvalidInput(
+                "GeoJSON contains no Polygon or MultiPolygon geometry".to_string(),
+            ));
+        }
+        Ok(Self::Polygons(polygons))
+    }
+}
+
+type Polygon = Vec<Vec<(f64, f64)>>;
+
+fn collect_polygons(value: &Value, polygons: &mut Vec<Polygon>) -> Result<(), MapradarError> {
+    match value["type"].as_str() {
+        Some("FeatureCollection") => {
+            for feature in value["features"].as_array().into_iter().flatten() {
+                collect_polygons(feature, polygons)?;
+            }
+        }
+        Some("Feature") => collect_polygons(&value["geometry"], polygons)?,
+        Some("GeometryCollection") => {
+            for geometry in value["geometries"].as_array().into_iter().flatten() {
+                collect_polygons(geometry, polygons)?;
+            }
+        }
+        Some("Polygon") => polygons.push(parse_polygon(&value["coordinates"])?),
+        Some("MultiPolygon") => {
+            for polygon in value["coordinates"].as_array().into_iter().flatten() {
+                polygons.push(parse_polygon(polygon)?);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Converts GeoJSON `[lng, lat]` rings into `(lat, lng)` rings.
+fn parse_polygon(coordinates: &Value) -> Result<Polygon, MapradarError> {
+    let invalid = || MapradarError::InvalidInput("Malformed polygon coordinates".to_string());
+    coordinates
+        .as_array()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|ring| {
+            ring.as_array()
+                .ok_or_else(invalid)?
+                .iter()
+                .map(
+                    |position| match (position[0].as_f64(), position[1].as_f64()) {
+                        (Some(lng), Some(lat)) => Ok((lat, lng)),
+                        _ => Err(invalid()),
+                    },
+                )
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,159 @@
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::MapradarError, kml::escape, models::TrackEnrichment};
+
+/// One recorded point of a GPX track.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Meters above sea level, from `<ele>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elevation_m: Option<f64>,
+    /// Timestamp as written in `<time>`, normally ISO 8601.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+}
+
+/// The points of a GPX file, joined in document order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GpxTrack {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub points: Vec<TrackPoint>,
+}
+
+impl GpxTrack {
+    /// Parses a GPX document, reading every track segment's `<trkpt>`s, or the `<rtept>`s of
+    /// its routes when it holds no tracks. The name is taken from the first track or route,
+    /// falling back to the file's metadata.
+    pub fn parse(xml: &str) -> Result<Self, MapradarError> {
+        let document = roxmltree::Document::parse(xml)
+            .map_err(|err| MapradarError::InvalidInput(format!("Invalid GPX: {}", err)))?;
+        let root = document.root_element();
+        if !root.has_tag_name("gpx") {
+            return Err(MapradarError::InvalidInput(format!(
+                "Invalid GPX: root element is <{}>, not <gpx>",
+                root.tag_name().name()
+            )));
+        }
+
+        let (container, point_tag) = if root.descendants().any(|n| n.has_tag_name("trkpt")) {
+            ("trk", "trkpt")
+        } else {
+            ("rte", "rtept")
+        };
+
+        let points = root
+            .descendants()
+            .filter(|node| node.has_tag_name(point_tag))
+            .map(|node| {
+                let coordinate = |attribute: &str| {
+                    node.attribute(attribute)
+                        .and_then(|value| value.trim().parse::<f64>().ok())
+                        .ok_or_else(|| {
+                            MapradarError::InvalidInput(format!(
+                                "Invalid GPX: <{}> without a numeric {} attribute",
+                                point_tag, attribute
+                            ))
+                        })
+                };
+                Ok(TrackPoint {
+                    latitude: coordinate("lat")?,
+                    longitude: coordinate("lon")?,
+                    elevation_m: child_text(node, "ele").and_then(|text| text.parse().ok()),
+                    time: child_text(node, "time").map(str::to_string),
+                })
+            })
+            .collect::<Result<Vec<_>, MapradarError>>()?;
+
+        let name = root
+            .children()
+            .find(|node| node.has_tag_name(container))
+            .and_then(|node| child_text(node, "name"))
+            .or_else(|| {
+                root.children()
+                    .find(|node| node.has_tag_name("metadata"))
+                    .and_then(|node| child_text(node, "name"))
+            })
+            .map(str::to_string);
+
+        Ok(Self { name, points })
+    }
+
+    /// The track's `(lat, lng)` vertices.
+    pub fn path(&self) -> Vec<(f64, f64)> {
+        self.points
+            .iter()
+            .map(|point| (point.latitude, point.longitude))
+            .collect()
+    }
+
+    /// Writes the track as GPX 1.1, with each amenity as a waypoint ahead of it.
+    ///
+    /// Waypoints carry the place name, its category as `<type>`, and a description giving its
+    /// position along the track, its distance off the track, and its address.
+    pub fn to_gpx(&self, enrichment: &TrackEnrichment) -> String {
+        let mut gpx = String::new();
+        gpx.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            gpx,
+            "<gpx version=\"1.1\" creator=\"mapradar {}\" xmlns=\"http://www.topografix.com/GPX/1/1\">",
+            env!("CARGO_PKG_VERSION")
+        );
+
+        for amenity in &enrichment.amenities {
+            let service = &amenity.service;
+            let mut description = format!(
+                "{:.2} km along the track, {:.0} m off it",
+                amenity.distance_along_km,
+                service.distance_km * 1000.0
+            );
+            if let Some(address) = &service.address {
+                let _ = write!(description, "; {}", address);
+            }
+            let _ = writeln!(
+                gpx,
+                "<wpt lat=\"{}\" lon=\"{}\"><name>{}</name><desc>{}</desc><type>{}</type></wpt>",
+                service.latitude,
+                service.longitude,
+                escape(&service.name),
+                escape(&description),
+                escape(&service.service_type.to_string())
+            );
+        }
+
+        gpx.push_str("<trk>\n");
+        if let Some(name) = &self.name {
+            let _ = writeln!(gpx, "<name>{}</name>", escape(name));
+        }
+        gpx.push_str("<trkseg>\n");
+        for point in &self.points {
+            let _ = write!(
+                gpx,
+                "<trkpt lat=\"{}\" lon=\"{}\">",
+                point.latitude, point.longitude
+            );
+            if let Some(elevation) = point.elevation_m {
+                let _ = write!(gpx, "<ele>{}</ele>", elevation);
+            }
+            if let Some(time) = &point.time {
+                let _ = write!(gpx, "<time>{}</time>", escape(time));
+            }
+            gpx.push_str("</trkpt>\n");
+        }
+        gpx.push_str("</trkseg>\n</trk>\n</gpx>\n");
+
+        gpx
+    }
+}
+
+fn child_text<'a>(node: roxmltree::Node<'a, '_>, tag: &str) -> Option<&'a str> {
+    node.children()
+        .find(|child| child.has_tag_name(tag))
+        .and_then(|child| child.text())
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
@@ -0,0 +1,249 @@
+//! gRPC server implementing the `mapradar.v1.Mapradar` service from `proto/mapradar.proto`.
+//!
+//! `StreamNearby` sends each service as its type's search completes, so clients can render
+//! results before the slowest search finishes. Errors map to gRPC status codes the same way the
+//! REST server maps them to HTTP statuses. The same port answers `/metrics`, `/healthz`, and `/readyz` over HTTP/1.1.
+
+use std::net::SocketAddr;
+
+use futures::{SinkExt, StreamExt, channel::mpsc};
+use tonic::{Request, Response, Status, service::Routes, transport::Server};
+
+use crate::{
+    client::MapradarClient,
+    coordinates::Coordinates,
+    error::MapradarError,
+    health,
+    metrics::{self, Metrics},
+    models::{PlaceCategory, SearchQuery, TravelMode},
+    rpc::http::shutdown_signal,
+};
+
+pub mod proto;
+
+include!(concat!(env!("OUT_DIR"), "/mapradar.v1.Mapradar.rs"));
+
+use mapradar_server::{Mapradar, MapradarServer};
+
+const DEFAULT_TYPES: &str = "bank";
+const DEFAULT_RADIUS_KM: f64 = 1.0;
+const DEFAULT_MAX_RESULTS: usize = 10;
+/// Services buffered per `StreamNearby` call before the search waits on the client.
+const STREAM_BUFFER: usize = 16;
+
+/// Serves the gRPC API on `addr` until Ctrl-C, letting in-flight calls finish.
+pub async fn serve(client: MapradarClient, addr: SocketAddr) -> Result<(), MapradarError> {
+    let metrics = client.metrics().cloned().unwrap_or_default();
+    let http_routes = metrics::router(metrics.clone()).merge(health::router(Some(client.clone())));
+    let routes =
+        Routes::from(http_routes).add_service(MapradarServer::new(GrpcService { client, metrics }));
+    Server::builder()
+        .accept_http1(true)
+        .add_routes(routes)
+        .serve_with_shutdown(addr, shutdown_signal())
+        .await
+        .map_err(|e| MapradarError::Unknown(format!("gRPC server failed: {}", e)))
+}
+
+struct GrpcService {
+    client: MapradarClient,
+    metrics: Metrics,
+}
+
+impl GrpcService {
+    /// Counts the call in the metrics, converting a failure to its status.
+    fn observe<T>(&self, method: &str, result: Result<T, MapradarError>) -> Result<T, Status> {
+        self.metrics
+            .record_request("grpc", method, result.as_ref().err());
+        Ok(result?)
+    }
+
+    /// Counts the call only if it failed, for calls that finish counting elsewhere.
+    fn observe_failure<T>(
+        &self,
+        method: &str,
+        result: Result<T, MapradarError>,
+    ) -> Result<T, Status> {
+        if let Err(err) = &result {
+            self.metrics.record_request("grpc", method, Some(err));
+        }
+        Ok(result?)
+    }
+}
+
+#[tonic::async_trait]
+impl Mapradar for GrpcService {
+    async fn geocode(
+        &self,
+        request: Request<proto::GeocodeRequest>,
+    ) -> Result<Response<proto::Location>, Status> {
+        let result = self.client.geocode_async(&request.get_ref().address).await;
+        let location = self.observe("Geocode", result)?;
+        Ok(Response::new(location.into()))
+    }
+
+    async fn reverse_geocode(
+        &self,
+        request: Request<proto::ReverseGeocodeRequest>,
+    ) -> Result<Response<proto::Location>, Status> {
+        let point = request.get_ref();
+        let result = match Coordinates::new(point.latitude, point.longitude) {
+            Ok(point) => self.client.reverse_geocode_async(point).await,
+            Err(err) => Err(err),
+        };
+        let location = self.observe("ReverseGeocode", result)?;
+        Ok(Response::new(location.into()))
+    }
+
+    async fn nearby(
+        &self,
+        request: Request<proto::NearbyRequest>,
+    ) -> Result<Response<proto::NearbyResponse>, Status> {
+        let result = async {
+            let search = NearbySearch::try_from(request.into_inner())?;
+            self.client
+                .fetch_intelligence_async(
+                    search.query,
+                    search.types,
+                    search.radius_km,
+                    search.max_results,
+                )
+                .await
+        }
+        .await;
+        let intel = self.observe("Nearby", result)?;
+        Ok(Response::new(proto::NearbyResponse {
+            location: Some(intel.location.into()),
+            services: intel.nearby_services.into_iter().map(Into::into).collect(),
+            warnings: intel.warnings,
+        }))
+    }
+
+    type StreamNearbyStream = mpsc::Receiver<Result<proto::Service, Status>>;
+
+    async fn stream_nearby(
+        &self,
+        request: Request<proto::NearbyRequest>,
+    ) -> Result<Response<Self::StreamNearbyStream>, Status> {
+        let search = NearbySearch::try_from(request.into_inner());
+        let search = self.observe_failure("StreamNearby", search)?;
+        let client = self.client.clone();
+        let metrics = self.metrics.clone();
+        let (mut tx, rx) = mpsc::channel(STREAM_BUFFER);
+        tokio::spawn(async move {
+            let services = client
+                .fetch_intelligence_stream(
+                    search.query,
+                    search.types,
+                    search.radius_km,
+                    search.max_results,
+                )
+                .await;
+            metrics.record_request("grpc", "StreamNearby", services.as_ref().err());
+            let mut services = match services {
+                Ok((_, services)) => services,
+                Err(err) => {
+                    let _ = tx.send(Err(err.into())).await;
+                    return;
+                }
+            };
+            while let Some(service) = services.next().await {
+                let item = service.map(Into::into).map_err(Into::into);
+                if tx.send(item).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Response::new(rx))
+    }
+
+    async fn route(
+        &self,
+        request: Request<proto::RouteRequest>,
+    ) -> Result<Response<proto::Route>, Status> {
+        let result = async {
+            let request = request.into_inner();
+            let origin = place_query(request.origin, "origin")?;
+            let destination = place_query(request.destination, "destination")?;
+            let mode = if request.mode.is_empty() {
+                TravelMode::default()
+            } else {
+                request.mode.parse()?
+            };
+            self.client.route_async(origin, destination, mode).await
+        }
+        .await;
+        let route = self.observe("Route", result)?;
+        Ok(Response::new(route.into()))
+    }
+}
+
+/// A `NearbyRequest` with defaults applied to unset fields.
+struct NearbySearch {
+    query: SearchQuery,
+    types: Vec<PlaceCategory>,
+    radius_km: f64,
+    max_results: usize,
+}
+
+impl TryFrom<proto::NearbyRequest> for NearbySearch {
+    type Error = MapradarError;
+
+    fn try_from(request: proto::NearbyRequest) -> Result<Self, Self::Error> {
+        let types = if request.types.is_empty() {
+            vec![DEFAULT_TYPES.to_string()]
+        } else {
+            request.types
+        };
+        Ok(Self {
+            query: place_query(request.place, "place")?,
+            types: types
+                .iter()
+                .map(|t| t.parse())
+                .collect::<Result<_, MapradarError>>()?,
+            radius_km: if request.radius_km > 0.0 {
+                request.radius_km
+            } else {
+                DEFAULT_RADIUS_KM
+            },
+            max_results: match request.max_results {
+                0 => DEFAULT_MAX_RESULTS,
+                n => n as usize,
+            },
+        })
+    }
+}
+
+fn place_query(place: Option<proto::Place>, field: &str) -> Result<SearchQuery, MapradarError> {
+    match place.and_then(|p| p.place) {
+        Some(proto::place::Place::Address(address)) => Ok(SearchQuery::from_address(address)),
+        Some(proto::place::Place::Coordinates(point)) => {
+            SearchQuery::from_coordinates(point.latitude, point.longitude)
+        }
+        None => Err(MapradarError::InvalidInput(format!(
+            "{} requires an address or coordinates",
+            field
+        ))),
+    }
+}
+
+impl From<MapradarError> for Status {
+    fn from(error: MapradarError) -> Self {
+        let message = error.to_string();
+        match error {
+            MapradarError::InvalidInput(_) | MapradarError::InvalidCoordinates { .. } => {
+                Status::invalid_argument(message)
+            }
+            MapradarError::AuthFailed { .. } => Status::unauthenticated(message),
+            MapradarError::AddressNotFound { .. } | MapradarError::ZeroResults => {
+                Status::not_found(message)
+            }
+            MapradarError::QuotaExceeded { .. } => Status::resource_exhausted(message),
+            MapradarError::Unsupported(_) => Status::unimplemented(message),
+            MapradarError::Network(_)
+            | MapradarError::Api { .. }
+            | MapradarError::Deserialization(_) => Status::unavailable(message),
+            _ => Status::internal(message),
+        }
+    }
+}
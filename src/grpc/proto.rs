@@ -0,0 +1,200 @@
+//! Protobuf messages of `mapradar.v1`, kept in step with `proto/mapradar.proto` by hand.
+
+use crate::models::{GeoLocation, NearbyService, Route as RouteModel};
+
+/// An address, or a point.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Place {
+    #[prost(oneof = "place::Place", tags = "1, 2")]
+    pub place: Option<place::Place>,
+}
+
+pub mod place {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Place {
+        #[prost(string, tag = "1")]
+        Address(String),
+        #[prost(message, tag = "2")]
+        Coordinates(super::Coordinates),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Coordinates {
+    #[prost(double, tag = "1")]
+    pub latitude: f64,
+    #[prost(double, tag = "2")]
+    pub longitude: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GeocodeRequest {
+    #[prost(string, tag = "1")]
+    pub address: String,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct ReverseGeocodeRequest {
+    #[prost(double, tag = "1")]
+    pub latitude: f64,
+    #[prost(double, tag = "2")]
+    pub longitude: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Location {
+    #[prost(string, tag = "1")]
+    pub address: String,
+    #[prost(double, tag = "2")]
+    pub latitude: f64,
+    #[prost(double, tag = "3")]
+    pub longitude: f64,
+    #[prost(string, optional, tag = "4")]
+    pub city: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    pub state: Option<String>,
+    #[prost(string, tag = "6")]
+    pub country: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct NearbyRequest {
+    #[prost(message, optional, tag = "1")]
+    pub place: Option<Place>,
+    #[prost(string, repeated, tag = "2")]
+    pub types: Vec<String>,
+    #[prost(double, tag = "3")]
+    pub radius_km: f64,
+    #[prost(uint32, tag = "4")]
+    pub max_results: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Service {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub r#type: String,
+    #[prost(double, tag = "3")]
+    pub latitude: f64,
+    #[prost(double, tag = "4")]
+    pub longitude: f64,
+    #[prost(double, tag = "5")]
+    pub distance_km: f64,
+    #[prost(string, optional, tag = "6")]
+    pub address: Option<String>,
+    #[prost(float, optional, tag = "7")]
+    pub rating: Option<f32>,
+    #[prost(string, optional, tag = "8")]
+    pub place_id: Option<String>,
+    #[prost(string, optional, tag = "9")]
+    pub phone_number: Option<String>,
+    #[prost(bool, optional, tag = "10")]
+    pub open_now: Option<bool>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct NearbyResponse {
+    #[prost(message, optional, tag = "1")]
+    pub location: Option<Location>,
+    #[prost(message, repeated, tag = "2")]
+    pub services: Vec<Service>,
+    #[prost(string, repeated, tag = "3")]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RouteRequest {
+    #[prost(message, optional, tag = "1")]
+    pub origin: Option<Place>,
+    #[prost(message, optional, tag = "2")]
+    pub destination: Option<Place>,
+    #[prost(string, tag = "3")]
+    pub mode: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RouteStep {
+    #[prost(string, tag = "1")]
+    pub instruction: String,
+    #[prost(double, tag = "2")]
+    pub distance_km: f64,
+    #[prost(uint64, tag = "3")]
+    pub duration_secs: u64,
+    #[prost(message, optional, tag = "4")]
+    pub start: Option<Coordinates>,
+    #[prost(message, optional, tag = "5")]
+    pub end: Option<Coordinates>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Route {
+    #[prost(string, tag = "1")]
+    pub mode: String,
+    #[prost(double, tag = "2")]
+    pub distance_km: f64,
+    #[prost(uint64, tag = "3")]
+    pub duration_secs: u64,
+    #[prost(string, tag = "4")]
+    pub polyline: String,
+    #[prost(message, repeated, tag = "5")]
+    pub steps: Vec<RouteStep>,
+}
+
+impl From<GeoLocation> for Location {
+    fn from(location: GeoLocation) -> Self {
+        Self {
+            address: location.address,
+            latitude: location.latitude,
+            longitude: location.longitude,
+            city: location.city,
+            state: location.state,
+            country: location.country,
+        }
+    }
+}
+
+impl From<NearbyService> for Service {
+    fn from(service: NearbyService) -> Self {
+        Self {
+            name: service.name,
+            r#type: service.service_type.to_string(),
+            latitude: service.latitude,
+            longitude: service.longitude,
+            distance_km: service.distance_km,
+            address: service.address,
+            rating: service.rating,
+            place_id: service.place_id,
+            phone_number: service.phone_number,
+            open_now: service.open_now,
+        }
+    }
+}
+
+impl From<RouteModel> for Route {
+    fn from(route: RouteModel) -> Self {
+        Self {
+            mode: format!("{:?}", route.mode).to_lowercase(),
+            distance_km: route.distance_km,
+            duration_secs: route.duration_secs,
+            polyline: route.polyline,
+            steps: route
+                .steps
+                .into_iter()
+                .map(|step| RouteStep {
+                    instruction: step.instruction,
+                    distance_km: step.distance_km,
+                    duration_secs: step.duration_secs,
+                    start: Some(Coordinates {
+                        latitude: step.start_latitude,
+                        longitude: step.start_longitude,
+                    }),
+                    end: Some(Coordinates {
+                        latitude: step.end_latitude,
+                        longitude: step.end_longitude,
+                    }),
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! Liveness and readiness reports for server modes, served at `/healthz` and `/readyz`.
+//!
+//! Liveness only says the process is answering. Readiness checks the cache backends and, unless
+//! asked for a cache-only check, sends one cheap upstream request (a reverse geocode of 0,0) to
+//! confirm the provider is reachable and accepts the credentials. Upstream probes are reused for
+//! [`PROBE_TTL`] so frequent readiness checks do not become upstream traffic.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::MapradarError;
+
+/// How long a server reuses an upstream probe result.
+pub const PROBE_TTL: Duration = Duration::from_secs(30);
+
+/// Outcome of the upstream probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderStatus {
+    Ok,
+    /// The request never got an answer (DNS, connection, or timeout failure).
+    Unreachable,
+    /// The provider rejected the credentials.
+    Unauthorized,
+    QuotaExceeded,
+    /// The provider answered with an error, or its response could not be read.
+    Error,
+    /// Only the cache was checked.
+    NotProbed,
+}
+
+/// Upstream provider health.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderHealth {
+    pub name: String,
+    pub status: ProviderStatus,
+    /// Whether the provider accepted the credentials; `None` when it was not asked.
+    pub credentials_valid: Option<bool>,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl ProviderHealth {
+    pub(crate) fn not_probed(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            status: ProviderStatus::NotProbed,
+            credentials_valid: None,
+            latency_ms: None,
+            error: None,
+        }
+    }
+
+    /// Classifies the probe result. A probe that finds no address still proves the provider is
+    /// reachable and accepts the credentials.
+    pub(crate) fn from_probe<T>(
+        name: &str,
+        result: Result<T, MapradarError>,
+        latency: Duration,
+    ) -> Self {
+        let (status, credentials_valid) = match &result {
+            Ok(_) | Err(MapradarError::AddressNotFound { .. } | MapradarError::ZeroResults) => {
+                (ProviderStatus::Ok, Some(true))
+            }
+            Err(MapradarError::AuthFailed { .. }) => (ProviderStatus::Unauthorized, Some(false)),
+            Err(MapradarError::QuotaExceeded { .. }) => (ProviderStatus::QuotaExceeded, Some(true)),
+            Err(MapradarError::Network(_)) => (ProviderStatus::Unreachable, None),
+            Err(_) => (ProviderStatus::Error, None),
+        };
+        Self {
+            name: name.to_string(),
+            status,
+            credentials_valid,
+            latency_ms: Some(latency.as_millis() as u64),
+            error: result
+                .err()
+                .filter(|_| status != ProviderStatus::Ok)
+                .map(|e| e.to_string()),
+        }
+    }
+}
+
+/// Health of the response cache tiers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheHealth {
+    /// `false` when caching is turned off, e.g. with `--no-cache`.
+    pub enabled: bool,
+    pub memory_entries: u64,
+    /// The on-disk tier, when one is configured.
+    pub disk: Option<DiskHealth>,
+}
+
+impl CacheHealth {
+    pub fn healthy(&self) -> bool {
+        self.disk.as_ref().is_none_or(|disk| disk.healthy)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskHealth {
+    pub healthy: bool,
+    pub path: Option<String>,
+    pub entries: Option<usize>,
+    pub error: Option<String>,
+}
+
+/// Body of `/readyz`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Readiness {
+    pub ready: bool,
+    /// `None` when the server has no credentials of its own and callers supply them.
+    pub provider: Option<ProviderHealth>,
+    pub cache: Option<CacheHealth>,
+}
+
+impl Readiness {
+    pub(crate) fn new(provider: ProviderHealth, cache: CacheHealth) -> Self {
+        let provider_ready = matches!(
+            provider.status,
+            ProviderStatus::Ok | ProviderStatus::NotProbed
+        );
+        Self {
+            ready: provider_ready && cache.healthy(),
+            provider: Some(provider),
+            cache: Some(cache),
+        }
+    }
+
+    /// Readiness of a server without a default client, which can always take requests that
+    /// bring their own key.
+    pub fn caller_credentials() -> Self {
+        Self {
+            ready: true,
+            provider: None,
+            cache: None,
+        }
+    }
+}
+
+/// Body of `/healthz`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Liveness {
+    pub status: String,
+    pub version: String,
+}
+
+impl Default for Liveness {
+    fn default() -> Self {
+        Self {
+            status: "ok".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+pub(crate) use server::router;
+
+#[cfg(feature = "server")]
+mod server {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Instant,
+    };
+
+    use axum::{
+        Json, Router,
+        extract::{Query, State},
+        http::StatusCode,
+        routing::get,
+    };
+    use serde::Deserialize;
+
+    use super::{Liveness, PROBE_TTL, ProviderHealth, Readiness};
+    use crate::client::MapradarClient;
+
+    #[derive(Clone)]
+    struct HealthState {
+        client: Option<MapradarClient>,
+        last_probe: Arc<Mutex<Option<(Instant, ProviderHealth)>>>,
+    }
+
+    #[derive(Deserialize)]
+    struct ReadyParams {
+        /// `cache` skips the upstream probe.
+        #[serde(default)]
+        probe: Option<String>,
+    }
+
+    /// Router serving `GET /healthz` and `GET /readyz` for `client`, or for a server whose
+    /// callers supply their own credentials when it is `None`.
+    pub(crate) fn router(client: Option<MapradarClient>) -> Router {
+        let state = HealthState {
+            client,
+            last_probe: Arc::new(Mutex::new(None)),
+        };
+        Router::new()
+            .route("/healthz", get(|| async { Json(Liveness::default()) }))
+            .route("/readyz", get(ready))
+            .with_state(state)
+    }
+
+    async fn ready(
+        State(state): State<HealthState>,
+        Query(params): Query<ReadyParams>,
+    ) -> (StatusCode, Json<Readiness>) {
+        let Some(client) = &state.client else {
+            return (StatusCode::OK, Json(Readiness::caller_credentials()));
+        };
+        let cache_only = params.probe.as_deref() == Some("cache");
+        let cached = state
+            .last_probe
+            .lock()
+            .ok()
+            .and_then(|last| last.clone())
+            .filter(|(at, _)| at.elapsed() < PROBE_TTL)
+            .map(|(_, provider)| provider);
+
+        let readiness = match (cache_only, cached) {
+            (true, _) => client.readiness_async(false).await,
+            (false, Some(provider)) => client.readiness_with(provider),
+            (false, None) => {
+                let readiness = client.readiness_async(true).await;
+                if let (Ok(mut last), Some(provider)) =
+                    (state.last_probe.lock(), readiness.provider.clone())
+                {
+                    *last = Some((Instant::now(), provider));
+                }
+                readiness
+            }
+        };
+        let status = if readiness.ready {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+        (status, Json(readiness))
+    }
+}
@@ -0,0 +1,179 @@
+//! Heatmap grids: amenity counts per cell over an area, for market analysis.
+//!
+//! A grid tiles a bounding box into cells of about the same size in metres, row by row from the
+//! south-west corner. Cells keep a single longitude width, taken at the box's middle latitude,
+//! so cells far north or south of it are somewhat narrower or wider than their nominal size.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    coordinates::Coordinates,
+    error::MapradarError,
+    geo::{EARTH_RADIUS_KM, Geometry},
+    models::NearbyService,
+};
+
+/// Most cells a grid may have; each one costs a search per service type.
+pub const MAX_CELLS: usize = 10_000;
+
+/// One cell of a heatmap grid, with the results counted in it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeatmapCell {
+    /// Rows count north from 0 at the southern edge.
+    pub row: usize,
+    /// Columns count east from 0 at the western edge.
+    pub column: usize,
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+    pub count: usize,
+    /// Results per service type.
+    pub by_type: BTreeMap<String, usize>,
+}
+
+impl HeatmapCell {
+    /// Centre of the cell.
+    pub fn center(&self) -> Coordinates {
+        Coordinates::new(
+            (self.south + self.north) / 2.0,
+            (self.west + self.east) / 2.0,
+        )
+        .expect("a heatmap cell lies within range")
+    }
+
+    /// The cell as a closed `[lng, lat]` ring, counter-clockwise from the south-west corner.
+    pub fn ring(&self) -> Vec<[f64; 2]> {
+        vec![
+            [self.west, self.south],
+            [self.east, self.south],
+            [self.east, self.north],
+            [self.west, self.north],
+            [self.west, self.south],
+        ]
+    }
+}
+
+/// Results counted per cell over a bounding box.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Heatmap {
+    pub min_lng: f64,
+    pub min_lat: f64,
+    pub max_lng: f64,
+    pub max_lat: f64,
+    /// Nominal cell size.
+    pub cell_m: f64,
+    pub rows: usize,
+    pub columns: usize,
+    /// Results counted across every cell.
+    pub total: usize,
+    /// Count of the densest cell, for scaling colours.
+    pub max_count: usize,
+    /// Every cell, row by row from the south-west corner.
+    pub cells: Vec<HeatmapCell>,
+    /// Searches that failed or were cut short without failing the whole heatmap.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl Heatmap {
+    /// Tiles the envelope of `area` into empty cells about `cell_m` metres square. Cells along
+    /// the northern and eastern edges are clipped to the box.
+    pub fn new(area: &Geometry, cell_m: f64) -> Result<Self, MapradarError> {
+        if !cell_m.is_finite() || cell_m <= 0.0 {
+            return Err(MapradarError::InvalidInput(
+                "Heatmap cell size must be positive".to_string(),
+            ));
+        }
+        let (min_lng, min_lat, max_lng, max_lat) = area.envelope().ok_or_else(|| {
+            MapradarError::InvalidInput("The heatmap area has no vertices".to_string())
+        })?;
+        if min_lng > max_lng {
+            return Err(MapradarError::InvalidInput(
+                "Heatmap areas crossing the antimeridian are not supported".to_string(),
+            ));
+        }
+
+        let (lat_step, lng_step) = steps(cell_m, min_lat, max_lat);
+        let count = |span: f64, step: f64| ((span / step).ceil() as usize).max(1);
+        let rows = count(max_lat - min_lat, lat_step);
+        let columns = count(max_lng - min_lng, lng_step);
+        if rows.saturating_mul(columns) > MAX_CELLS {
+            return Err(MapradarError::InvalidInput(format!(
+                "A {:.0} m grid over this area has {} cells (at most {}); use larger cells",
+                cell_m,
+                rows.saturating_mul(columns),
+                MAX_CELLS
+            )));
+        }
+
+        let cells = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (row, column)))
+            .map(|(row, column)| {
+                let south = min_lat + row as f64 * lat_step;
+                let west = min_lng + column as f64 * lng_step;
+                HeatmapCell {
+                    row,
+                    column,
+                    south,
+                    west,
+                    north: (south + lat_step).min(max_lat),
+                    east: (west + lng_step).min(max_lng),
+                    count: 0,
+                    by_type: BTreeMap::new(),
+                }
+            })
+            .collect();
+        Ok(Self {
+            min_lng,
+            min_lat,
+            max_lng,
+            max_lat,
+            cell_m,
+            rows,
+            columns,
+            total: 0,
+            max_count: 0,
+            cells,
+            warnings: Vec::new(),
+        })
+    }
+
+    /// Index into `cells` of the cell containing a point, or `None` outside the box.
+    pub fn cell_index(&self, latitude: f64, longitude: f64) -> Option<usize> {
+        if !(self.min_lat..=self.max_lat).contains(&latitude)
+            || !(self.min_lng..=self.max_lng).contains(&longitude)
+        {
+            return None;
+        }
+        let (lat_step, lng_step) = steps(self.cell_m, self.min_lat, self.max_lat);
+        let row = (((latitude - self.min_lat) / lat_step) as usize).min(self.rows - 1);
+        let column = (((longitude - self.min_lng) / lng_step) as usize).min(self.columns - 1);
+        Some(row * self.columns + column)
+    }
+
+    /// Counts each service into the cell it lies in; services outside the box are skipped.
+    pub fn add<'a>(&mut self, services: impl IntoIterator<Item = &'a NearbyService>) {
+        for service in services {
+            let Some(index) = self.cell_index(service.latitude, service.longitude) else {
+                continue;
+            };
+            let cell = &mut self.cells[index];
+            cell.count += 1;
+            *cell
+                .by_type
+                .entry(service.service_type.to_string())
+                .or_insert(0) += 1;
+            self.total += 1;
+            self.max_count = self.max_count.max(cell.count);
+        }
+    }
+}
+
+/// Cell height and width in degrees, the width taken at the middle latitude.
+fn steps(cell_m: f64, min_lat: f64, max_lat: f64) -> (f64, f64) {
+    let lat_step = (cell_m / 1000.0 / EARTH_RADIUS_KM).to_degrees();
+    let mid_lat = ((min_lat + max_lat) / 2.0).to_radians();
+    (lat_step, lat_step / mid_lat.cos().max(f64::EPSILON))
+}
@@ -0,0 +1,171 @@
+//! Local history of executed queries, for auditing what was searched and re-running it.
+//!
+//! Each entry keeps the command, its arguments, when it ran, and a SHA-256 hash of the result
+//! it printed, so that a re-run can be checked for changes without storing the result itself.
+
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::error::MapradarError;
+
+/// Shared handle to the history database; clones record into the same history.
+#[derive(Clone)]
+pub struct QueryHistory {
+    conn: Arc<Mutex<Connection>>,
+}
+
+/// One executed query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: i64,
+    /// Unix timestamp of the run, in seconds.
+    pub executed_at: u64,
+    pub command: String,
+    /// Arguments after the program name, as given, with credentials left out.
+    pub args: Vec<String>,
+    /// Hex SHA-256 of the JSON result, once one was produced.
+    pub result_hash: Option<String>,
+}
+
+impl QueryHistory {
+    /// Opens (or creates) the history database at `path`.
+    pub fn open(path: &Path) -> Result<Self, MapradarError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                executed_at INTEGER NOT NULL,
+                command TEXT NOT NULL,
+                args TEXT NOT NULL,
+                result_hash TEXT
+            );",
+        )?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Default location: `$XDG_DATA_HOME/mapradar/history.sqlite`, falling back to
+    /// `~/.local/share`, next to the usage ledger.
+    pub fn default_path() -> PathBuf {
+        let base = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })
+            .unwrap_or_else(std::env::temp_dir);
+        base.join("mapradar").join("history.sqlite")
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, MapradarError> {
+        self.conn
+            .lock()
+            .map_err(|_| MapradarError::Unknown("Query history lock poisoned".to_string()))
+    }
+
+    /// Records a query about to run, returning its ID.
+    pub fn record(&self, command: &str, args: &[String]) -> Result<i64, MapradarError> {
+        let conn = self.lock()?;
+        conn.execute(
+            "INSERT INTO history (executed_at, command, args) VALUES (?1, ?2, ?3)",
+            params![now_secs() as i64, command, serde_json::to_string(args)?],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Stores the hash of the result printed by query `id`.
+    pub fn record_result(&self, id: i64, result: &[u8]) -> Result<(), MapradarError> {
+        self.lock()?.execute(
+            "UPDATE history SET result_hash = ?1 WHERE id = ?2",
+            params![result_hash(result), id],
+        )?;
+        Ok(())
+    }
+
+    /// The most recent `limit` queries, newest first, optionally only those of `command`.
+    pub fn list(
+        &self,
+        limit: usize,
+        command: Option<&str>,
+    ) -> Result<Vec<HistoryEntry>, MapradarError> {
+        let conn = self.lock()?;
+        let mut statement = conn.prepare(
+            "SELECT id, executed_at, command, args, result_hash FROM history
+             WHERE ?1 IS NULL OR command = ?1
+             ORDER BY id DESC LIMIT ?2",
+        )?;
+        let rows = statement
+            .query_map(params![command, limit as i64], entry_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows.into_iter().map(parse_entry).collect()
+    }
+
+    /// Looks up one query by ID.
+    pub fn get(&self, id: i64) -> Result<Option<HistoryEntry>, MapradarError> {
+        let conn = self.lock()?;
+        let row = conn
+            .query_row(
+                "SELECT id, executed_at, command, args, result_hash FROM history WHERE id = ?1",
+                params![id],
+                entry_from_row,
+            )
+            .optional()?;
+        row.map(parse_entry).transpose()
+    }
+
+    /// Deletes every entry, returning how many were removed.
+    pub fn clear(&self) -> Result<usize, MapradarError> {
+        Ok(self.lock()?.execute("DELETE FROM history", [])?)
+    }
+}
+
+/// Hex SHA-256 of a result, as stored in [`HistoryEntry::result_hash`].
+pub fn result_hash(result: &[u8]) -> String {
+    Sha256::digest(result)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// A row with its arguments still JSON-encoded.
+type RawEntry = (i64, i64, String, String, Option<String>);
+
+fn entry_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<RawEntry> {
+    Ok((
+        row.get(0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+    ))
+}
+
+fn parse_entry(
+    (id, executed_at, command, args, result_hash): RawEntry,
+) -> Result<HistoryEntry, MapradarError> {
+    Ok(HistoryEntry {
+        id,
+        executed_at: executed_at as u64,
+        command,
+        args: serde_json::from_str(&args)?,
+        result_hash,
+    })
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
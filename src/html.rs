@@ -0,0 +1,205 @@
+use std::fmt::Write as _;
+
+use serde_json::json;
+
+use crate::{
+    kml::escape, models::LocationIntelligence, scoring::AmenityScore, staticmap::marker_color,
+    tabular::ServiceRow,
+};
+
+const LEAFLET_CSS: &str = "https://unpkg.com/leaflet@1.9.4/dist/leaflet.css";
+const LEAFLET_JS: &str = "https://unpkg.com/leaflet@1.9.4/dist/leaflet.js";
+const ORIGIN_COLOR: &str = "#d93025";
+
+const STYLE: &str = "\
+body{font-family:system-ui,-apple-system,'Segoe UI',sans-serif;margin:0 auto;max-width:1100px;padding:1.5rem;color:#202124}\
+h1{font-size:1.5rem;margin:0 0 .25rem}h2{font-size:1.1rem;margin:1.5rem 0 .5rem}\
+.meta{color:#5f6368;margin:0 0 1rem}\
+#map{height:420px;border-radius:8px;border:1px solid #dadce0}\
+table{border-collapse:collapse;width:100%;font-size:.9rem}\
+th,td{text-align:left;padding:.4rem .6rem;border-bottom:1px solid #eee}\
+th{background:#f8f9fa;position:sticky;top:0}\
+#results th{cursor:pointer;user-select:none}\
+#results th[data-dir=asc]::after{content:' \\25B2'}#results th[data-dir=desc]::after{content:' \\25BC'}\
+td.num,th.num{text-align:right}\
+.swatch{display:inline-block;width:.7rem;height:.7rem;border-radius:50%;margin-right:.4rem;vertical-align:middle}\
+.score{font-size:2.5rem;font-weight:600}.score small{font-size:1rem;color:#5f6368}\
+.warning{color:#b06000}";
+
+/// Draws the map from the embedded data, and sorts the results table by the clicked column.
+const SCRIPT: &str = r#"
+const data = JSON.parse(document.getElementById('mapradar-data').textContent);
+const map = L.map('map');
+L.tileLayer('https://tile.openstreetmap.org/{z}/{x}/{y}.png', {
+  maxZoom: 19,
+  attribution: '&copy; <a href="https://www.openstreetmap.org/copyright">OpenStreetMap</a> contributors'
+}).addTo(map);
+const popup = (lines) => {
+  const el = document.createElement('div');
+  lines.forEach((line, i) => {
+    const row = document.createElement(i === 0 ? 'strong' : 'div');
+    row.textContent = line;
+    el.appendChild(row);
+  });
+  return el;
+};
+const bounds = [[data.origin.lat, data.origin.lng]];
+L.circleMarker(bounds[0], {radius: 9, color: data.origin.color, fillOpacity: 0.9})
+  .bindPopup(popup([data.origin.address]))
+  .addTo(map);
+data.services.forEach((s) => {
+  bounds.push([s.lat, s.lng]);
+  L.circleMarker([s.lat, s.lng], {radius: 6, color: s.color, fillOpacity: 0.8})
+    .bindPopup(popup([s.name, s.type, s.distance_km.toFixed(2) + ' km']))
+    .addTo(map);
+});
+if (bounds.length > 1) { map.fitBounds(bounds, {padding: [24, 24]}); } else { map.setView(bounds[0], 15); }
+
+const table = document.getElementById('results');
+if (table) {
+  table.querySelectorAll('th').forEach((th, column) => {
+    th.addEventListener('click', () => {
+      const dir = th.dataset.dir === 'asc' ? 'desc' : 'asc';
+      table.querySelectorAll('th').forEach((other) => delete other.dataset.dir);
+      th.dataset.dir = dir;
+      const body = table.tBodies[0];
+      const value = (row) => row.cells[column].dataset.value ?? row.cells[column].textContent;
+      const rows = Array.from(body.rows).sort((a, b) => {
+        const [x, y] = [value(a), value(b)];
+        const order = x === '' ? 1 : y === '' ? -1
+          : (isNaN(x) || isNaN(y) ? x.localeCompare(y) : x - y);
+        return dir === 'asc' ? order : -order;
+      });
+      rows.forEach((row) => body.appendChild(row));
+    });
+  });
+}
+"#;
+
+/// Renders nearby-search results as a single HTML page: a Leaflet map of the location and its
+/// services, a results table sortable by any column, and the amenity score when one is set.
+///
+/// Everything is inline except Leaflet itself and the map tiles, which load from their CDNs
+/// when the page is opened.
+pub fn to_html(intel: &LocationIntelligence) -> String {
+    let location = &intel.location;
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    let _ = writeln!(
+        html,
+        "<title>{} - mapradar report</title>",
+        escape(&location.address)
+    );
+    let _ = writeln!(html, "<link rel=\"stylesheet\" href=\"{}\">", LEAFLET_CSS);
+    let _ = writeln!(html, "<script src=\"{}\"></script>", LEAFLET_JS);
+    let _ = writeln!(html, "<style>{}</style>\n</head>\n<body>", STYLE);
+
+    let _ = writeln!(html, "<h1>{}</h1>", escape(&location.address));
+    let _ = writeln!(
+        html,
+        "<p class=\"meta\">{:.5}, {:.5} &#183; {} services found &#183; generated by mapradar {}</p>",
+        location.latitude,
+        location.longitude,
+        intel.nearby_services.len(),
+        env!("CARGO_PKG_VERSION")
+    );
+    for warning in &intel.warnings {
+        let _ = writeln!(html, "<p class=\"warning\">{}</p>", escape(warning));
+    }
+
+    if let Some(score) = &intel.score {
+        write_score(&mut html, score);
+    }
+
+    html.push_str("<h2>Map</h2>\n<div id=\"map\"></div>\n");
+    write_results(&mut html, intel);
+
+    let data = json!({
+        "origin": {
+            "lat": location.latitude,
+            "lng": location.longitude,
+            "address": location.address,
+            "color": ORIGIN_COLOR,
+        },
+        "services": intel.nearby_services.iter().map(|service| json!({
+            "name": service.name,
+            "type": ServiceRow::from(service).service_type,
+            "lat": service.latitude,
+            "lng": service.longitude,
+            "distance_km": service.distance_km,
+            "color": css_color(marker_color(&service.service_type)),
+        })).collect::<Vec<_>>(),
+    });
+    // A "</script>" inside a place name must not end the data block early.
+    let _ = writeln!(
+        html,
+        "<script type=\"application/json\" id=\"mapradar-data\">{}</script>",
+        data.to_string().replace("</", "<\\/")
+    );
+    let _ = writeln!(html, "<script>{}</script>\n</body>\n</html>", SCRIPT);
+
+    html
+}
+
+fn write_score(html: &mut String, score: &AmenityScore) {
+    html.push_str("<h2>Amenity score</h2>\n");
+    let _ = writeln!(
+        html,
+        "<p class=\"score\">{:.0} <small>/ 100</small></p>",
+        score.score
+    );
+    html.push_str("<table>\n<thead><tr><th>Type</th><th class=\"num\">Weight</th><th class=\"num\">Found</th><th class=\"num\">Nearest</th><th class=\"num\">Score</th></tr></thead>\n<tbody>\n");
+    for category in &score.categories {
+        let nearest = category
+            .nearest_km
+            .map_or_else(|| "-".to_string(), |km| format!("{:.2} km", km));
+        let _ = writeln!(
+            html,
+            "<tr><td><span class=\"swatch\" style=\"background:{}\"></span>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{:.0}</td></tr>",
+            css_color(marker_color(&category.category)),
+            escape(&category.category.to_string()),
+            category.weight,
+            category.count,
+            nearest,
+            category.score
+        );
+    }
+    html.push_str("</tbody>\n</table>\n");
+}
+
+fn write_results(html: &mut String, intel: &LocationIntelligence) {
+    html.push_str("<h2>Results</h2>\n");
+    if intel.nearby_services.is_empty() {
+        html.push_str("<p class=\"meta\">No services found.</p>\n");
+        return;
+    }
+
+    html.push_str("<table id=\"results\">\n<thead><tr><th>Name</th><th>Type</th><th class=\"num\">Distance</th><th class=\"num\">Rating</th><th>Address</th></tr></thead>\n<tbody>\n");
+    for service in &intel.nearby_services {
+        let row = ServiceRow::from(service);
+        let (rating_value, rating) = service.rating.map_or_else(
+            || (String::new(), "-".to_string()),
+            |rating| (rating.to_string(), format!("{:.1}", rating)),
+        );
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td><span class=\"swatch\" style=\"background:{}\"></span>{}</td><td class=\"num\" data-value=\"{}\">{:.2} km</td><td class=\"num\" data-value=\"{}\">{}</td><td>{}</td></tr>",
+            escape(row.name),
+            css_color(marker_color(&service.service_type)),
+            escape(&row.service_type),
+            service.distance_km,
+            service.distance_km,
+            rating_value,
+            rating,
+            escape(row.address.unwrap_or_default())
+        );
+    }
+    html.push_str("</tbody>\n</table>\n");
+}
+
+/// Converts a `0xRRGGBB` marker color to CSS `#RRGGBB`.
+fn css_color(color: &str) -> String {
+    format!("#{}", color.trim_start_matches("0x"))
+}
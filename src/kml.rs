@@ -0,0 +1,128 @@
+use std::{
+    fmt::Write as _,
+    io::{Seek, Write},
+};
+
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+use crate::{
+    error::MapradarError,
+    models::{LocationIntelligence, NearbyService, PlaceCategory, ServiceType},
+};
+
+const SERVICE_ICON: &str = "https://maps.google.com/mapfiles/kml/shapes/placemark_circle.png";
+const ORIGIN_ICON: &str = "https://maps.google.com/mapfiles/kml/paddle/red-stars.png";
+const CUSTOM_STYLE: &str = "type-custom";
+
+/// Icon tints in KML `aabbggrr` order, cycled across service types.
+const PALETTE: [&str; 12] = [
+    "ff0000ff", "ff00a5ff", "ff00ffff", "ff00ff00", "ffffff00", "ffff0000", "ffff00ff", "ff800080",
+    "ff008080", "ff808000", "ff4080ff", "ff80ff40",
+];
+
+/// Renders the searched location and its nearby services as a KML document.
+///
+/// The origin is its own placemark; services are grouped under a folder and styled per
+/// service type, with custom categories sharing one style.
+pub fn to_kml(intel: &LocationIntelligence) -> String {
+    let mut kml = String::new();
+    let location = &intel.location;
+
+    kml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    kml.push_str("<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n");
+    let _ = writeln!(kml, "<name>{}</name>", escape(&location.address));
+
+    let _ = writeln!(
+        kml,
+        "<Style id=\"origin\"><IconStyle><scale>1.3</scale><Icon><href>{}</href></Icon></IconStyle></Style>",
+        ORIGIN_ICON
+    );
+    for (index, service_type) in ServiceType::ALL.iter().enumerate() {
+        write_style(
+            &mut kml,
+            &style_id(service_type),
+            PALETTE[index % PALETTE.len()],
+        );
+    }
+    write_style(&mut kml, CUSTOM_STYLE, "ffffffff");
+
+    let _ = writeln!(
+        kml,
+        "<Placemark><name>{}</name><styleUrl>#origin</styleUrl><Point><coordinates>{},{}</coordinates></Point></Placemark>",
+        escape(&location.address),
+        location.longitude,
+        location.latitude
+    );
+
+    kml.push_str("<Folder>\n<name>Nearby services</name>\n");
+    for service in &intel.nearby_services {
+        write_placemark(&mut kml, service);
+    }
+    kml.push_str("</Folder>\n</Document>\n</kml>\n");
+
+    kml
+}
+
+/// Writes `kml` as a KMZ archive (a zip holding `doc.kml`).
+pub fn write_kmz<W: Write + Seek>(writer: W, kml: &str) -> Result<(), MapradarError> {
+    let mut zip = ZipWriter::new(writer);
+    zip.start_file("doc.kml", SimpleFileOptions::default())
+        .map_err(zip_error)?;
+    zip.write_all(kml.as_bytes())?;
+    zip.finish().map_err(zip_error)?;
+    Ok(())
+}
+
+fn zip_error(err: zip::result::ZipError) -> MapradarError {
+    MapradarError::Io(std::io::Error::other(err))
+}
+
+fn style_id(service_type: &ServiceType) -> String {
+    format!("type-{}", service_type.slug())
+}
+
+fn write_style(kml: &mut String, id: &str, color: &str) {
+    let _ = writeln!(
+        kml,
+        "<Style id=\"{}\"><IconStyle><color>{}</color><Icon><href>{}</href></Icon></IconStyle></Style>",
+        id, color, SERVICE_ICON
+    );
+}
+
+fn write_placemark(kml: &mut String, service: &NearbyService) {
+    let style = match &service.service_type {
+        PlaceCategory::Known(service_type) => style_id(service_type),
+        PlaceCategory::Custom(_) => CUSTOM_STYLE.to_string(),
+    };
+
+    let mut description = format!(
+        "{} &#183; {:.2} km",
+        escape(&service.service_type.to_string()),
+        service.distance_km
+    );
+    if let Some(address) = &service.address {
+        let _ = write!(description, "<br/>{}", escape(address));
+    }
+    if let Some(rating) = service.rating {
+        let _ = write!(description, "<br/>Rating: {:.1}", rating);
+    }
+
+    let _ = writeln!(
+        kml,
+        "<Placemark><name>{}</name><description><![CDATA[{}]]></description><styleUrl>#{}</styleUrl><Point><coordinates>{},{}</coordinates></Point></Placemark>",
+        escape(&service.name),
+        description,
+        style,
+        service.longitude,
+        service.latitude
+    );
+}
+
+/// Escapes the five XML special characters.
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -6,9 +6,70 @@
 //!
 //! - **Geocoding** - Convert addresses to coordinates
 //! - **Reverse Geocoding** - Convert coordinates to addresses
+//! - **Administrative Boundaries** - Country, state, LGA or county, and ward containing a point
+//! - **Coordinates** - Range-checked `Latitude`/`Longitude` newtypes, parsed from "lat,lng" or DMS
+//! - **Plus Codes** - Open Location Code encoding and decoding, with plus codes accepted as addresses
+//! - **Address Parsing** - Structured components from providers, or parsed offline
+//! - **Postcodes** - Postal code centres, extents, and localities, and address postcode checks
+//! - **Address Verification** - Exact, corrected, ambiguous, or not-found verdicts with per-component diffs
+//! - **Fuzzy Matching** - Rank a local CSV of places against a name, by trigram and edit similarity and distance
+//! - **Localization** - Addresses and place names in a requested language, recorded on results
+//! - **Region Biasing** - Restrict geocoding and autocomplete to countries or prefer results near a point
+//! - **Autocomplete** - Address suggestions for partial input, biased by country or area
+//! - **Batch Geocoding** - Geocode files of addresses or reverse geocode files of points concurrently
+//! - **Resumable Batches** - Checkpoint files of finished rows, so interrupted batches skip them on resume
+//! - **Parquet** - Typed batch output for pandas, Polars, and DuckDB (`parquet` feature)
+//! - **Routing** - Driving, walking, and cycling directions
+//! - **Isochrones** - Areas reachable within a travel-time budget
+//! - **Time Zones** - IANA zone, UTC offset, and DST, offline with the `offline-tz` feature
+//! - **Elevation** - Terrain height at points and profiles along paths
+//! - **Snap to Road** - Correct noisy GPS points onto the nearest roads, with per-point snap distances
+//! - **Transit Departures** - Next scheduled departures at nearby stops from a GTFS feed (`gtfs` feature)
+//! - **Offline Transit Stops** - Imported GTFS stops and routes merged into nearby transit results (`gtfs` feature)
+//! - **Geodesy** - Haversine and Vincenty distances, bearings, and destination points
+//! - **Units** - Metric or imperial radii and distances, with mile and foot conversions
+//! - **Geofencing** - Point-in-circle and point-in-polygon checks against named fences
 //! - **Nearby Search** - Find banks, hospitals, schools, and more
+//! - **Track Enrichment** - Amenities along GPX tracks, exported as annotated GPX or GeoJSON
+//! - **Place Details** - Phone, website, opening hours, and photo URLs for a place ID
+//! - **Geohash** - Encode, decode, and neighbour geohashes, and group nearby results per cell
+//! - **Clustering** - DBSCAN groups of nearby results with centroids, to find commercial hubs
+//! - **Meeting Points** - Centroid or fairest point among several people, with nearby results
+//! - **Travel Times** - Filter and sort nearby results by travel time via the distance matrix
+//! - **Heatmaps** - Amenity counts per grid cell over a bounding box, as a GeoJSON grid
+//! - **Spatial Index** - r-tree over nearby results for local nearest and radius queries
+//! - **Amenity Scores** - 0-100 walkability ratings from weighted distances per service type
+//! - **Query History** - Past queries with their arguments and result hashes, ready to re-run
+//! - **Watch** - Additions, removals, and rating changes between nearby-search snapshots
+//! - **Snapshot Diffs** - Added, removed, and changed services between two saved results, or a JSON Patch
+//! - **GeoJSON** - Export results as GeoJSON features
+//! - **KML/KMZ** - Export nearby results for Google Earth
+//! - **DataFrames** - Nearby results and locations as polars or pandas DataFrames from Python
+//! - **SQLite Export** - Append nearby results to normalized tables for querying with SQL
+//! - **HTML Reports** - Self-contained pages with a Leaflet map, sortable results, and the amenity score
+//! - **Static Maps** - PNG images of nearby results with per-type markers and a radius circle
+//! - **Pluggable Providers** - Google Maps or OpenStreetMap (Nominatim/Overpass)
+//! - **Offline Mode** - Nearby search and coarse geocoding from imported OSM extracts (`offline` feature)
+//! - **Webhooks** - Deliver results as signed JSON POSTs instead of printing them
+//! - **Dry Runs** - Plan upstream requests and estimate their cost without sending them
+//! - **Testing** - `MockProvider` and record/replay fixtures for offline tests and demos
+//! - **OS Keyring** - API keys kept in the Keychain, Credential Manager, or Secret Service (`keyring` feature)
+//! - **Key Rotation** - Round-robin or failover over several API keys, skipping rejected ones
+//! - **Usage Ledger** - Count upstream calls and cache hits per day, with a daily call budget
+//! - **Health Checks** - `/healthz` and `/readyz` with an upstream probe and cache status in server modes
+//! - **Metrics** - Request, error, upstream latency, cache, and rate-limit metrics at `/metrics` in server modes
+//! - **Middleware** - Request and response hooks for gateway headers, signing, logging, or short-circuiting
+//! - **Progress** - Per-row and per-service-type progress events for bars and spinners
+//! - **Tracing** - Request, retry, and cache events through the `tracing` crate
+//! - **Cancellation** - A `CancellationToken` that abandons in-flight requests and ends batches early
+//! - **Retries** - Exponential backoff with jitter for transient failures
 //! - **Caching** - Automatic in-memory cache for repeated queries
-//! - **JSON-RPC 2.0** - Built-in response format for microservices
+//! - **JSON-RPC 2.0** - Response format and a server over stdio or HTTP (`server` feature)
+//! - **REST Server** - `/geocode`, `/reverse`, and `/nearby` over HTTP (`server` feature)
+//! - **gRPC Server** - Geocode, reverse, nearby, streaming nearby, and route over gRPC (`grpc` feature)
+//! - **MCP Server** - Geocoding and nearby search as Model Context Protocol tools
+//! - **Blocking API** - Synchronous client behind the `blocking` feature
+//! - **C ABI** - Opaque client handle and JSON results for C, C++, and Go (`ffi` feature)
 //!
 //! ## Example
 //!
@@ -17,7 +78,7 @@
 //!
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
-//!     let client = MapradarClient::new("YOUR_API_KEY".to_string());
+//!     let client = MapradarClient::builder().api_key("YOUR_API_KEY").build()?;
 //!     let location = client.geocode_async("Times Square, NYC").await?;
 //!     println!("{}, {}", location.latitude, location.longitude);
 //!     Ok(())
@@ -27,23 +88,157 @@
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
 
+pub mod address;
+pub mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod cache;
 pub mod client;
+pub mod clustering;
+#[cfg(feature = "parquet")]
+pub mod columnar;
+pub mod coordinates;
+#[cfg(feature = "keyring")]
+pub mod credentials;
+#[cfg(feature = "python")]
+pub mod dataframe;
+pub mod diff;
 pub mod error;
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fuzzy;
+pub mod geo;
+pub mod geofence;
+pub mod geohash;
+pub mod geojson;
+pub mod gpx;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod health;
+pub mod heatmap;
+pub mod history;
+pub mod html;
+pub mod kml;
+pub mod mcp;
+pub mod metrics;
 pub mod models;
+#[cfg(feature = "offline")]
+pub mod osm;
+pub mod plan;
+pub mod plus_code;
+pub mod progress;
+pub mod providers;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "server")]
+pub mod rest;
+pub mod rpc;
+pub mod scoring;
+pub mod sink;
+pub mod spatial;
+pub mod staticmap;
+pub mod tabular;
+pub mod testing;
+#[cfg(feature = "offline-tz")]
+pub mod timezone;
+#[cfg(feature = "gtfs")]
+pub mod transit;
+pub mod transport;
+pub mod units;
+pub mod usage;
 pub mod utils;
+pub mod verify;
+pub mod watch;
 
 #[cfg(feature = "python")]
 #[pymodule]
 fn mapradar(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<models::GeoLocation>()?;
+    m.add_class::<models::AddressComponents>()?;
+    m.add_class::<models::TimeZoneInfo>()?;
+    m.add_class::<models::ElevationSample>()?;
+    m.add_class::<models::SnappedPoint>()?;
+    m.add_class::<models::TrackAmenity>()?;
+    m.add_class::<models::TrackEnrichment>()?;
+    m.add_class::<models::GeocodeCandidate>()?;
+    m.add_class::<models::MatchType>()?;
     m.add_class::<models::TravelParameters>()?;
     m.add_class::<models::ServiceType>()?;
     m.add_class::<models::NearbyService>()?;
+    m.add_class::<models::OpeningHours>()?;
+    m.add_class::<models::OpeningInterval>()?;
+    m.add_class::<models::PlaceDetails>()?;
+    m.add_class::<models::PlacePhoto>()?;
+    m.add_class::<models::Departure>()?;
     m.add_class::<models::LocationIntelligence>()?;
+    m.add_class::<models::FailedServiceType>()?;
     m.add_class::<models::SearchQuery>()?;
+    m.add_class::<models::SearchRegion>()?;
+    m.add_class::<models::PostcodeArea>()?;
+    m.add_class::<models::PostcodeCheck>()?;
+    m.add_class::<models::TravelMode>()?;
+    m.add_class::<models::RouteStep>()?;
+    m.add_class::<models::Route>()?;
+    m.add_class::<models::MatrixElement>()?;
+    m.add_class::<models::DistanceMatrix>()?;
+    m.add_class::<models::Isochrone>()?;
+    m.add_class::<models::AdminArea>()?;
+    m.add_class::<models::AdminHierarchy>()?;
+    m.add_class::<models::MeetingStrategy>()?;
+    m.add_class::<models::MeetingPoint>()?;
+    m.add_class::<models::Suggestion>()?;
     m.add_class::<models::JsonRpcError>()?;
     m.add_class::<models::JsonRpcResponse>()?;
+    m.add_class::<batch::BatchRecord>()?;
+    m.add_class::<clustering::ServiceCluster>()?;
+    m.add_class::<scoring::AmenityScore>()?;
+    m.add_class::<scoring::CategoryScore>()?;
+    m.add_class::<verify::Verdict>()?;
+    m.add_class::<verify::ComponentStatus>()?;
+    m.add_class::<verify::ComponentDiff>()?;
+    m.add_class::<verify::AddressVerification>()?;
     m.add_class::<client::MapradarClient>()?;
+    m.add_class::<fuzzy::LocalPlace>()?;
+    m.add_class::<fuzzy::FuzzyMatch>()?;
+    m.add_function(wrap_pyfunction!(dataframe::locations_to_dataframe, m)?)?;
+    m.add_function(wrap_pyfunction!(fuzzy::fuzzy_match, m)?)?;
+    m.add_function(wrap_pyfunction!(diff::diff_snapshots, m)?)?;
+    #[cfg(feature = "gtfs")]
+    m.add_function(wrap_pyfunction!(transit::attach_departures, m)?)?;
+
+    use error::exceptions;
+    let py = m.py();
+    m.add("MapradarError", py.get_type::<exceptions::MapradarError>())?;
+    m.add(
+        "AuthFailedError",
+        py.get_type::<exceptions::AuthFailedError>(),
+    )?;
+    m.add(
+        "QuotaExceededError",
+        py.get_type::<exceptions::QuotaExceededError>(),
+    )?;
+    m.add(
+        "InvalidCoordinatesError",
+        py.get_type::<exceptions::InvalidCoordinatesError>(),
+    )?;
+    m.add(
+        "AddressNotFoundError",
+        py.get_type::<exceptions::AddressNotFoundError>(),
+    )?;
+    m.add(
+        "InvalidInputError",
+        py.get_type::<exceptions::InvalidInputError>(),
+    )?;
+    m.add("NetworkError", py.get_type::<exceptions::NetworkError>())?;
+    m.add(
+        "DeserializationError",
+        py.get_type::<exceptions::DeserializationError>(),
+    )?;
+    m.add("ProviderError", py.get_type::<exceptions::ProviderError>())?;
+    m.add(
+        "UnsupportedError",
+        py.get_type::<exceptions::UnsupportedError>(),
+    )?;
     Ok(())
 }
@@ -1,30 +1,557 @@
-use clap::{Parser, Subcommand};
+mod cli;
+
+use cli::config::Config;
+use cli::progress::ProgressBars;
+use cli::select::{InFieldOrder, Query, Selection};
+use cli::template::Template;
+
+use clap::parser::ValueSource;
+use clap::{ArgGroup, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use colored::*;
-use mapradar::client::MapradarClient;
-use mapradar::models::{SearchQuery, ServiceType, TravelParameters};
+use futures::StreamExt;
+use mapradar::address;
+use mapradar::batch::{self, BatchRecord, Checkpoint, QueryOutcome};
+use mapradar::cache::DiskCache;
+use mapradar::cache::disk::DEFAULT_DISK_TTL_SECS;
+use mapradar::client::{MapradarClient, MapradarClientBuilder};
+use mapradar::coordinates::Coordinates;
+use mapradar::diff;
+use mapradar::error::MapradarError;
+use mapradar::export::{self, ExportMetadata, SqliteExport};
+use mapradar::fuzzy;
+use mapradar::geo::{self, DistanceMethod, Geometry};
+use mapradar::geofence::{Geofence, fences_containing};
+use mapradar::geohash;
+use mapradar::geojson::{ToGeoJson, feature_collection};
+use mapradar::gpx::GpxTrack;
+use mapradar::health::{ProviderHealth, ProviderStatus};
+use mapradar::heatmap::Heatmap;
+use mapradar::history::QueryHistory;
+use mapradar::html;
+use mapradar::kml;
+use mapradar::mcp;
+use mapradar::metrics::Metrics;
+use mapradar::models::{
+    AddressComponents, AutocompleteBias, GeoLocation, LocationIntelligence, MeetingPoint,
+    MeetingStrategy, NearbyService, PlaceCategory, SearchQuery, SearchRegion, TimeZoneInfo,
+    TrackEnrichment, TravelMode,
+};
+use mapradar::plus_code;
+use mapradar::providers::{KeyRotation, ProviderKind};
+use mapradar::rpc;
+use mapradar::scoring::ScoreWeights;
+use mapradar::sink::WebhookSink;
+use mapradar::staticmap::{self, StaticMap};
+use mapradar::tabular::{
+    BatchRow, ClusterRow, GeohashRow, HeatmapRow, RowWriter, ServiceColumn, ServiceRow, SnapRow,
+    SortKey, render_clusters, render_comparison, render_heatmap, render_service_clusters,
+    render_table, render_usage,
+};
+use mapradar::testing::Fixtures;
+#[cfg(feature = "gtfs")]
+use mapradar::transit::{GtfsFeed, GtfsIndex};
+use mapradar::transport::{CancellationToken, RateLimit, RateLimiter, RetryPolicy};
+use mapradar::units::Units;
+use mapradar::usage::UsageLedger;
+use mapradar::watch::{self, ChangeEvent};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, IsTerminal, Write};
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing_subscriber::{EnvFilter, fmt::format::FmtSpan};
+
+const DEFAULT_HTTP_PORT: u16 = 8080;
+const DEFAULT_GRPC_PORT: u16 = 50051;
+
+/// Address or point argument that stands for the queries read from stdin.
+const STDIN_ARG: &str = "-";
+
+/// Client of a `--dry-run` invocation, kept so the plan can be printed from [`exit_with`].
+static DRY_RUN_CLIENT: OnceLock<MapradarClient> = OnceLock::new();
+
+/// `--error-format`, set once the arguments are parsed.
+static ERROR_FORMAT: OnceLock<ErrorFormat> = OnceLock::new();
 
 #[derive(Parser)]
 #[command(name = "mapradar")]
 #[command(about = "CLI for Mapradar Location Intelligence", long_about = None)]
 struct Cli {
     #[arg(short, long, env = "MAPRADAR_API_KEY")]
-    api_key: String,
+    api_key: Option<String>,
+
+    /// Several Google API keys, comma-separated, rotated per request and skipped while rejected
+    /// or out of quota (takes precedence over --api-key)
+    #[arg(
+        long,
+        global = true,
+        env = "MAPRADAR_API_KEYS",
+        value_delimiter = ',',
+        hide_env_values = true
+    )]
+    api_keys: Vec<String>,
+
+    /// Order in which --api-keys are used (round-robin or failover) [default: round-robin]
+    #[arg(long, global = true, env = "MAPRADAR_KEY_ROTATION")]
+    key_rotation: Option<KeyRotation>,
+
+    /// Upstream provider (google, nominatim, or offline) [default: google]
+    #[arg(short, long, global = true, env = "MAPRADAR_PROVIDER")]
+    provider: Option<ProviderKind>,
+
+    /// Output format for geocode, reverse, and nearby results [default: json]
+    #[arg(short, long, global = true, value_enum)]
+    format: Option<OutputFormat>,
+
+    /// Units for --radius and displayed distances: metric (meters, kilometers) or imperial
+    /// (feet, miles) [default: metric]
+    #[arg(long, global = true, env = "MAPRADAR_UNITS")]
+    units: Option<Units>,
+
+    /// Bypass the in-memory and on-disk response caches
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// How long on-disk cache entries stay valid, in seconds [default: 86400]
+    #[arg(long, global = true)]
+    cache_ttl: Option<u64>,
+
+    /// Retries for transient network and server errors
+    #[arg(long, global = true, default_value_t = 2)]
+    retries: u32,
+
+    /// Base delay before the first retry, doubled on each attempt
+    #[arg(long, global = true, default_value_t = 200)]
+    retry_delay_ms: u64,
+
+    /// Most result pages to fetch per nearby search when --max-results exceeds one page
+    #[arg(long, global = true, default_value_t = mapradar::client::DEFAULT_MAX_PAGES)]
+    max_pages: usize,
+
+    /// Upstream searches or geocodes to run at once (service types in nearby, rows in
+    /// batch-geocode and batch-reverse)
+    #[arg(long, global = true, default_value_t = mapradar::client::DEFAULT_CONCURRENCY)]
+    concurrency: usize,
+
+    /// Abort once this many upstream calls have been made today (UTC), across all commands
+    #[arg(long, global = true, env = "MAPRADAR_MAX_CALLS_PER_DAY")]
+    max_calls_per_day: Option<u64>,
+
+    /// Maximum upstream request rate, e.g. "10/s" or "600/min"
+    #[arg(long, global = true, env = "MAPRADAR_RATE_LIMIT")]
+    rate_limit: Option<RateLimit>,
+
+    /// Give up on an upstream request attempt after this many seconds
+    #[arg(long, global = true)]
+    timeout: Option<f64>,
+
+    /// Send provider requests to this server instead, e.g. a self-hosted Nominatim or a mock
+    #[arg(long, global = true, env = "MAPRADAR_BASE_URL")]
+    base_url: Option<String>,
+
+    /// Ask the provider for addresses and place names in this language, e.g. "fr" or "pt-BR"
+    #[arg(
+        long = "lang",
+        global = true,
+        value_name = "CODE",
+        env = "MAPRADAR_LANG"
+    )]
+    language: Option<String>,
+
+    /// Route upstream requests through an HTTP or SOCKS5 proxy, e.g. "socks5h://127.0.0.1:1080"
+    #[arg(long, global = true, env = "MAPRADAR_PROXY")]
+    proxy: Option<String>,
+
+    /// Index searched by --provider offline [default: ~/.local/share/mapradar/osm.sqlite]
+    #[arg(long, global = true, value_name = "PATH", env = "MAPRADAR_OSM_DB")]
+    osm_db: Option<PathBuf>,
+
+    /// Save every upstream response as a JSON fixture in this directory
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Answer upstream requests from fixtures saved with --record, without network access
+    #[arg(long, global = true, value_name = "DIR")]
+    replay: Option<PathBuf>,
+
+    /// Print results as text from this template instead of in the output format, e.g. "{name} is
+    /// {distance_km | round}km away ({rating}★)", one line per place
+    #[arg(long, global = true, conflicts_with = "post_to")]
+    template: Option<Template>,
+
+    /// Print only these fields of each place, or of a result without places, as JSON, e.g.
+    /// "name,distance_km,rating" (dotted paths such as location.address reach nested fields)
+    #[arg(
+        long,
+        global = true,
+        value_delimiter = ',',
+        conflicts_with_all = ["post_to", "query"]
+    )]
+    fields: Vec<String>,
+
+    /// Print what this jq filter makes of the JSON result instead, e.g.
+    /// '.nearby_services[] | select(.rating > 4) | .name'
+    #[arg(long, global = true, conflicts_with = "post_to")]
+    query: Option<Query>,
+
+    /// POST the JSON result to this webhook URL instead of printing it
+    #[arg(long, global = true)]
+    post_to: Option<String>,
+
+    /// Extra header for webhook deliveries, as "Name: value" (repeatable)
+    #[arg(long = "post-header", global = true)]
+    post_headers: Vec<String>,
+
+    /// Sign webhook deliveries with HMAC-SHA256 under this secret (X-Mapradar-Signature header)
+    #[arg(
+        long,
+        global = true,
+        env = "MAPRADAR_WEBHOOK_SECRET",
+        hide_env_values = true
+    )]
+    post_secret: Option<String>,
+
+    /// Print the upstream requests the command would make, with estimated cost, without sending
+    /// them
+    #[arg(long, global = true, conflicts_with = "post_to")]
+    dry_run: bool,
+
+    /// Leave this query out of the history kept for `mapradar history`
+    #[arg(long, global = true, env = "MAPRADAR_NO_HISTORY")]
+    no_history: bool,
+
+    /// Hide progress bars and spinners, which are also hidden when stdout is not a terminal
+    #[arg(short, long, global = true, env = "MAPRADAR_QUIET")]
+    quiet: bool,
+
+    /// Log upstream requests, retries, and timing (-v), plus cache hits and misses (-vv)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Log everything, including span timings for each upstream call
+    #[arg(long, global = true)]
+    trace: bool,
+
+    /// Log line format, written to stderr
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    log_format: LogFormat,
+
+    /// Error format on stderr: text, or a JSON object with the error's kind, message, exit code,
+    /// and details
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value = "text",
+        env = "MAPRADAR_ERROR_FORMAT"
+    )]
+    error_format: ErrorFormat,
+
+    /// Config file with default settings [default: ~/.config/mapradar/config.toml]
+    #[arg(long = "config", global = true, env = "MAPRADAR_CONFIG")]
+    config_path: Option<PathBuf>,
 
     #[command(subcommand)]
     command: Commands,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Json,
+    Geojson,
+    Csv,
+    Tsv,
+    Table,
+    Kml,
+    Gpx,
+    Html,
+    Parquet,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LogFormat {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ErrorFormat {
+    Text,
+    Json,
+}
+
+impl OutputFormat {
+    /// Field delimiter for the flat, spreadsheet-friendly formats.
+    fn delimiter(self) -> Option<u8> {
+        match self {
+            Self::Csv => Some(b','),
+            Self::Tsv => Some(b'\t'),
+            Self::Json
+            | Self::Geojson
+            | Self::Table
+            | Self::Kml
+            | Self::Gpx
+            | Self::Html
+            | Self::Parquet => None,
+        }
+    }
+}
+
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
 enum Commands {
     /// Geocode an address to coordinates
-    Geocode { address: String },
+    Geocode {
+        /// Address to geocode, or "-" to read one query per line from stdin (addresses,
+        /// "lat,lng", or NDJSON objects with an optional id) and print NDJSON
+        address: String,
+
+        /// List every matching candidate with its confidence instead of only the best match
+        #[arg(long)]
+        all: bool,
+
+        /// Drop candidates below this confidence (0.0 to 1.0)
+        #[arg(long, requires = "all")]
+        min_confidence: Option<f64>,
+
+        /// Include the time zone at the location
+        #[arg(long, conflicts_with = "all")]
+        with_timezone: bool,
+
+        /// Include the ground elevation at the location
+        #[arg(long, conflicts_with = "all")]
+        with_elevation: bool,
+
+        /// Only accept matches in these ISO 3166-1 alpha-2 countries, e.g. "NG,GH"
+        #[arg(long, value_delimiter = ',', value_name = "CODES")]
+        country: Vec<String>,
+
+        /// Prefer matches within 50 km of this point, as "lat,lng"
+        #[arg(long, value_name = "LAT,LNG", allow_hyphen_values = true)]
+        bias_around: Option<Coordinates>,
+    },
 
     /// Reverse geocode coordinates to an address
-    Reverse { latitude: f64, longitude: f64 },
+    Reverse {
+        /// Point as "lat,lng", separate latitude and longitude, or degrees-minutes-seconds such
+        /// as 6°31'28"N 3°22'45"E; "-" reads queries from stdin like --stdin
+        #[arg(
+            value_name = "LAT,LNG",
+            allow_hyphen_values = true,
+            required_unless_present = "stdin"
+        )]
+        point: Option<String>,
+
+        /// Longitude, when the latitude is given on its own
+        #[arg(value_name = "LNG", allow_negative_numbers = true)]
+        longitude: Option<String>,
+
+        /// Read one query per line from stdin ("lat,lng" or NDJSON objects with an optional id)
+        /// and print NDJSON
+        #[arg(long, conflicts_with = "point")]
+        stdin: bool,
+
+        /// Include the time zone at the location
+        #[arg(long)]
+        with_timezone: bool,
+
+        /// Include the ground elevation at the location
+        #[arg(long)]
+        with_elevation: bool,
+    },
+
+    /// Look up ground elevation at points or along a path
+    Elevation {
+        /// Points as "lat,lng" or addresses; several points form a path, and "-" reads points
+        /// from stdin
+        #[arg(required = true, allow_hyphen_values = true)]
+        points: Vec<String>,
+
+        /// Sample this many evenly spaced points along the path instead of only its vertices
+        #[arg(short, long)]
+        samples: Option<usize>,
+    },
+
+    /// Snap noisy GPS points onto the nearest roads
+    Snap {
+        /// GPX track or route, CSV with latitude/longitude columns, or "lat,lng" or NDJSON
+        /// lines; "-" reads stdin
+        input: PathBuf,
+
+        /// Write results (JSON, GeoJSON, or rows with --format csv/tsv) to this file instead of
+        /// stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Show the time zone, UTC offset, and DST status at coordinates
+    Timezone {
+        /// Point as "lat,lng", separate latitude and longitude, or degrees-minutes-seconds; "-"
+        /// reads one query per line from stdin and prints NDJSON
+        #[arg(value_name = "LAT,LNG", allow_hyphen_values = true)]
+        point: String,
+
+        /// Longitude, when the latitude is given on its own
+        #[arg(value_name = "LNG", allow_negative_numbers = true)]
+        longitude: Option<String>,
+    },
+
+    /// Verify an address for delivery: geocode it, reverse-geocode the match, and report a
+    /// verdict (exact, corrected, ambiguous, not_found) with per-component differences
+    Verify {
+        /// Address to verify, or "-" to read one per line from stdin and print NDJSON
+        address: String,
+    },
+
+    /// Look up a postcode's centre, bounding box, and localities, or check an address's postcode
+    Postcode {
+        /// Postcode to look up, e.g. "SW1A 2AA"
+        #[arg(required_unless_present = "check")]
+        postcode: Option<String>,
+
+        /// Country of the postcode, as an ISO 3166-1 alpha-2 code
+        #[arg(short, long, required_unless_present = "check")]
+        country: Option<String>,
+
+        /// Geocode this address instead and report whether the postcode written in it matches
+        /// the location it resolves to; "-" reads addresses from stdin and prints NDJSON
+        #[arg(long, value_name = "ADDRESS", conflicts_with_all = ["postcode", "country"])]
+        check: Option<String>,
+    },
+
+    /// Show the administrative areas (country, state, LGA or county, ward) containing a point
+    Admin {
+        /// Point as "lat,lng", separate latitude and longitude, or degrees-minutes-seconds; "-"
+        /// reads one query per line from stdin and prints NDJSON
+        #[arg(value_name = "LAT,LNG", allow_hyphen_values = true)]
+        point: String,
+
+        /// Longitude, when the latitude is given on its own
+        #[arg(value_name = "LNG", allow_negative_numbers = true)]
+        longitude: Option<String>,
+
+        /// Include each area's boundary polygons (OpenStreetMap provider only)
+        #[arg(long)]
+        with_geometry: bool,
+    },
+
+    /// Split an address into house number, street, city, postcode, etc.
+    ParseAddress {
+        /// Address to split, or "-" to read one per line from stdin and print NDJSON
+        address: String,
+
+        /// Geocode the address and use the provider's components instead of parsing offline
+        #[arg(long)]
+        geocode: bool,
+    },
+
+    /// Encode a point as a plus code (Open Location Code), without any API call
+    Encode {
+        /// Point as "lat,lng", separate latitude and longitude, or degrees-minutes-seconds; "-"
+        /// reads one query per line from stdin and prints NDJSON
+        #[arg(value_name = "LAT,LNG", allow_hyphen_values = true)]
+        point: String,
+
+        /// Longitude, when the latitude is given on its own
+        #[arg(value_name = "LNG", allow_negative_numbers = true)]
+        longitude: Option<String>,
+
+        /// Digits in the code: 2, 4, 6, 8, or 10 to 15; 10 is about 14 m square, 11 about 3 m
+        #[arg(short, long, default_value_t = plus_code::DEFAULT_CODE_LENGTH)]
+        length: usize,
+    },
+
+    /// Decode a plus code to the area it names and its centre, without any API call
+    Decode {
+        /// Full plus code such as "6FR5GC9M+7V", or a short code with --near
+        code: String,
+
+        /// Complete a short code such as "GC9M+7V" with the area nearest this point
+        #[arg(long, value_name = "LAT,LNG", allow_hyphen_values = true)]
+        near: Option<Coordinates>,
+    },
+
+    /// Fuzzy-match a name against a local CSV of places, without any API call
+    Match {
+        /// Name to look for; typos and reordered words are tolerated
+        query: String,
+
+        /// CSV with a header row and name, latitude, and longitude columns
+        #[arg(long, value_name = "FILE")]
+        candidates: PathBuf,
+
+        /// Rank nearer places higher, as "lat,lng"
+        #[arg(long, value_name = "LAT,LNG", allow_hyphen_values = true)]
+        near: Option<Coordinates>,
+
+        /// Share of the score given to closeness to --near, from 0.0 to 1.0
+        #[arg(long, default_value_t = fuzzy::DEFAULT_DISTANCE_WEIGHT, requires = "near")]
+        distance_weight: f64,
+
+        /// Drop matches scoring below this (0.0 to 1.0)
+        #[arg(long, default_value_t = fuzzy::DEFAULT_MIN_SCORE)]
+        min_score: f64,
+
+        /// Maximum number of matches
+        #[arg(short, long, default_value_t = 10)]
+        limit: usize,
+    },
+
+    /// Compare two saved `nearby` results, listing added, removed, and changed services
+    Diff {
+        /// Older result, as saved from `nearby` JSON output
+        old: PathBuf,
+
+        /// Newer result
+        new: PathBuf,
+
+        /// Print an RFC 6902 JSON Patch turning the older result into the newer one
+        #[arg(long)]
+        patch: bool,
+    },
+
+    /// Look up phone, website, opening hours, and photos for a place ID from `nearby`
+    Details {
+        /// Place ID, e.g. a Google ID or "osm:node/123"
+        place_id: String,
+
+        /// Attach key-free URLs for up to this many photos
+        #[arg(long, value_name = "N")]
+        with_photos: Option<usize>,
+
+        /// Save the attached photos into this directory
+        #[arg(long, value_name = "DIR", requires = "with_photos")]
+        download_photos: Option<PathBuf>,
+    },
+
+    /// Suggest addresses completing a partial input
+    Suggest {
+        /// Partially typed address or place name
+        partial: String,
+
+        /// Maximum number of suggestions
+        #[arg(short, long, default_value_t = 5)]
+        limit: usize,
+
+        /// Restrict suggestions to these ISO 3166-1 alpha-2 country codes, e.g. "NG,GH"
+        #[arg(short, long, value_delimiter = ',', value_name = "CODES")]
+        country: Vec<String>,
+
+        /// Prefer suggestions inside this box: minLon,minLat,maxLon,maxLat
+        #[arg(long, allow_hyphen_values = true)]
+        bbox: Option<Geometry>,
+
+        /// Prefer suggestions within 50 km of this point, as "lat,lng"
+        #[arg(long, value_name = "LAT,LNG", allow_hyphen_values = true)]
+        bias_around: Option<Coordinates>,
+    },
 
     /// Find nearby amenities
     Nearby {
+        /// Address to search around, or "-" to read queries from stdin like --stdin
         #[arg(short, long, alias = "addr")]
         address: Option<String>,
 
@@ -34,22 +561,414 @@ enum Commands {
         #[arg(long, alias = "lng", alias = "lon")]
         longitude: Option<f64>,
 
-        /// Radius in meters (default 1000)
-        #[arg(short, long, default_value_t = 1000.0)]
-        radius: f64,
+        /// Point as "lat,lng" or degrees-minutes-seconds such as 6°31'28"N 3°22'45"E
+        #[arg(long, value_name = "LAT,LNG", allow_hyphen_values = true, conflicts_with_all = ["address", "latitude", "longitude"])]
+        at: Option<Coordinates>,
 
-        /// Type of amenity (bank, hospital, school, etc.)
-        #[arg(short, long, default_value = "bank")]
-        r#type: String,
+        /// Search around the approximate location of this IP address
+        #[arg(long, conflicts_with_all = ["address", "latitude", "longitude", "at"])]
+        ip: Option<IpAddr>,
+
+        /// Search around your approximate location, found from your public IP address
+        #[arg(long, conflicts_with_all = ["address", "latitude", "longitude", "at", "ip"])]
+        my_location: bool,
+
+        /// Search around each query read from stdin (addresses, "lat,lng", or NDJSON objects
+        /// with an optional id), printing one NDJSON result per line in input order
+        #[arg(long, conflicts_with_all = ["address", "latitude", "longitude", "at", "ip", "my_location", "stream", "output"])]
+        stdin: bool,
+
+        /// Only accept addresses geocoded in these ISO 3166-1 alpha-2 countries, e.g. "NG,GH"
+        #[arg(long, value_delimiter = ',', value_name = "CODES")]
+        country: Vec<String>,
+
+        /// Prefer addresses geocoded within 50 km of this point, as "lat,lng"
+        #[arg(long, value_name = "LAT,LNG", allow_hyphen_values = true)]
+        bias_around: Option<Coordinates>,
+
+        /// Radius in meters, or feet with --units imperial [default: 1000 m]
+        #[arg(short, long)]
+        radius: Option<f64>,
+
+        /// Comma-separated amenity types (bank, hospital, school, etc.), custom:<tag>, or key=value [default: bank]
+        #[arg(short, long)]
+        r#type: Option<String>,
 
         /// Maximum number of results to return per service
         #[arg(short, long, alias = "limit", default_value_t = 10)]
         max_results: usize,
+
+        /// Print each service as an NDJSON line as soon as its search completes
+        #[arg(long)]
+        stream: bool,
+
+        /// Order results by distance, rating, score (rating balanced against distance), name, or
+        /// travel-time [default: distance, or travel-time with --max-travel-minutes]
+        #[arg(long, alias = "sort")]
+        sort_by: Option<SortKey>,
+
+        /// Keep only results rated at least this much (unrated places are dropped)
+        #[arg(long)]
+        min_rating: Option<f32>,
+
+        /// Keep only places reported open right now (places without hours are dropped)
+        #[arg(long)]
+        open_now: bool,
+
+        /// Keep only places reachable within this many minutes by --mode, timed with the
+        /// provider's distance matrix, and report each one's travel_time_min
+        #[arg(long, value_name = "MINUTES", conflicts_with_all = ["stream", "along_route"])]
+        max_travel_minutes: Option<f64>,
+
+        /// Comma-separated table columns (name, type, distance, rating, address, lat, lng, phone, open, time)
+        #[arg(long, value_delimiter = ',')]
+        columns: Vec<ServiceColumn>,
+
+        /// Keep only results inside this box: minLon,minLat,maxLon,maxLat
+        #[arg(long, allow_hyphen_values = true)]
+        bbox: Option<Geometry>,
+
+        /// Keep only results inside the polygons of this GeoJSON file
+        #[arg(long, conflicts_with = "bbox")]
+        polygon: Option<PathBuf>,
+
+        /// Write results to this file instead of stdout (a .kmz path with --format kml writes a KMZ archive;
+        /// a .sqlite, .sqlite3, or .db path appends to a SQLite database)
+        #[arg(short, long, conflicts_with = "stream")]
+        output: Option<PathBuf>,
+
+        /// Include the ground elevation at the searched location
+        #[arg(long, conflicts_with = "stream")]
+        with_elevation: bool,
+
+        /// Merge results that are the same place found under several types
+        #[arg(long, conflicts_with = "stream")]
+        dedupe: bool,
+
+        /// Group results by geohash cell at this precision (1-12; 6 is about 1.2 by 0.6 km, 7
+        /// about 150 m) and print each cell with its counts, densest first
+        #[arg(long, value_name = "PRECISION", conflicts_with_all = ["stream", "stdin", "along_route"])]
+        cluster_by_geohash: Option<usize>,
+
+        /// Group results lying within this many meters (feet with --units imperial) of each
+        /// other into clusters (DBSCAN), printing each with its centroid and members, largest
+        /// first
+        #[arg(long, value_name = "DISTANCE", conflicts_with_all = ["stream", "stdin", "along_route", "cluster_by_geohash"])]
+        cluster: Option<f64>,
+
+        /// Results needed within the --cluster distance of a place, itself included, to start a
+        /// cluster
+        #[arg(long, value_name = "N", default_value_t = 3, requires = "cluster")]
+        min_points: usize,
+
+        /// Attach a 0-100 amenity score for the searched types
+        #[arg(long, conflicts_with = "stream")]
+        score: bool,
+
+        /// Attach key-free URLs for up to this many photos per place (one details lookup each)
+        #[arg(long, value_name = "N", conflicts_with = "stream")]
+        with_photos: Option<usize>,
+
+        /// Save the attached photos into this directory
+        #[arg(long, value_name = "DIR", requires = "with_photos")]
+        download_photos: Option<PathBuf>,
+
+        /// Attach the next scheduled departures to bus stops and train stations from this GTFS
+        /// feed (zip archive or directory)
+        #[arg(long, value_name = "GTFS", conflicts_with = "stream")]
+        with_departures: Option<PathBuf>,
+
+        /// Departures to attach per stop
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 3,
+            requires = "with_departures"
+        )]
+        departures: usize,
+
+        /// TOML file of per-type score weights
+        #[arg(long, requires = "score")]
+        weights: Option<PathBuf>,
+
+        /// Search along the route between two places, given as "origin..destination" (each an
+        /// address or "lat,lng"), ordering results by distance along the route
+        #[arg(long, value_name = "FROM..TO", allow_hyphen_values = true, conflicts_with_all = ["address", "latitude", "longitude", "at", "ip", "my_location", "stdin", "stream", "with_elevation", "score", "with_photos", "with_departures"])]
+        along_route: Option<String>,
+
+        /// Meters (feet with --units imperial) either side of the route to search with
+        /// --along-route [default: the radius]
+        #[arg(long, requires = "along_route")]
+        corridor: Option<f64>,
+
+        /// Travel mode for --along-route and --max-travel-minutes (driving, walking, cycling)
+        /// [default: driving]
+        #[arg(long)]
+        mode: Option<TravelMode>,
+    },
+
+    /// Rate a location 0-100 by how close it is to everyday amenities
+    Score {
+        /// Address to score, or "-" to score each query read from stdin and print NDJSON
+        #[arg(short, long, alias = "addr")]
+        address: Option<String>,
+
+        #[arg(long, alias = "lat", allow_hyphen_values = true)]
+        latitude: Option<f64>,
+
+        #[arg(long, alias = "lng", alias = "lon", allow_hyphen_values = true)]
+        longitude: Option<f64>,
+
+        /// Point as "lat,lng" or degrees-minutes-seconds such as 6°31'28"N 3°22'45"E
+        #[arg(long, value_name = "LAT,LNG", allow_hyphen_values = true, conflicts_with_all = ["address", "latitude", "longitude"])]
+        at: Option<Coordinates>,
+
+        /// Comma-separated amenity types to score [default: every type in the weights]
+        #[arg(short, long)]
+        r#type: Option<String>,
+
+        /// Search radius in meters, or feet with --units imperial [default: the weights'
+        /// max_distance_km]
+        #[arg(short, long)]
+        radius: Option<f64>,
+
+        /// TOML file of per-type weights and distance thresholds
+        #[arg(short, long)]
+        weights: Option<PathBuf>,
+
+        /// Maximum number of results to fetch per type
+        #[arg(short, long, default_value_t = 5)]
+        max_results: usize,
+    },
+
+    /// Write a shareable HTML report: a map, a sortable results table, and the amenity score
+    Report {
+        #[arg(short, long, alias = "addr")]
+        address: Option<String>,
+
+        #[arg(long, alias = "lat", allow_hyphen_values = true)]
+        latitude: Option<f64>,
+
+        #[arg(long, alias = "lng", alias = "lon", allow_hyphen_values = true)]
+        longitude: Option<f64>,
+
+        /// Point as "lat,lng" or degrees-minutes-seconds such as 6°31'28"N 3°22'45"E
+        #[arg(long, value_name = "LAT,LNG", allow_hyphen_values = true, conflicts_with_all = ["address", "latitude", "longitude"])]
+        at: Option<Coordinates>,
+
+        /// Comma-separated amenity types [default: every type in the weights]
+        #[arg(short, long)]
+        r#type: Option<String>,
+
+        /// Search radius in meters, or feet with --units imperial [default: the weights'
+        /// max_distance_km]
+        #[arg(short, long)]
+        radius: Option<f64>,
+
+        /// TOML file of per-type weights and distance thresholds
+        #[arg(short, long)]
+        weights: Option<PathBuf>,
+
+        /// Maximum number of results to fetch per type
+        #[arg(short, long, default_value_t = 10)]
+        max_results: usize,
+
+        /// HTML file to write instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Compare amenities around two locations side by side
+    Compare {
+        /// First location, as an address or "lat,lng"; "-" compares each query read from stdin
+        /// with the second location and prints NDJSON
+        #[arg(allow_hyphen_values = true)]
+        first: String,
+
+        /// Second location, as an address or "lat,lng", or "-" to read it from stdin like the
+        /// first
+        #[arg(allow_hyphen_values = true)]
+        second: String,
+
+        /// Comma-separated amenity types to compare [default: every type in the weights]
+        #[arg(short, long)]
+        r#type: Option<String>,
+
+        /// Search radius in meters, or feet with --units imperial [default: the weights'
+        /// max_distance_km]
+        #[arg(short, long)]
+        radius: Option<f64>,
+
+        /// TOML file of per-type score weights
+        #[arg(short, long)]
+        weights: Option<PathBuf>,
+
+        /// Maximum number of results to fetch per type
+        #[arg(short, long, default_value_t = 10)]
+        max_results: usize,
+    },
+
+    /// Re-run a nearby search periodically and report added, removed, and re-rated services
+    Watch {
+        #[arg(short, long, alias = "addr")]
+        address: Option<String>,
+
+        #[arg(long, alias = "lat", allow_hyphen_values = true)]
+        latitude: Option<f64>,
+
+        #[arg(long, alias = "lng", alias = "lon", allow_hyphen_values = true)]
+        longitude: Option<f64>,
+
+        /// Point as "lat,lng" or degrees-minutes-seconds such as 6°31'28"N 3°22'45"E
+        #[arg(long, value_name = "LAT,LNG", allow_hyphen_values = true, conflicts_with_all = ["address", "latitude", "longitude"])]
+        at: Option<Coordinates>,
+
+        /// Comma-separated amenity types [default: bank]
+        #[arg(short, long)]
+        r#type: Option<String>,
+
+        /// Radius in meters, or feet with --units imperial [default: 1000 m]
+        #[arg(short, long)]
+        radius: Option<f64>,
+
+        /// Maximum number of results to track per service
+        #[arg(short, long, default_value_t = 20)]
+        max_results: usize,
+
+        /// Time between checks, e.g. "90s", "15m", "1h", or "1d"
+        #[arg(short, long, default_value = "1h", value_parser = watch::parse_interval)]
+        interval: Duration,
+
+        /// POST each batch of changes as JSON to this URL (see --post-header and --post-secret)
+        #[arg(long)]
+        webhook: Option<String>,
+    },
+
+    /// Render a location and its nearby services onto a static map image (PNG, Google provider)
+    Map {
+        #[arg(short, long, alias = "addr")]
+        address: Option<String>,
+
+        #[arg(long, alias = "lat", allow_hyphen_values = true)]
+        latitude: Option<f64>,
+
+        #[arg(long, alias = "lng", alias = "lon", allow_hyphen_values = true)]
+        longitude: Option<f64>,
+
+        /// Point as "lat,lng" or degrees-minutes-seconds such as 6°31'28"N 3°22'45"E
+        #[arg(long, value_name = "LAT,LNG", allow_hyphen_values = true, conflicts_with_all = ["address", "latitude", "longitude"])]
+        at: Option<Coordinates>,
+
+        /// Comma-separated amenity types, each drawn in its own marker color [default: bank]
+        #[arg(short, long)]
+        r#type: Option<String>,
+
+        /// Radius in meters, or feet with --units imperial [default: 1000 m]
+        #[arg(short, long)]
+        radius: Option<f64>,
+
+        /// Maximum number of results to draw per service
+        #[arg(short, long, default_value_t = 10)]
+        max_results: usize,
+
+        /// Outline the search radius as a circle around the location
+        #[arg(long)]
+        circle: bool,
+
+        /// Image size as WIDTHxHEIGHT, at most 640x640
+        #[arg(long, default_value = "640x640", value_parser = staticmap::parse_size)]
+        size: (u32, u32),
+
+        /// PNG file to write
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+
+    /// Test points against circle and polygon geofences, offline
+    Geofence {
+        #[command(subcommand)]
+        action: GeofenceAction,
+    },
+
+    /// Geocode every address in a CSV or newline-delimited file
+    BatchGeocode {
+        /// Input file (CSV with header, or one address per line); "-" reads stdin
+        input: PathBuf,
+
+        /// CSV column holding the address (default "address")
+        #[arg(short, long)]
+        column: Option<String>,
+
+        /// Write results (NDJSON, or rows with --format csv/tsv) to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Record finished rows in this file as they complete, so an interrupted run can carry
+        /// on with --resume
+        #[arg(long, value_name = "PATH")]
+        checkpoint: Option<PathBuf>,
+
+        /// Skip the rows already finished in --checkpoint
+        #[arg(long, requires = "checkpoint")]
+        resume: bool,
+    },
+
+    /// Reverse geocode every point in a CSV or newline-delimited file
+    BatchReverse {
+        /// Input file (CSV with latitude/longitude columns, or "lat,lng" or NDJSON lines); "-"
+        /// reads stdin
+        input: PathBuf,
+
+        /// Write results (NDJSON, or rows with --format csv/tsv) to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Record finished rows in this file as they complete, so an interrupted run can carry
+        /// on with --resume
+        #[arg(long, value_name = "PATH")]
+        checkpoint: Option<PathBuf>,
+
+        /// Skip the rows already finished in --checkpoint
+        #[arg(long, requires = "checkpoint")]
+        resume: bool,
+    },
+
+    /// Find amenities along a GPX track, such as fuel stations near a planned ride
+    EnrichTrack {
+        /// GPX file of a recorded track or a route
+        track: PathBuf,
+
+        /// Comma-separated amenity types (bank, hospital, fuel-station, etc.), custom:<tag>, or
+        /// key=value [default: bank]
+        #[arg(short, long)]
+        r#type: Option<String>,
+
+        /// Distance from the track to search, in meters or feet with --units imperial
+        /// [default: 500 m]
+        #[arg(short, long)]
+        radius: Option<f64>,
+
+        /// Search around a point every this many meters (feet with --units imperial) along the
+        /// track [default: the radius]
+        #[arg(long)]
+        spacing: Option<f64>,
+
+        /// Maximum number of results per type at each searched point
+        #[arg(short, long, alias = "limit", default_value_t = 10)]
+        max_results: usize,
+
+        /// Write results to this file instead of stdout (JSON, GeoJSON, or annotated GPX with
+        /// --format gpx)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
 
-    /// Calculate travel distance between two points
-    Distance {
-        #[arg(long, help = "Origin address")]
+    /// Get directions between two addresses or coordinates
+    Route {
+        #[arg(
+            long,
+            help = "Origin address, or \"-\" to read origins from stdin and print NDJSON"
+        )]
         origin_addr: Option<String>,
 
         #[arg(long, help = "Origin latitude")]
@@ -58,7 +977,10 @@ enum Commands {
         #[arg(long, help = "Origin longitude")]
         origin_lng: Option<f64>,
 
-        #[arg(long, help = "Destination address")]
+        #[arg(
+            long,
+            help = "Destination address, or \"-\" to read destinations from stdin and print NDJSON"
+        )]
         dest_addr: Option<String>,
 
         #[arg(long, help = "Destination latitude")]
@@ -66,117 +988,4112 @@ enum Commands {
 
         #[arg(long, help = "Destination longitude")]
         dest_lng: Option<f64>,
+
+        /// Travel mode (driving, walking, cycling)
+        #[arg(short, long, default_value = "driving")]
+        mode: TravelMode,
     },
-}
 
-#[tokio::main]
-async fn main() {
-    dotenvy::dotenv().ok();
+    /// Compute distances between several origins and destinations
+    Matrix {
+        /// Origin address or "lat,lng" (repeatable); "-" reads origins from stdin
+        #[arg(short, long = "origin", required = true)]
+        origins: Vec<String>,
 
-    let cli = Cli::parse();
-    let client = MapradarClient::new(cli.api_key);
+        /// Destination address or "lat,lng" (repeatable); "-" reads destinations from stdin
+        #[arg(short, long = "destination", required = true)]
+        destinations: Vec<String>,
 
-    match cli.command {
-        Commands::Geocode { address } => match client.geocode_async(&address).await {
-            Ok(loc) => println!("{}", serde_json::to_string_pretty(&loc).unwrap()),
-            Err(e) => {
-                eprintln!("{} {}", "Error:".red().bold(), e);
-                process::exit(1);
-            }
-        },
-        Commands::Reverse {
-            latitude,
-            longitude,
-        } => match client.reverse_geocode_async(latitude, longitude).await {
-            Ok(address) => println!("{:?}", address),
-            Err(e) => {
-                eprintln!("{} {}", "Error:".red().bold(), e);
-                process::exit(1);
-            }
-        },
-        Commands::Nearby {
-            address,
-            latitude,
-            longitude,
-            radius,
-            r#type,
-            max_results,
-        } => {
+        /// Travel mode (driving, walking, cycling); omit for straight-line distances
+        #[arg(short, long)]
+        mode: Option<TravelMode>,
+    },
+
+    /// Show the area reachable within a travel-time budget
+    Reachable {
+        /// Starting address, or "-" to read one query per line from stdin and print NDJSON
+        #[arg(short, long, alias = "addr")]
+        address: Option<String>,
+
+        #[arg(long, alias = "lat")]
+        latitude: Option<f64>,
+
+        #[arg(long, alias = "lng", alias = "lon")]
+        longitude: Option<f64>,
+
+        /// Point as "lat,lng" or degrees-minutes-seconds such as 6°31'28"N 3°22'45"E
+        #[arg(long, value_name = "LAT,LNG", allow_hyphen_values = true, conflicts_with_all = ["address", "latitude", "longitude"])]
+        at: Option<Coordinates>,
+
+        /// Travel-time budget in minutes
+        #[arg(short, long, default_value_t = 15.0)]
+        minutes: f64,
+
+        /// Travel mode (driving, walking, cycling)
+        #[arg(long, default_value = "walking")]
+        mode: TravelMode,
+    },
+
+    /// Find a fair place for several people to meet, and the amenities around it
+    Midpoint {
+        /// Participant's address or point as "lat,lng" (repeat for each, at least two); "-"
+        /// reads participants from stdin
+        #[arg(
+            short,
+            long = "address",
+            alias = "addr",
+            required = true,
+            allow_hyphen_values = true
+        )]
+        addresses: Vec<String>,
+
+        /// Where to meet: centroid (the geographic centre) or minimax (the shortest longest
+        /// straight-line trip)
+        #[arg(long, default_value = "centroid")]
+        strategy: MeetingStrategy,
+
+        /// Comma-separated amenity types (restaurant, cafe, mall, etc.), custom:<tag>, or
+        /// key=value [default: restaurant]
+        #[arg(short, long)]
+        r#type: Option<String>,
+
+        /// Radius around the meeting point in meters, or feet with --units imperial
+        /// [default: 1000 m]
+        #[arg(short, long)]
+        radius: Option<f64>,
+
+        /// Maximum number of results to return per service
+        #[arg(short, long, alias = "limit", default_value_t = 10)]
+        max_results: usize,
+
+        /// Comma-separated table columns (name, type, distance, rating, address, lat, lng, phone, open)
+        #[arg(long, value_delimiter = ',')]
+        columns: Vec<ServiceColumn>,
+
+        /// Write results to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Count amenities per grid cell over an area, as a GeoJSON grid for market analysis
+    Heatmap {
+        /// Area to tile: minLon,minLat,maxLon,maxLat
+        #[arg(long, allow_hyphen_values = true)]
+        bbox: Geometry,
+
+        /// Comma-separated amenity types (bank, restaurant, school, etc.), custom:<tag>, or
+        /// key=value [default: bank]
+        #[arg(short, long)]
+        r#type: Option<String>,
+
+        /// Cell size in meters, or feet with --units imperial [default: 250 m]
+        #[arg(long)]
+        cell: Option<f64>,
+
+        /// Maximum number of results per type searched in each cell
+        #[arg(short, long, alias = "limit", default_value_t = 20)]
+        max_results: usize,
+
+        /// Write the grid to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Show upstream calls per day and endpoint, and the calls saved by the cache
+    Usage {
+        /// Days to include, today included
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+
+        /// Delete the recorded usage history
+        #[arg(long, conflicts_with = "days")]
+        clear: bool,
+    },
+
+    /// List, inspect, or re-run past queries
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+
+    /// Store the API key in the OS keyring instead of the environment or shell history
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
+
+    /// Check the configured API keys
+    Keys {
+        #[command(subcommand)]
+        action: KeysAction,
+    },
+
+    /// Inspect or clear the on-disk response cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// Build or inspect the local OpenStreetMap index used by --provider offline
+    #[cfg(feature = "offline")]
+    Osm {
+        #[command(subcommand)]
+        action: OsmAction,
+    },
+
+    /// Import GTFS feeds whose stops and routes complete nearby transit searches
+    #[cfg(feature = "gtfs")]
+    Gtfs {
+        #[command(subcommand)]
+        action: GtfsAction,
+    },
+
+    /// Calculate travel distance between two points
+    Distance {
+        /// Origin address or "lat,lng" (instead of the --origin-* flags); "-" reads origins from
+        /// stdin and prints NDJSON
+        #[arg(requires = "to", conflicts_with_all = ["origin_addr", "origin_lat", "origin_lng"])]
+        from: Option<String>,
+
+        /// Destination address or "lat,lng" (instead of the --dest-* flags), or "-" to read
+        /// destinations from stdin
+        #[arg(conflicts_with_all = ["dest_addr", "dest_lat", "dest_lng"])]
+        to: Option<String>,
+
+        #[arg(
+            long,
+            help = "Origin address, or \"-\" to read origins from stdin and print NDJSON"
+        )]
+        origin_addr: Option<String>,
+
+        #[arg(long, help = "Origin latitude")]
+        origin_lat: Option<f64>,
+
+        #[arg(long, help = "Origin longitude")]
+        origin_lng: Option<f64>,
+
+        #[arg(
+            long,
+            help = "Destination address, or \"-\" to read destinations from stdin and print NDJSON"
+        )]
+        dest_addr: Option<String>,
+
+        #[arg(long, help = "Destination latitude")]
+        dest_lat: Option<f64>,
+
+        #[arg(long, help = "Destination longitude")]
+        dest_lng: Option<f64>,
+
+        /// Distance formula: haversine (spherical) or vincenty (WGS-84 ellipsoid)
+        #[arg(long, default_value = "haversine")]
+        method: DistanceMethod,
+    },
+
+    /// Save `nearby` arguments under a name in the config file, to apply to any address with
+    /// `mapradar run`
+    SaveSearch {
+        /// Name of the search, e.g. "family" or "commuter"; saving the same name again replaces it
+        name: String,
+
+        /// `nearby` arguments to save, e.g. --type school,hospital --radius 2000
+        #[arg(
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            value_name = "NEARBY_ARGS"
+        )]
+        args: Vec<String>,
+    },
+
+    /// Run a search saved with `mapradar save-search`
+    Run {
+        /// Name of the saved search
+        name: String,
+
+        /// Further `nearby` arguments, usually the location (--address, --at, ...); an option
+        /// given here replaces the saved one
+        #[arg(
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            value_name = "NEARBY_ARGS"
+        )]
+        args: Vec<String>,
+    },
+
+    /// List or remove saved searches
+    Searches {
+        #[command(subcommand)]
+        action: SearchesAction,
+    },
+
+    /// Read or change the defaults stored in the config file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Run a long-lived server exposing Mapradar to other programs
+    #[command(group(ArgGroup::new("mode").required(true)))]
+    Serve {
+        /// Speak the Model Context Protocol over stdio, offering geocode, reverse_geocode, and nearby_search tools
+        #[arg(long, group = "mode")]
+        mcp: bool,
+
+        /// Answer JSON-RPC 2.0 requests (geocode, reverse, nearby) on stdio, or over HTTP with --port
+        #[arg(long, group = "mode")]
+        rpc: bool,
+
+        /// Serve a REST API with /geocode, /reverse, and /nearby endpoints
+        #[arg(long, group = "mode")]
+        http: bool,
+
+        /// Serve the gRPC API from proto/mapradar.proto, including streaming nearby search
+        #[arg(long, group = "mode")]
+        grpc: bool,
+
+        /// Port to listen on (REST defaults to 8080, gRPC to 50051; JSON-RPC uses stdio unless a port is given)
+        #[arg(long, conflicts_with = "mcp")]
+        port: Option<u16>,
+
+        /// Address to bind the HTTP or gRPC server to
+        #[arg(long, default_value = "127.0.0.1", conflicts_with = "mcp")]
+        host: IpAddr,
+    },
+
+    /// Explore nearby services interactively in the terminal
+    #[cfg(feature = "tui")]
+    Tui,
+
+    /// Print a shell completion script, e.g. `mapradar completions bash > ~/.local/share/bash-completion/completions/mapradar`
+    Completions {
+        /// Shell to generate completions for
+        shell: clap_complete::Shell,
+    },
+
+    /// Print the man page in roff format, e.g. `mapradar manpage > ~/.local/share/man/man1/mapradar.1`
+    Manpage,
+}
+
+impl Commands {
+    /// Whether the command produces a single JSON result that `--post-to` can deliver and
+    /// `--dry-run` can plan.
+    fn has_single_result(&self) -> bool {
+        !self.reads_stdin()
+            && matches!(
+                self,
+                Self::Geocode { .. }
+                    | Self::Reverse { .. }
+                    | Self::Elevation { .. }
+                    | Self::Timezone { .. }
+                    | Self::Admin { .. }
+                    | Self::Postcode { .. }
+                    | Self::Verify { .. }
+                    | Self::ParseAddress { .. }
+                    | Self::Encode { .. }
+                    | Self::Decode { .. }
+                    | Self::Match { .. }
+                    | Self::Diff { .. }
+                    | Self::Suggest { .. }
+                    | Self::Details { .. }
+                    | Self::Nearby {
+                        stream: false,
+                        output: None,
+                        ..
+                    }
+                    | Self::EnrichTrack { output: None, .. }
+                    | Self::Snap { output: None, .. }
+                    | Self::Score { .. }
+                    | Self::Compare { .. }
+                    | Self::Route { .. }
+                    | Self::Matrix { .. }
+                    | Self::Reachable { .. }
+                    | Self::Midpoint { output: None, .. }
+                    | Self::Heatmap { output: None, .. }
+            )
+    }
+
+    /// Whether the command draws progress: a row count for batch geocoding, or a spinner per
+    /// service type for a single nearby search.
+    fn shows_progress(&self) -> bool {
+        !self.reads_stdin()
+            && matches!(
+                self,
+                Self::BatchGeocode { .. }
+                    | Self::BatchReverse { .. }
+                    | Self::Nearby { stream: false, .. }
+            )
+    }
+
+    /// Whether Ctrl-C cancels the command's requests and lets it write the results it has,
+    /// rather than stopping it at once. Servers and watches run until stopped, the TUI reads
+    /// Ctrl-C as a key, and stdin queries are answered line by line as they arrive.
+    fn traps_interrupts(&self) -> bool {
+        match self {
+            Self::Serve { .. } | Self::Watch { .. } => false,
+            #[cfg(feature = "tui")]
+            Self::Tui => false,
+            _ => !self.reads_stdin(),
+        }
+    }
+
+    /// Whether the command runs a query worth keeping in the history, rather than managing
+    /// settings, keys, local data, or a server.
+    fn is_query(&self) -> bool {
+        match self {
+            Self::Config { .. }
+            | Self::Auth { .. }
+            | Self::Keys { .. }
+            | Self::Cache { .. }
+            | Self::Usage { .. }
+            | Self::History { .. }
+            | Self::SaveSearch { .. }
+            | Self::Searches { .. }
+            | Self::Serve { .. }
+            | Self::Completions { .. }
+            | Self::Manpage => false,
+            #[cfg(feature = "offline")]
+            Self::Osm { .. } => false,
+            #[cfg(feature = "gtfs")]
+            Self::Gtfs { .. } => false,
+            _ => true,
+        }
+    }
+
+    /// Address and point arguments given as "-", which read their queries from stdin.
+    fn stdin_args(&self) -> usize {
+        let given = |arg: &Option<String>| usize::from(arg.as_deref() == Some(STDIN_ARG));
+        let listed = |args: &[String]| args.iter().filter(|arg| *arg == STDIN_ARG).count();
+        match self {
+            Self::Geocode { address, .. }
+            | Self::Verify { address }
+            | Self::ParseAddress { address, .. } => usize::from(address == STDIN_ARG),
+            Self::Timezone { point, .. }
+            | Self::Admin { point, .. }
+            | Self::Encode { point, .. } => usize::from(point == STDIN_ARG),
+            Self::Reverse { stdin, point, .. } => usize::from(*stdin) + given(point),
+            Self::Nearby { stdin, address, .. } => usize::from(*stdin) + given(address),
+            Self::Postcode { check, .. } => given(check),
+            Self::Score { address, .. }
+            | Self::Reachable { address, .. }
+            | Self::Report { address, .. }
+            | Self::Watch { address, .. }
+            | Self::Map { address, .. } => given(address),
+            Self::Route {
+                origin_addr,
+                dest_addr,
+                ..
+            } => given(origin_addr) + given(dest_addr),
+            Self::Distance {
+                from,
+                to,
+                origin_addr,
+                dest_addr,
+                ..
+            } => given(from) + given(to) + given(origin_addr) + given(dest_addr),
+            Self::Compare { first, second, .. } => {
+                usize::from(first == STDIN_ARG) + usize::from(second == STDIN_ARG)
+            }
+            Self::Elevation { points, .. } => listed(points),
+            Self::Midpoint { addresses, .. } => listed(addresses),
+            Self::Matrix {
+                origins,
+                destinations,
+                ..
+            } => listed(origins) + listed(destinations),
+            _ => 0,
+        }
+    }
+
+    /// Whether the command answers each query read from stdin and prints one NDJSON outcome per
+    /// line. Commands taking a list of points read the whole list from stdin instead.
+    fn reads_stdin(&self) -> bool {
+        self.stdin_args() > 0
+            && !matches!(
+                self,
+                Self::Elevation { .. } | Self::Midpoint { .. } | Self::Matrix { .. }
+            )
+    }
+}
+
+#[derive(Subcommand)]
+enum HistoryAction {
+    /// Show recent queries, newest first
+    List {
+        /// Maximum number of queries
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+
+        /// Only queries of this command, e.g. "nearby"
+        #[arg(long)]
+        command: Option<String>,
+    },
+
+    /// Show one query with its arguments and result hash
+    Show { id: i64 },
+
+    /// Run a past query again with the same arguments, then report whether its result changed
+    Rerun { id: i64 },
+
+    /// Delete the recorded history
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum GeofenceAction {
+    /// Report which fences contain each point, as one JSON line per point
+    Check {
+        /// GeoJSON file of polygon fences and Point fences with a radius_m property
+        fences: PathBuf,
+
+        /// Points as "lat,lng"
+        #[arg(allow_hyphen_values = true, required_unless_present = "input")]
+        points: Vec<String>,
+
+        /// File of "lat,lng" points, one per line ("-" reads stdin)
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// Only test these fences (comma-separated names)
+        #[arg(long, value_delimiter = ',')]
+        fence: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuthAction {
+    /// Read an API key from stdin and store it in the OS keyring, where it takes precedence over
+    /// MAPRADAR_API_KEY and --api-key
+    Login,
+
+    /// Remove the stored API key
+    Logout,
+}
+
+#[derive(Subcommand)]
+enum KeysAction {
+    /// Send one request with each key and report whether it is accepted and within quota
+    Verify,
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Remove every cached response
+    Clear,
+
+    /// Show entry counts and size of the cache
+    Stats,
+}
+
+#[cfg(feature = "offline")]
+#[derive(Subcommand)]
+enum OsmAction {
+    /// Add the named, tagged nodes and ways of an OSM PBF extract to the index
+    Import {
+        /// Extract to import, e.g. from https://download.geofabrik.de
+        pbf: PathBuf,
+    },
+
+    /// Show place counts and size of the index
+    Stats,
+}
+
+#[cfg(feature = "gtfs")]
+#[derive(Subcommand)]
+enum GtfsAction {
+    /// Add the stops of a GTFS feed, with the routes serving them, to the local cache database
+    Import {
+        /// GTFS zip archive or directory; importing the same path again replaces it
+        feed: PathBuf,
+    },
+
+    /// List imported feeds with their stop counts and the area they span
+    Stats,
+}
+
+#[derive(Subcommand)]
+enum SearchesAction {
+    /// Show every saved search with its arguments
+    List,
+
+    /// Delete a saved search
+    Remove { name: String },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Store a default (keys: api_key, api_keys, key_rotation, provider, format, radius, units,
+    /// types, cache_ttl, no_cache, rate_limit, max_calls_per_day)
+    Set {
+        key: String,
+        #[arg(allow_hyphen_values = true)]
+        value: String,
+    },
+
+    /// Print one stored value
+    Get { key: String },
+
+    /// Remove a stored value
+    Unset { key: String },
+
+    /// Show every stored value and the config file location
+    List,
+}
+
+/// Full command-line definition, shared by argument parsing, completions, and the man page.
+fn command() -> clap::Command {
+    Cli::command()
+}
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+
+    let matches = command()
+        .try_get_matches()
+        .unwrap_or_else(|e| exit_with_usage(e));
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| exit_with_usage(e));
+    ERROR_FORMAT.get_or_init(|| cli.error_format);
+    if let Commands::Run { name, args } = &cli.command {
+        let config_path = cli.config_path.clone().unwrap_or_else(Config::default_path);
+        let config = Config::load(&config_path).unwrap_or_else(|e| exit_with(e));
+        let saved = config.search(name).unwrap_or_else(|e| exit_with(e));
+        let argv = saved_search_argv(saved, args);
+        let matches = command()
+            .try_get_matches_from(argv)
+            .unwrap_or_else(|e| exit_with_usage(e));
+        cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| exit_with_usage(e));
+    }
+    match &cli.command {
+        Commands::Completions { shell } => {
+            let mut script = Vec::new();
+            clap_complete::generate(*shell, &mut command(), "mapradar", &mut script);
+            if let Err(e) = io::stdout().write_all(&script) {
+                exit_with(e.into());
+            }
+            return;
+        }
+        Commands::Manpage => {
+            let man = clap_mangen::Man::new(command().version(env!("CARGO_PKG_VERSION")));
+            if let Err(e) = man.render(&mut io::stdout()) {
+                exit_with(e.into());
+            }
+            return;
+        }
+        _ => {}
+    }
+    let command_name = matches.subcommand_name().unwrap_or_default();
+    init_logging(cli.verbose, cli.trace, cli.log_format);
+    let config_path = cli.config_path.clone().unwrap_or_else(Config::default_path);
+    let config = Config::load(&config_path).unwrap_or_else(|e| exit_with(e));
+
+    let retry_policy = RetryPolicy::default()
+        .max_attempts(cli.retries + 1)
+        .base_delay(Duration::from_millis(cli.retry_delay_ms));
+    let mut sink = ResultSink {
+        webhook: cli.post_to.as_deref().map(|url| {
+            webhook_sink(
+                url,
+                &cli.post_headers,
+                cli.post_secret.as_deref(),
+                retry_policy.clone(),
+            )
+            .unwrap_or_else(|e| exit_with(e))
+        }),
+        dry_run: false,
+        history: None,
+        custom: CustomOutput::new(&cli),
+    };
+    if sink.is_webhook() && !cli.command.has_single_result() {
+        exit_with(MapradarError::InvalidInput(
+            "--post-to is only available for commands that print a single JSON result".to_string(),
+        ))
+    }
+
+    if cli.dry_run && !cli.command.has_single_result() {
+        exit_with(MapradarError::InvalidInput(
+            "--dry-run is only available for commands that print a single JSON result".to_string(),
+        ))
+    }
+
+    if let Commands::Config { action } = &cli.command {
+        run_config_action(action, config, &config_path);
+        return;
+    }
+
+    if let Commands::SaveSearch { name, args } = &cli.command {
+        save_search(name, args, config, &config_path);
+        return;
+    }
+
+    if let Commands::Searches { action } = &cli.command {
+        run_searches_action(action, config, &config_path);
+        return;
+    }
+
+    if let Commands::Auth { action } = &cli.command {
+        run_auth_action(action);
+        return;
+    }
+
+    if let Commands::History { action } = &cli.command {
+        run_history_action(action);
+        return;
+    }
+
+    if !cli.no_history && !cli.dry_run && cli.command.is_query() {
+        match QueryHistory::open(&QueryHistory::default_path()).and_then(|history| {
+            Ok((
+                history.record(command_name, &history_args(command_name))?,
+                history,
+            ))
+        }) {
+            Ok((id, history)) => sink.history = Some((history, id)),
+            Err(e) => eprintln!(
+                "{} query not saved to history: {}",
+                "Warning:".yellow().bold(),
+                e
+            ),
+        }
+    }
+
+    if let Commands::ParseAddress {
+        address,
+        geocode: false,
+    } = &cli.command
+    {
+        if address == STDIN_ARG {
+            run_stdin_queries(cli.concurrency, sink.custom.as_ref(), async |query| {
+                query_address(query)
+                    .map(|address| components_json(&address::parse_address(&address)))
+            })
+            .await;
+        } else {
+            sink.json(&components_json(&address::parse_address(address)))
+                .await;
+        }
+        return;
+    }
+
+    if let Commands::Geofence { action } = &cli.command {
+        run_geofence_action(action);
+        return;
+    }
+
+    if let Commands::Encode {
+        point,
+        longitude,
+        length,
+    } = &cli.command
+    {
+        let encode = |point: Coordinates| {
+            plus_code::encode(point, *length).map(|code| {
+                serde_json::json!({
+                    "plus_code": code,
+                    "latitude": point.latitude(),
+                    "longitude": point.longitude(),
+                })
+            })
+        };
+        if point == STDIN_ARG {
+            run_stdin_queries(cli.concurrency, sink.custom.as_ref(), async |query| {
+                query.coordinates().map(encode).unwrap_or_else(|| {
+                    Err(MapradarError::InvalidInput(
+                        "Expected coordinates; encoding runs offline and cannot geocode"
+                            .to_string(),
+                    ))
+                })
+            })
+            .await;
+        } else {
+            let encoded = encode(parse_point_args(point, longitude.as_deref()))
+                .unwrap_or_else(|e| exit_with(e));
+            sink.json(&encoded).await;
+        }
+        return;
+    }
+
+    if let Commands::Match {
+        query,
+        candidates,
+        near,
+        distance_weight,
+        min_score,
+        limit,
+    } = &cli.command
+    {
+        let places = fuzzy::read_places(candidates).unwrap_or_else(|e| exit_with(e));
+        let options = fuzzy::FuzzyOptions {
+            near: *near,
+            distance_weight: *distance_weight,
+            min_score: *min_score,
+            limit: *limit,
+            ..fuzzy::FuzzyOptions::default()
+        };
+        sink.json(&fuzzy::rank(query, &places, &options)).await;
+        return;
+    }
+
+    if let Commands::Diff { old, new, patch } = &cli.command {
+        let (before, after) = match (diff::read_snapshot(old), diff::read_snapshot(new)) {
+            (Ok(before), Ok(after)) => (before, after),
+            (Err(e), _) | (_, Err(e)) => exit_with(e),
+        };
+        if *patch {
+            sink.json(&diff::json_patch(&before, &after)).await;
+        } else {
+            sink.json(&diff::compare(&before, &after)).await;
+        }
+        return;
+    }
+
+    if let Commands::Decode { code, near } = &cli.command {
+        let code = match near {
+            Some(near) => plus_code::recover_nearest(code, *near),
+            None => Ok(code.to_uppercase()),
+        };
+        let (code, area) = match code.and_then(|code| plus_code::decode(&code).map(|a| (code, a))) {
+            Ok(decoded) => decoded,
+            Err(e) => exit_with(e),
+        };
+        let center = area.center();
+        sink.json(&serde_json::json!({
+            "plus_code": code,
+            "latitude": center.latitude(),
+            "longitude": center.longitude(),
+            "south": area.south,
+            "west": area.west,
+            "north": area.north,
+            "east": area.east,
+            "code_length": area.code_length,
+        }))
+        .await;
+        return;
+    }
+
+    #[cfg(feature = "offline")]
+    if let Commands::Osm { action } = &cli.command {
+        run_osm_action(action, cli.osm_db.as_deref());
+        return;
+    }
+
+    #[cfg(feature = "gtfs")]
+    if let Commands::Gtfs { action } = &cli.command {
+        run_gtfs_action(action);
+        return;
+    }
+
+    let format = cli.format.or(config.format()).unwrap_or(OutputFormat::Json);
+    let units = cli.units.or(config.units()).unwrap_or_default();
+    // Watch compares fresh results against its own snapshots, so it never reads cached searches.
+    let caching = !(cli.no_cache
+        || config.no_cache.unwrap_or(false)
+        || matches!(cli.command, Commands::Watch { .. }));
+    // Only open the cache when something reads it, so --no-cache works without a writable cache
+    // directory.
+    let disk_cache = (caching
+        || matches!(cli.command, Commands::Cache { .. } | Commands::Watch { .. }))
+    .then(|| {
+        DiskCache::open(
+            &DiskCache::default_path(),
+            Duration::from_secs(
+                cli.cache_ttl
+                    .or(config.cache_ttl)
+                    .unwrap_or(DEFAULT_DISK_TTL_SECS),
+            ),
+        )
+        .unwrap_or_else(|e| exit_with(e))
+    });
+
+    if cli.command.stdin_args() > 1 {
+        exit_with(MapradarError::InvalidInput(
+            "Only one address or point can be read from stdin (\"-\")".to_string(),
+        ))
+    }
+
+    if cli.command.stdin_args() > 0
+        && matches!(
+            cli.command,
+            Commands::Report { .. } | Commands::Watch { .. } | Commands::Map { .. }
+        )
+    {
+        exit_with(MapradarError::InvalidInput(
+            "report, watch, and map follow a single location and cannot read it from stdin"
+                .to_string(),
+        ))
+    }
+
+    if cli.command.reads_stdin() && format != OutputFormat::Json {
+        exit_with(MapradarError::InvalidInput(
+            "Queries read from stdin are answered as NDJSON; use --format json".to_string(),
+        ))
+    }
+
+    if format.delimiter().is_some()
+        && !matches!(
+            cli.command,
+            Commands::Nearby { .. }
+                | Commands::Midpoint { .. }
+                | Commands::Heatmap { .. }
+                | Commands::BatchGeocode { .. }
+                | Commands::BatchReverse { .. }
+                | Commands::Snap { .. }
+        )
+    {
+        exit_with(MapradarError::InvalidInput(
+            "CSV and TSV output are only available for nearby, midpoint, heatmap, batch-geocode, batch-reverse, and snap"
+                .to_string(),
+        ))
+    }
+
+    if sink.is_webhook() && !matches!(format, OutputFormat::Json | OutputFormat::Geojson) {
+        exit_with(MapradarError::InvalidInput(
+            "--post-to delivers JSON or GeoJSON only".to_string(),
+        ))
+    }
+
+    if format == OutputFormat::Table
+        && !matches!(
+            cli.command,
+            Commands::Nearby { stream: false, .. }
+                | Commands::Midpoint { .. }
+                | Commands::Heatmap { .. }
+                | Commands::Compare { .. }
+                | Commands::Usage { .. }
+        )
+    {
+        exit_with(MapradarError::InvalidInput(
+            "Table output is only available for nearby without --stream, midpoint, heatmap, compare, and usage"
+                .to_string(),
+        ))
+    }
+
+    if format == OutputFormat::Kml && !matches!(cli.command, Commands::Nearby { stream: false, .. })
+    {
+        exit_with(MapradarError::InvalidInput(
+            "KML output is only available for nearby without --stream".to_string(),
+        ))
+    }
+
+    if format == OutputFormat::Html
+        && !matches!(
+            cli.command,
+            Commands::Nearby { stream: false, .. } | Commands::Report { .. }
+        )
+    {
+        exit_with(MapradarError::InvalidInput(
+            "HTML output is only available for nearby without --stream, and report".to_string(),
+        ))
+    }
+
+    if format == OutputFormat::Gpx && !matches!(cli.command, Commands::EnrichTrack { .. }) {
+        exit_with(MapradarError::InvalidInput(
+            "GPX output is only available for enrich-track".to_string(),
+        ))
+    }
+
+    if format == OutputFormat::Parquet {
+        if !matches!(
+            cli.command,
+            Commands::BatchGeocode { .. } | Commands::BatchReverse { .. }
+        ) {
+            exit_with(MapradarError::InvalidInput(
+                "Parquet output is only available for batch-geocode and batch-reverse".to_string(),
+            ))
+        }
+        if !cfg!(feature = "parquet") {
+            exit_with(MapradarError::Unsupported(
+                "Parquet output requires building mapradar with the `parquet` feature".to_string(),
+            ))
+        }
+    }
+
+    if let (Commands::Cache { action }, Some(disk_cache)) = (&cli.command, &disk_cache) {
+        run_cache_action(action, disk_cache);
+        return;
+    }
+
+    let max_calls_per_day = cli.max_calls_per_day.or(config.max_calls_per_day);
+    let ledger = match UsageLedger::open(&UsageLedger::default_path()) {
+        Ok(ledger) => Some(ledger.command(command_name)),
+        // Without a ledger the budget cannot be enforced, so only carry on when none is set.
+        Err(e) if max_calls_per_day.is_some() || matches!(cli.command, Commands::Usage { .. }) => {
+            exit_with(e)
+        }
+        Err(e) => {
+            eprintln!("{} usage not recorded: {}", "Warning:".yellow().bold(), e);
+            None
+        }
+    };
+
+    if let (Commands::Usage { days, clear }, Some(ledger)) = (&cli.command, &ledger) {
+        run_usage(ledger, *days, *clear, format, max_calls_per_day);
+        return;
+    }
+
+    let provider_kind = cli.provider.or(config.provider()).unwrap_or_default();
+    let mut builder = MapradarClient::builder()
+        .provider(provider_kind)
+        .retry_policy(retry_policy.clone())
+        .max_pages(cli.max_pages)
+        .concurrency(cli.concurrency);
+    if let Some(rate_limit) = cli.rate_limit.or(config.rate_limit()) {
+        builder = builder.rate_limiter(RateLimiter::new(rate_limit));
+    }
+    if let Some(timeout) = cli.timeout {
+        match Duration::try_from_secs_f64(timeout) {
+            Ok(timeout) if !timeout.is_zero() => builder = builder.timeout(timeout),
+            _ => exit_with(MapradarError::InvalidInput(
+                "--timeout must be a positive number of seconds".to_string(),
+            )),
+        }
+    }
+    if let Some(base_url) = cli.base_url {
+        builder = builder.base_url(base_url);
+    }
+    if let Some(language) = cli.language {
+        builder = builder.language(language);
+    }
+    if let Some(proxy) = cli.proxy {
+        builder = builder.proxy(proxy);
+    }
+    if let Some(path) = cli.osm_db {
+        builder = builder.offline_index(path);
+    }
+    #[cfg(feature = "gtfs")]
+    match GtfsIndex::open_imported(&GtfsIndex::default_path()) {
+        Ok(Some(index)) => builder = builder.transit_index(index),
+        Ok(None) => {}
+        Err(e) => eprintln!(
+            "{} imported GTFS stops not searched: {}",
+            "Warning:".yellow().bold(),
+            e
+        ),
+    }
+    if let Some(dir) = cli.record {
+        builder = builder.fixtures(Fixtures::record(dir));
+    } else if let Some(dir) = cli.replay {
+        builder = builder.fixtures(Fixtures::replay(dir));
+    }
+    builder = match &disk_cache {
+        Some(disk_cache) if caching => builder.disk_cache(disk_cache.clone()),
+        _ => builder.no_cache(),
+    };
+    let api_keys = if provider_kind == ProviderKind::Google {
+        resolve_api_keys(cli.api_key, cli.api_keys, &matches, &config)
+    } else {
+        None
+    };
+    if let Some(api_keys) = &api_keys {
+        builder = builder.api_keys(api_keys.clone());
+    }
+    if let Some(rotation) = cli.key_rotation.or(config.key_rotation()) {
+        builder = builder.key_rotation(rotation);
+    }
+    if cli.dry_run {
+        builder = builder.dry_run();
+    }
+    if let Some(mut ledger) = ledger {
+        if let Some(max) = max_calls_per_day {
+            ledger = ledger.max_calls_per_day(max);
+        }
+        builder = builder.usage_ledger(ledger);
+    }
+    // JSON-RPC over HTTP and gRPC report upstream and cache metrics at /metrics; REST attaches
+    // its own registry, shared by its passthrough clients.
+    if matches!(
+        cli.command,
+        Commands::Serve {
+            rpc: true,
+            port: Some(_),
+            ..
+        } | Commands::Serve { grpc: true, .. }
+    ) {
+        builder = builder.metrics(Metrics::new());
+    }
+    if let Commands::Keys {
+        action: KeysAction::Verify,
+    } = cli.command
+    {
+        run_keys_verify(&builder, provider_kind, api_keys.unwrap_or_default()).await;
+        return;
+    }
+    if let Commands::Serve {
+        http: true,
+        host,
+        port,
+        ..
+    } = cli.command
+    {
+        let addr = SocketAddr::new(host, port.unwrap_or(DEFAULT_HTTP_PORT));
+        serve_rest(builder, addr).await;
+        return;
+    }
+
+    // Bars would garble log lines and dry-run plans on stderr, so those runs go without them.
+    if cli.command.shows_progress()
+        && !(cli.quiet || cli.dry_run || cli.verbose > 0 || cli.trace)
+        && io::stdout().is_terminal()
+    {
+        builder = builder.progress(ProgressBars::default());
+    }
+    let interrupted = CancellationToken::new();
+    if cli.command.traps_interrupts() {
+        trap_interrupts(interrupted.clone());
+        builder = builder.cancellation(interrupted.clone());
+    }
+    let client = match builder.build() {
+        Ok(client) => client,
+        Err(e) => exit_with(e),
+    };
+    if cli.dry_run {
+        DRY_RUN_CLIENT.get_or_init(|| client.clone());
+        sink.dry_run = true;
+    }
+
+    match cli.command {
+        Commands::Geocode {
+            address,
+            all,
+            with_timezone,
+            with_elevation,
+            country,
+            bias_around,
+            ..
+        } if address == "-" => {
+            if all {
+                exit_with(MapradarError::InvalidInput(
+                    "--all is not available for queries read from stdin".to_string(),
+                ))
+            }
+            let region = search_region(&country, bias_around);
+            run_stdin_queries(
+                cli.concurrency,
+                sink.custom.as_ref(),
+                |query: SearchQuery| {
+                    let query = query.within(region.clone());
+                    locate_with_extras(&client, query, with_timezone, with_elevation)
+                },
+            )
+            .await;
+        }
+        Commands::Geocode {
+            address,
+            all: true,
+            min_confidence,
+            country,
+            bias_around,
+            ..
+        } => match client
+            .geocode_all_in_async(&address, &search_region(&country, bias_around))
+            .await
+        {
+            Ok(mut candidates) => {
+                if let Some(min_confidence) = min_confidence {
+                    candidates.retain(|c| c.confidence >= min_confidence);
+                }
+                match format {
+                    OutputFormat::Geojson => {
+                        sink.json(&feature_collection(
+                            candidates.iter().map(ToGeoJson::to_feature).collect(),
+                        ))
+                        .await
+                    }
+                    _ => sink.json(&candidates).await,
+                }
+            }
+            Err(e) => exit_with(e),
+        },
+        Commands::Geocode {
+            address,
+            with_timezone,
+            with_elevation,
+            country,
+            bias_around,
+            ..
+        } => {
+            let region = search_region(&country, bias_around);
+            let result = match client.geocode_in_async(&address, &region).await {
+                Ok(loc) if with_timezone => client.attach_timezone_async(loc).await,
+                result => result,
+            };
+            let result = match result {
+                Ok(loc) if with_elevation => client.attach_elevation_async(loc).await,
+                result => result,
+            };
+            match result {
+                Ok(loc) => match format {
+                    OutputFormat::Geojson => {
+                        sink.json(&feature_collection(vec![loc.to_feature()])).await
+                    }
+                    _ => sink.json(&loc).await,
+                },
+                Err(e) => exit_with(e),
+            }
+        }
+        Commands::Reverse {
+            stdin,
+            point,
+            with_timezone,
+            with_elevation,
+            ..
+        } if stdin || point.as_deref() == Some(STDIN_ARG) => {
+            run_stdin_queries(cli.concurrency, sink.custom.as_ref(), |query| {
+                locate_with_extras(&client, query, with_timezone, with_elevation)
+            })
+            .await;
+        }
+        Commands::Reverse {
+            point,
+            longitude,
+            with_timezone,
+            with_elevation,
+            ..
+        } => {
+            let point = parse_point_args(&point.unwrap_or_default(), longitude.as_deref());
+            let result = match client.reverse_geocode_async(point).await {
+                Ok(address) if with_timezone => client.attach_timezone_async(address).await,
+                result => result,
+            };
+            let result = match result {
+                Ok(address) if with_elevation => client.attach_elevation_async(address).await,
+                result => result,
+            };
+            match result {
+                Ok(address) => match format {
+                    OutputFormat::Geojson => {
+                        sink.json(&feature_collection(vec![address.to_feature()]))
+                            .await
+                    }
+                    _ => sink.json(&address).await,
+                },
+                Err(e) => exit_with(e),
+            }
+        }
+        Commands::Elevation { points, samples } => {
+            let queries = parse_points(&points);
+            let path = match futures::future::try_join_all(
+                queries.iter().map(|q| client.resolve_coordinates_async(q)),
+            )
+            .await
+            {
+                Ok(path) => path,
+                Err(e) => exit_with(e),
+            };
+
+            match client.elevation_profile_async(&path, samples).await {
+                Ok(profile) if profile.len() == 1 => sink.json(&profile[0]).await,
+                Ok(profile) => sink.json(&profile).await,
+                Err(e) => exit_with(e),
+            }
+        }
+        Commands::Snap { input, output } => {
+            let is_gpx = input
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("gpx"));
+            let points = if is_gpx {
+                std::fs::read_to_string(&input)
+                    .map_err(MapradarError::from)
+                    .and_then(|xml| GpxTrack::parse(&xml))
+                    .map(|track| track.path())
+            } else {
+                batch::read_coordinates(&input).map(|coordinates| {
+                    coordinates
+                        .iter()
+                        .map(|c| (c.latitude(), c.longitude()))
+                        .collect()
+                })
+            }
+            .unwrap_or_else(|e| exit_with(e));
+
+            let snapped = client
+                .snap_to_road_async(&points)
+                .await
+                .unwrap_or_else(|e| exit_with(e));
+            let written = match (format.delimiter(), output) {
+                (_, output) if sink.custom.is_some() => {
+                    sink.record(&snapped);
+                    sink.custom(&snapped, output.as_ref()).unwrap_or(Ok(()))
+                }
+                (Some(delimiter), output) => open_output(output.as_ref())
+                    .map_err(MapradarError::from)
+                    .and_then(|writer| {
+                        sink.record(&snapped);
+                        RowWriter::new(writer, delimiter)
+                            .write_all(snapped.iter().map(SnapRow::from))
+                    }),
+                (None, output) => {
+                    let document = match format {
+                        OutputFormat::Geojson => {
+                            feature_collection(snapped.iter().map(ToGeoJson::to_feature).collect())
+                        }
+                        _ => serde_json::to_value(&snapped).unwrap(),
+                    };
+                    match output {
+                        Some(path) => {
+                            sink.record(&document);
+                            std::fs::write(path, serde_json::to_string_pretty(&document).unwrap())
+                                .map_err(MapradarError::from)
+                        }
+                        None => {
+                            sink.json(&document).await;
+                            Ok(())
+                        }
+                    }
+                }
+            };
+            if let Err(e) = written {
+                exit_with(e)
+            }
+            eprintln!(
+                "{} {} of {} points snapped to roads",
+                "Done:".green().bold(),
+                snapped.iter().filter(|p| p.is_snapped()).count(),
+                snapped.len()
+            );
+        }
+        Commands::Timezone { point, .. } if point == STDIN_ARG => {
+            run_stdin_queries(cli.concurrency, sink.custom.as_ref(), async |query| {
+                let point = query_point(&client, &query).await?;
+                client
+                    .timezone_async(point)
+                    .await
+                    .map(|tz| timezone_json(&tz))
+            })
+            .await;
+        }
+        Commands::Timezone { point, longitude } => match client
+            .timezone_async(parse_point_args(&point, longitude.as_deref()))
+            .await
+        {
+            Ok(timezone) => sink.json(&timezone_json(&timezone)).await,
+            Err(e) => exit_with(e),
+        },
+        Commands::Verify { address } if address == STDIN_ARG => {
+            run_stdin_queries(cli.concurrency, sink.custom.as_ref(), async |query| {
+                client.verify_address_async(&query_address(query)?).await
+            })
+            .await;
+        }
+        Commands::Verify { address } => match client.verify_address_async(&address).await {
+            Ok(verification) => sink.json(&verification).await,
+            Err(e) => exit_with(e),
+        },
+        Commands::Postcode {
+            check: Some(address),
+            ..
+        } if address == STDIN_ARG => {
+            run_stdin_queries(cli.concurrency, sink.custom.as_ref(), async |query| {
+                client.check_postcode_async(&query_address(query)?).await
+            })
+            .await;
+        }
+        Commands::Postcode {
+            postcode,
+            country,
+            check,
+        } => match (check, postcode, country) {
+            (Some(address), _, _) => match client.check_postcode_async(&address).await {
+                Ok(check) => sink.json(&check).await,
+                Err(e) => exit_with(e),
+            },
+            (None, Some(postcode), Some(country)) => {
+                match client.postcode_async(&postcode, &country).await {
+                    Ok(area) => sink.json(&area).await,
+                    Err(e) => exit_with(e),
+                }
+            }
+            _ => unreachable!("clap requires a postcode and country without --check"),
+        },
+        Commands::Admin {
+            point,
+            with_geometry,
+            ..
+        } if point == STDIN_ARG => {
+            run_stdin_queries(cli.concurrency, sink.custom.as_ref(), async |query| {
+                let point = query_point(&client, &query).await?;
+                client.admin_lookup_async(point, with_geometry).await
+            })
+            .await;
+        }
+        Commands::Admin {
+            point,
+            longitude,
+            with_geometry,
+        } => match client
+            .admin_lookup_async(
+                parse_point_args(&point, longitude.as_deref()),
+                with_geometry,
+            )
+            .await
+        {
+            Ok(hierarchy) => match format {
+                OutputFormat::Geojson => sink.json(&hierarchy.to_feature_collection()).await,
+                _ => sink.json(&hierarchy).await,
+            },
+            Err(e) => exit_with(e),
+        },
+        Commands::ParseAddress { address, .. } if address == STDIN_ARG => {
+            run_stdin_queries(cli.concurrency, sink.custom.as_ref(), async |query| {
+                let location = client.geocode_async(&query_address(query)?).await?;
+                Ok(components_json(&location.components()))
+            })
+            .await;
+        }
+        Commands::ParseAddress { address, .. } => match client.geocode_async(&address).await {
+            Ok(location) => sink.json(&components_json(&location.components())).await,
+            Err(e) => exit_with(e),
+        },
+        Commands::Details {
+            place_id,
+            with_photos,
+            download_photos,
+        } => {
+            let mut details = client
+                .place_details_async(&place_id)
+                .await
+                .unwrap_or_else(|e| exit_with(e));
+            if let Some(limit) = with_photos {
+                details.photos.truncate(limit);
+                client
+                    .resolve_photo_urls_async(&mut details.photos)
+                    .await
+                    .unwrap_or_else(|e| exit_with(e));
+                if let Some(dir) = &download_photos {
+                    client
+                        .download_photos_async(&mut details.photos, dir, &details.place_id)
+                        .await
+                        .unwrap_or_else(|e| exit_with(e));
+                }
+            }
+            sink.json(&details).await;
+        }
+        Commands::Suggest {
+            partial,
+            limit,
+            country,
+            bbox,
+            bias_around,
+        } => {
+            let bias = AutocompleteBias {
+                countries: country,
+                bounds: bbox,
+                around: bias_around,
+            };
+            match client
+                .autocomplete_with_bias_async(&partial, limit, &bias)
+                .await
+            {
+                Ok(suggestions) => sink.json(&suggestions).await,
+                Err(e) => exit_with(e),
+            }
+        }
+        Commands::Nearby {
+            address,
+            latitude,
+            longitude,
+            at,
+            ip,
+            my_location,
+            stdin,
+            country,
+            bias_around,
+            radius,
+            r#type,
+            max_results,
+            stream,
+            sort_by,
+            min_rating,
+            open_now,
+            max_travel_minutes,
+            columns,
+            bbox,
+            polygon,
+            output,
+            with_elevation,
+            dedupe,
+            cluster_by_geohash,
+            cluster,
+            min_points,
+            score,
+            weights,
+            with_photos,
+            download_photos,
+            with_departures,
+            departures,
+            along_route,
+            corridor,
+            mode,
+        } => {
+            if cluster_by_geohash
+                .is_some_and(|precision| !(1..=geohash::MAX_PRECISION).contains(&precision))
+            {
+                exit_with(MapradarError::InvalidInput(format!(
+                    "--cluster-by-geohash takes a precision from 1 to {}",
+                    geohash::MAX_PRECISION
+                )))
+            }
+            if cluster.is_some_and(|distance| distance.is_nan() || distance <= 0.0)
+                || min_points == 0
+            {
+                exit_with(MapradarError::InvalidInput(
+                    "--cluster takes a positive distance and --min-points at least 1".to_string(),
+                ))
+            }
+            if max_travel_minutes.is_some_and(|minutes| minutes.is_nan() || minutes <= 0.0) {
+                exit_with(MapradarError::InvalidInput(
+                    "--max-travel-minutes takes a positive number of minutes".to_string(),
+                ))
+            }
+            if mode.is_some() && along_route.is_none() && max_travel_minutes.is_none() {
+                exit_with(MapradarError::InvalidInput(
+                    "--mode applies to --along-route and --max-travel-minutes only".to_string(),
+                ))
+            }
+            let mode = mode.unwrap_or_default();
+            let region = search_region(&country, bias_around);
+            let columns = if columns.is_empty() && max_travel_minutes.is_some() {
+                ServiceColumn::WITH_TRAVEL_TIME.to_vec()
+            } else {
+                columns
+            };
+            let sort_by = sort_by.unwrap_or(if max_travel_minutes.is_some() {
+                SortKey::TravelTime
+            } else {
+                SortKey::Distance
+            });
+            let cluster_eps_m = cluster.map(|distance| units.radius_to_meters(distance));
+            if (cluster_by_geohash.is_some() || cluster.is_some())
+                && (matches!(format, OutputFormat::Kml | OutputFormat::Html)
+                    || output.as_deref().is_some_and(export::is_sqlite_path))
+            {
+                exit_with(MapradarError::InvalidInput(
+                    "KML, HTML, and SQLite output are not available with --cluster or --cluster-by-geohash"
+                        .to_string(),
+                ))
+            }
+            let weights = score.then(|| load_weights(weights.as_deref()));
+            #[cfg(feature = "gtfs")]
+            let feed = with_departures
+                .as_deref()
+                .map(|path| GtfsFeed::open(path).unwrap_or_else(|e| exit_with(e)));
+            #[cfg(not(feature = "gtfs"))]
+            if with_departures.is_some() {
+                let _ = departures;
+                exit_with(MapradarError::Unsupported(
+                    "--with-departures requires building mapradar with the `gtfs` feature"
+                        .to_string(),
+                ))
+            }
+            let area = match (bbox, polygon) {
+                (Some(bbox), _) => Some(bbox),
+                (None, Some(path)) => Some(read_polygon(&path).unwrap_or_else(|e| exit_with(e))),
+                (None, None) => None,
+            };
+            let radius = radius
+                .map(|radius| units.radius_to_meters(radius))
+                .or(config.radius)
+                .unwrap_or(1000.0);
+            let radius_km = radius / 1000.0;
+            let service_types = r#type
+                .or(config.types)
+                .unwrap_or_else(|| "bank".to_string())
+                .split(",")
+                .map(str::parse)
+                .collect::<Result<Vec<PlaceCategory>, MapradarError>>()
+                .unwrap_or_else(|e| exit_with(e));
+
+            // Filters, ordering, and extras applied to each search's results.
+            let refine = async |mut intel: LocationIntelligence| {
+                if with_elevation {
+                    let location = intel.location.clone();
+                    intel.location = client.attach_elevation_async(location).await?;
+                }
+                if let Some(area) = &area {
+                    intel.filter_within(area);
+                }
+                if dedupe {
+                    intel = intel.deduplicated();
+                }
+                if let Some(min_rating) = min_rating {
+                    intel.filter_min_rating(min_rating);
+                }
+                if open_now {
+                    intel.filter_open_now();
+                }
+                if let Some(minutes) = max_travel_minutes {
+                    client.attach_travel_times_async(&mut intel, mode).await?;
+                    intel.filter_max_travel_minutes(minutes);
+                }
+                if let Some(weights) = &weights {
+                    intel.score = Some(weights.score(&intel, &service_types));
+                }
+                intel.sort_by(sort_by);
+                if let Some(limit) = with_photos {
+                    client
+                        .attach_photos_async(&mut intel.nearby_services, limit)
+                        .await?;
+                }
+                #[cfg(feature = "gtfs")]
+                if let Some(feed) = &feed {
+                    feed.attach_departures(&mut intel.nearby_services, departures)?;
+                }
+                if let Some(dir) = &download_photos {
+                    for service in &mut intel.nearby_services {
+                        if let Some(place_id) = &service.place_id {
+                            client
+                                .download_photos_async(&mut service.photos, dir, place_id)
+                                .await?;
+                        }
+                    }
+                }
+                Ok::<_, MapradarError>(intel)
+            };
+
+            if let Some(along_route) = along_route {
+                if matches!(format, OutputFormat::Kml | OutputFormat::Html) {
+                    exit_with(MapradarError::InvalidInput(
+                        "KML and HTML output are not available with --along-route".to_string(),
+                    ))
+                }
+                let (origin, destination) = along_route
+                    .split_once("..")
+                    .map(|(origin, destination)| {
+                        (
+                            parse_point(origin.trim()).within(region.clone()),
+                            parse_point(destination.trim()).within(region.clone()),
+                        )
+                    })
+                    .unwrap_or_else(|| {
+                        exit_with(MapradarError::InvalidInput(
+                            "--along-route expects \"origin..destination\"".to_string(),
+                        ))
+                    });
+
+                let mut corridor = client
+                    .search_along_route_async(
+                        origin,
+                        destination,
+                        mode,
+                        service_types,
+                        corridor.map_or(radius, |corridor| units.radius_to_meters(corridor)),
+                        max_results,
+                    )
+                    .await
+                    .unwrap_or_else(|e| exit_with(e));
+                for warning in &corridor.warnings {
+                    eprintln!("{} {}", "Warning:".yellow().bold(), warning);
+                }
+                corridor.amenities.retain(|amenity| {
+                    let service = &amenity.service;
+                    area.as_ref()
+                        .is_none_or(|area| area.contains(service.latitude, service.longitude))
+                        && min_rating
+                            .is_none_or(|min| service.rating.is_some_and(|rating| rating >= min))
+                        && (!open_now || service.open_now == Some(true))
+                });
+
+                let columns = if columns.is_empty() {
+                    &ServiceColumn::DEFAULT[..]
+                } else {
+                    &columns[..]
+                };
+                if sink.is_webhook() {
+                    match format {
+                        OutputFormat::Geojson => sink.json(&corridor.to_feature_collection()).await,
+                        _ => sink.json(&corridor).await,
+                    }
+                } else if !sink.dry_run {
+                    sink.record(&corridor);
+                    if let Err(e) = sink.custom(&corridor, output.as_ref()).unwrap_or_else(|| {
+                        write_corridor(&corridor, format, columns, units, output.as_ref())
+                    }) {
+                        exit_with(e)
+                    }
+                }
+                return;
+            }
+
+            if stdin || address.as_deref() == Some("-") {
+                run_stdin_queries(
+                    cli.concurrency,
+                    sink.custom.as_ref(),
+                    async |query: SearchQuery| {
+                        let intel = client
+                            .fetch_intelligence_async(
+                                query.within(region.clone()),
+                                service_types.clone(),
+                                radius_km,
+                                max_results,
+                            )
+                            .await?;
+                        refine(intel).await
+                    },
+                )
+                .await;
+                return;
+            }
+
+            let query = if let Some(point) = at {
+                SearchQuery::at(point)
+            } else if let Some(ip) = ip {
+                SearchQuery::from_ip(ip)
+            } else if my_location {
+                SearchQuery::from_public_ip()
+            } else if let Some(latitude_val) = latitude {
+                if let Some(longitude_val) = longitude {
+                    SearchQuery::from_coordinates(latitude_val, longitude_val)
+                        .unwrap_or_else(|e| exit_with(e))
+                } else {
+                    exit_with(MapradarError::InvalidInput(
+                        "Longitude is required when latitude is provided".to_string(),
+                    ))
+                }
+            } else {
+                if let Some(address_val) = address {
+                    SearchQuery::from_address(address_val).within(region.clone())
+                } else {
+                    exit_with(MapradarError::InvalidInput(
+                        "Either address or coordinates must be provided".to_string(),
+                    ))
+                }
+            };
+
+            if stream {
+                let mut services = match client
+                    .fetch_intelligence_stream(query, service_types, radius_km, max_results)
+                    .await
+                {
+                    Ok((_, services)) => services,
+                    Err(e) => exit_with(e),
+                };
+
+                let mut rows = format
+                    .delimiter()
+                    .map(|delimiter| RowWriter::new(io::stdout(), delimiter));
+                let mut stdout = io::stdout().lock();
+                while let Some(result) = services.next().await {
+                    match result {
+                        Ok(service)
+                            if area.as_ref().is_some_and(|area| {
+                                !area.contains(service.latitude, service.longitude)
+                            }) || min_rating.is_some_and(|min| {
+                                service.rating.is_none_or(|rating| rating < min)
+                            }) || (open_now && service.open_now != Some(true)) => {}
+                        Ok(service) => {
+                            let written = match (&mut rows, format) {
+                                (Some(rows), _) => rows
+                                    .write(ServiceRow::from(&service).in_units(units))
+                                    .is_ok(),
+                                (None, _) if let Some(custom) = &sink.custom => {
+                                    let text = custom
+                                        .render(&serde_json::to_value(&service).unwrap(), true)
+                                        .unwrap_or_else(|e| exit_with(e));
+                                    stdout.write_all(text.as_bytes()).is_ok()
+                                }
+                                (None, OutputFormat::Geojson) => {
+                                    writeln!(stdout, "{}", service.to_feature()).is_ok()
+                                }
+                                (None, _) => {
+                                    writeln!(stdout, "{}", serde_json::to_string(&service).unwrap())
+                                        .is_ok()
+                                }
+                            };
+                            if !written || stdout.flush().is_err() {
+                                break;
+                            }
+                        }
+                        Err(MapradarError::Cancelled) => {}
+                        Err(e) => eprintln!("{} {}", "Warning:".yellow().bold(), e),
+                    }
+                }
+                if interrupted.is_cancelled() {
+                    exit_with(MapradarError::Cancelled)
+                }
+                return;
+            }
+
+            let export = output
+                .as_deref()
+                .filter(|path| export::is_sqlite_path(path))
+                .map(|_| ExportMetadata {
+                    query: query.clone(),
+                    service_types: service_types.clone(),
+                    radius_m: radius,
+                    provider: client.provider_name().to_string(),
+                });
+            match client
+                .fetch_intelligence_async(query, service_types.clone(), radius_km, max_results)
+                .await
+            {
+                Ok(intel) => {
+                    print_warnings(&intel);
+                    let intel = refine(intel).await.unwrap_or_else(|e| exit_with(e));
+                    let columns = if columns.is_empty() {
+                        &ServiceColumn::DEFAULT[..]
+                    } else {
+                        &columns[..]
+                    };
+                    let grouped = match (cluster_by_geohash, cluster_eps_m) {
+                        (Some(precision), _) => Some(
+                            group_by_geohash(&intel, precision, format)
+                                .unwrap_or_else(|e| exit_with(e)),
+                        ),
+                        (None, Some(eps_m)) => Some(
+                            group_by_proximity(&intel, eps_m, min_points, format, units)
+                                .unwrap_or_else(|e| exit_with(e)),
+                        ),
+                        (None, None) => None,
+                    };
+                    if let Some(grouped) = grouped {
+                        if sink.is_webhook() {
+                            sink.json(&grouped.document).await
+                        } else if !sink.dry_run {
+                            sink.record(&grouped.document);
+                            if let Err(e) = sink
+                                .custom(&grouped.document, output.as_ref())
+                                .unwrap_or_else(|| grouped.write(output.as_ref()))
+                            {
+                                exit_with(e)
+                            }
+                        }
+                    } else if sink.is_webhook() {
+                        match format {
+                            OutputFormat::Geojson => {
+                                sink.json(&intel.to_feature_collection()).await
+                            }
+                            _ => sink.json(&intel).await,
+                        }
+                    } else if !sink.dry_run
+                        && let (Some(metadata), Some(path)) = (&export, &output)
+                    {
+                        sink.record(&intel);
+                        let query_id = SqliteExport::open(path)
+                            .and_then(|mut db| db.write(&intel, metadata))
+                            .unwrap_or_else(|e| exit_with(e));
+                        eprintln!(
+                            "{} Exported {} services to {} (query {})",
+                            "Done:".green().bold(),
+                            intel.nearby_services.len(),
+                            path.display(),
+                            query_id
+                        );
+                    } else if !sink.dry_run {
+                        sink.record(&intel);
+                        if let Err(e) = sink.custom(&intel, output.as_ref()).unwrap_or_else(|| {
+                            write_intelligence(&intel, format, columns, units, output.as_ref())
+                        }) {
+                            exit_with(e)
+                        }
+                    }
+                }
+                Err(e) => exit_with(e),
+            }
+        }
+        Commands::Score {
+            address,
+            latitude,
+            longitude,
+            at,
+            r#type,
+            radius,
+            weights,
+            max_results,
+        } => {
+            let weights = load_weights(weights.as_deref());
+            let categories = match r#type {
+                Some(types) => types
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<Result<Vec<PlaceCategory>, MapradarError>>()
+                    .unwrap_or_else(|e| exit_with(e)),
+                None => weights.categories(),
+            };
+            let radius_km = radius.map_or(weights.max_distance_km(), |radius| {
+                units.radius_to_meters(radius) / 1000.0
+            });
+            if address.as_deref() == Some(STDIN_ARG) {
+                run_stdin_queries(cli.concurrency, sink.custom.as_ref(), async |query| {
+                    let intel = client
+                        .fetch_intelligence_async(query, categories.clone(), radius_km, max_results)
+                        .await?;
+                    Ok(weights.score(&intel, &categories))
+                })
+                .await;
+            } else {
+                let query = location_query(at, address, latitude, longitude);
+                match client
+                    .fetch_intelligence_async(query, categories.clone(), radius_km, max_results)
+                    .await
+                {
+                    Ok(intel) => {
+                        print_warnings(&intel);
+                        sink.json(&weights.score(&intel, &categories)).await
+                    }
+                    Err(e) => exit_with(e),
+                }
+            }
+        }
+        Commands::Report {
+            address,
+            latitude,
+            longitude,
+            at,
+            r#type,
+            radius,
+            weights,
+            max_results,
+            output,
+        } => {
+            let weights = load_weights(weights.as_deref());
+            let categories = match r#type {
+                Some(types) => types
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<Result<Vec<PlaceCategory>, MapradarError>>()
+                    .unwrap_or_else(|e| exit_with(e)),
+                None => weights.categories(),
+            };
+            let radius_km = radius.map_or(weights.max_distance_km(), |radius| {
+                units.radius_to_meters(radius) / 1000.0
+            });
+            let query = location_query(at, address, latitude, longitude);
+
+            let mut intel = client
+                .fetch_intelligence_async(query, categories.clone(), radius_km, max_results)
+                .await
+                .unwrap_or_else(|e| exit_with(e));
+            print_warnings(&intel);
+            intel.score = Some(weights.score(&intel, &categories));
+
+            let written = open_output(output.as_ref()).and_then(|mut writer| {
+                writer.write_all(html::to_html(&intel).as_bytes())?;
+                writer.flush()
+            });
+            if let Err(e) = written {
+                exit_with(e.into())
+            }
+            if let Some(path) = &output {
+                eprintln!(
+                    "{} report written to {}",
+                    "Done:".green().bold(),
+                    path.display()
+                );
+            }
+        }
+        Commands::Compare {
+            first,
+            second,
+            r#type,
+            radius,
+            weights,
+            max_results,
+        } => {
+            let weights = load_weights(weights.as_deref());
+            let categories = match r#type {
+                Some(types) => types
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<Result<Vec<PlaceCategory>, MapradarError>>()
+                    .unwrap_or_else(|e| exit_with(e)),
+                None => weights.categories(),
+            };
+            let radius_km = radius.map_or(weights.max_distance_km(), |radius| {
+                units.radius_to_meters(radius) / 1000.0
+            });
+
+            let stdin_first = first == STDIN_ARG;
+            if stdin_first || second == STDIN_ARG {
+                // The other location is searched once and compared with every line.
+                let other = if stdin_first { &second } else { &first };
+                let fixed = client
+                    .fetch_intelligence_async(
+                        parse_point(other),
+                        categories.clone(),
+                        radius_km,
+                        max_results,
+                    )
+                    .await
+                    .unwrap_or_else(|e| exit_with(e));
+                print_warnings(&fixed);
+                run_stdin_queries(cli.concurrency, sink.custom.as_ref(), async |query| {
+                    let read = client
+                        .fetch_intelligence_async(query, categories.clone(), radius_km, max_results)
+                        .await?;
+                    let (first, second) = stdin_pair(stdin_first, &read, &fixed);
+                    Ok(weights.compare(first, second, &categories))
+                })
+                .await;
+            } else {
+                let (first, second) = futures::try_join!(
+                    client.fetch_intelligence_async(
+                        parse_point(&first),
+                        categories.clone(),
+                        radius_km,
+                        max_results
+                    ),
+                    client.fetch_intelligence_async(
+                        parse_point(&second),
+                        categories.clone(),
+                        radius_km,
+                        max_results
+                    ),
+                )
+                .unwrap_or_else(|e| exit_with(e));
+                print_warnings(&first);
+                print_warnings(&second);
+
+                let comparison = weights.compare(&first, &second, &categories);
+                match format {
+                    OutputFormat::Table if sink.custom.is_none() => {
+                        println!("{}", render_comparison(&comparison, units));
+                        sink.record(&comparison);
+                    }
+                    _ => sink.json(&comparison).await,
+                }
+            }
+        }
+        Commands::Watch {
+            address,
+            latitude,
+            longitude,
+            at,
+            r#type,
+            radius,
+            max_results,
+            interval,
+            webhook,
+        } => {
+            let radius_km = radius
+                .map(|radius| units.radius_to_meters(radius))
+                .or(config.radius)
+                .unwrap_or(1000.0)
+                / 1000.0;
+            let types = r#type
+                .or(config.types)
+                .unwrap_or_else(|| "bank".to_string());
+            let service_types = types
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<PlaceCategory>, MapradarError>>()
+                .unwrap_or_else(|e| exit_with(e));
+            let query = location_query(at, address, latitude, longitude);
+
+            run_watch(
+                &client,
+                disk_cache.as_ref().expect("watch opens the disk cache"),
+                query,
+                &service_types,
+                radius_km,
+                max_results,
+                interval,
+                webhook.map(|url| {
+                    webhook_sink(
+                        &url,
+                        &cli.post_headers,
+                        cli.post_secret.as_deref(),
+                        retry_policy.clone(),
+                    )
+                    .unwrap_or_else(|e| exit_with(e))
+                }),
+            )
+            .await;
+        }
+        Commands::Map {
+            address,
+            latitude,
+            longitude,
+            at,
+            r#type,
+            radius,
+            max_results,
+            circle,
+            size: (width, height),
+            output,
+        } => {
+            let radius = radius
+                .map(|radius| units.radius_to_meters(radius))
+                .or(config.radius)
+                .unwrap_or(1000.0);
             let service_types = r#type
-                .split(",")
-                .map(|s| match s.trim() {
-                    "bank" => ServiceType::Bank,
-                    "hospital" => ServiceType::Hospital,
-                    "school" => ServiceType::School,
-                    "restaurant" => ServiceType::Restaurant,
-                    "bus-stop" => ServiceType::BusStop,
-                    "market" => ServiceType::Market,
-                    "mall" => ServiceType::Mall,
-                    "fuel-station" => ServiceType::FuelStation,
-                    "train-station" => ServiceType::TrainStation,
-                    "taxi-stand" => ServiceType::TaxiStand,
-                    "landmark" => ServiceType::Landmark,
-                    _ => ServiceType::Landmark, // Default fallback
+                .or(config.types)
+                .unwrap_or_else(|| "bank".to_string())
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<PlaceCategory>, MapradarError>>()
+                .unwrap_or_else(|e| exit_with(e));
+            let query = location_query(at, address, latitude, longitude);
+
+            let intel = client
+                .fetch_intelligence_async(query, service_types, radius / 1000.0, max_results)
+                .await
+                .unwrap_or_else(|e| exit_with(e));
+            print_warnings(&intel);
+
+            let mut map = StaticMap::from_intelligence(&intel, width, height);
+            if circle {
+                map = map.with_circle(intel.location.latitude, intel.location.longitude, radius);
+            }
+            let png = client
+                .static_map_async(&map)
+                .await
+                .unwrap_or_else(|e| exit_with(e));
+            if let Err(e) = std::fs::write(&output, png) {
+                exit_with(e.into())
+            }
+            eprintln!(
+                "{} {} services around {} drawn to {}",
+                "Done:".green().bold(),
+                intel.nearby_services.len(),
+                intel.location.address,
+                output.display()
+            );
+        }
+        Commands::BatchGeocode {
+            input,
+            column,
+            output,
+            checkpoint,
+            resume,
+        } => {
+            let addresses = match batch::read_addresses(&input, column.as_deref()) {
+                Ok(addresses) => addresses,
+                Err(e) => exit_with(e),
+            };
+
+            let rows = addresses.len();
+            let mut checkpoint = checkpoint.map(|path| open_checkpoint(path, resume));
+            let records = match &mut checkpoint {
+                Some(checkpoint) => client
+                    .batch_geocode_resumable_async(addresses, cli.concurrency, checkpoint)
+                    .await
+                    .unwrap_or_else(|e| exit_with(e)),
+                None => client.batch_geocode_async(addresses, cli.concurrency).await,
+            };
+            if let Err(e) = sink
+                .custom(&records, output.as_ref())
+                .unwrap_or_else(|| write_batch(&records, format, output.as_ref()))
+            {
+                exit_with(e)
+            }
+
+            print_batch_summary(&records, rows, "geocoded");
+            if let Some(checkpoint) = checkpoint {
+                finish_checkpoint(checkpoint, rows);
+            }
+        }
+        Commands::BatchReverse {
+            input,
+            output,
+            checkpoint,
+            resume,
+        } => {
+            let coordinates = match batch::read_coordinates(&input) {
+                Ok(coordinates) => coordinates,
+                Err(e) => exit_with(e),
+            };
+
+            let rows = coordinates.len();
+            let mut checkpoint = checkpoint.map(|path| open_checkpoint(path, resume));
+            let records = match &mut checkpoint {
+                Some(checkpoint) => client
+                    .batch_reverse_geocode_resumable_async(coordinates, cli.concurrency, checkpoint)
+                    .await
+                    .unwrap_or_else(|e| exit_with(e)),
+                None => {
+                    client
+                        .batch_reverse_geocode_async(coordinates, cli.concurrency)
+                        .await
+                }
+            };
+            if let Err(e) = sink
+                .custom(&records, output.as_ref())
+                .unwrap_or_else(|| write_batch(&records, format, output.as_ref()))
+            {
+                exit_with(e)
+            }
+
+            print_batch_summary(&records, rows, "reverse geocoded");
+            if let Some(checkpoint) = checkpoint {
+                finish_checkpoint(checkpoint, rows);
+            }
+        }
+        Commands::EnrichTrack {
+            track,
+            r#type,
+            radius,
+            spacing,
+            max_results,
+            output,
+        } => {
+            let track = std::fs::read_to_string(&track)
+                .map_err(MapradarError::from)
+                .and_then(|xml| GpxTrack::parse(&xml))
+                .unwrap_or_else(|e| exit_with(e));
+            let service_types = r#type
+                .or(config.types)
+                .unwrap_or_else(|| "bank".to_string())
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<PlaceCategory>, MapradarError>>()
+                .unwrap_or_else(|e| exit_with(e));
+            let radius = radius.map_or(500.0, |radius| units.radius_to_meters(radius));
+            let spacing = spacing.map_or(radius, |spacing| units.radius_to_meters(spacing));
+
+            let enrichment = client
+                .enrich_track_async(
+                    &track.path(),
+                    service_types,
+                    radius,
+                    spacing / 1000.0,
+                    max_results,
+                )
+                .await
+                .unwrap_or_else(|e| exit_with(e));
+            for warning in &enrichment.warnings {
+                eprintln!("{} {}", "Warning:".yellow().bold(), warning);
+            }
+
+            match (format, output) {
+                (_, Some(path)) => {
+                    if let Some(written) = sink.custom(&enrichment, Some(&path)) {
+                        if let Err(e) = written {
+                            exit_with(e)
+                        }
+                        sink.record(&enrichment);
+                        return;
+                    }
+                    let written = match format {
+                        OutputFormat::Gpx => track.to_gpx(&enrichment),
+                        OutputFormat::Geojson => {
+                            serde_json::to_string_pretty(&track.to_feature_collection(&enrichment))
+                                .unwrap()
+                        }
+                        _ => serde_json::to_string_pretty(&enrichment).unwrap(),
+                    };
+                    if let Err(e) = std::fs::write(&path, written) {
+                        exit_with(e.into())
+                    }
+                    sink.record(&enrichment);
+                    eprintln!(
+                        "{} {} amenities along {} written to {}",
+                        "Done:".green().bold(),
+                        enrichment.amenities.len(),
+                        units.format_distance(enrichment.distance_km),
+                        path.display()
+                    );
+                }
+                (OutputFormat::Gpx, None) if sink.custom.is_none() => {
+                    if !sink.dry_run {
+                        print!("{}", track.to_gpx(&enrichment));
+                        sink.record(&enrichment);
+                    }
+                }
+                (OutputFormat::Geojson, None) => {
+                    sink.json(&track.to_feature_collection(&enrichment)).await
+                }
+                (_, None) => sink.json(&enrichment).await,
+            }
+        }
+        Commands::Route {
+            origin_addr,
+            origin_lat,
+            origin_lng,
+            dest_addr,
+            dest_lat,
+            dest_lng,
+            mode,
+        } if origin_addr.as_deref() == Some(STDIN_ARG)
+            || dest_addr.as_deref() == Some(STDIN_ARG) =>
+        {
+            let stdin_first = origin_addr.as_deref() == Some(STDIN_ARG);
+            let fixed = if stdin_first {
+                point_query("destination", dest_addr, dest_lat, dest_lng)
+            } else {
+                point_query("origin", origin_addr, origin_lat, origin_lng)
+            }
+            .unwrap_or_else(|e| exit_with(e));
+            run_stdin_queries(cli.concurrency, sink.custom.as_ref(), async |query| {
+                let (origin, destination) = stdin_pair(stdin_first, query, fixed.clone());
+                client.route_async(origin, destination, mode).await
+            })
+            .await;
+        }
+        Commands::Route {
+            origin_addr,
+            origin_lat,
+            origin_lng,
+            dest_addr,
+            dest_lat,
+            dest_lng,
+            mode,
+        } => {
+            let points =
+                point_query("origin", origin_addr, origin_lat, origin_lng).and_then(|origin| {
+                    point_query("destination", dest_addr, dest_lat, dest_lng)
+                        .map(|destination| (origin, destination))
+                });
+            let (origin, destination) = match points {
+                Ok(points) => points,
+                Err(message) => exit_with(message),
+            };
+
+            match client.route_async(origin, destination, mode).await {
+                Ok(route) => match format {
+                    OutputFormat::Geojson => {
+                        sink.json(&feature_collection(vec![route.to_feature()]))
+                            .await
+                    }
+                    _ => sink.json(&route).await,
+                },
+                Err(e) => exit_with(e),
+            }
+        }
+        Commands::Matrix {
+            origins,
+            destinations,
+            mode,
+        } => {
+            let origins = parse_points(&origins);
+            let destinations = parse_points(&destinations);
+
+            match client
+                .distance_matrix_async(origins, destinations, mode)
+                .await
+            {
+                Ok(matrix) => sink.json(&matrix).await,
+                Err(e) => exit_with(e),
+            }
+        }
+        Commands::Reachable {
+            address,
+            minutes,
+            mode,
+            ..
+        } if address.as_deref() == Some(STDIN_ARG) => {
+            run_stdin_queries(cli.concurrency, sink.custom.as_ref(), async |query| {
+                let center = query_point(&client, &query).await?;
+                client.isochrone_async(center, minutes, mode).await
+            })
+            .await;
+        }
+        Commands::Reachable {
+            address,
+            latitude,
+            longitude,
+            at,
+            minutes,
+            mode,
+        } => {
+            let origin = match (at, point_query("origin", address, latitude, longitude)) {
+                (Some(point), _) => Ok(point),
+                (None, Ok(query)) => query_point(&client, &query).await,
+                (None, Err(message)) => exit_with(message),
+            };
+
+            let result = match origin {
+                Ok(center) => client.isochrone_async(center, minutes, mode).await,
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(isochrone) => match format {
+                    OutputFormat::Geojson => {
+                        sink.json(&feature_collection(vec![isochrone.to_feature()]))
+                            .await
+                    }
+                    _ => sink.json(&isochrone).await,
+                },
+                Err(e) => exit_with(e),
+            }
+        }
+        Commands::Midpoint {
+            addresses,
+            strategy,
+            r#type,
+            radius,
+            max_results,
+            columns,
+            output,
+        } => {
+            let participants = parse_points(&addresses);
+            let service_types = r#type
+                .or(config.types)
+                .unwrap_or_else(|| "restaurant".to_string())
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<PlaceCategory>, MapradarError>>()
+                .unwrap_or_else(|e| exit_with(e));
+            let radius = radius
+                .map(|radius| units.radius_to_meters(radius))
+                .or(config.radius)
+                .unwrap_or(1000.0);
+
+            let meeting = client
+                .meeting_point_async(
+                    participants,
+                    strategy,
+                    service_types,
+                    radius / 1000.0,
+                    max_results,
+                )
+                .await
+                .unwrap_or_else(|e| exit_with(e));
+            print_warnings(&meeting.intelligence);
+
+            let columns = if columns.is_empty() {
+                &ServiceColumn::DEFAULT[..]
+            } else {
+                &columns[..]
+            };
+            if sink.is_webhook() {
+                match format {
+                    OutputFormat::Geojson => sink.json(&meeting.to_feature_collection()).await,
+                    _ => sink.json(&meeting).await,
+                }
+            } else if !sink.dry_run {
+                sink.record(&meeting);
+                if let Err(e) = sink.custom(&meeting, output.as_ref()).unwrap_or_else(|| {
+                    write_meeting_point(&meeting, format, columns, units, output.as_ref())
+                }) {
+                    exit_with(e)
+                }
+            }
+        }
+        Commands::Heatmap {
+            bbox,
+            r#type,
+            cell,
+            max_results,
+            output,
+        } => {
+            let service_types = r#type
+                .or(config.types)
+                .unwrap_or_else(|| "bank".to_string())
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<PlaceCategory>, MapradarError>>()
+                .unwrap_or_else(|e| exit_with(e));
+            let cell = cell.map_or(250.0, |cell| units.radius_to_meters(cell));
+
+            let heatmap = client
+                .heatmap_async(&bbox, service_types, cell, max_results)
+                .await
+                .unwrap_or_else(|e| exit_with(e));
+            for warning in &heatmap.warnings {
+                eprintln!("{} {}", "Warning:".yellow().bold(), warning);
+            }
+
+            let grid = heatmap_grid(&heatmap, format).unwrap_or_else(|e| exit_with(e));
+            if sink.is_webhook() {
+                sink.json(&grid.document).await
+            } else if !sink.dry_run {
+                sink.record(&grid.document);
+                if let Err(e) = sink
+                    .custom(&grid.document, output.as_ref())
+                    .unwrap_or_else(|| grid.write(output.as_ref()))
+                {
+                    exit_with(e)
+                }
+            }
+        }
+        Commands::Cache { .. }
+        | Commands::Auth { .. }
+        | Commands::History { .. }
+        | Commands::SaveSearch { .. }
+        | Commands::Run { .. }
+        | Commands::Searches { .. }
+        | Commands::Keys { .. }
+        | Commands::Config { .. }
+        | Commands::Geofence { .. }
+        | Commands::Encode { .. }
+        | Commands::Decode { .. }
+        | Commands::Match { .. }
+        | Commands::Diff { .. }
+        | Commands::Usage { .. }
+        | Commands::Completions { .. }
+        | Commands::Manpage => {
+            unreachable!("handled before the client is built")
+        }
+        #[cfg(feature = "offline")]
+        Commands::Osm { .. } => unreachable!("handled before the client is built"),
+        #[cfg(feature = "gtfs")]
+        Commands::Gtfs { .. } => unreachable!("handled before the client is built"),
+        Commands::Serve { mcp: true, .. } => {
+            eprintln!(
+                "{} MCP server on stdio ({} provider)",
+                "Serving:".green().bold(),
+                client.provider_name()
+            );
+            if let Err(e) = mcp::serve_stdio(client).await {
+                exit_with(e)
+            }
+        }
+        Commands::Serve {
+            rpc: true,
+            port: None,
+            ..
+        } => {
+            eprintln!(
+                "{} JSON-RPC on stdio ({} provider)",
+                "Serving:".green().bold(),
+                client.provider_name()
+            );
+            if let Err(e) = rpc::serve_stdio(client).await {
+                exit_with(e)
+            }
+        }
+        Commands::Serve {
+            rpc: true,
+            port: Some(port),
+            host,
+            ..
+        } => serve_rpc_http(client, SocketAddr::new(host, port)).await,
+        Commands::Serve {
+            grpc: true,
+            port,
+            host,
+            ..
+        } => {
+            serve_grpc(
+                client,
+                SocketAddr::new(host, port.unwrap_or(DEFAULT_GRPC_PORT)),
+            )
+            .await
+        }
+        Commands::Serve { .. } => {
+            unreachable!("REST serving is handled before the client is built")
+        }
+        #[cfg(feature = "tui")]
+        Commands::Tui => {
+            if let Err(e) = cli::tui::run(client).await {
+                exit_with(e.into())
+            }
+        }
+        Commands::Distance {
+            from,
+            to,
+            origin_addr,
+            origin_lat,
+            origin_lng,
+            dest_addr,
+            dest_lat,
+            dest_lng,
+            method,
+        } => {
+            let read_origin = [&from, &origin_addr]
+                .iter()
+                .any(|arg| arg.as_deref() == Some(STDIN_ARG));
+            let read_destination = [&to, &dest_addr]
+                .iter()
+                .any(|arg| arg.as_deref() == Some(STDIN_ARG));
+            let origin = match from {
+                Some(from) => Ok(parse_point(&from)),
+                None => point_query("origin", origin_addr, origin_lat, origin_lng),
+            };
+            let destination = match to {
+                Some(to) => Ok(parse_point(&to)),
+                None => point_query("destination", dest_addr, dest_lat, dest_lng),
+            };
+            let (origin, destination) = match origin.and_then(|o| destination.map(|d| (o, d))) {
+                Ok(points) => points,
+                Err(e) => exit_with(e),
+            };
+
+            if read_origin || read_destination {
+                let fixed = if read_origin { destination } else { origin };
+                let fixed = client
+                    .resolve_coordinates_async(&fixed)
+                    .await
+                    .unwrap_or_else(|e| exit_with(e));
+                run_stdin_queries(cli.concurrency, sink.custom.as_ref(), async |query| {
+                    let read = client.resolve_coordinates_async(&query).await?;
+                    let ((lat1, lng1), (lat2, lng2)) = stdin_pair(read_origin, read, fixed);
+                    let bearing = geo::initial_bearing(lat1, lng1, lat2, lng2);
+                    Ok(serde_json::json!({
+                        "distance_km": method.distance(lat1, lng1, lat2, lng2),
+                        "method": method.to_string(),
+                        "bearing": bearing,
+                        "compass": geo::compass_point(bearing),
+                    }))
                 })
-                .collect::<Vec<ServiceType>>();
+                .await;
+            } else {
+                let points = tokio::try_join!(
+                    client.resolve_coordinates_async(&origin),
+                    client.resolve_coordinates_async(&destination)
+                );
+                let ((lat1, lng1), (lat2, lng2)) = match points {
+                    Ok(points) => points,
+                    Err(e) => exit_with(e),
+                };
+
+                let distance = method.distance(lat1, lng1, lat2, lng2);
+                let bearing = geo::initial_bearing(lat1, lng1, lat2, lng2);
+                let other = match units {
+                    Units::Metric => Units::Imperial,
+                    Units::Imperial => Units::Metric,
+                };
+                println!(
+                    "{} {} ({}, {})",
+                    "Distance:".green().bold(),
+                    units.format_distance(distance),
+                    other.format_distance(distance),
+                    method
+                );
+                println!(
+                    "{} {:.1}° {}",
+                    "Bearing:".green().bold(),
+                    bearing,
+                    geo::compass_point(bearing)
+                );
+            }
+        }
+    }
+    print_request_plan();
+    if interrupted.is_cancelled() {
+        exit_with(MapradarError::Cancelled)
+    }
+}
+
+/// Builds a query from either an address or a complete latitude/longitude pair.
+fn point_query(
+    label: &str,
+    address: Option<String>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+) -> Result<SearchQuery, MapradarError> {
+    match (latitude, longitude, address) {
+        (Some(lat), Some(lng), _) => SearchQuery::from_coordinates(lat, lng),
+        (None, None, Some(address)) => Ok(SearchQuery::from_address(address)),
+        (None, None, None) => Err(MapradarError::InvalidInput(format!(
+            "{} address or coordinates are required",
+            label
+        ))),
+        _ => Err(MapradarError::InvalidInput(format!(
+            "Both {} latitude and longitude are required when using coordinates",
+            label
+        ))),
+    }
+}
+
+#[cfg(feature = "server")]
+async fn serve_rpc_http(client: MapradarClient, addr: SocketAddr) {
+    eprintln!(
+        "{} JSON-RPC on http://{} ({} provider)",
+        "Serving:".green().bold(),
+        addr,
+        client.provider_name()
+    );
+    if let Err(e) = rpc::http::serve_http(client, addr).await {
+        exit_with(e)
+    }
+}
+
+#[cfg(not(feature = "server"))]
+async fn serve_rpc_http(_client: MapradarClient, _addr: SocketAddr) {
+    exit_with(MapradarError::Unsupported(
+        "HTTP serving requires the `server` feature".to_string(),
+    ))
+}
+
+#[cfg(feature = "server")]
+async fn serve_rest(builder: MapradarClientBuilder, addr: SocketAddr) {
+    eprintln!(
+        "{} REST API on http://{} (Ctrl-C to stop)",
+        "Serving:".green().bold(),
+        addr
+    );
+    if let Err(e) = mapradar::rest::serve(builder, addr).await {
+        exit_with(e)
+    }
+}
+
+#[cfg(not(feature = "server"))]
+async fn serve_rest(_builder: MapradarClientBuilder, _addr: SocketAddr) {
+    exit_with(MapradarError::Unsupported(
+        "HTTP serving requires the `server` feature".to_string(),
+    ))
+}
+
+#[cfg(feature = "grpc")]
+async fn serve_grpc(client: MapradarClient, addr: SocketAddr) {
+    eprintln!(
+        "{} gRPC on {} ({} provider)",
+        "Serving:".green().bold(),
+        addr,
+        client.provider_name()
+    );
+    if let Err(e) = mapradar::grpc::serve(client, addr).await {
+        exit_with(e)
+    }
+}
+
+#[cfg(not(feature = "grpc"))]
+async fn serve_grpc(_client: MapradarClient, _addr: SocketAddr) {
+    exit_with(MapradarError::Unsupported(
+        "gRPC serving requires the `grpc` feature".to_string(),
+    ))
+}
+
+/// Reports service-type searches that failed while the rest succeeded.
+fn print_warnings(intel: &LocationIntelligence) {
+    for warning in &intel.warnings {
+        eprintln!("{} {}", "Warning:".yellow().bold(), warning);
+    }
+}
+
+/// Prints the error and exits with the status code for its class of failure.
+fn exit_with(err: MapradarError) -> ! {
+    // A dry run stops at the first request whose response is needed; what was planned so far is
+    // the result.
+    if matches!(err, MapradarError::DryRun) && print_request_plan() {
+        process::exit(0);
+    }
+    match error_format() {
+        ErrorFormat::Json => eprintln!("{}", err.to_json()),
+        ErrorFormat::Text => eprintln!("{} {}", "Error:".red().bold(), err),
+    }
+    process::exit(err.exit_code());
+}
+
+/// Reports a command-line parsing error. Help and version requests print as usual, while usage
+/// errors exit like other invalid input, with code 64 rather than clap's 2, which would read as
+/// a rejected API key.
+fn exit_with_usage(err: clap::Error) -> ! {
+    if !err.use_stderr() {
+        err.exit()
+    }
+    if error_format() == ErrorFormat::Json {
+        let rendered = err.to_string();
+        let message = rendered.lines().next().unwrap_or_default();
+        exit_with(MapradarError::InvalidInput(
+            message.trim_start_matches("error: ").to_string(),
+        ))
+    }
+    let _ = err.print();
+    process::exit(MapradarError::InvalidInput(String::new()).exit_code());
+}
+
+/// `--error-format`, read from the raw arguments and environment for errors met before they are
+/// parsed.
+fn error_format() -> ErrorFormat {
+    *ERROR_FORMAT.get_or_init(|| {
+        let mut format = std::env::var("MAPRADAR_ERROR_FORMAT").ok();
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--error-format" {
+                format = args.next();
+            } else if let Some(value) = arg.strip_prefix("--error-format=") {
+                format = Some(value.to_string());
+            }
+        }
+        format
+            .and_then(|format| ErrorFormat::from_str(&format, true).ok())
+            .unwrap_or(ErrorFormat::Text)
+    })
+}
+
+/// Prints the requests recorded by a `--dry-run` client and a cost summary on stderr. Returns
+/// false when this is not a dry run.
+fn print_request_plan() -> bool {
+    let Some(plan) = DRY_RUN_CLIENT.get().and_then(MapradarClient::request_plan) else {
+        return false;
+    };
+    println!("{}", serde_json::to_string_pretty(&plan).unwrap());
+    eprintln!(
+        "{} {} request{}, estimated ${:.4} at list price",
+        "Dry run:".cyan().bold(),
+        plan.total_requests,
+        if plan.total_requests == 1 { "" } else { "s" },
+        plan.estimated_cost_usd
+    );
+    true
+}
+
+/// Answers every query read from stdin with `task`, `concurrency` at a time, printing one NDJSON
+/// line per input line in input order with its row, its `id` when given, and the result or error.
+/// With `--fields`, `--query`, or `--template`, each answered line is printed as they ask instead,
+/// and errors are warned of.
+async fn run_stdin_queries<T: Serialize>(
+    concurrency: usize,
+    custom: Option<&CustomOutput>,
+    task: impl AsyncFn(SearchQuery) -> Result<T, MapradarError>,
+) {
+    let queries = batch::read_queries(io::stdin().lock()).unwrap_or_else(|e| exit_with(e));
+    let task = &task;
+    let mut outcomes = futures::stream::iter(queries)
+        .map(|input| async move {
+            let result = match input.query {
+                Ok(query) => task(query).await,
+                Err(e) => Err(e),
+            };
+            QueryOutcome::new(input.row, input.id, result)
+        })
+        .buffered(concurrency.max(1));
+
+    let (mut succeeded, mut failed) = (0, 0);
+    let mut stdout = io::stdout().lock();
+    while let Some(outcome) = outcomes.next().await {
+        if outcome.error.is_some() {
+            failed += 1;
+        } else {
+            succeeded += 1;
+        }
+        let written = match (custom, &outcome.error) {
+            (Some(_), Some(error)) => {
+                eprintln!(
+                    "{} row {}: {}",
+                    "Warning:".yellow().bold(),
+                    outcome.row,
+                    error
+                );
+                Ok(())
+            }
+            (Some(custom), None) => {
+                let text = custom
+                    .render(&serde_json::to_value(&outcome).unwrap(), true)
+                    .unwrap_or_else(|e| exit_with(e));
+                stdout.write_all(text.as_bytes())
+            }
+            (None, _) => writeln!(stdout, "{}", serde_json::to_string(&outcome).unwrap()),
+        };
+        if written.is_err() {
+            return;
+        }
+    }
+    eprintln!(
+        "{} {} answered, {} failed",
+        "Done:".green().bold(),
+        succeeded,
+        failed
+    );
+}
+
+/// Every query read from stdin, for a "-" among a list of points, exiting on a line that does
+/// not parse.
+fn read_stdin_points() -> Vec<SearchQuery> {
+    batch::read_queries(io::stdin().lock())
+        .unwrap_or_else(|e| exit_with(e))
+        .into_iter()
+        .map(|input| input.query.unwrap_or_else(|e| exit_with(e)))
+        .collect()
+}
+
+/// Parses each argument like [`parse_point`], reading the queries from stdin in place of "-".
+fn parse_points(inputs: &[String]) -> Vec<SearchQuery> {
+    inputs
+        .iter()
+        .flat_map(|input| match input.as_str() {
+            STDIN_ARG => read_stdin_points(),
+            input => vec![parse_point(input)],
+        })
+        .collect()
+}
+
+/// Point of a query read from stdin, geocoding an address.
+async fn query_point(
+    client: &MapradarClient,
+    query: &SearchQuery,
+) -> Result<Coordinates, MapradarError> {
+    client
+        .resolve_coordinates_async(query)
+        .await
+        .and_then(Coordinates::try_from)
+}
+
+/// Address of a query read from stdin, for commands that only take addresses.
+fn query_address(query: SearchQuery) -> Result<String, MapradarError> {
+    match query {
+        SearchQuery::Address { address, .. } => Ok(address),
+        _ => Err(MapradarError::InvalidInput(
+            "Expected an address, not coordinates or an IP".to_string(),
+        )),
+    }
+}
+
+/// Puts what was read from stdin on its side of a two-location command, `fixed` on the other.
+fn stdin_pair<T>(stdin_first: bool, read: T, fixed: T) -> (T, T) {
+    if stdin_first {
+        (read, fixed)
+    } else {
+        (fixed, read)
+    }
+}
 
-            let query = if let Some(latitude_val) = latitude {
-                if let Some(longitude_val) = longitude {
-                    SearchQuery::from_coordinates(latitude_val, longitude_val)
-                } else {
+/// Time zone with its UTC offset written out as `+HH:MM`.
+fn timezone_json(timezone: &TimeZoneInfo) -> serde_json::Value {
+    let mut value = serde_json::to_value(timezone).unwrap();
+    value["utc_offset"] = serde_json::Value::String(timezone.utc_offset());
+    value
+}
+
+/// Locates a query, adding its time zone and elevation when asked.
+async fn locate_with_extras(
+    client: &MapradarClient,
+    query: SearchQuery,
+    with_timezone: bool,
+    with_elevation: bool,
+) -> Result<GeoLocation, MapradarError> {
+    let mut location = client.locate_async(query).await?;
+    if with_timezone {
+        location = client.attach_timezone_async(location).await?;
+    }
+    if with_elevation {
+        location = client.attach_elevation_async(location).await?;
+    }
+    Ok(location)
+}
+
+/// Reads a point given as one argument ("lat,lng" or DMS) or as separate latitude and longitude
+/// arguments, exiting when it is invalid.
+fn parse_point_args(point: &str, longitude: Option<&str>) -> Coordinates {
+    match longitude {
+        Some(longitude) => format!("{},{}", point, longitude).parse(),
+        None => point.parse(),
+    }
+    .unwrap_or_else(|e| exit_with(e))
+}
+
+/// Region from --country and --bias-around, exiting on an invalid country code.
+fn search_region(countries: &[String], bias_around: Option<Coordinates>) -> SearchRegion {
+    let region = SearchRegion::in_countries(countries).unwrap_or_else(|e| exit_with(e));
+    match bias_around {
+        Some(point) => region.with_bias_around(point),
+        None => region,
+    }
+}
+
+/// Query for the location given to score, report, watch, and map: `--at`, or an address, or
+/// both latitude and longitude, exiting when none is complete or the coordinates are invalid.
+fn location_query(
+    at: Option<Coordinates>,
+    address: Option<String>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+) -> SearchQuery {
+    match (at, address, latitude, longitude) {
+        (Some(point), ..) => SearchQuery::at(point),
+        (None, _, Some(lat), Some(lng)) => {
+            SearchQuery::from_coordinates(lat, lng).unwrap_or_else(|e| exit_with(e))
+        }
+        (None, Some(address), None, None) => SearchQuery::from_address(address),
+        _ => exit_with(MapradarError::InvalidInput(
+            "Provide either an address or both latitude and longitude".to_string(),
+        )),
+    }
+}
+
+/// Treats "lat,lng" as coordinates and anything else as an address, exiting on coordinates out
+/// of range.
+fn parse_point(input: &str) -> SearchQuery {
+    match input.parse::<Coordinates>() {
+        Ok(point) => SearchQuery::at(point),
+        Err(e @ MapradarError::InvalidCoordinates { .. }) => exit_with(e),
+        Err(_) => SearchQuery::from_address(input.to_string()),
+    }
+}
+
+/// Checks the search every `interval`, printing each change as a JSON line and posting
+/// non-empty batches to `webhook`. The latest results are kept in the disk cache, so a restarted
+/// watch picks up where it left off while the snapshot is younger than the cache TTL.
+#[allow(clippy::too_many_arguments)]
+async fn run_watch(
+    client: &MapradarClient,
+    disk_cache: &DiskCache,
+    mut query: SearchQuery,
+    service_types: &[PlaceCategory],
+    radius_km: f64,
+    max_results: usize,
+    interval: Duration,
+    webhook: Option<WebhookSink>,
+) {
+    let types = service_types
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut snapshot_key = None;
+
+    loop {
+        ticker.tick().await;
+        let intel = match client
+            .fetch_intelligence_async(
+                query.clone(),
+                service_types.to_vec(),
+                radius_km,
+                max_results,
+            )
+            .await
+        {
+            Ok(intel) => intel,
+            // Without a first result there is nothing to watch.
+            Err(e) if snapshot_key.is_none() => exit_with(e),
+            Err(e) => {
+                eprintln!("{} {}", "Warning:".yellow().bold(), e);
+                continue;
+            }
+        };
+        // A partial result would report the failed types' services as removed.
+        if !intel.warnings.is_empty() {
+            print_warnings(&intel);
+            continue;
+        }
+
+        // Later checks reuse the resolved coordinates instead of geocoding again.
+        let key = snapshot_key.get_or_insert_with(|| {
+            let location = &intel.location;
+            if let Ok(point) = location.coordinates() {
+                query = SearchQuery::at(point);
+            }
+            format!(
+                "{}:{:.5},{:.5}:{}:{:.0}",
+                client.provider_name(),
+                location.latitude,
+                location.longitude,
+                types,
+                radius_km * 1000.0
+            )
+        });
+
+        let previous: Option<Vec<NearbyService>> = disk_cache.get("watch", key);
+        disk_cache.set("watch", key, &intel.nearby_services);
+        let Some(previous) = previous else {
+            eprintln!(
+                "{} {} services around {}",
+                "Watching:".green().bold(),
+                intel.nearby_services.len(),
+                intel.location.address
+            );
+            continue;
+        };
+
+        let changes = watch::diff(&previous, &intel.nearby_services);
+        if changes.is_empty() {
+            continue;
+        }
+        for change in &changes {
+            println!("{}", serde_json::to_string(change).unwrap());
+        }
+        if let Some(webhook) = &webhook {
+            let event = ChangeEvent {
+                location: intel.location,
+                checked_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or_default(),
+                changes,
+            };
+            if let Err(e) = webhook.deliver(&event).await {
+                eprintln!(
+                    "{} webhook delivery failed: {}",
+                    "Warning:".yellow().bold(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+fn run_geofence_action(action: &GeofenceAction) {
+    let GeofenceAction::Check {
+        fences,
+        points,
+        input,
+        fence,
+    } = action;
+
+    let mut fences = Geofence::load(fences).unwrap_or_else(|e| exit_with(e));
+    if !fence.is_empty() {
+        if let Some(missing) = fence
+            .iter()
+            .find(|name| !fences.iter().any(|f| &f.name == *name))
+        {
+            exit_with(MapradarError::InvalidInput(format!(
+                "No fence named '{}'",
+                missing
+            )))
+        }
+        fences.retain(|f| fence.contains(&f.name));
+    }
+
+    let lines: Box<dyn Iterator<Item = io::Result<String>>> = match input {
+        Some(path) if path.as_os_str() == "-" => Box::new(io::stdin().lines()),
+        Some(path) => match File::open(path) {
+            Ok(file) => Box::new(io::BufReader::new(file).lines()),
+            Err(e) => exit_with(e.into()),
+        },
+        None => Box::new(points.clone().into_iter().map(Ok)),
+    };
+
+    let mut stdout = io::stdout().lock();
+    for line in lines {
+        let line = line.unwrap_or_else(|e| exit_with(e.into()));
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (latitude, longitude) = match line.parse::<Coordinates>() {
+            Ok(point) => point.as_tuple(),
+            Err(e) => {
+                eprintln!("{} {}", "Warning:".yellow().bold(), e);
+                continue;
+            }
+        };
+        let value = serde_json::json!({
+            "latitude": latitude,
+            "longitude": longitude,
+            "fences": fences_containing(&fences, latitude, longitude),
+        });
+        if writeln!(stdout, "{}", value).is_err() {
+            break;
+        }
+    }
+}
+
+/// Clears the disk cache or prints its entry counts and size.
+fn run_cache_action(action: &CacheAction, disk_cache: &DiskCache) {
+    match action {
+        CacheAction::Clear => match disk_cache.clear() {
+            Ok(deleted) => println!("{} removed {} entries", "Cleared:".green().bold(), deleted),
+            Err(e) => exit_with(e),
+        },
+        CacheAction::Stats => match disk_cache.stats() {
+            Ok(stats) => println!("{}", serde_json::to_string_pretty(&stats).unwrap()),
+            Err(e) => exit_with(e),
+        },
+    }
+}
+
+#[cfg(feature = "offline")]
+fn run_osm_action(action: &OsmAction, path: Option<&Path>) {
+    use mapradar::osm::OsmIndex;
+
+    let path = path.map_or_else(OsmIndex::default_path, Path::to_path_buf);
+    match action {
+        OsmAction::Import { pbf } => {
+            let index = OsmIndex::open(&path).unwrap_or_else(|e| exit_with(e));
+            eprintln!(
+                "{} {} into {}",
+                "Importing:".green().bold(),
+                pbf.display(),
+                path.display()
+            );
+            match index.import(pbf) {
+                Ok(stats) => println!("{}", serde_json::to_string_pretty(&stats).unwrap()),
+                Err(e) => exit_with(e),
+            }
+        }
+        OsmAction::Stats => match OsmIndex::open_existing(&path).and_then(|index| index.stats()) {
+            Ok(stats) => println!("{}", serde_json::to_string_pretty(&stats).unwrap()),
+            Err(e) => exit_with(e),
+        },
+    }
+}
+
+#[cfg(feature = "gtfs")]
+fn run_gtfs_action(action: &GtfsAction) {
+    let path = GtfsIndex::default_path();
+    let index = GtfsIndex::open(&path).unwrap_or_else(|e| exit_with(e));
+    match action {
+        GtfsAction::Import { feed } => {
+            eprintln!(
+                "{} {} into {}",
+                "Importing:".green().bold(),
+                feed.display(),
+                path.display()
+            );
+            match index.import(feed) {
+                Ok(stats) => println!("{}", serde_json::to_string_pretty(&stats).unwrap()),
+                Err(e) => exit_with(e),
+            }
+        }
+        GtfsAction::Stats => match index.stats() {
+            Ok(stats) => println!("{}", serde_json::to_string_pretty(&stats).unwrap()),
+            Err(e) => exit_with(e),
+        },
+    }
+}
+
+/// Arguments of this invocation for the query history, leaving out API keys and webhook
+/// credentials; a re-run takes them from the environment, config, or keyring instead.
+fn history_args(command_name: &str) -> Vec<String> {
+    const SECRET_FLAGS: &[&str] = &["--api-key", "--api-keys", "--post-header", "--post-secret"];
+    let mut args = Vec::new();
+    let mut skip_value = false;
+    let mut before_command = true;
+    for arg in std::env::args().skip(1) {
+        if std::mem::take(&mut skip_value) {
+            continue;
+        }
+        if before_command && arg == command_name {
+            before_command = false;
+        }
+        let flag = arg.split('=').next().unwrap_or_default();
+        if SECRET_FLAGS.contains(&flag) {
+            skip_value = !arg.contains('=');
+            continue;
+        }
+        // `-a` is --api-key before the command name, and a command's own flag after it.
+        if before_command && arg.starts_with("-a") {
+            skip_value = arg == "-a";
+            continue;
+        }
+        args.push(arg);
+    }
+    args
+}
+
+fn run_history_action(action: &HistoryAction) {
+    let history =
+        QueryHistory::open(&QueryHistory::default_path()).unwrap_or_else(|e| exit_with(e));
+    let entry = |id: i64| match history.get(id) {
+        Ok(Some(entry)) => entry,
+        Ok(None) => exit_with(MapradarError::InvalidInput(format!(
+            "No query #{} in the history",
+            id
+        ))),
+        Err(e) => exit_with(e),
+    };
+    match action {
+        HistoryAction::List { limit, command } => match history.list(*limit, command.as_deref()) {
+            Ok(entries) => println!("{}", serde_json::to_string_pretty(&entries).unwrap()),
+            Err(e) => exit_with(e),
+        },
+        HistoryAction::Show { id } => {
+            println!("{}", serde_json::to_string_pretty(&entry(*id)).unwrap());
+        }
+        HistoryAction::Rerun { id } => {
+            let previous = entry(*id);
+            eprintln!(
+                "{} mapradar {}",
+                "Rerunning:".green().bold(),
+                previous.args.join(" ")
+            );
+            let program = std::env::current_exe().unwrap_or_else(|e| exit_with(e.into()));
+            let status = process::Command::new(program)
+                .args(&previous.args)
+                .status()
+                .unwrap_or_else(|e| exit_with(e.into()));
+            // The re-run records itself; compare its result with the original's.
+            let rerun = history
+                .list(1, Some(&previous.command))
+                .ok()
+                .and_then(|entries| entries.into_iter().next())
+                .filter(|rerun| rerun.id > previous.id);
+            if let (Some(rerun), Some(before)) = (rerun, &previous.result_hash)
+                && let Some(after) = &rerun.result_hash
+            {
+                if after == before {
                     eprintln!(
-                        "{} Longitude is required when latitude is provided",
-                        "Error:".red().bold()
+                        "{} result unchanged since #{}",
+                        "Same:".green().bold(),
+                        previous.id
                     );
-                    process::exit(1);
-                }
-            } else {
-                if let Some(address_val) = address {
-                    SearchQuery::from_address(address_val)
                 } else {
                     eprintln!(
-                        "{} Either address or coordinates must be provided",
-                        "Error:".red().bold()
+                        "{} result differs from #{} (now #{})",
+                        "Changed:".yellow().bold(),
+                        previous.id,
+                        rerun.id
                     );
-                    process::exit(1);
                 }
-            };
+            }
+            process::exit(status.code().unwrap_or(1));
+        }
+        HistoryAction::Clear => match history.clear() {
+            Ok(deleted) => println!("{} removed {} queries", "Cleared:".green().bold(), deleted),
+            Err(e) => exit_with(e),
+        },
+    }
+}
 
-            match client
-                .fetch_intelligence_async(query, service_types, radius, max_results)
-                .await
-            {
-                Ok(intel) => println!("{}", serde_json::to_string_pretty(&intel).unwrap()),
-                Err(e) => {
-                    eprintln!("{} {}", "Error:".red().bold(), e);
-                    process::exit(1);
-                }
+/// Prints the usage report, or clears the history, and shows today's standing against the budget.
+fn run_usage(
+    ledger: &UsageLedger,
+    days: u32,
+    clear: bool,
+    format: OutputFormat,
+    max_calls_per_day: Option<u64>,
+) {
+    if clear {
+        match ledger.clear() {
+            Ok(deleted) => println!("{} removed {} records", "Cleared:".green().bold(), deleted),
+            Err(e) => exit_with(e),
+        }
+        return;
+    }
+    let report = ledger.report(days).unwrap_or_else(|e| exit_with(e));
+    match format {
+        OutputFormat::Table => println!("{}", render_usage(&report)),
+        _ => println!("{}", serde_json::to_string_pretty(&report).unwrap()),
+    }
+    if let Some(max) = max_calls_per_day {
+        let label = if report.calls_today >= max {
+            "Budget exhausted:".red().bold()
+        } else if report.calls_today * 5 >= max * 4 {
+            "Budget warning:".yellow().bold()
+        } else {
+            "Budget:".green().bold()
+        };
+        eprintln!(
+            "{} {} of {} calls used today",
+            label, report.calls_today, max
+        );
+    }
+}
+
+/// API keys from the OS keyring, then the environment, then flags, then the config file.
+/// `--api-keys` and `MAPRADAR_API_KEYS` win over their single-key counterparts.
+fn resolve_api_keys(
+    api_key: Option<String>,
+    api_keys: Vec<String>,
+    matches: &ArgMatches,
+    config: &Config,
+) -> Option<Vec<String>> {
+    let from = |source: ValueSource| {
+        let api_keys = (matches.value_source("api_keys") == Some(source))
+            .then(|| api_keys.clone())
+            .filter(|api_keys| !api_keys.is_empty());
+        api_keys.or_else(|| {
+            (matches.value_source("api_key") == Some(source))
+                .then(|| api_key.clone().map(|api_key| vec![api_key]))
+                .flatten()
+        })
+    };
+    stored_api_key()
+        .map(|api_key| vec![api_key])
+        .or_else(|| from(ValueSource::EnvVariable))
+        .or_else(|| from(ValueSource::CommandLine))
+        .or_else(|| config.api_keys())
+}
+
+/// The key saved with `auth login`. An unavailable keyring is logged and treated as empty, so
+/// the other sources still apply.
+#[cfg(feature = "keyring")]
+fn stored_api_key() -> Option<String> {
+    mapradar::credentials::load_api_key().unwrap_or_else(|e| {
+        tracing::debug!(error = %e, "no API key from the OS keyring");
+        None
+    })
+}
+
+#[cfg(not(feature = "keyring"))]
+fn stored_api_key() -> Option<String> {
+    None
+}
+
+#[cfg(feature = "keyring")]
+fn run_auth_action(action: &AuthAction) {
+    use mapradar::credentials;
+
+    match action {
+        AuthAction::Login => {
+            if io::stdin().is_terminal() {
+                eprint!("API key: ");
+                let _ = io::stderr().flush();
+            }
+            let mut api_key = String::new();
+            if let Err(e) = io::stdin().read_line(&mut api_key) {
+                exit_with(e.into())
+            }
+            let api_key = api_key.trim();
+            if api_key.is_empty() {
+                exit_with(MapradarError::InvalidInput(
+                    "No API key given on stdin".to_string(),
+                ))
+            }
+            match credentials::store_api_key(api_key) {
+                Ok(()) => eprintln!(
+                    "{} API key {} in the OS keyring",
+                    "Stored:".green().bold(),
+                    mask(api_key)
+                ),
+                Err(e) => exit_with(e),
             }
         }
-        Commands::Distance {
-            origin_addr,
-            origin_lat,
-            origin_lng,
-            dest_addr,
-            dest_lat,
-            dest_lng,
-        } => {
-            let params = TravelParameters {
-                origin_latitude: origin_lat,
-                origin_longitude: origin_lng,
-                origin_address: origin_addr,
-                destination_latitude: dest_lat,
-                destination_longitude: dest_lng,
-                destination_address: dest_addr,
-            };
+        AuthAction::Logout => match credentials::delete_api_key() {
+            Ok(true) => eprintln!("{} API key from the OS keyring", "Removed:".green().bold()),
+            Ok(false) => eprintln!("No API key is stored in the OS keyring"),
+            Err(e) => exit_with(e),
+        },
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+fn run_auth_action(_action: &AuthAction) {
+    exit_with(MapradarError::Unsupported(
+        "OS keyring storage requires the `keyring` feature".to_string(),
+    ))
+}
+
+/// Result of checking one API key with `keys verify`.
+#[derive(Serialize)]
+struct KeyCheck {
+    key: String,
+    /// `available`, `exceeded`, or `unknown` when the key could not be checked. Google does not
+    /// report how much quota remains.
+    quota: &'static str,
+    #[serde(flatten)]
+    provider: ProviderHealth,
+}
+
+/// Probes each key with one uncached request and prints the results, exiting non-zero when any
+/// key is not accepted.
+async fn run_keys_verify(
+    builder: &MapradarClientBuilder,
+    provider: ProviderKind,
+    api_keys: Vec<String>,
+) {
+    if provider != ProviderKind::Google {
+        exit_with(MapradarError::InvalidInput(format!(
+            "The {} provider does not use API keys",
+            provider
+        )))
+    }
+    if api_keys.is_empty() {
+        exit_with(MapradarError::Config(
+            "No API keys configured; set --api-key, --api-keys, or MAPRADAR_API_KEYS".to_string(),
+        ))
+    }
 
-            match client.calculate_travel_distance_async(params).await {
-                Ok(dist) => println!("{} {:.2} km", "Distance:".green().bold(), dist),
-                Err(e) => {
-                    eprintln!("{} {}", "Error:".red().bold(), e);
-                    process::exit(1);
+    let mut checks = Vec::new();
+    for api_key in api_keys {
+        let client = builder
+            .clone()
+            .api_key(api_key.as_str())
+            .build()
+            .unwrap_or_else(|e| exit_with(e));
+        let Some(provider) = client.readiness_async(true).await.provider else {
+            continue;
+        };
+        let quota = match provider.status {
+            ProviderStatus::QuotaExceeded => "exceeded",
+            ProviderStatus::Ok => "available",
+            _ => "unknown",
+        };
+        checks.push(KeyCheck {
+            key: mask(&api_key),
+            quota,
+            provider,
+        });
+    }
+    println!("{}", serde_json::to_string_pretty(&checks).unwrap());
+
+    let accepted = checks
+        .iter()
+        .filter(|check| check.provider.credentials_valid == Some(true))
+        .count();
+    let label = if accepted == checks.len() {
+        "Verified:".green().bold()
+    } else {
+        "Verified:".red().bold()
+    };
+    eprintln!("{} {} of {} keys accepted", label, accepted, checks.len());
+    if accepted < checks.len() {
+        process::exit(1);
+    }
+}
+
+fn run_config_action(action: &ConfigAction, mut config: Config, path: &Path) {
+    let saved = match action {
+        ConfigAction::Set { key, value } => config.set(key, value).and_then(|_| config.save(path)),
+        ConfigAction::Unset { key } => config.unset(key).and_then(|_| config.save(path)),
+        ConfigAction::Get { key } => {
+            match config.get(key) {
+                Ok(Some(value)) => println!("{}", value),
+                Ok(None) => process::exit(1),
+                Err(e) => exit_with(e),
+            }
+            return;
+        }
+        ConfigAction::List => {
+            println!("{} {}", "Config:".green().bold(), path.display());
+            for (key, value) in config.entries() {
+                let value = if key == "api_key" {
+                    mask(&value)
+                } else if key == "api_keys" {
+                    value.split(',').map(mask).collect::<Vec<_>>().join(",")
+                } else {
+                    value
+                };
+                println!("{} = {}", key.cyan(), value);
+            }
+            return;
+        }
+    };
+    if let Err(e) = saved {
+        exit_with(e)
+    }
+}
+
+fn save_search(name: &str, args: &[String], mut config: Config, path: &Path) {
+    // Parsed as `nearby` arguments now, rather than failing on each later run.
+    let nearby = ["mapradar", "nearby"].into_iter().map(String::from);
+    if let Err(e) = command().try_get_matches_from(nearby.chain(args.iter().cloned())) {
+        exit_with_usage(e)
+    }
+    if let Err(e) = config
+        .save_search(name, args)
+        .and_then(|_| config.save(path))
+    {
+        exit_with(e)
+    }
+}
+
+fn run_searches_action(action: &SearchesAction, mut config: Config, path: &Path) {
+    match action {
+        SearchesAction::List => {
+            for (name, args) in &config.searches {
+                let args = args
+                    .iter()
+                    .map(|arg| {
+                        if arg.is_empty() || arg.contains(char::is_whitespace) {
+                            format!("{:?}", arg)
+                        } else {
+                            arg.clone()
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                println!("{} = {}", name.cyan(), args.join(" "));
+            }
+        }
+        SearchesAction::Remove { name } => {
+            if let Err(e) = config.remove_search(name).and_then(|_| config.save(path)) {
+                exit_with(e)
+            }
+        }
+    }
+}
+
+/// Command line running `nearby` with a saved search's arguments followed by those given to
+/// `mapradar run`, leaving out each saved option that the given ones repeat.
+fn saved_search_argv(saved: &[String], given: &[String]) -> Vec<String> {
+    let root = command();
+    let nearby = root
+        .find_subcommand("nearby")
+        .expect("nearby is a subcommand");
+    let replaced = option_groups(&root, nearby, given)
+        .into_iter()
+        .filter_map(|(id, _)| id)
+        .collect::<Vec<_>>();
+
+    // Global options before `run` keep their place.
+    let mut argv = std::env::args()
+        .take_while(|arg| arg != "run")
+        .collect::<Vec<_>>();
+    argv.push("nearby".to_string());
+    for (id, tokens) in option_groups(&root, nearby, saved) {
+        if id.is_none_or(|id| !replaced.contains(&id)) {
+            argv.extend(tokens);
+        }
+    }
+    argv.extend(given.iter().cloned());
+    argv
+}
+
+/// Splits `nearby` arguments into options, each with its value and keyed by argument ID; anything
+/// not recognised as an option is kept on its own, without an ID.
+fn option_groups(
+    root: &clap::Command,
+    nearby: &clap::Command,
+    args: &[String],
+) -> Vec<(Option<String>, Vec<String>)> {
+    let known = nearby
+        .get_arguments()
+        .chain(root.get_arguments())
+        .collect::<Vec<_>>();
+    let mut groups = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (option, inline_value) = if let Some(long) = arg.strip_prefix("--") {
+            let name = long.split('=').next().unwrap_or_default();
+            let option = known.iter().find(|option| {
+                option.get_long() == Some(name)
+                    || option
+                        .get_all_aliases()
+                        .is_some_and(|aliases| aliases.contains(&name))
+            });
+            (option, long.contains('='))
+        } else if let Some(short) = arg.strip_prefix('-').and_then(|rest| rest.chars().next()) {
+            let option = known
+                .iter()
+                .find(|option| option.get_short() == Some(short));
+            (option, arg.len() > 2)
+        } else {
+            (None, false)
+        };
+        let Some(option) = option else {
+            groups.push((None, vec![arg.clone()]));
+            continue;
+        };
+        let mut tokens = vec![arg.clone()];
+        if !inline_value && option.get_action().takes_values() {
+            tokens.extend(args.next().cloned());
+        }
+        groups.push((Some(option.get_id().to_string()), tokens));
+    }
+    groups
+}
+
+/// Hides all but the last four characters of a secret.
+fn mask(secret: &str) -> String {
+    let visible: String = secret
+        .chars()
+        .skip(secret.chars().count().saturating_sub(4))
+        .collect();
+    format!("****{}", visible)
+}
+
+/// Loads the polygons of a GeoJSON file for filtering.
+fn read_polygon(path: &Path) -> Result<Geometry, MapradarError> {
+    let text = std::fs::read_to_string(path)?;
+    let value: serde_json::Value = serde_json::from_str(&text).map_err(|err| {
+        MapradarError::InvalidInput(format!("{} is not valid JSON: {}", path.display(), err))
+    })?;
+    Geometry::from_geojson(&value)
+}
+
+/// Reads score weights from `path`, or the built-in defaults when no file is given.
+fn load_weights(path: Option<&Path>) -> ScoreWeights {
+    path.map_or_else(|| Ok(ScoreWeights::default()), ScoreWeights::load)
+        .unwrap_or_else(|e| exit_with(e))
+}
+
+/// Address components with their normalized single-line form.
+fn components_json(components: &AddressComponents) -> serde_json::Value {
+    let mut value = serde_json::to_value(components).unwrap();
+    value["normalized"] = serde_json::Value::String(components.normalized());
+    value
+}
+
+/// Sends `tracing` output to stderr. `MAPRADAR_LOG` (an `EnvFilter` directive such as
+/// `mapradar=debug`) overrides the level chosen by `-v` and `--trace`.
+fn init_logging(verbose: u8, trace: bool, format: LogFormat) {
+    let level = match (trace, verbose) {
+        (true, _) => "trace",
+        (false, 0) => "warn",
+        (false, 1) => "info",
+        (false, _) => "debug",
+    };
+    let filter = EnvFilter::try_from_env("MAPRADAR_LOG")
+        .unwrap_or_else(|_| EnvFilter::new(format!("mapradar={}", level)));
+    let span_events = if trace { FmtSpan::CLOSE } else { FmtSpan::NONE };
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(span_events)
+        .with_writer(io::stderr);
+    match format {
+        LogFormat::Text => subscriber.init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
+}
+
+/// Destination for commands that produce a single JSON result: stdout, or a webhook with
+/// `--post-to`.
+struct ResultSink {
+    webhook: Option<WebhookSink>,
+    /// Discards results under `--dry-run`, whose output is the request plan instead.
+    dry_run: bool,
+    /// History entry of this query, given the hash of its result.
+    history: Option<(QueryHistory, i64)>,
+    /// Replaces the output format of printed results.
+    custom: Option<CustomOutput>,
+}
+
+impl ResultSink {
+    fn is_webhook(&self) -> bool {
+        self.webhook.is_some()
+    }
+
+    async fn json<T: Serialize + ?Sized>(&self, value: &T) {
+        if self.dry_run {
+            return;
+        }
+        match &self.webhook {
+            Some(webhook) => match webhook.deliver(value).await {
+                Ok(()) => eprintln!("{} {}", "Delivered:".green().bold(), webhook.url()),
+                Err(e) => exit_with(e),
+            },
+            None => match self.custom(value, None) {
+                Some(Err(e)) => exit_with(e),
+                Some(Ok(())) => {}
+                None => println!("{}", serde_json::to_string_pretty(value).unwrap()),
+            },
+        }
+        self.record(value);
+    }
+
+    /// Writes a result as `--fields`, `--query`, or `--template` ask to `output`, or stdout, or
+    /// returns `None` without them so that the caller writes it in the output format.
+    fn custom<T: Serialize + ?Sized>(
+        &self,
+        value: &T,
+        output: Option<&PathBuf>,
+    ) -> Option<Result<(), MapradarError>> {
+        let custom = self.custom.as_ref()?;
+        Some(write_custom(custom, value, output))
+    }
+
+    /// Stores the hash of a result written elsewhere than through [`Self::json`] in the query's
+    /// history entry. A lost hash only leaves the entry without one.
+    fn record<T: Serialize + ?Sized>(&self, value: &T) {
+        if let Some((history, id)) = &self.history
+            && let Ok(body) = serde_json::to_string_pretty(value)
+        {
+            let _ = history.record_result(*id, body.as_bytes());
+        }
+    }
+}
+
+/// Results picked out with `--fields` or `--query`, rendered with `--template`, or both, in place
+/// of the output format.
+#[derive(Clone)]
+struct CustomOutput {
+    selection: Option<Selection>,
+    template: Option<Template>,
+}
+
+impl CustomOutput {
+    fn new(cli: &Cli) -> Option<Self> {
+        let selection = match (&cli.query, cli.fields.is_empty()) {
+            (Some(query), _) => Some(Selection::Query(query.clone())),
+            (None, false) => Some(Selection::Fields(cli.fields.clone())),
+            (None, true) => None,
+        };
+        (selection.is_some() || cli.template.is_some()).then(|| Self {
+            selection,
+            template: cli.template.clone(),
+        })
+    }
+
+    /// Renders each value left of `result` through the template, or else prints it as JSON,
+    /// pretty unless `compact`, with strings bare as `jq -r` prints them.
+    fn render(&self, result: &serde_json::Value, compact: bool) -> Result<String, MapradarError> {
+        let values = match &self.selection {
+            Some(selection) => selection.apply(result)?,
+            None => vec![result.clone()],
+        };
+        let fields = match &self.selection {
+            Some(Selection::Fields(fields)) => &fields[..],
+            _ => &[],
+        };
+        let mut text = String::new();
+        for value in values {
+            let line = match (&self.template, value) {
+                (Some(template), value) => {
+                    text.push_str(&template.render(&value)?);
+                    continue;
                 }
+                (None, serde_json::Value::String(value)) => value,
+                (None, value) if compact => serde_json::to_string(&InFieldOrder(&value, fields))?,
+                (None, value) => serde_json::to_string_pretty(&InFieldOrder(&value, fields))?,
+            };
+            text.push_str(&line);
+            text.push('\n');
+        }
+        Ok(text)
+    }
+}
+
+fn write_custom<T: Serialize + ?Sized>(
+    custom: &CustomOutput,
+    value: &T,
+    output: Option<&PathBuf>,
+) -> Result<(), MapradarError> {
+    let text = custom.render(&serde_json::to_value(value)?, false)?;
+    let mut writer = open_output(output)?;
+    writer.write_all(text.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Builds a webhook with the global `--post-header` and `--post-secret` settings.
+fn webhook_sink(
+    url: &str,
+    headers: &[String],
+    secret: Option<&str>,
+    retry_policy: RetryPolicy,
+) -> Result<WebhookSink, MapradarError> {
+    let mut webhook = WebhookSink::new(url)?.retry_policy(retry_policy);
+    for header in headers {
+        let (name, value) = header.split_once(':').ok_or_else(|| {
+            MapradarError::InvalidInput(format!(
+                "Invalid header '{}' (expected \"Name: value\")",
+                header
+            ))
+        })?;
+        webhook = webhook.header(name, value)?;
+    }
+    if let Some(secret) = secret {
+        webhook = webhook.secret(secret);
+    }
+    Ok(webhook)
+}
+
+/// Writes amenities found along a route in the selected format to `output`, or stdout. Rows
+/// and tables keep the route order, with each place's distance off the route.
+fn write_corridor(
+    corridor: &TrackEnrichment,
+    format: OutputFormat,
+    columns: &[ServiceColumn],
+    units: Units,
+    output: Option<&PathBuf>,
+) -> Result<(), MapradarError> {
+    let services: Vec<NearbyService> = corridor
+        .amenities
+        .iter()
+        .map(|amenity| amenity.service.clone())
+        .collect();
+
+    let mut writer = open_output(output)?;
+    match (format, format.delimiter()) {
+        (_, Some(delimiter)) => RowWriter::new(&mut writer, delimiter)
+            .write_all(services.iter().map(|s| ServiceRow::from(s).in_units(units)))?,
+        (OutputFormat::Geojson, _) => writeln!(
+            writer,
+            "{}",
+            serde_json::to_string_pretty(&corridor.to_feature_collection())?
+        )?,
+        (OutputFormat::Table, _) => {
+            writeln!(writer, "{}", render_table(&services, columns, units))?
+        }
+        _ => writeln!(writer, "{}", serde_json::to_string_pretty(corridor)?)?,
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes nearby-search results in the selected format to `output`, or stdout.
+fn write_intelligence(
+    intel: &LocationIntelligence,
+    format: OutputFormat,
+    columns: &[ServiceColumn],
+    units: Units,
+    output: Option<&PathBuf>,
+) -> Result<(), MapradarError> {
+    if let Some(path) = output
+        && path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("kmz"))
+    {
+        if format != OutputFormat::Kml {
+            return Err(MapradarError::InvalidInput(
+                "A .kmz output file requires --format kml".to_string(),
+            ));
+        }
+        return kml::write_kmz(File::create(path)?, &kml::to_kml(intel));
+    }
+
+    let mut writer = open_output(output)?;
+    match (format, format.delimiter()) {
+        (_, Some(delimiter)) => RowWriter::new(&mut writer, delimiter).write_all(
+            intel
+                .nearby_services
+                .iter()
+                .map(|s| ServiceRow::from(s).in_units(units)),
+        )?,
+        (OutputFormat::Geojson, _) => writeln!(
+            writer,
+            "{}",
+            serde_json::to_string_pretty(&intel.to_feature_collection())?
+        )?,
+        (OutputFormat::Table, _) => writeln!(
+            writer,
+            "{}",
+            render_table(&intel.nearby_services, columns, units)
+        )?,
+        (OutputFormat::Kml, _) => writer.write_all(kml::to_kml(intel).as_bytes())?,
+        (OutputFormat::Html, _) => writer.write_all(html::to_html(intel).as_bytes())?,
+        _ => writeln!(writer, "{}", serde_json::to_string_pretty(intel)?)?,
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes a meeting point in the selected format: the results around it as rows, or as a table
+/// after each participant's distance, or the whole meeting point as JSON or GeoJSON.
+fn write_meeting_point(
+    meeting: &MeetingPoint,
+    format: OutputFormat,
+    columns: &[ServiceColumn],
+    units: Units,
+    output: Option<&PathBuf>,
+) -> Result<(), MapradarError> {
+    let services = &meeting.intelligence.nearby_services;
+    let mut writer = open_output(output)?;
+    match (format, format.delimiter()) {
+        (_, Some(delimiter)) => RowWriter::new(&mut writer, delimiter)
+            .write_all(services.iter().map(|s| ServiceRow::from(s).in_units(units)))?,
+        (OutputFormat::Geojson, _) => writeln!(
+            writer,
+            "{}",
+            serde_json::to_string_pretty(&meeting.to_feature_collection())?
+        )?,
+        (OutputFormat::Table, _) => {
+            let location = &meeting.intelligence.location;
+            writeln!(
+                writer,
+                "{} {} ({:.5}, {:.5})",
+                "Meet at:".green().bold(),
+                location.address,
+                location.latitude,
+                location.longitude
+            )?;
+            for (participant, km) in meeting.participants.iter().zip(&meeting.distances_km) {
+                writeln!(
+                    writer,
+                    "  {:>10}  from {}",
+                    units.format_distance(*km),
+                    participant.address
+                )?;
+            }
+            writeln!(writer)?;
+            writeln!(writer, "{}", render_table(services, columns, units))?
+        }
+        _ => writeln!(writer, "{}", serde_json::to_string_pretty(meeting)?)?,
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Nearby results grouped by `--cluster-by-geohash` or `--cluster`, or a heatmap grid, rendered
+/// for the output format.
+struct Grouped {
+    /// JSON or GeoJSON document, for JSON-based formats and webhooks.
+    document: serde_json::Value,
+    /// Table or delimited rows, for the other formats.
+    text: Option<String>,
+}
+
+impl Grouped {
+    fn new<G: ToGeoJson>(
+        intel: &LocationIntelligence,
+        groups: &[G],
+        json: serde_json::Value,
+        format: OutputFormat,
+        text: impl FnOnce() -> Result<String, MapradarError>,
+    ) -> Result<Self, MapradarError> {
+        let document = match format {
+            OutputFormat::Geojson => {
+                let mut origin = intel.location.to_feature();
+                origin["properties"]["role"] = serde_json::json!("origin");
+                let mut features = vec![origin];
+                features.extend(groups.iter().map(ToGeoJson::to_feature));
+                feature_collection(features)
             }
+            _ => json,
+        };
+        let text = match format {
+            OutputFormat::Table | OutputFormat::Csv | OutputFormat::Tsv => Some(text()?),
+            _ => None,
+        };
+        Ok(Self { document, text })
+    }
+
+    fn write(&self, output: Option<&PathBuf>) -> Result<(), MapradarError> {
+        let mut writer = open_output(output)?;
+        match &self.text {
+            Some(text) => write!(writer, "{}", text)?,
+            None => writeln!(writer, "{}", serde_json::to_string_pretty(&self.document)?)?,
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Delimited rows as text, header first.
+fn delimited_rows<R: serde::Serialize>(
+    rows: impl IntoIterator<Item = R>,
+    delimiter: u8,
+) -> Result<String, MapradarError> {
+    let mut buffer = Vec::new();
+    RowWriter::new(&mut buffer, delimiter).write_all(rows)?;
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+/// Groups results per geohash cell, densest first.
+fn group_by_geohash(
+    intel: &LocationIntelligence,
+    precision: usize,
+    format: OutputFormat,
+) -> Result<Grouped, MapradarError> {
+    let clusters = geohash::cluster(&intel.nearby_services, precision)?;
+    let json = serde_json::json!({
+        "location": intel.location,
+        "precision": precision,
+        "total": intel.nearby_services.len(),
+        "clusters": clusters,
+    });
+    Grouped::new(intel, &clusters, json, format, || {
+        match format.delimiter() {
+            Some(delimiter) => delimited_rows(clusters.iter().map(GeohashRow::from), delimiter),
+            None => Ok(format!("{}\n", render_clusters(&clusters))),
+        }
+    })
+}
+
+/// Groups results lying within `eps_m` of each other with DBSCAN, largest cluster first.
+fn group_by_proximity(
+    intel: &LocationIntelligence,
+    eps_m: f64,
+    min_points: usize,
+    format: OutputFormat,
+    units: Units,
+) -> Result<Grouped, MapradarError> {
+    let clusters = intel.cluster(eps_m, min_points);
+    let clustered: usize = clusters.iter().map(|cluster| cluster.count).sum();
+    let json = serde_json::json!({
+        "location": intel.location,
+        "eps_m": eps_m,
+        "min_points": min_points,
+        "total": intel.nearby_services.len(),
+        "unclustered": intel.nearby_services.len() - clustered,
+        "clusters": clusters,
+    });
+    Grouped::new(intel, &clusters, json, format, || {
+        match format.delimiter() {
+            Some(delimiter) => delimited_rows(clusters.iter().map(ClusterRow::from), delimiter),
+            None => Ok(format!("{}\n", render_service_clusters(&clusters, units))),
+        }
+    })
+}
+
+/// Renders a heatmap as a GeoJSON grid, JSON, delimited rows of every cell, or a table of the
+/// cells with results.
+fn heatmap_grid(heatmap: &Heatmap, format: OutputFormat) -> Result<Grouped, MapradarError> {
+    let document = match format {
+        OutputFormat::Geojson => heatmap.to_feature_collection(),
+        _ => serde_json::to_value(heatmap)?,
+    };
+    let text = match (format, format.delimiter()) {
+        (_, Some(delimiter)) => Some(delimited_rows(
+            heatmap.cells.iter().map(HeatmapRow::from),
+            delimiter,
+        )?),
+        (OutputFormat::Table, None) => Some(format!("{}\n", render_heatmap(heatmap))),
+        _ => None,
+    };
+    Ok(Grouped { document, text })
+}
+
+/// Writes batch geocoding records as delimited rows, Parquet, or NDJSON.
+fn write_batch(
+    records: &[BatchRecord],
+    format: OutputFormat,
+    output: Option<&PathBuf>,
+) -> Result<(), MapradarError> {
+    match (format, format.delimiter()) {
+        (_, Some(delimiter)) => RowWriter::new(open_output(output)?, delimiter)
+            .write_all(records.iter().map(BatchRow::from)),
+        #[cfg(feature = "parquet")]
+        (OutputFormat::Parquet, _) => {
+            let mut writer = open_output(output)?;
+            writer.write_all(&mapradar::columnar::to_parquet(records)?)?;
+            writer.flush()?;
+            Ok(())
+        }
+        _ => Ok(write_ndjson(records, output)?),
+    }
+}
+
+/// Cancels `token` on the first Ctrl-C, so in-flight requests stop and the results obtained so
+/// far can still be written, and exits at once on the second.
+fn trap_interrupts(token: CancellationToken) {
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        eprintln!(
+            "{} finishing up; press Ctrl-C again to quit now",
+            "Interrupted:".yellow().bold()
+        );
+        token.cancel();
+        if tokio::signal::ctrl_c().await.is_ok() {
+            process::exit(MapradarError::Cancelled.exit_code());
         }
+    });
+}
+
+/// Opens `--checkpoint`, carrying on with its finished rows under `--resume`.
+fn open_checkpoint(path: PathBuf, resume: bool) -> Checkpoint {
+    let checkpoint = if resume {
+        Checkpoint::resume(path)
+    } else {
+        Checkpoint::create(path)
+    }
+    .unwrap_or_else(|e| exit_with(e));
+    if checkpoint.completed() > 0 {
+        eprintln!(
+            "{} skipping {} rows finished in {}",
+            "Resuming:".green().bold(),
+            checkpoint.completed(),
+            checkpoint.path().display()
+        );
+    }
+    checkpoint
+}
+
+/// Reports how many of a batch's `rows` were `done` (e.g. "geocoded"), failed, or never
+/// reached because of an interruption.
+fn print_batch_summary(records: &[BatchRecord], rows: usize, done: &str) {
+    let failed = records.iter().filter(|r| !r.is_ok()).count();
+    let unreached = rows - records.len();
+    let label = if unreached > 0 {
+        "Interrupted:".yellow().bold()
+    } else {
+        "Done:".green().bold()
+    };
+    eprint!(
+        "{} {} {}, {} failed",
+        label,
+        records.len() - failed,
+        done,
+        failed
+    );
+    if unreached > 0 {
+        eprint!(", {} not reached", unreached);
+    }
+    eprintln!();
+}
+
+/// Deletes the checkpoint of a job whose `rows` are all finished, now that its results are
+/// written; otherwise keeps it for `--resume` to retry the rest.
+fn finish_checkpoint(checkpoint: Checkpoint, rows: usize) {
+    let unfinished = rows - checkpoint.completed();
+    if unfinished > 0 {
+        eprintln!(
+            "{} {} rows are left to --resume --checkpoint {}",
+            "Warning:".yellow().bold(),
+            unfinished,
+            checkpoint.path().display()
+        );
+    } else if let Err(e) = checkpoint.remove() {
+        eprintln!(
+            "{} checkpoint not removed: {}",
+            "Warning:".yellow().bold(),
+            e
+        );
+    }
+}
+
+/// Opens the given file for writing, or stdout when no file is given.
+fn open_output(output: Option<&PathBuf>) -> io::Result<Box<dyn Write>> {
+    Ok(match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    })
+}
+
+/// Writes one JSON document per line to the given file, or stdout when no file is given.
+fn write_ndjson<T: serde::Serialize>(items: &[T], output: Option<&PathBuf>) -> io::Result<()> {
+    let mut writer = open_output(output)?;
+
+    for item in items {
+        serde_json::to_writer(&mut writer, item)?;
+        writeln!(writer)?;
     }
+
+    writer.flush()
 }
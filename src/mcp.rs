@@ -0,0 +1,144 @@
+//! Model Context Protocol server exposing geocoding and nearby search as tools.
+//!
+//! Messages are newline-delimited JSON-RPC 2.0 on stdin/stdout, as in the MCP stdio transport.
+//! Nothing else may be written to stdout while the server runs; diagnostics go to stderr.
+//! Tools are thin wrappers over the [`rpc`](crate::rpc) methods.
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::{
+    client::MapradarClient,
+    error::MapradarError,
+    rpc::{self, INVALID_PARAMS, METHOD_NOT_FOUND, PARSE_ERROR, RpcRequest, RpcResponse},
+};
+
+/// MCP revision this server implements.
+pub const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Serves MCP requests from stdin until it closes.
+pub async fn serve_stdio(client: MapradarClient) -> Result<(), MapradarError> {
+    rpc::serve_lines(|line| {
+        let client = &client;
+        async move { handle_message(client, &line).await }
+    })
+    .await
+}
+
+/// Handles one JSON-RPC message, returning the response to send, or `None` for notifications.
+pub async fn handle_message(client: &MapradarClient, message: &str) -> Option<Value> {
+    let response = match serde_json::from_str(message) {
+        Ok(message) => match RpcRequest::parse(message) {
+            Ok(request) => handle_request(client, request).await?,
+            Err(response) => *response,
+        },
+        Err(err) => RpcResponse::failure(Value::Null, PARSE_ERROR, format!("Parse error: {}", err)),
+    };
+    serde_json::to_value(response).ok()
+}
+
+async fn handle_request(client: &MapradarClient, request: RpcRequest) -> Option<RpcResponse> {
+    // Notifications (e.g. notifications/initialized) never get a reply.
+    let id = request.id?;
+
+    let result = match request.method.as_str() {
+        "initialize" => Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": {
+                "name": "mapradar",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => call_tool(client, request.params).await,
+        other => Err((METHOD_NOT_FOUND, format!("Unknown method '{}'", other))),
+    };
+
+    Some(match result {
+        Ok(result) => RpcResponse::success(id, result),
+        Err((code, message)) => RpcResponse::failure(id, code, message),
+    })
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "geocode",
+            "description": "Convert an address or place name to coordinates and address details.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "address": { "type": "string", "description": "Address or place name" },
+                },
+                "required": ["address"],
+            },
+        },
+        {
+            "name": "reverse_geocode",
+            "description": "Convert latitude/longitude coordinates to the nearest address.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "latitude": { "type": "number" },
+                    "longitude": { "type": "number" },
+                },
+                "required": ["latitude", "longitude"],
+            },
+        },
+        {
+            "name": "nearby_search",
+            "description": "Find services such as banks, hospitals, or pharmacies near an address or coordinates, sorted by distance.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "address": { "type": "string", "description": "Address to search around" },
+                    "latitude": { "type": "number", "description": "Latitude, used instead of address" },
+                    "longitude": { "type": "number", "description": "Longitude, used instead of address" },
+                    "types": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Service types (bank, hospital, school, ...), custom:<tag>, or key=value",
+                        "default": ["bank"],
+                    },
+                    "radius_km": { "type": "number", "default": 1.0 },
+                    "max_results": { "type": "integer", "minimum": 1, "default": 10 },
+                },
+            },
+        },
+    ])
+}
+
+#[derive(Deserialize)]
+struct ToolCall {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+/// Runs a tool. Protocol problems become JSON-RPC errors; tool failures become `isError` results
+/// so the calling model can read and react to them.
+async fn call_tool(client: &MapradarClient, params: Value) -> Result<Value, (i32, String)> {
+    let call: ToolCall =
+        serde_json::from_value(params).map_err(|err| (INVALID_PARAMS, err.to_string()))?;
+
+    let method = match call.name.as_str() {
+        "geocode" => "geocode",
+        "reverse_geocode" => "reverse",
+        "nearby_search" => "nearby",
+        other => return Err((INVALID_PARAMS, format!("Unknown tool '{}'", other))),
+    };
+
+    Ok(match rpc::call(client, method, call.arguments).await {
+        Ok(value) => json!({
+            "content": [{ "type": "text", "text": value.to_string() }],
+            "structuredContent": value,
+            "isError": false,
+        }),
+        Err(err) => json!({
+            "content": [{ "type": "text", "text": err.to_string() }],
+            "isError": true,
+        }),
+    })
+}
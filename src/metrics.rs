@@ -0,0 +1,320 @@
+//! In-process metrics for server modes, exposed in the Prometheus text format at `/metrics`.
+//!
+//! A [`Metrics`] handle is attached to a client with
+//! [`MapradarClientBuilder::metrics`](crate::client::MapradarClientBuilder::metrics); its
+//! transport then records upstream latency and rate-limit waits, and its cache records hits and
+//! misses. Servers record each request they answer, and the error variant when it fails.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::error::MapradarError;
+
+/// Content type of [`Metrics::render`] output.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Upper bounds, in seconds, of the upstream latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 10] = [0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// Upper bounds, in seconds, of the rate-limit wait histogram buckets.
+const WAIT_BUCKETS: [f64; 8] = [0.001, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 30.0];
+
+/// Shared metrics registry; clones record into the same counters.
+#[derive(Clone, Default)]
+pub struct Metrics {
+    registry: Arc<Mutex<Registry>>,
+}
+
+#[derive(Default)]
+struct Registry {
+    /// By server, method, and outcome (`ok` or `error`).
+    requests: BTreeMap<(String, String, &'static str), u64>,
+    /// By server and error variant.
+    errors: BTreeMap<(String, &'static str), u64>,
+    /// By host and HTTP status, or `error` when no response arrived.
+    upstream_requests: BTreeMap<(String, String), u64>,
+    upstream_latency: BTreeMap<String, Histogram>,
+    /// By cache namespace and whether the lookup hit.
+    cache_lookups: BTreeMap<(String, bool), u64>,
+    rate_limit_wait: Option<Histogram>,
+}
+
+struct Histogram {
+    bounds: &'static [f64],
+    /// Observations at or below each bound; the `+Inf` bucket is `count`.
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            buckets: vec![0; bounds.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(self.bounds) {
+            if value <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &[(&str, &str)]) {
+        for (bucket, bound) in self.buckets.iter().zip(self.bounds) {
+            let le = bound.to_string();
+            let labels = [labels, &[("le", le.as_str())]].concat();
+            sample(out, &format!("{}_bucket", name), &labels, *bucket as f64);
+        }
+        let labels_inf = [labels, &[("le", "+Inf")]].concat();
+        sample(
+            out,
+            &format!("{}_bucket", name),
+            &labels_inf,
+            self.count as f64,
+        );
+        sample(out, &format!("{}_sum", name), labels, self.sum);
+        sample(out, &format!("{}_count", name), labels, self.count as f64);
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_registry(&self, update: impl FnOnce(&mut Registry)) {
+        if let Ok(mut registry) = self.registry.lock() {
+            update(&mut registry);
+        }
+    }
+
+    /// Counts a request answered by `server` (e.g. `rest`), and how it failed, if it did.
+    pub fn record_request(&self, server: &str, method: &str, error: Option<&MapradarError>) {
+        self.record_request_variant(server, method, error.map(MapradarError::variant_name));
+    }
+
+    /// Like [`Metrics::record_request`], given the failure's [`MapradarError::variant_name`].
+    pub(crate) fn record_request_variant(
+        &self,
+        server: &str,
+        method: &str,
+        variant: Option<&'static str>,
+    ) {
+        self.with_registry(|registry| {
+            let outcome = if variant.is_some() { "error" } else { "ok" };
+            *registry
+                .requests
+                .entry((server.to_string(), method.to_string(), outcome))
+                .or_default() += 1;
+            if let Some(variant) = variant {
+                *registry
+                    .errors
+                    .entry((server.to_string(), variant))
+                    .or_default() += 1;
+            }
+        });
+    }
+
+    /// Records one upstream attempt: its host, HTTP status (`None` when it failed without a
+    /// response), and how long it took.
+    pub(crate) fn observe_upstream(&self, host: &str, status: Option<u16>, elapsed: Duration) {
+        self.with_registry(|registry| {
+            let status = status.map_or_else(|| "error".to_string(), |s| s.to_string());
+            *registry
+                .upstream_requests
+                .entry((host.to_string(), status))
+                .or_default() += 1;
+            registry
+                .upstream_latency
+                .entry(host.to_string())
+                .or_insert_with(|| Histogram::new(&LATENCY_BUCKETS))
+                .observe(elapsed.as_secs_f64());
+        });
+    }
+
+    /// Records how long a request waited for the rate limiter.
+    pub(crate) fn observe_rate_limit_wait(&self, waited: Duration) {
+        self.with_registry(|registry| {
+            registry
+                .rate_limit_wait
+                .get_or_insert_with(|| Histogram::new(&WAIT_BUCKETS))
+                .observe(waited.as_secs_f64());
+        });
+    }
+
+    pub(crate) fn record_cache_lookup(&self, namespace: &str, hit: bool) {
+        self.with_registry(|registry| {
+            *registry
+                .cache_lookups
+                .entry((namespace.to_string(), hit))
+                .or_default() += 1;
+        });
+    }
+
+    /// Renders every metric in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let Ok(registry) = self.registry.lock() else {
+            return out;
+        };
+
+        header(
+            &mut out,
+            "mapradar_requests_total",
+            "counter",
+            "Requests answered, by server, method, and outcome.",
+        );
+        for ((server, method, outcome), count) in &registry.requests {
+            let labels = [
+                ("server", server.as_str()),
+                ("method", method),
+                ("outcome", outcome),
+            ];
+            sample(&mut out, "mapradar_requests_total", &labels, *count as f64);
+        }
+
+        header(
+            &mut out,
+            "mapradar_errors_total",
+            "counter",
+            "Failed requests, by server and error variant.",
+        );
+        for ((server, variant), count) in &registry.errors {
+            let labels = [("server", server.as_str()), ("variant", variant)];
+            sample(&mut out, "mapradar_errors_total", &labels, *count as f64);
+        }
+
+        header(
+            &mut out,
+            "mapradar_upstream_requests_total",
+            "counter",
+            "Upstream request attempts, by host and HTTP status, including retries.",
+        );
+        for ((host, status), count) in &registry.upstream_requests {
+            let labels = [("host", host.as_str()), ("status", status.as_str())];
+            sample(
+                &mut out,
+                "mapradar_upstream_requests_total",
+                &labels,
+                *count as f64,
+            );
+        }
+
+        header(
+            &mut out,
+            "mapradar_upstream_request_duration_seconds",
+            "histogram",
+            "Upstream request latency, by host.",
+        );
+        for (host, histogram) in &registry.upstream_latency {
+            histogram.render(
+                &mut out,
+                "mapradar_upstream_request_duration_seconds",
+                &[("host", host)],
+            );
+        }
+
+        header(
+            &mut out,
+            "mapradar_cache_lookups_total",
+            "counter",
+            "Response cache lookups, by namespace and result.",
+        );
+        for ((namespace, hit), count) in &registry.cache_lookups {
+            let result = if *hit { "hit" } else { "miss" };
+            let labels = [("namespace", namespace.as_str()), ("result", result)];
+            sample(
+                &mut out,
+                "mapradar_cache_lookups_total",
+                &labels,
+                *count as f64,
+            );
+        }
+
+        let (hits, lookups) =
+            registry
+                .cache_lookups
+                .iter()
+                .fold((0, 0), |(hits, lookups), ((_, hit), count)| {
+                    (hits + if *hit { *count } else { 0 }, lookups + count)
+                });
+        header(
+            &mut out,
+            "mapradar_cache_hit_ratio",
+            "gauge",
+            "Share of cache lookups that hit, across all namespaces.",
+        );
+        let ratio = if lookups == 0 {
+            0.0
+        } else {
+            hits as f64 / lookups as f64
+        };
+        sample(&mut out, "mapradar_cache_hit_ratio", &[], ratio);
+
+        header(
+            &mut out,
+            "mapradar_rate_limit_wait_seconds",
+            "histogram",
+            "Time requests spent waiting for the client-side rate limiter.",
+        );
+        registry
+            .rate_limit_wait
+            .as_ref()
+            .unwrap_or(&Histogram::new(&WAIT_BUCKETS))
+            .render(&mut out, "mapradar_rate_limit_wait_seconds", &[]);
+
+        out
+    }
+}
+
+/// Router serving `metrics` at `GET /metrics`, for merging into a server's router.
+#[cfg(feature = "server")]
+pub(crate) fn router(metrics: Metrics) -> axum::Router {
+    use axum::{extract::State, http::header, routing::get};
+
+    axum::Router::new()
+        .route(
+            "/metrics",
+            get(|State(metrics): State<Metrics>| async move {
+                ([(header::CONTENT_TYPE, CONTENT_TYPE)], metrics.render())
+            }),
+        )
+        .with_state(metrics)
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(label, value)| format!("{}=\"{}\"", label, escape(value)))
+            .collect();
+        let _ = write!(out, "{{{}}}", labels.join(","));
+    }
+    let _ = writeln!(out, " {}", value);
+}
+
+/// Escapes a label value as the text format requires.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
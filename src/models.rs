@@ -2,6 +2,8 @@
 use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::coordinates::{Coordinates, Latitude, Longitude};
+
 /// Represents a geographic location.
 #[cfg_attr(feature = "python", pyclass(get_all, set_all))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,11 +14,41 @@ pub struct GeoLocation {
     pub city: Option<String>,
     pub state: Option<String>,
     pub country: String,
+    /// Structured address as reported by the provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_components: Option<AddressComponents>,
+    /// Time zone at the location, when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<TimeZoneInfo>,
+    /// Ground elevation above sea level in meters, when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elevation_m: Option<f64>,
+    /// Language the provider was asked to write the address in (e.g. "fr"), when one was set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
 }
 
-#[cfg(feature = "python")]
-#[pymethods]
 impl GeoLocation {
+    /// The location's point, failing if the provider reported one out of range.
+    pub fn coordinates(&self) -> Result<Coordinates, crate::error::MapradarError> {
+        Coordinates::new(self.latitude, self.longitude)
+    }
+
+    /// Structured address: the provider's components when available, otherwise parsed offline
+    /// from `address`.
+    pub fn components(&self) -> AddressComponents {
+        self.address_components.clone().unwrap_or_else(|| {
+            let mut components = crate::address::parse_address(&self.address);
+            if components.country_code.is_none() && !self.country.is_empty() {
+                components.country_code = Some(self.country.clone());
+            }
+            components
+        })
+    }
+}
+
+#[cfg(feature = "python")]
+crate::python::data_model!(GeoLocation {
     /// Returns a string representation for debugging in Python.
     fn __repr__(&self) -> String {
         format!(
@@ -24,6 +56,268 @@ impl GeoLocation {
             self.address, self.latitude, self.longitude
         )
     }
+
+    /// Structured address: the provider's components when available, otherwise parsed offline.
+    #[pyo3(name = "components")]
+    fn py_components(&self) -> AddressComponents {
+        self.components()
+    }
+
+    /// GeoJSON `Point` feature, for shapely, geopandas, and folium.
+    #[getter]
+    fn __geo_interface__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        use crate::geojson::ToGeoJson;
+        crate::python::json_to_py(py, &self.to_feature())
+    }
+});
+
+/// Time zone in effect at a location.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeZoneInfo {
+    /// IANA identifier, e.g. `Africa/Lagos`.
+    pub id: String,
+    /// Current offset from UTC in seconds, including any daylight-saving shift.
+    pub utc_offset_secs: i32,
+    /// Whether daylight-saving time is currently in effect.
+    pub dst: bool,
+}
+
+impl TimeZoneInfo {
+    /// Offset formatted as `+HH:MM` or `-HH:MM`.
+    pub fn utc_offset(&self) -> String {
+        let sign = if self.utc_offset_secs < 0 { '-' } else { '+' };
+        let minutes = self.utc_offset_secs.unsigned_abs() / 60;
+        format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+    }
+}
+
+/// Ground elevation at one point of a path.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElevationSample {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Distance along the path from its first point.
+    pub distance_km: f64,
+    /// Meters above sea level.
+    pub elevation_m: f64,
+}
+
+/// A GPS point moved onto the nearest road.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnappedPoint {
+    /// Position of the point in the input.
+    pub index: usize,
+    /// Corrected position, or the original one when no road was found nearby.
+    pub latitude: f64,
+    pub longitude: f64,
+    pub original_latitude: f64,
+    pub original_longitude: f64,
+    /// How far the point moved, or `None` when it could not be snapped.
+    pub snap_distance_m: Option<f64>,
+    /// Name of the road, when the provider reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub road: Option<String>,
+    /// Provider ID of the road segment, when the provider reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub place_id: Option<String>,
+}
+
+impl SnappedPoint {
+    /// The point at `index`, moved from `original` to `snapped`.
+    pub fn snapped(index: usize, original: (f64, f64), snapped: (f64, f64)) -> Self {
+        Self {
+            index,
+            latitude: snapped.0,
+            longitude: snapped.1,
+            original_latitude: original.0,
+            original_longitude: original.1,
+            snap_distance_m: Some(
+                crate::geo::haversine_distance(original.0, original.1, snapped.0, snapped.1)
+                    * 1000.0,
+            ),
+            road: None,
+            place_id: None,
+        }
+    }
+
+    /// The point at `index`, left where it was because no road was found nearby.
+    pub fn unsnapped(index: usize, original: (f64, f64)) -> Self {
+        Self {
+            index,
+            latitude: original.0,
+            longitude: original.1,
+            original_latitude: original.0,
+            original_longitude: original.1,
+            snap_distance_m: None,
+            road: None,
+            place_id: None,
+        }
+    }
+
+    pub fn is_snapped(&self) -> bool {
+        self.snap_distance_m.is_some()
+    }
+}
+
+/// An address split into its parts. Fields the source did not provide are `None`.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AddressComponents {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub house_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub street: Option<String>,
+    /// Neighbourhood, district, or sublocality within the city.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suburb: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postcode: Option<String>,
+    /// ISO 3166-1 alpha-2 code, uppercase.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+}
+
+impl AddressComponents {
+    /// Single-line address in "number street, suburb, city, state postcode, country" order.
+    pub fn normalized(&self) -> String {
+        let street = [self.house_number.as_deref(), self.street.as_deref()];
+        let region = [self.state.as_deref(), self.postcode.as_deref()];
+        let join = |words: &[Option<&str>]| {
+            words
+                .iter()
+                .flatten()
+                .copied()
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        [
+            join(&street),
+            self.suburb.clone().unwrap_or_default(),
+            self.city.clone().unwrap_or_default(),
+            join(&region),
+            self.country_code.clone().unwrap_or_default(),
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+/// How precisely a geocoding candidate locates the queried address.
+#[cfg_attr(feature = "python", pyclass(eq, eq_int))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatchType {
+    /// The exact building or point was found.
+    Exact,
+    /// Position estimated between two known house numbers.
+    Interpolated,
+    /// Centre of a street, locality, or larger area.
+    Approximate,
+}
+
+/// One of possibly several locations matching an address, most confident first.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeocodeCandidate {
+    pub location: GeoLocation,
+    /// Match confidence from 0.0 to 1.0.
+    pub confidence: f64,
+    pub match_type: MatchType,
+    /// `(min_lng, min_lat, max_lng, max_lat)` extent of the match, when the provider reports one.
+    pub bounding_box: Option<(f64, f64, f64, f64)>,
+}
+
+/// Area covered by a postal code.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostcodeArea {
+    pub postcode: String,
+    /// ISO 3166-1 alpha-2 code, uppercase.
+    pub country: String,
+    /// Centre of the area, as reported by the provider.
+    pub latitude: f64,
+    pub longitude: f64,
+    /// `(min_lng, min_lat, max_lng, max_lat)` extent, when the provider reports one.
+    pub bounding_box: Option<(f64, f64, f64, f64)>,
+    /// Cities, towns, or districts the postcode covers.
+    pub localities: Vec<String>,
+}
+
+impl PostcodeArea {
+    /// Merges the geocoding matches for a postcode: the first match gives the centre, the box
+    /// encloses every match, and each match contributes its city (or failing that, suburb).
+    pub fn from_candidates(
+        postcode: &str,
+        country: &str,
+        candidates: &[GeocodeCandidate],
+    ) -> Option<Self> {
+        let first = candidates.first()?;
+        let bounding_box = candidates
+            .iter()
+            .filter_map(|candidate| candidate.bounding_box)
+            .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)));
+        let mut localities: Vec<String> = Vec::new();
+        for candidate in candidates {
+            let components = candidate.location.components();
+            if let Some(locality) = components.city.or(components.suburb)
+                && !localities.contains(&locality)
+            {
+                localities.push(locality);
+            }
+        }
+        Some(Self {
+            postcode: postcode.trim().to_uppercase(),
+            country: country.to_uppercase(),
+            latitude: first.location.latitude,
+            longitude: first.location.longitude,
+            bounding_box,
+            localities,
+        })
+    }
+}
+
+/// Whether the postcode written in an address agrees with where the address geocodes.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostcodeCheck {
+    pub address: String,
+    /// Postcode parsed from `address`, if it has one.
+    pub postcode: Option<String>,
+    /// Postcode of the geocoded location, if the provider reports one.
+    pub geocoded_postcode: Option<String>,
+    /// `None` when either postcode is missing; see [`postcodes_match`](crate::address::postcodes_match).
+    pub matches: Option<bool>,
+    pub location: GeoLocation,
+}
+
+impl PostcodeCheck {
+    /// Compares the postcode parsed from `address` with the one `location` reports.
+    pub fn new(address: &str, location: GeoLocation) -> Self {
+        let postcode = crate::address::parse_address(address).postcode;
+        let geocoded_postcode = location.components().postcode;
+        let matches = match (&postcode, &geocoded_postcode) {
+            (Some(written), Some(geocoded)) => {
+                Some(crate::address::postcodes_match(written, geocoded))
+            }
+            _ => None,
+        };
+        Self {
+            address: address.to_string(),
+            postcode,
+            geocoded_postcode,
+            matches,
+            location,
+        }
+    }
 }
 
 /// Represents travel parameters for distance calculation.
@@ -39,8 +333,7 @@ pub struct TravelParameters {
 }
 
 #[cfg(feature = "python")]
-#[pymethods]
-impl TravelParameters {
+crate::python::data_model!(TravelParameters {
     #[new]
     #[pyo3(signature = (
         origin_latitude=None,
@@ -67,11 +360,11 @@ impl TravelParameters {
             destination_address,
         }
     }
-}
+});
 
 /// Supported amenity types for nearby search.
 #[cfg_attr(feature = "python", pyclass(eq, eq_int))]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ServiceType {
     BusStop,
     Market,
@@ -84,6 +377,222 @@ pub enum ServiceType {
     TrainStation,
     TaxiStand,
     Landmark,
+    Pharmacy,
+    PoliceStation,
+    FireStation,
+    Gym,
+    Park,
+    PlaceOfWorship,
+    Airport,
+    Hotel,
+    Supermarket,
+    Cafe,
+    Cinema,
+    Library,
+    PostOffice,
+}
+
+impl ServiceType {
+    /// Every built-in service type, in declaration order.
+    pub const ALL: [ServiceType; 24] = [
+        Self::BusStop,
+        Self::Market,
+        Self::School,
+        Self::Mall,
+        Self::Hospital,
+        Self::Bank,
+        Self::Restaurant,
+        Self::FuelStation,
+        Self::TrainStation,
+        Self::TaxiStand,
+        Self::Landmark,
+        Self::Pharmacy,
+        Self::PoliceStation,
+        Self::FireStation,
+        Self::Gym,
+        Self::Park,
+        Self::PlaceOfWorship,
+        Self::Airport,
+        Self::Hotel,
+        Self::Supermarket,
+        Self::Cafe,
+        Self::Cinema,
+        Self::Library,
+        Self::PostOffice,
+    ];
+
+    /// Human-readable name, e.g. "Bus Stop".
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::BusStop => "Bus Stop",
+            Self::Market => "Market",
+            Self::School => "School",
+            Self::Mall => "Mall",
+            Self::Hospital => "Hospital",
+            Self::Bank => "Bank",
+            Self::Restaurant => "Restaurant",
+            Self::FuelStation => "Fuel Station",
+            Self::TrainStation => "Train Station",
+            Self::TaxiStand => "Taxi Stand",
+            Self::Landmark => "Landmark",
+            Self::Pharmacy => "Pharmacy",
+            Self::PoliceStation => "Police Station",
+            Self::FireStation => "Fire Station",
+            Self::Gym => "Gym",
+            Self::Park => "Park",
+            Self::PlaceOfWorship => "Place of Worship",
+            Self::Airport => "Airport",
+            Self::Hotel => "Hotel",
+            Self::Supermarket => "Supermarket",
+            Self::Cafe => "Cafe",
+            Self::Cinema => "Cinema",
+            Self::Library => "Library",
+            Self::PostOffice => "Post Office",
+        }
+    }
+
+    /// Dash-separated name accepted on the command line, e.g. "bus-stop".
+    pub fn slug(&self) -> String {
+        self.label().to_lowercase().replace(' ', "-")
+    }
+}
+
+impl std::fmt::Display for ServiceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl std::str::FromStr for ServiceType {
+    type Err = crate::error::MapradarError;
+
+    /// Parses names case-insensitively, ignoring `-`, `_`, and spaces (`bus-stop`, `BusStop`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .collect::<String>()
+            .to_lowercase();
+
+        match name.as_str() {
+            "busstop" | "busstation" => Ok(Self::BusStop),
+            "market" => Ok(Self::Market),
+            "school" => Ok(Self::School),
+            "mall" | "shoppingmall" => Ok(Self::Mall),
+            "hospital" => Ok(Self::Hospital),
+            "bank" => Ok(Self::Bank),
+            "restaurant" => Ok(Self::Restaurant),
+            "fuelstation" | "gasstation" => Ok(Self::FuelStation),
+            "trainstation" => Ok(Self::TrainStation),
+            "taxistand" => Ok(Self::TaxiStand),
+            "landmark" => Ok(Self::Landmark),
+            "pharmacy" => Ok(Self::Pharmacy),
+            "policestation" | "police" => Ok(Self::PoliceStation),
+            "firestation" => Ok(Self::FireStation),
+            "gym" => Ok(Self::Gym),
+            "park" => Ok(Self::Park),
+            "placeofworship" => Ok(Self::PlaceOfWorship),
+            "airport" => Ok(Self::Airport),
+            "hotel" => Ok(Self::Hotel),
+            "supermarket" => Ok(Self::Supermarket),
+            "cafe" => Ok(Self::Cafe),
+            "cinema" => Ok(Self::Cinema),
+            "library" => Ok(Self::Library),
+            "postoffice" => Ok(Self::PostOffice),
+            _ => {
+                let valid: Vec<String> = Self::ALL.iter().map(Self::slug).collect();
+                Err(crate::error::MapradarError::InvalidInput(format!(
+                    "Unknown service type '{}' (expected one of: {})",
+                    s.trim(),
+                    valid.join(", ")
+                )))
+            }
+        }
+    }
+}
+
+/// Category searched for in nearby queries: a built-in [`ServiceType`] or a provider-specific tag.
+///
+/// Custom tags are passed to the provider as-is, e.g. Google place types such as `"pharmacy"`
+/// or `"atm"`, or OpenStreetMap `key=value` tags such as `"leisure=fitness_centre"` (a bare
+/// value is treated as an `amenity`).
+#[cfg_attr(feature = "python", derive(IntoPyObject))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PlaceCategory {
+    Known(ServiceType),
+    Custom(String),
+}
+
+impl PlaceCategory {
+    /// Creates a custom category, rejecting empty tags or characters providers cannot accept.
+    pub fn custom(tag: &str) -> Result<Self, crate::error::MapradarError> {
+        let tag = tag.trim().to_lowercase();
+        let valid = tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ':' | '='));
+
+        if tag.is_empty() || !valid {
+            return Err(crate::error::MapradarError::InvalidInput(format!(
+                "Invalid place category '{}' (use letters, digits, '_', '-', ':' or key=value)",
+                tag
+            )));
+        }
+        Ok(Self::Custom(tag))
+    }
+}
+
+impl From<ServiceType> for PlaceCategory {
+    fn from(service_type: ServiceType) -> Self {
+        Self::Known(service_type)
+    }
+}
+
+impl std::fmt::Display for PlaceCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Known(service_type) => write!(f, "{}", service_type),
+            Self::Custom(tag) => write!(f, "custom:{}", tag),
+        }
+    }
+}
+
+impl std::str::FromStr for PlaceCategory {
+    type Err = crate::error::MapradarError;
+
+    /// Parses a built-in type name, `custom:<tag>`, or an OSM `key=value` tag.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(tag) = s.strip_prefix("custom:") {
+            return Self::custom(tag);
+        }
+        if s.contains('=') {
+            return Self::custom(s);
+        }
+        match s.parse::<ServiceType>() {
+            Ok(service_type) => Ok(Self::Known(service_type)),
+            Err(crate::error::MapradarError::InvalidInput(message)) => {
+                Err(crate::error::MapradarError::InvalidInput(format!(
+                    "{}; prefix provider-specific categories with 'custom:' (e.g. custom:atm)",
+                    message
+                )))
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+impl<'a, 'py> FromPyObject<'a, 'py> for PlaceCategory {
+    type Error = PyErr;
+
+    /// Accepts a `ServiceType` member or any string understood by `PlaceCategory::from_str`.
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        if let Ok(service_type) = obj.extract::<ServiceType>() {
+            return Ok(Self::Known(service_type));
+        }
+        Ok(obj.extract::<String>()?.parse()?)
+    }
 }
 
 /// Represents a specific amenity found near a location.
@@ -91,7 +600,7 @@ pub enum ServiceType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NearbyService {
     pub name: String,
-    pub service_type: ServiceType,
+    pub service_type: PlaceCategory,
     pub latitude: f64,
     pub longitude: f64,
     pub distance_km: f64,
@@ -100,6 +609,322 @@ pub struct NearbyService {
     pub place_id: Option<String>,
     pub phone_number: Option<String>,
     pub open_now: Option<bool>,
+    /// Weekly hours, when the provider reports them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opening_hours: Option<OpeningHours>,
+    /// Photos attached by [`MapradarClient::attach_photos_async`](crate::client::MapradarClient::attach_photos_async).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub photos: Vec<PlacePhoto>,
+    /// Every category the place was found under, set by
+    /// [`LocationIntelligence::deduplicated`]; empty otherwise.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub service_types: Vec<PlaceCategory>,
+    /// Minutes to travel here from the searched point, set by
+    /// [`MapradarClient::attach_travel_times_async`](crate::client::MapradarClient::attach_travel_times_async).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub travel_time_min: Option<f64>,
+    /// Next departures from a bus stop or train station, soonest first, set by
+    /// `GtfsFeed::attach_departures` (`gtfs` feature).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub departures: Vec<Departure>,
+    /// Transit routes serving a bus stop or train station, from a GTFS feed imported with
+    /// `GtfsIndex::import` (`gtfs` feature).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<String>,
+}
+
+/// Rating assumed for unrated places in [`SortKey::Score`], the middle of the 1-5 scale.
+const NEUTRAL_RATING: f32 = 3.0;
+
+/// Ordering applied to nearby services.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortKey {
+    /// Nearest first.
+    #[default]
+    Distance,
+    /// Highest rated first; unrated places last.
+    Rating,
+    /// Best [`NearbyService::rank_score`] first, balancing rating against distance.
+    Score,
+    /// Alphabetical, case-insensitive.
+    Name,
+    /// Quickest to reach first; places without a travel time last.
+    TravelTime,
+}
+
+impl std::str::FromStr for SortKey {
+    type Err = crate::error::MapradarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "distance" => Ok(Self::Distance),
+            "rating" => Ok(Self::Rating),
+            "score" => Ok(Self::Score),
+            "name" => Ok(Self::Name),
+            "travel-time" | "time" => Ok(Self::TravelTime),
+            other => Err(crate::error::MapradarError::InvalidInput(format!(
+                "Unknown sort key '{}' (expected distance, rating, score, name, or travel-time)",
+                other
+            ))),
+        }
+    }
+}
+
+impl SortKey {
+    /// Sorts services in place; ties keep their current order.
+    pub fn sort(self, services: &mut [NearbyService]) {
+        use std::cmp::Ordering;
+
+        match self {
+            Self::Distance => services.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km)),
+            Self::Rating => services.sort_by(|a, b| match (a.rating, b.rating) {
+                (Some(a), Some(b)) => b.total_cmp(&a),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }),
+            Self::Score => services.sort_by(|a, b| b.rank_score().total_cmp(&a.rank_score())),
+            Self::Name => services.sort_by_key(|service| service.name.to_lowercase()),
+            Self::TravelTime => {
+                services.sort_by(|a, b| match (a.travel_time_min, b.travel_time_min) {
+                    (Some(a), Some(b)) => a.total_cmp(&b),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+crate::python::data_model!(NearbyService {
+    /// GeoJSON `Point` feature, for shapely, geopandas, and folium.
+    #[getter]
+    fn __geo_interface__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        use crate::geojson::ToGeoJson;
+        crate::python::json_to_py(py, &self.to_feature())
+    }
+
+    /// Distance from the searched point in miles.
+    #[getter]
+    #[pyo3(name = "distance_mi")]
+    fn py_distance_mi(&self) -> f64 {
+        self.distance_mi()
+    }
+});
+
+/// Results closer than this with the same name are taken to be one place.
+const DUPLICATE_DISTANCE_KM: f64 = 0.05;
+
+impl NearbyService {
+    /// Distance from the searched point in miles.
+    pub fn distance_mi(&self) -> f64 {
+        crate::units::km_to_miles(self.distance_km)
+    }
+
+    /// Distance from the searched point in `units`: kilometres or miles.
+    pub fn distance_in(&self, units: crate::units::Units) -> f64 {
+        units.distance_from_km(self.distance_km)
+    }
+
+    /// 0-1 ranking used by [`SortKey::Score`]: 60% rating (out of 5, unrated places counted as
+    /// 3) and 40% proximity, which is 1 at the searched point and halves every kilometre.
+    pub fn rank_score(&self) -> f64 {
+        let rating = f64::from(self.rating.unwrap_or(NEUTRAL_RATING).clamp(0.0, 5.0)) / 5.0;
+        let proximity = 0.5_f64.powf(self.distance_km.max(0.0));
+        0.6 * rating + 0.4 * proximity
+    }
+
+    /// Whether the service was found under `category`, directly or through a merged duplicate.
+    pub fn matches_category(&self, category: &PlaceCategory) -> bool {
+        &self.service_type == category || self.service_types.contains(category)
+    }
+
+    /// Same place ID, or, when either has none, the same name within a few metres.
+    pub(crate) fn is_same_place(&self, other: &NearbyService) -> bool {
+        match (&self.place_id, &other.place_id) {
+            (Some(a), Some(b)) => a == b,
+            _ => {
+                self.name.trim().eq_ignore_ascii_case(other.name.trim())
+                    && crate::geo::haversine_distance(
+                        self.latitude,
+                        self.longitude,
+                        other.latitude,
+                        other.longitude,
+                    ) <= DUPLICATE_DISTANCE_KM
+            }
+        }
+    }
+
+    /// Folds a duplicate into this result: its categories, and any details this one lacks.
+    fn absorb(&mut self, other: NearbyService) {
+        if self.service_types.is_empty() {
+            self.service_types.push(self.service_type.clone());
+        }
+        for category in std::iter::once(other.service_type).chain(other.service_types) {
+            if !self.service_types.contains(&category) {
+                self.service_types.push(category);
+            }
+        }
+        self.address = self.address.take().or(other.address);
+        self.rating = self.rating.or(other.rating);
+        self.place_id = self.place_id.take().or(other.place_id);
+        self.phone_number = self.phone_number.take().or(other.phone_number);
+        self.open_now = self.open_now.or(other.open_now);
+        self.opening_hours = self.opening_hours.take().or(other.opening_hours);
+        self.travel_time_min = self.travel_time_min.or(other.travel_time_min);
+        if self.photos.is_empty() {
+            self.photos = other.photos;
+        }
+    }
+}
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+const MINUTES_PER_WEEK: u32 = 7 * MINUTES_PER_DAY;
+
+/// A span of opening time within one day, in the place's local time. Days count from 0 (Sunday)
+/// to 6 (Saturday); a `close_minute` of 1440 means open until midnight.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpeningInterval {
+    pub day: u8,
+    /// Minutes after midnight.
+    pub open_minute: u16,
+    /// Minutes after midnight, greater than `open_minute`.
+    pub close_minute: u16,
+}
+
+/// Weekly opening hours as per-day intervals.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OpeningHours {
+    /// Ordered by day, then opening time.
+    pub intervals: Vec<OpeningInterval>,
+    /// The provider's human-readable lines, e.g. "Monday: 8:00 AM – 5:00 PM".
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weekday_text: Vec<String>,
+}
+
+impl OpeningHours {
+    /// Open all day, every day.
+    pub fn always_open() -> Self {
+        let mut hours = Self::default();
+        hours.add_span(0, MINUTES_PER_WEEK);
+        hours
+    }
+
+    /// Adds opening time from `start` to `end`, both minutes since Sunday midnight. Spans that
+    /// run past midnight are split across days, and an `end` before `start` wraps into the next
+    /// week.
+    pub fn add_span(&mut self, start: u32, end: u32) {
+        let end = if end <= start {
+            end + MINUTES_PER_WEEK
+        } else {
+            end
+        };
+        let mut cursor = start;
+        while cursor < end {
+            let day_start = cursor - cursor % MINUTES_PER_DAY;
+            let day_end = (day_start + MINUTES_PER_DAY).min(end);
+            self.intervals.push(OpeningInterval {
+                day: ((day_start / MINUTES_PER_DAY) % 7) as u8,
+                open_minute: (cursor - day_start) as u16,
+                close_minute: (day_end - day_start) as u16,
+            });
+            cursor = day_end;
+        }
+        self.intervals
+            .sort_by_key(|interval| (interval.day, interval.open_minute));
+    }
+
+    /// Intervals on `day` (0 = Sunday).
+    pub fn on(&self, day: u8) -> Vec<OpeningInterval> {
+        self.intervals
+            .iter()
+            .filter(|interval| interval.day == day)
+            .copied()
+            .collect()
+    }
+
+    /// Whether the place is open on `day` (0 = Sunday) at `minute` after local midnight.
+    pub fn is_open_at(&self, day: u8, minute: u16) -> bool {
+        self.intervals.iter().any(|interval| {
+            interval.day == day && interval.open_minute <= minute && minute < interval.close_minute
+        })
+    }
+}
+
+#[cfg(feature = "python")]
+crate::python::data_model!(OpeningHours {
+    #[pyo3(name = "on")]
+    fn py_on(&self, day: u8) -> Vec<OpeningInterval> {
+        self.on(day)
+    }
+
+    #[pyo3(name = "is_open_at")]
+    fn py_is_open_at(&self, day: u8, minute: u16) -> bool {
+        self.is_open_at(day, minute)
+    }
+});
+
+/// Everything a provider knows about one place, looked up by its place ID.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaceDetails {
+    pub place_id: String,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub address: Option<String>,
+    pub phone_number: Option<String>,
+    pub website: Option<String>,
+    pub rating: Option<f32>,
+    /// Number of user reviews behind `rating`.
+    pub reviews_count: Option<u32>,
+    pub open_now: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opening_hours: Option<OpeningHours>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub photos: Vec<PlacePhoto>,
+    /// Provider categories, e.g. `bank` and `finance`, or OSM `key=value` tags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<String>,
+}
+
+/// A photo of a place: the provider's reference, and its URL once resolved.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlacePhoto {
+    pub reference: String,
+    pub width: u32,
+    pub height: u32,
+    /// HTML attributions the provider requires alongside the photo.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributions: Vec<String>,
+    /// Image URL that needs no API key, once resolved from `reference`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Where the image was saved, when downloaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<std::path::PathBuf>,
+}
+
+/// A scheduled departure from a transit stop.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Departure {
+    /// Route short name, such as "BRT1", or its long name.
+    pub route: String,
+    /// Destination shown on the vehicle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub headsign: Option<String>,
+    /// Scheduled local departure time with its UTC offset (RFC 3339).
+    pub departs_at: String,
+    /// Whole minutes from when departures were looked up.
+    pub minutes_until: u32,
+    /// ID of the stop or platform it leaves from, in the source feed.
+    pub stop_id: String,
 }
 
 /// Comprehensive intelligence about a location.
@@ -109,16 +934,101 @@ pub struct LocationIntelligence {
     pub location: GeoLocation,
     pub nearby_services: Vec<NearbyService>,
     pub total_services_found: usize,
+    /// Amenity score for the searched categories, when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<crate::scoring::AmenityScore>,
+    /// Service types whose search failed upstream; the other types' results are kept.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_service_types: Vec<FailedServiceType>,
+    /// Human-readable notes on anything missing from the result, such as failed searches.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// A service-type search that failed while the others in the same request succeeded.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedServiceType {
+    pub service_type: PlaceCategory,
+    pub error: String,
 }
 
 #[cfg(feature = "python")]
-#[pymethods]
-impl LocationIntelligence {
+crate::python::data_model!(LocationIntelligence {
     #[new]
     pub fn py_new(location: GeoLocation, nearby_services: Vec<NearbyService>) -> Self {
         Self::new(location, nearby_services)
     }
-}
+
+    /// Computes the amenity score over `types` (default: every weighted type), optionally
+    /// reading weights from a TOML file, and stores it on `score`.
+    #[pyo3(name = "compute_score", signature = (types=None, weights_path=None))]
+    pub fn py_compute_score(
+        &mut self,
+        types: Option<Vec<String>>,
+        weights_path: Option<std::path::PathBuf>,
+    ) -> PyResult<crate::scoring::AmenityScore> {
+        let weights = match weights_path {
+            Some(path) => crate::scoring::ScoreWeights::load(&path)?,
+            None => crate::scoring::ScoreWeights::default(),
+        };
+        let categories = match types {
+            Some(types) => types
+                .iter()
+                .map(|t| t.parse())
+                .collect::<Result<Vec<PlaceCategory>, _>>()?,
+            None => weights.categories(),
+        };
+        let score = weights.score(self, &categories);
+        self.score = Some(score.clone());
+        Ok(score)
+    }
+
+    #[pyo3(name = "deduplicated")]
+    fn py_deduplicated(&self) -> Self {
+        self.clone().deduplicated()
+    }
+
+    /// Orders `nearby_services` by "distance", "rating", "score", or "name".
+    #[pyo3(name = "sort_by", signature = (key="distance"))]
+    fn py_sort_by(&mut self, key: &str) -> PyResult<()> {
+        self.sort_by(key.parse()?);
+        Ok(())
+    }
+
+    #[pyo3(name = "filter_min_rating")]
+    fn py_filter_min_rating(&mut self, min_rating: f32) {
+        self.filter_min_rating(min_rating);
+    }
+
+    #[pyo3(name = "filter_open_now")]
+    fn py_filter_open_now(&mut self) {
+        self.filter_open_now();
+    }
+
+    #[pyo3(name = "filter_max_travel_minutes")]
+    fn py_filter_max_travel_minutes(&mut self, minutes: f64) {
+        self.filter_max_travel_minutes(minutes);
+    }
+
+    /// Groups nearby services lying within `eps_m` meters of each other; see `cluster`.
+    #[pyo3(name = "cluster", signature = (eps_m, min_pts=3))]
+    fn py_cluster(&self, eps_m: f64, min_pts: usize) -> Vec<crate::clustering::ServiceCluster> {
+        self.cluster(eps_m, min_pts)
+    }
+
+    /// Nearby services as a DataFrame, one row each, using "polars" or "pandas".
+    /// GeoJSON `FeatureCollection` of the location and its services, for geopandas and folium.
+    #[getter]
+    fn __geo_interface__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        crate::python::json_to_py(py, &self.to_feature_collection())
+    }
+
+    #[pyo3(name = "to_dataframe", signature = (backend="polars"))]
+    fn py_to_dataframe<'py>(&self, py: Python<'py>, backend: &str) -> PyResult<Bound<'py, PyAny>> {
+        crate::dataframe::services_frame(py, self, backend)
+    }
+});
 
 impl LocationIntelligence {
     pub fn new(location: GeoLocation, nearby_services: Vec<NearbyService>) -> Self {
@@ -127,47 +1037,626 @@ impl LocationIntelligence {
             location,
             nearby_services,
             total_services_found: total,
+            score: None,
+            failed_service_types: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Drops nearby services that fall outside `area`, keeping the searched location.
+    pub fn filter_within(&mut self, area: &crate::geo::Geometry) {
+        self.nearby_services
+            .retain(|service| area.contains(service.latitude, service.longitude));
+        self.total_services_found = self.nearby_services.len();
+    }
+
+    /// Orders `nearby_services` by `key`.
+    pub fn sort_by(&mut self, key: SortKey) {
+        key.sort(&mut self.nearby_services);
+    }
+
+    /// Keeps services rated at least `min_rating`, dropping unrated ones.
+    pub fn filter_min_rating(&mut self, min_rating: f32) {
+        self.nearby_services
+            .retain(|service| service.rating.is_some_and(|rating| rating >= min_rating));
+        self.total_services_found = self.nearby_services.len();
+    }
+
+    /// Keeps services known to be open now, dropping closed ones and those without hours.
+    pub fn filter_open_now(&mut self) {
+        self.nearby_services
+            .retain(|service| service.open_now == Some(true));
+        self.total_services_found = self.nearby_services.len();
+    }
+
+    /// Keeps services reachable within `minutes`, dropping those without a travel time; see
+    /// [`MapradarClient::attach_travel_times_async`](crate::client::MapradarClient::attach_travel_times_async).
+    pub fn filter_max_travel_minutes(&mut self, minutes: f64) {
+        self.nearby_services.retain(|service| {
+            service
+                .travel_time_min
+                .is_some_and(|travel_time| travel_time <= minutes)
+        });
+        self.total_services_found = self.nearby_services.len();
+    }
+
+    /// Merges results that are the same place found under several categories, such as a bank
+    /// inside a mall, into the first of them, listing every category in `service_types`.
+    /// Places match on place ID, or by name within 50 m when an ID is missing.
+    pub fn deduplicated(mut self) -> Self {
+        self.nearby_services = merge_duplicates(std::mem::take(&mut self.nearby_services));
+        self.total_services_found = self.nearby_services.len();
+        self
+    }
+
+    /// Groups nearby services into spatial clusters with DBSCAN: services with at least
+    /// `min_pts` others (themselves included) within `eps_m` meters seed a cluster, which takes in
+    /// every service within `eps_m` of its seeds. Largest cluster first; services in no cluster
+    /// are left out.
+    pub fn cluster(&self, eps_m: f64, min_pts: usize) -> Vec<crate::clustering::ServiceCluster> {
+        crate::clustering::dbscan(&self.nearby_services, eps_m, min_pts)
+    }
+}
+
+/// Merges results that are the same place into the first of them, keeping input order.
+pub(crate) fn merge_duplicates(services: Vec<NearbyService>) -> Vec<NearbyService> {
+    let mut merged: Vec<NearbyService> = Vec::with_capacity(services.len());
+    for mut service in services {
+        match merged.iter_mut().find(|kept| kept.is_same_place(&service)) {
+            Some(kept) => kept.absorb(service),
+            None => {
+                if service.service_types.is_empty() {
+                    service.service_types.push(service.service_type.clone());
+                }
+                merged.push(service);
+            }
+        }
+    }
+    merged
+}
+
+/// An amenity found along a track, placed at the point of the track nearest to it.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackAmenity {
+    /// The place; its `distance_km` is its distance from the track.
+    #[serde(flatten)]
+    pub service: NearbyService,
+    /// Distance along the track, from its start, to the point nearest the place.
+    pub distance_along_km: f64,
+}
+
+/// Amenities found near a track, such as a recorded ride or a planned route.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrackEnrichment {
+    /// Length of the track.
+    pub distance_km: f64,
+    /// Number of points along the track that were searched around.
+    pub samples: usize,
+    /// Ordered by distance along the track.
+    pub amenities: Vec<TrackAmenity>,
+    /// Searches that failed without failing the whole enrichment.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// The route that was searched along, for a search between two places.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub route: Option<Route>,
+}
+
+/// Means of travel used for routing.
+#[cfg_attr(feature = "python", pyclass(eq, eq_int))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TravelMode {
+    #[default]
+    Driving,
+    Walking,
+    Cycling,
+}
+
+impl std::str::FromStr for TravelMode {
+    type Err = crate::error::MapradarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "driving" | "drive" | "car" => Ok(Self::Driving),
+            "walking" | "walk" | "foot" => Ok(Self::Walking),
+            "cycling" | "bicycling" | "bike" => Ok(Self::Cycling),
+            other => Err(crate::error::MapradarError::InvalidInput(format!(
+                "Unknown travel mode '{}' (expected driving, walking, or cycling)",
+                other
+            ))),
         }
     }
 }
 
+/// A single maneuver along a route.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteStep {
+    pub instruction: String,
+    pub distance_km: f64,
+    pub duration_secs: u64,
+    pub start_latitude: f64,
+    pub start_longitude: f64,
+    pub end_latitude: f64,
+    pub end_longitude: f64,
+}
+
+/// Travel route between two points.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Route {
+    pub mode: TravelMode,
+    pub distance_km: f64,
+    pub duration_secs: u64,
+    /// Encoded polyline (precision 5) of the full route geometry.
+    pub polyline: String,
+    pub steps: Vec<RouteStep>,
+}
+
+/// Distance and travel time from one origin to one destination.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatrixElement {
+    /// `OK`, or the provider status explaining why the pair could not be routed.
+    pub status: String,
+    pub distance_km: Option<f64>,
+    pub duration_secs: Option<u64>,
+}
+
+impl MatrixElement {
+    pub fn ok(distance_km: f64, duration_secs: Option<u64>) -> Self {
+        Self {
+            status: "OK".to_string(),
+            distance_km: Some(distance_km),
+            duration_secs,
+        }
+    }
+
+    pub fn failed(status: impl Into<String>) -> Self {
+        Self {
+            status: status.into(),
+            distance_km: None,
+            duration_secs: None,
+        }
+    }
+}
+
+/// NxM distances between every origin and destination.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistanceMatrix {
+    /// Travel mode used, or `None` for straight-line (haversine) distances.
+    pub mode: Option<TravelMode>,
+    pub origins: Vec<(f64, f64)>,
+    pub destinations: Vec<(f64, f64)>,
+    /// One row per origin, one element per destination.
+    pub rows: Vec<Vec<MatrixElement>>,
+}
+
+/// Area reachable from a point within a travel-time budget.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Isochrone {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub minutes: f64,
+    pub mode: TravelMode,
+    /// Closed ring of `(lat, lng)` vertices outlining the reachable area.
+    pub polygon: Vec<(f64, f64)>,
+}
+
+/// Polygons, each an outer ring followed by any holes, as `(lat, lng)` vertices.
+pub type Polygons = Vec<Vec<Vec<(f64, f64)>>>;
+
+/// An administrative area (country, state, county or LGA, ward) containing a point.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminArea {
+    /// Boundary ID: `osm:relation/<id>` from OpenStreetMap, or a Google place ID.
+    pub id: String,
+    pub name: String,
+    /// OpenStreetMap `admin_level`: 2 for countries, 4 for states, 6 for counties or LGAs, and 8
+    /// or more for municipalities and wards. Google's area types are mapped onto the same scale.
+    pub admin_level: u8,
+    /// ISO 3166 code, when the area has one (e.g. "NG", "NG-LA").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Boundary polygons; only filled in when asked for, and only by providers with boundary
+    /// data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geometry: Option<Polygons>,
+}
+
+/// The administrative areas containing a point, from the country down.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminHierarchy {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Ordered by `admin_level`, broadest first.
+    pub areas: Vec<AdminArea>,
+}
+
+impl AdminHierarchy {
+    /// The most specific area at or above `admin_level`, e.g. 4 for the state.
+    pub fn at_level(&self, admin_level: u8) -> Option<&AdminArea> {
+        self.areas
+            .iter()
+            .rev()
+            .find(|area| area.admin_level <= admin_level)
+    }
+}
+
+#[cfg(feature = "python")]
+crate::python::data_model!(AdminHierarchy {
+    /// The most specific area at or above `admin_level`, e.g. 4 for the state.
+    #[pyo3(name = "at_level")]
+    fn py_at_level(&self, admin_level: u8) -> Option<AdminArea> {
+        self.at_level(admin_level).cloned()
+    }
+});
+
+/// How [`MeetingPoint`]s are placed among the participants.
+#[cfg_attr(feature = "python", pyclass(eq, eq_int))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MeetingStrategy {
+    /// The geographic centroid; fair on average, but an outlier pulls it away from everyone.
+    #[default]
+    Centroid,
+    /// The point minimising the longest straight-line distance anyone travels.
+    Minimax,
+}
+
+impl std::str::FromStr for MeetingStrategy {
+    type Err = crate::error::MapradarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "centroid" | "center" | "centre" => Ok(Self::Centroid),
+            "minimax" | "fair" | "fairest" => Ok(Self::Minimax),
+            other => Err(crate::error::MapradarError::InvalidInput(format!(
+                "Unknown meeting strategy '{}' (expected centroid or minimax)",
+                other
+            ))),
+        }
+    }
+}
+
+/// A place for several people to meet, with the amenities around it.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingPoint {
+    pub strategy: MeetingStrategy,
+    /// The located participants, in input order.
+    pub participants: Vec<GeoLocation>,
+    /// Straight-line distance from each participant to the meeting point, in input order.
+    pub distances_km: Vec<f64>,
+    /// Longest of `distances_km`.
+    pub max_distance_km: f64,
+    /// Nearby search around the meeting point, whose `location` is the meeting point.
+    pub intelligence: LocationIntelligence,
+}
+
+/// A completion for a partially typed address, in the provider's ranking order.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Suggestion {
+    /// Full text of the suggested address or place.
+    pub description: String,
+    /// Leading part of the description, usually the street address or place name.
+    pub main_text: String,
+    /// Remainder of the description, usually locality and country.
+    pub secondary_text: Option<String>,
+    pub place_id: Option<String>,
+    pub types: Vec<String>,
+}
+
+/// Hints that steer autocomplete toward a region.
+#[derive(Debug, Clone, Default)]
+pub struct AutocompleteBias {
+    /// ISO 3166-1 alpha-2 codes; suggestions are restricted to these countries.
+    pub countries: Vec<String>,
+    /// Area whose suggestions are preferred, though others may still be returned.
+    pub bounds: Option<crate::geo::Geometry>,
+    /// Suggestions within [`BIAS_RADIUS_KM`] of this point are preferred.
+    pub around: Option<Coordinates>,
+}
+
+/// How far around [`SearchRegion::bias_around`] and [`AutocompleteBias::around`] results are
+/// preferred.
+pub const BIAS_RADIUS_KM: f64 = 50.0;
+
+/// Where an address search should land: only in some countries, preferably near a point, or both.
+#[cfg_attr(feature = "python", pyclass)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchRegion {
+    /// Upper-case ISO 3166-1 alpha-2 codes; matches elsewhere are dropped. Empty allows anywhere.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub countries: Vec<String>,
+    /// Matches within [`BIAS_RADIUS_KM`] of this point are preferred, though others may still be
+    /// returned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bias_around: Option<Coordinates>,
+}
+
+#[cfg(feature = "python")]
+crate::python::data_model!(SearchRegion {
+    #[new]
+    #[pyo3(signature = (countries=Vec::new(), bias_around=None))]
+    fn py_new(
+        countries: Vec<String>,
+        bias_around: Option<(f64, f64)>,
+    ) -> Result<Self, crate::error::MapradarError> {
+        let region = Self::in_countries(countries)?;
+        Ok(match bias_around {
+            Some((latitude, longitude)) => {
+                region.with_bias_around(Coordinates::new(latitude, longitude)?)
+            }
+            None => region,
+        })
+    }
+
+    #[getter(countries)]
+    fn py_countries(&self) -> Vec<String> {
+        self.countries.clone()
+    }
+
+    /// The bias point as `(latitude, longitude)`.
+    #[getter(bias_around)]
+    fn py_bias_around(&self) -> Option<(f64, f64)> {
+        self.bias_around.map(Coordinates::as_tuple)
+    }
+});
+
+impl SearchRegion {
+    /// Restricts searches to `countries`, given as ISO 3166-1 alpha-2 codes in either case.
+    pub fn in_countries<I, S>(countries: I) -> Result<Self, crate::error::MapradarError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let countries = countries
+            .into_iter()
+            .map(|country| country_code(country.as_ref()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            countries,
+            bias_around: None,
+        })
+    }
+
+    /// Prefers matches near `point`.
+    pub fn with_bias_around(mut self, point: Coordinates) -> Self {
+        self.bias_around = Some(point);
+        self
+    }
+
+    /// Whether the region neither restricts nor biases a search.
+    pub fn is_empty(&self) -> bool {
+        self.countries.is_empty() && self.bias_around.is_none()
+    }
+
+    /// Whether `location` is in one of `countries`, or anywhere when there are none.
+    pub fn allows(&self, location: &GeoLocation) -> bool {
+        self.countries.is_empty()
+            || self
+                .countries
+                .iter()
+                .any(|country| country.eq_ignore_ascii_case(&location.country))
+    }
+
+    /// Box reaching [`BIAS_RADIUS_KM`] around `bias_around`, as `(min_lng, min_lat, max_lng,
+    /// max_lat)`.
+    pub fn bias_box(&self) -> Option<(f64, f64, f64, f64)> {
+        let (lat, lng) = self.bias_around?.as_tuple();
+        let toward = |bearing| crate::geo::destination_point(lat, lng, bearing, BIAS_RADIUS_KM);
+        Some((
+            toward(270.0).1,
+            toward(180.0).0,
+            toward(90.0).1,
+            toward(0.0).0,
+        ))
+    }
+}
+
+/// Written as "NG,GH@6.5,3.37": the countries, then the bias point after an "@".
+impl std::fmt::Display for SearchRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.countries.join(","))?;
+        match self.bias_around {
+            Some(point) => write!(f, "@{}", point),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Checks an ISO 3166-1 alpha-2 country code, returning it in upper case.
+pub(crate) fn country_code(country: &str) -> Result<String, crate::error::MapradarError> {
+    let country = country.trim();
+    if country.len() == 2 && country.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(country.to_ascii_uppercase())
+    } else {
+        Err(crate::error::MapradarError::InvalidInput(format!(
+            "Invalid country code '{}' (expected ISO 3166-1 alpha-2, e.g. 'NG')",
+            country
+        )))
+    }
+}
+
+/// Checks a postcode's characters and length, returning it trimmed and upper-cased.
+pub(crate) fn postcode(code: &str) -> Result<String, crate::error::MapradarError> {
+    let code = code.trim();
+    let length = crate::address::normalize_postcode(code).len();
+    if (2..=10).contains(&length)
+        && code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-')
+    {
+        Ok(code.to_uppercase())
+    } else {
+        Err(crate::error::MapradarError::InvalidInput(format!(
+            "Invalid postcode '{}'",
+            code
+        )))
+    }
+}
+
 /// Represents a search query, either by address or coordinates.
 #[cfg_attr(feature = "python", pyclass)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SearchQuery {
-    Address { address: String },
-    Coordinates { latitude: f64, longitude: f64 },
+    Address {
+        address: String,
+        /// Countries the address must be in, and a point to prefer matches near.
+        #[serde(default, skip_serializing_if = "SearchRegion::is_empty")]
+        region: SearchRegion,
+    },
+    Coordinates {
+        latitude: Latitude,
+        longitude: Longitude,
+    },
+    /// Approximate location of an IP address; `None` uses the caller's public address.
+    Ip { ip: Option<std::net::IpAddr> },
 }
 
 #[cfg(feature = "python")]
-#[pymethods]
-impl SearchQuery {
+crate::python::data_model!(SearchQuery {
     #[staticmethod]
     pub fn from_address(address: String) -> Self {
-        Self::Address { address }
+        Self::Address {
+            address,
+            region: SearchRegion::default(),
+        }
     }
 
     #[staticmethod]
-    pub fn from_coordinates(latitude: f64, longitude: f64) -> Self {
-        Self::Coordinates {
-            latitude,
-            longitude,
-        }
+    pub fn from_coordinates(
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Self, crate::error::MapradarError> {
+        Coordinates::new(latitude, longitude).map(Self::at)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_plus_code")]
+    pub fn py_from_plus_code(code: &str) -> Result<Self, crate::error::MapradarError> {
+        Self::from_plus_code(code)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_postcode")]
+    pub fn py_from_postcode(code: &str, country: &str) -> Result<Self, crate::error::MapradarError> {
+        Self::from_postcode(code, country)
+    }
+
+    #[staticmethod]
+    pub fn from_ip(ip: std::net::IpAddr) -> Self {
+        Self::Ip { ip: Some(ip) }
     }
-}
+
+    /// Locates the caller by their public IP address.
+    #[staticmethod]
+    pub fn from_public_ip() -> Self {
+        Self::Ip { ip: None }
+    }
+
+    /// A copy of the query geocoded within `region`.
+    #[pyo3(name = "within")]
+    fn py_within(&self, region: SearchRegion) -> Self {
+        self.clone().within(region)
+    }
+});
 
 #[cfg(not(feature = "python"))]
 impl SearchQuery {
     pub fn from_address(address: String) -> Self {
-        Self::Address { address }
+        Self::Address {
+            address,
+            region: SearchRegion::default(),
+        }
     }
 
-    pub fn from_coordinates(latitude: f64, longitude: f64) -> Self {
+    /// Validates the point; see [`Coordinates::new`].
+    pub fn from_coordinates(
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Self, crate::error::MapradarError> {
+        Coordinates::new(latitude, longitude).map(Self::at)
+    }
+
+    pub fn from_ip(ip: std::net::IpAddr) -> Self {
+        Self::Ip { ip: Some(ip) }
+    }
+
+    /// Locates the caller by their public IP address.
+    pub fn from_public_ip() -> Self {
+        Self::Ip { ip: None }
+    }
+}
+
+impl SearchQuery {
+    pub fn at(coordinates: Coordinates) -> Self {
         Self::Coordinates {
-            latitude,
-            longitude,
+            latitude: coordinates.lat,
+            longitude: coordinates.lon,
+        }
+    }
+
+    /// Queries a plus code: a full code becomes the centre of its area, decoded locally, and a
+    /// short code followed by a locality, such as "GC9M+7V Lagos", is resolved when geocoded.
+    /// Anything else fails with [`MapradarError::InvalidInput`](crate::error::MapradarError).
+    pub fn from_plus_code(code: &str) -> Result<Self, crate::error::MapradarError> {
+        match crate::plus_code::split_locality(code) {
+            Some((code, _)) if crate::plus_code::is_full(&code) => {
+                Ok(Self::at(crate::plus_code::decode(&code)?.center()))
+            }
+            Some((_, Some(_))) => Ok(Self::from_address(code.trim().to_string())),
+            _ => Err(crate::error::MapradarError::InvalidInput(format!(
+                "Invalid plus code '{}' (expected a full code, or a short code and locality)",
+                code
+            ))),
         }
     }
+
+    /// Queries a postal code in a country, e.g. `("SW1A 2AA", "GB")`, geocoded as an address
+    /// restricted to that country.
+    pub fn from_postcode(code: &str, country: &str) -> Result<Self, crate::error::MapradarError> {
+        Ok(Self::Address {
+            address: postcode(code)?,
+            region: SearchRegion::in_countries([country])?,
+        })
+    }
+
+    /// Geocodes an address query within `region`; other queries need no geocoding and are
+    /// returned unchanged.
+    pub fn within(self, region: SearchRegion) -> Self {
+        match self {
+            Self::Address { address, .. } => Self::Address { address, region },
+            query => query,
+        }
+    }
+
+    /// The point of a coordinate query.
+    pub fn coordinates(&self) -> Option<Coordinates> {
+        match self {
+            Self::Coordinates {
+                latitude,
+                longitude,
+            } => Some(Coordinates {
+                lat: *latitude,
+                lon: *longitude,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl From<Coordinates> for SearchQuery {
+    fn from(coordinates: Coordinates) -> Self {
+        Self::at(coordinates)
+    }
 }
 
 /// Represents a JSON-RPC 2.0 error object.
@@ -180,14 +1669,13 @@ pub struct JsonRpcError {
 }
 
 #[cfg(feature = "python")]
-#[pymethods]
-impl JsonRpcError {
+crate::python::data_model!(JsonRpcError {
     #[new]
     #[pyo3(signature = (code, message, data=None))]
     pub fn py_new(code: i32, message: String, data: Option<String>) -> Self {
         Self::new(code, message, data)
     }
-}
+});
 
 impl JsonRpcError {
     pub fn new(code: i32, message: String, data: Option<String>) -> Self {
@@ -210,20 +1698,13 @@ pub struct JsonRpcResponse {
 }
 
 #[cfg(feature = "python")]
-#[pymethods]
-impl JsonRpcResponse {
+crate::python::data_model!(JsonRpcResponse {
     #[new]
     #[pyo3(signature = (id, result=None, error=None))]
     pub fn py_new(id: String, result: Option<String>, error: Option<JsonRpcError>) -> Self {
         Self::new(id, result, error)
     }
-
-    /// Converts the response to a JSON string.
-    pub fn to_json(&self) -> PyResult<String> {
-        serde_json::to_string(self)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))
-    }
-}
+});
 
 impl JsonRpcResponse {
     pub fn new(id: String, result: Option<String>, error: Option<JsonRpcError>) -> Self {
@@ -240,3 +1721,32 @@ impl JsonRpcResponse {
         serde_json::to_string(self)
     }
 }
+
+/// Dict and JSON conversion for the models without Python methods of their own.
+#[cfg(feature = "python")]
+mod py_models {
+    use super::*;
+
+    crate::python::data_model!(TimeZoneInfo);
+    crate::python::data_model!(ElevationSample);
+    crate::python::data_model!(SnappedPoint);
+    crate::python::data_model!(AddressComponents);
+    crate::python::data_model!(GeocodeCandidate);
+    crate::python::data_model!(PostcodeArea);
+    crate::python::data_model!(PostcodeCheck);
+    crate::python::data_model!(OpeningInterval);
+    crate::python::data_model!(PlaceDetails);
+    crate::python::data_model!(PlacePhoto);
+    crate::python::data_model!(Departure);
+    crate::python::data_model!(FailedServiceType);
+    crate::python::data_model!(TrackAmenity);
+    crate::python::data_model!(TrackEnrichment);
+    crate::python::data_model!(RouteStep);
+    crate::python::data_model!(Route);
+    crate::python::data_model!(MatrixElement);
+    crate::python::data_model!(DistanceMatrix);
+    crate::python::data_model!(Isochrone);
+    crate::python::data_model!(AdminArea);
+    crate::python::data_model!(MeetingPoint);
+    crate::python::data_model!(Suggestion);
+}
@@ -0,0 +1,453 @@
+//! Local index of OpenStreetMap places imported from PBF extracts (`offline` feature).
+//!
+//! Named nodes and ways carrying a category tag (`amenity`, `shop`, `leisure`, ...) or a `place`
+//! tag are stored in SQLite, with an R*Tree over their coordinates for bounding-box queries.
+//! Ways are reduced to the mean of their node positions. Relations are not imported, so areas
+//! mapped only as multipolygons are missing.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use osmpbf::{Element, ElementReader};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::MapradarError, geo::haversine_distance};
+
+/// Tag keys whose values classify a place and can be searched as categories.
+const CATEGORY_KEYS: &[&str] = &[
+    "amenity",
+    "shop",
+    "leisure",
+    "tourism",
+    "highway",
+    "railway",
+    "aeroway",
+    "public_transport",
+    "office",
+    "healthcare",
+    "craft",
+];
+
+/// Kilometers per degree of latitude.
+const KM_PER_DEGREE: f64 = 111.32;
+
+/// A named place in the index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsmPlace {
+    /// `node/<id>` or `way/<id>`.
+    pub osm_ref: String,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Value of the `place` tag (`city`, `suburb`, ...), for settlements and districts.
+    pub place: Option<String>,
+    /// `addr:housenumber` and `addr:street`, when tagged.
+    pub address: Option<String>,
+    pub phone: Option<String>,
+}
+
+/// Counts reported after an import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportStats {
+    pub nodes: usize,
+    pub ways: usize,
+    /// Places already in the index (e.g. from an overlapping extract), left unchanged.
+    pub duplicates: usize,
+}
+
+/// Size of the index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexStats {
+    pub path: String,
+    pub places: usize,
+    pub settlements: usize,
+    pub size_bytes: u64,
+}
+
+/// Handle to an imported OSM index; clones share one connection.
+#[derive(Clone)]
+pub struct OsmIndex {
+    conn: Arc<Mutex<Connection>>,
+    path: PathBuf,
+}
+
+/// Place waiting for its way's node positions during import.
+struct PendingWay {
+    id: i64,
+    tags: Vec<(String, String)>,
+    refs: Vec<i64>,
+}
+
+impl OsmIndex {
+    /// Opens (or creates) the index database at `path`.
+    pub fn open(path: &Path) -> Result<Self, MapradarError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS places (
+                id INTEGER PRIMARY KEY,
+                osm_ref TEXT NOT NULL UNIQUE,
+                name TEXT NOT NULL,
+                latitude REAL NOT NULL,
+                longitude REAL NOT NULL,
+                place TEXT,
+                address TEXT,
+                phone TEXT
+            );
+            CREATE TABLE IF NOT EXISTS place_tags (
+                place_id INTEGER NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS place_tags_key_value ON place_tags (key, value);
+            CREATE INDEX IF NOT EXISTS places_name ON places (name COLLATE NOCASE);
+            CREATE VIRTUAL TABLE IF NOT EXISTS places_rtree
+                USING rtree(id, min_lat, max_lat, min_lng, max_lng);",
+        )?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Opens an existing index, failing with a hint to import one if none exists.
+    pub fn open_existing(path: &Path) -> Result<Self, MapradarError> {
+        if !path.exists() {
+            return Err(MapradarError::Config(format!(
+                "No offline index at {} (create one with `mapradar osm import <file.osm.pbf>`)",
+                path.display()
+            )));
+        }
+        Self::open(path)
+    }
+
+    /// Default location: `$XDG_DATA_HOME/mapradar/osm.sqlite`, falling back to `~/.local/share`.
+    pub fn default_path() -> PathBuf {
+        let base = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })
+            .unwrap_or_else(std::env::temp_dir);
+        base.join("mapradar").join("osm.sqlite")
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, MapradarError> {
+        self.conn
+            .lock()
+            .map_err(|_| MapradarError::Unknown("OSM index lock poisoned".to_string()))
+    }
+
+    /// Adds the places in a `.osm.pbf` extract. Importing several extracts builds one index;
+    /// places present in more than one are kept once.
+    ///
+    /// Reads the file twice: once for tagged nodes and ways, then for the positions of the
+    /// ways' nodes.
+    pub fn import(&self, pbf: &Path) -> Result<ImportStats, MapradarError> {
+        let mut nodes = Vec::new();
+        let mut ways = Vec::new();
+        ElementReader::from_path(pbf)
+            .map_err(osm_error)?
+            .for_each(|element| match element {
+                Element::Node(node) => {
+                    if let Some(tags) = interesting(node.tags()) {
+                        nodes.push((node.id(), node.lat(), node.lon(), tags));
+                    }
+                }
+                Element::DenseNode(node) => {
+                    if let Some(tags) = interesting(node.tags()) {
+                        nodes.push((node.id(), node.lat(), node.lon(), tags));
+                    }
+                }
+                Element::Way(way) => {
+                    if let Some(tags) = interesting(way.tags()) {
+                        ways.push(PendingWay {
+                            id: way.id(),
+                            tags,
+                            refs: way.refs().collect(),
+                        });
+                    }
+                }
+                Element::Relation(_) => {}
+            })
+            .map_err(osm_error)?;
+
+        let wanted: HashSet<i64> = ways
+            .iter()
+            .flat_map(|way| way.refs.iter().copied())
+            .collect();
+        let mut positions: HashMap<i64, (f64, f64)> = HashMap::with_capacity(wanted.len());
+        if !wanted.is_empty() {
+            ElementReader::from_path(pbf)
+                .map_err(osm_error)?
+                .for_each(|element| {
+                    let (id, lat, lng) = match element {
+                        Element::Node(node) => (node.id(), node.lat(), node.lon()),
+                        Element::DenseNode(node) => (node.id(), node.lat(), node.lon()),
+                        _ => return,
+                    };
+                    if wanted.contains(&id) {
+                        positions.insert(id, (lat, lng));
+                    }
+                })
+                .map_err(osm_error)?;
+        }
+
+        let mut conn = self.lock()?;
+        let tx = conn.transaction()?;
+        let mut stats = ImportStats {
+            nodes: 0,
+            ways: 0,
+            duplicates: 0,
+        };
+        for (id, lat, lng, tags) in nodes {
+            if insert_place(&tx, &format!("node/{}", id), lat, lng, &tags)? {
+                stats.nodes += 1;
+            } else {
+                stats.duplicates += 1;
+            }
+        }
+        for way in ways {
+            let points: Vec<(f64, f64)> = way
+                .refs
+                .iter()
+                .filter_map(|node| positions.get(node).copied())
+                .collect();
+            if points.is_empty() {
+                continue;
+            }
+            let lat = points.iter().map(|p| p.0).sum::<f64>() / points.len() as f64;
+            let lng = points.iter().map(|p| p.1).sum::<f64>() / points.len() as f64;
+            if insert_place(&tx, &format!("way/{}", way.id), lat, lng, &way.tags)? {
+                stats.ways += 1;
+            } else {
+                stats.duplicates += 1;
+            }
+        }
+        tx.commit()?;
+        Ok(stats)
+    }
+
+    pub fn stats(&self) -> Result<IndexStats, MapradarError> {
+        let conn = self.lock()?;
+        let places: i64 = conn.query_row("SELECT COUNT(*) FROM places", [], |row| row.get(0))?;
+        let settlements: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM places WHERE place IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(IndexStats {
+            path: self.path.display().to_string(),
+            places: places as usize,
+            settlements: settlements as usize,
+            size_bytes: std::fs::metadata(&self.path)
+                .map(|m| m.len())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Places tagged `key=value` within `radius_km` of a point, nearest first.
+    pub fn nearby(
+        &self,
+        lat: f64,
+        lng: f64,
+        key: &str,
+        value: &str,
+        radius_km: f64,
+        limit: usize,
+    ) -> Result<Vec<(OsmPlace, f64)>, MapradarError> {
+        let (min_lat, max_lat, min_lng, max_lng) = bounding_box(lat, lng, radius_km);
+        let conn = self.lock()?;
+        let mut statement = conn.prepare(
+            "SELECT p.osm_ref, p.name, p.latitude, p.longitude, p.place, p.address, p.phone
+             FROM places_rtree r JOIN places p ON p.id = r.id
+             WHERE r.min_lat >= ?1 AND r.max_lat <= ?2 AND r.min_lng >= ?3 AND r.max_lng <= ?4
+               AND p.id IN (SELECT place_id FROM place_tags WHERE key = ?5 AND value = ?6)",
+        )?;
+        let places = statement
+            .query_map(
+                params![min_lat, max_lat, min_lng, max_lng, key, value],
+                place_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(nearest(places, lat, lng, radius_km, limit))
+    }
+
+    /// Settlements or districts whose `place` tag is one of `kinds`, within `radius_km`,
+    /// nearest first.
+    pub fn nearest_settlements(
+        &self,
+        lat: f64,
+        lng: f64,
+        kinds: &[&str],
+        radius_km: f64,
+        limit: usize,
+    ) -> Result<Vec<(OsmPlace, f64)>, MapradarError> {
+        let (min_lat, max_lat, min_lng, max_lng) = bounding_box(lat, lng, radius_km);
+        let conn = self.lock()?;
+        let mut statement = conn.prepare(
+            "SELECT p.osm_ref, p.name, p.latitude, p.longitude, p.place, p.address, p.phone
+             FROM places_rtree r JOIN places p ON p.id = r.id
+             WHERE r.min_lat >= ?1 AND r.max_lat <= ?2 AND r.min_lng >= ?3 AND r.max_lng <= ?4
+               AND p.place IS NOT NULL",
+        )?;
+        let places = statement
+            .query_map(params![min_lat, max_lat, min_lng, max_lng], place_from_row)?
+            .filter(|place| {
+                place
+                    .as_ref()
+                    .map_or(true, |p| kinds.contains(&p.place.as_deref().unwrap_or("")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(nearest(places, lat, lng, radius_km, limit))
+    }
+
+    /// Places named `name` (case-insensitive), settlements first, largest first.
+    pub fn find_by_name(&self, name: &str, limit: usize) -> Result<Vec<OsmPlace>, MapradarError> {
+        let conn = self.lock()?;
+        let mut statement = conn.prepare(
+            "SELECT osm_ref, name, latitude, longitude, place, address, phone FROM places
+             WHERE name = ?1 COLLATE NOCASE
+             ORDER BY CASE place
+                 WHEN 'city' THEN 0 WHEN 'town' THEN 1 WHEN 'suburb' THEN 2
+                 WHEN 'village' THEN 3 WHEN 'neighbourhood' THEN 4 WHEN 'quarter' THEN 4
+                 WHEN 'hamlet' THEN 5 ELSE 6 END
+             LIMIT ?2",
+        )?;
+        let places = statement
+            .query_map(params![name.trim(), limit as i64], place_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(places)
+    }
+
+    /// Looks up one place by its `node/<id>` or `way/<id>` reference.
+    pub fn get(&self, osm_ref: &str) -> Result<Option<OsmPlace>, MapradarError> {
+        let conn = self.lock()?;
+        Ok(conn
+            .query_row(
+                "SELECT osm_ref, name, latitude, longitude, place, address, phone FROM places
+                 WHERE osm_ref = ?1",
+                params![osm_ref],
+                place_from_row,
+            )
+            .optional()?)
+    }
+}
+
+fn osm_error(err: osmpbf::Error) -> MapradarError {
+    MapradarError::InvalidInput(format!("Failed to read OSM extract: {}", err))
+}
+
+/// Tags of a named element worth indexing: one with a category or `place` tag.
+fn interesting<'a>(
+    tags: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Option<Vec<(String, String)>> {
+    let tags: Vec<(String, String)> = tags
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let named = tags.iter().any(|(key, _)| key == "name");
+    let classified = tags
+        .iter()
+        .any(|(key, _)| key == "place" || CATEGORY_KEYS.contains(&key.as_str()));
+    (named && classified).then_some(tags)
+}
+
+/// Inserts a place unless its reference is already indexed; returns whether it was added.
+fn insert_place(
+    conn: &Connection,
+    osm_ref: &str,
+    lat: f64,
+    lng: f64,
+    tags: &[(String, String)],
+) -> Result<bool, MapradarError> {
+    let tag = |name: &str| {
+        tags.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let address = match (tag("addr:housenumber"), tag("addr:street")) {
+        (Some(number), Some(street)) => Some(format!("{} {}", number, street)),
+        (None, Some(street)) => Some(street.to_string()),
+        _ => None,
+    };
+
+    let inserted = conn.execute(
+        "INSERT OR IGNORE INTO places (osm_ref, name, latitude, longitude, place, address, phone)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            osm_ref,
+            tag("name").unwrap_or_default(),
+            lat,
+            lng,
+            tag("place"),
+            address,
+            tag("phone").or_else(|| tag("contact:phone")),
+        ],
+    )?;
+    if inserted == 0 {
+        return Ok(false);
+    }
+
+    let id = conn.last_insert_rowid();
+    conn.execute(
+        "INSERT INTO places_rtree (id, min_lat, max_lat, min_lng, max_lng) VALUES (?1, ?2, ?2, ?3, ?3)",
+        params![id, lat, lng],
+    )?;
+    for (key, value) in tags {
+        if CATEGORY_KEYS.contains(&key.as_str()) {
+            conn.execute(
+                "INSERT INTO place_tags (place_id, key, value) VALUES (?1, ?2, ?3)",
+                params![id, key, value],
+            )?;
+        }
+    }
+    Ok(true)
+}
+
+fn place_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<OsmPlace> {
+    Ok(OsmPlace {
+        osm_ref: row.get(0)?,
+        name: row.get(1)?,
+        latitude: row.get(2)?,
+        longitude: row.get(3)?,
+        place: row.get(4)?,
+        address: row.get(5)?,
+        phone: row.get(6)?,
+    })
+}
+
+/// Box enclosing every point within `radius_km`, as (min lat, max lat, min lng, max lng).
+fn bounding_box(lat: f64, lng: f64, radius_km: f64) -> (f64, f64, f64, f64) {
+    let dlat = radius_km / KM_PER_DEGREE;
+    let dlng = radius_km / (KM_PER_DEGREE * lat.to_radians().cos().max(0.01));
+    (lat - dlat, lat + dlat, lng - dlng, lng + dlng)
+}
+
+/// Keeps places within `radius_km` (the box corners are farther), nearest first.
+fn nearest(
+    places: Vec<OsmPlace>,
+    lat: f64,
+    lng: f64,
+    radius_km: f64,
+    limit: usize,
+) -> Vec<(OsmPlace, f64)> {
+    let mut places: Vec<(OsmPlace, f64)> = places
+        .into_iter()
+        .map(|place| {
+            let distance = haversine_distance(lat, lng, place.latitude, place.longitude);
+            (place, distance)
+        })
+        .filter(|(_, distance)| *distance <= radius_km)
+        .collect();
+    places.sort_by(|a, b| a.1.total_cmp(&b.1));
+    places.truncate(limit);
+    places
+}
@@ -0,0 +1,129 @@
+//! Request planning for dry runs.
+//!
+//! A client built with [`MapradarClientBuilder::dry_run`](crate::client::MapradarClientBuilder::dry_run)
+//! records every upstream request instead of sending it, failing each one with
+//! [`MapradarError::DryRun`](crate::error::MapradarError::DryRun). Lookups that later requests
+//! depend on (geocoding an origin before a nearby search, for example) resolve to a placeholder
+//! location so the rest of the operation can still be planned.
+
+use std::sync::{Arc, Mutex};
+
+use reqwest::{Request, Url};
+use serde::{Deserialize, Serialize};
+
+/// Google Maps Platform list prices in USD per request (per element for Distance Matrix),
+/// before the monthly free credit and volume discounts.
+const GOOGLE_PRICES: &[(&str, &str, f64)] = &[
+    ("/maps/api/geocode/", "Geocoding", 0.005),
+    (
+        "/maps/api/place/nearbysearch/",
+        "Places Nearby Search",
+        0.032,
+    ),
+    (
+        "/maps/api/place/autocomplete/",
+        "Places Autocomplete",
+        0.00283,
+    ),
+    ("/maps/api/place/details/", "Place Details", 0.017),
+    ("/maps/api/place/photo", "Places Photo", 0.007),
+    ("/maps/api/staticmap", "Static Maps", 0.002),
+    ("/maps/api/directions/", "Directions", 0.005),
+    ("/maps/api/distancematrix/", "Distance Matrix", 0.005),
+    ("/maps/api/elevation/", "Elevation", 0.005),
+    ("/maps/api/timezone/", "Time Zone", 0.005),
+    ("/v1/snapToRoads", "Roads", 0.01),
+];
+
+/// One upstream request that a dry run would have sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedRequest {
+    pub method: String,
+    /// Full URL with credentials redacted.
+    pub url: String,
+    /// Request body, for POST requests such as Overpass queries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Billing name of the endpoint, e.g. "Geocoding".
+    pub endpoint: String,
+    pub estimated_cost_usd: f64,
+}
+
+/// Every request recorded by a dry run, with the estimated total cost.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestPlan {
+    pub requests: Vec<PlannedRequest>,
+    pub total_requests: usize,
+    pub estimated_cost_usd: f64,
+}
+
+/// Shared log of planned requests; clones append to the same plan.
+#[derive(Clone, Default)]
+pub struct RequestRecorder {
+    requests: Arc<Mutex<Vec<PlannedRequest>>>,
+}
+
+impl RequestRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn record(&self, request: &Request) {
+        let (endpoint, cost) = estimate(request.url());
+        let planned = PlannedRequest {
+            method: request.method().to_string(),
+            url: crate::transport::redact_url(request.url()).to_string(),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(|bytes| String::from_utf8_lossy(bytes).into_owned()),
+            endpoint,
+            estimated_cost_usd: cost,
+        };
+        if let Ok(mut requests) = self.requests.lock() {
+            requests.push(planned);
+        }
+    }
+
+    /// Snapshot of the requests recorded so far.
+    pub fn plan(&self) -> RequestPlan {
+        let requests = self
+            .requests
+            .lock()
+            .map(|requests| requests.clone())
+            .unwrap_or_default();
+        let cost = requests
+            .iter()
+            .fold(0.0, |total, r| total + r.estimated_cost_usd);
+        RequestPlan {
+            total_requests: requests.len(),
+            estimated_cost_usd: (cost * 100_000.0).round() / 100_000.0,
+            requests,
+        }
+    }
+}
+
+/// Billing name and list price of a request. Requests to free services (Nominatim, Overpass,
+/// OSRM, Open-Meteo, ipapi) cost nothing.
+pub(crate) fn estimate(url: &Url) -> (String, f64) {
+    if matches!(
+        url.host_str(),
+        Some("maps.googleapis.com" | "roads.googleapis.com")
+    ) && let Some((_, name, price)) = GOOGLE_PRICES
+        .iter()
+        .find(|(path, _, _)| url.path().starts_with(path))
+    {
+        let elements = if *name == "Distance Matrix" {
+            let count = |param: &str| {
+                url.query_pairs()
+                    .find(|(name, _)| name == param)
+                    .map_or(1, |(_, value)| value.split('|').count())
+            };
+            count("origins") * count("destinations")
+        } else {
+            1
+        };
+        return (name.to_string(), price * elements as f64);
+    }
+    (url.host_str().unwrap_or_default().to_string(), 0.0)
+}
@@ -0,0 +1,288 @@
+//! Open Location Code (Plus Codes), encoded and decoded locally.
+//!
+//! A full code such as "6FR5GC9M+7V" names an area roughly 14 m square anywhere on Earth. A short
+//! code such as "GC9M+7V" drops the first digits and needs a nearby reference point, usually a
+//! locality written after it: "GC9M+7V Lagos". See <https://plus.codes> for the specification.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{coordinates::Coordinates, error::MapradarError};
+
+/// Digits used by plus codes, in value order.
+const ALPHABET: &[u8; 20] = b"23456789CFGHJMPQRVWX";
+const SEPARATOR: char = '+';
+const SEPARATOR_POSITION: usize = 8;
+const PADDING: char = '0';
+const ENCODING_BASE: i64 = 20;
+/// Digits in the pair section of a code, before the grid refinement starts.
+const PAIR_CODE_LENGTH: usize = 10;
+const MAX_DIGIT_COUNT: usize = 15;
+const GRID_COLUMNS: i64 = 4;
+const GRID_ROWS: i64 = 5;
+/// Value of the first pair digit, in pair-precision units.
+const PAIR_FIRST_PLACE_VALUE: i64 = 160_000;
+/// Units per degree at the end of the pair section.
+const PAIR_PRECISION: i64 = 8_000;
+/// Units per degree at the last grid digit: 8000 * 5^5 for latitude, 8000 * 4^5 for longitude.
+const FINAL_LAT_PRECISION: i64 = 25_000_000;
+const FINAL_LNG_PRECISION: i64 = 8_192_000;
+
+/// Code length used when none is given: 10 digits, about 14 by 14 metres.
+pub const DEFAULT_CODE_LENGTH: usize = PAIR_CODE_LENGTH;
+
+/// Area named by a plus code, in degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CodeArea {
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+    /// Significant digits in the code.
+    pub code_length: usize,
+}
+
+impl CodeArea {
+    /// Centre of the area, the point a code stands for.
+    pub fn center(&self) -> Coordinates {
+        let lat = (self.south + (self.north - self.south) / 2.0).min(90.0);
+        let lng = (self.west + (self.east - self.west) / 2.0).min(180.0);
+        Coordinates::new(lat, lng).expect("a decoded code area lies within range")
+    }
+}
+
+/// Encodes `point` with `code_length` digits: 2, 4, 6, or 8, or anything from 10 to 15. Longer
+/// codes name smaller areas; see [`DEFAULT_CODE_LENGTH`].
+pub fn encode(point: Coordinates, code_length: usize) -> Result<String, MapradarError> {
+    if code_length < 2 || (code_length < PAIR_CODE_LENGTH && code_length % 2 == 1) {
+        return Err(MapradarError::InvalidInput(format!(
+            "Invalid plus code length {} (expected 2, 4, 6, 8, or 10 to 15)",
+            code_length
+        )));
+    }
+    Ok(encode_degrees(
+        point.latitude(),
+        point.longitude(),
+        code_length.min(MAX_DIGIT_COUNT),
+    ))
+}
+
+/// Encodes any latitude and longitude, clipping the latitude and wrapping the longitude.
+fn encode_degrees(lat: f64, lng: f64, code_length: usize) -> String {
+    let lat_span = 180 * FINAL_LAT_PRECISION;
+    let lng_span = 360 * FINAL_LNG_PRECISION;
+    let mut lat_val = ((lat * FINAL_LAT_PRECISION as f64).floor() as i64
+        + 90 * FINAL_LAT_PRECISION)
+        .clamp(0, lat_span - 1);
+    let mut lng_val = ((lng * FINAL_LNG_PRECISION as f64).floor() as i64
+        + 180 * FINAL_LNG_PRECISION)
+        .rem_euclid(lng_span);
+
+    let mut digits = Vec::with_capacity(MAX_DIGIT_COUNT);
+    if code_length > PAIR_CODE_LENGTH {
+        for _ in PAIR_CODE_LENGTH..MAX_DIGIT_COUNT {
+            let index = (lat_val % GRID_ROWS) * GRID_COLUMNS + lng_val % GRID_COLUMNS;
+            digits.push(ALPHABET[index as usize]);
+            lat_val /= GRID_ROWS;
+            lng_val /= GRID_COLUMNS;
+        }
+    } else {
+        lat_val /= GRID_ROWS.pow(5);
+        lng_val /= GRID_COLUMNS.pow(5);
+    }
+    for _ in 0..PAIR_CODE_LENGTH / 2 {
+        digits.push(ALPHABET[(lng_val % ENCODING_BASE) as usize]);
+        digits.push(ALPHABET[(lat_val % ENCODING_BASE) as usize]);
+        lat_val /= ENCODING_BASE;
+        lng_val /= ENCODING_BASE;
+    }
+    digits.reverse();
+
+    let digits: String = digits[..code_length].iter().map(|&b| b as char).collect();
+    if code_length >= SEPARATOR_POSITION {
+        format!(
+            "{}{}{}",
+            &digits[..SEPARATOR_POSITION],
+            SEPARATOR,
+            &digits[SEPARATOR_POSITION..]
+        )
+    } else {
+        format!(
+            "{}{}{}",
+            digits,
+            PADDING.to_string().repeat(SEPARATOR_POSITION - code_length),
+            SEPARATOR
+        )
+    }
+}
+
+/// Decodes a full code to the area it names. Short codes fail; recover them first with
+/// [`recover_nearest`].
+pub fn decode(code: &str) -> Result<CodeArea, MapradarError> {
+    if !is_full(code) {
+        return Err(invalid(code));
+    }
+    let digits: Vec<i64> = code
+        .chars()
+        .filter(|&c| c != SEPARATOR && c != PADDING)
+        .map(digit_value)
+        .collect::<Option<_>>()
+        .ok_or_else(|| invalid(code))?;
+
+    let pair_digits = digits.len().min(PAIR_CODE_LENGTH);
+    let mut lat = -90 * PAIR_PRECISION;
+    let mut lng = -180 * PAIR_PRECISION;
+    let mut place_value = PAIR_FIRST_PLACE_VALUE;
+    for i in (0..pair_digits).step_by(2) {
+        lat += digits[i] * place_value;
+        lng += digits[i + 1] * place_value;
+        if i + 2 < pair_digits {
+            place_value /= ENCODING_BASE;
+        }
+    }
+    let mut lat_precision = place_value as f64 / PAIR_PRECISION as f64;
+    let mut lng_precision = lat_precision;
+
+    let (mut grid_lat, mut grid_lng) = (0, 0);
+    let code_length = digits.len().min(MAX_DIGIT_COUNT);
+    if digits.len() > PAIR_CODE_LENGTH {
+        let mut row_value = GRID_ROWS.pow(4);
+        let mut column_value = GRID_COLUMNS.pow(4);
+        for (i, digit) in digits
+            .iter()
+            .enumerate()
+            .take(code_length)
+            .skip(PAIR_CODE_LENGTH)
+        {
+            grid_lat += digit / GRID_COLUMNS * row_value;
+            grid_lng += digit % GRID_COLUMNS * column_value;
+            if i + 1 < code_length {
+                row_value /= GRID_ROWS;
+                column_value /= GRID_COLUMNS;
+            }
+        }
+        lat_precision = row_value as f64 / FINAL_LAT_PRECISION as f64;
+        lng_precision = column_value as f64 / FINAL_LNG_PRECISION as f64;
+    }
+
+    let south = lat as f64 / PAIR_PRECISION as f64 + grid_lat as f64 / FINAL_LAT_PRECISION as f64;
+    let west = lng as f64 / PAIR_PRECISION as f64 + grid_lng as f64 / FINAL_LNG_PRECISION as f64;
+    Ok(CodeArea {
+        south,
+        west,
+        north: south + lat_precision,
+        east: west + lng_precision,
+        code_length,
+    })
+}
+
+/// Whether `code` is a well-formed full or short code.
+pub fn is_valid(code: &str) -> bool {
+    let Some(separator) = code.find(SEPARATOR) else {
+        return false;
+    };
+    if code.len() == 1
+        || code.matches(SEPARATOR).count() > 1
+        || separator > SEPARATOR_POSITION
+        || separator % 2 == 1
+    {
+        return false;
+    }
+    if let Some(padding) = code.find(PADDING) {
+        // Padding is one even run straight before the separator, in full codes only.
+        let padding_end = code.rfind(PADDING).unwrap_or(padding) + 1;
+        if separator < SEPARATOR_POSITION
+            || padding == 0
+            || (padding_end - padding) % 2 == 1
+            || code[padding..padding_end].chars().any(|c| c != PADDING)
+            || !code.ends_with(SEPARATOR)
+        {
+            return false;
+        }
+    }
+    if code.len() - separator - 1 == 1 {
+        return false;
+    }
+    code.chars()
+        .all(|c| c == SEPARATOR || c == PADDING || digit_value(c).is_some())
+}
+
+/// Whether `code` is a valid code missing its leading digits.
+pub fn is_short(code: &str) -> bool {
+    is_valid(code)
+        && code
+            .find(SEPARATOR)
+            .is_some_and(|at| at < SEPARATOR_POSITION)
+}
+
+/// Whether `code` is a valid code that locates an area on its own.
+pub fn is_full(code: &str) -> bool {
+    if !is_valid(code) || is_short(code) {
+        return false;
+    }
+    let mut chars = code.chars();
+    let first_lat = chars.next().and_then(digit_value).unwrap_or(0);
+    let first_lng = chars.next().and_then(digit_value).unwrap_or(0);
+    first_lat * ENCODING_BASE < 180 && first_lng * ENCODING_BASE < 360
+}
+
+/// Completes a short code with the digits of the nearest matching area to `reference`. Full codes
+/// are returned as they are, upper-cased.
+pub fn recover_nearest(code: &str, reference: Coordinates) -> Result<String, MapradarError> {
+    if is_full(code) {
+        return Ok(code.to_uppercase());
+    }
+    if !is_short(code) {
+        return Err(invalid(code));
+    }
+    let code = code.to_uppercase();
+    let missing = SEPARATOR_POSITION - code.find(SEPARATOR).unwrap_or(SEPARATOR_POSITION);
+    let resolution = (ENCODING_BASE as f64).powi(2 - (missing / 2) as i32);
+    let half = resolution / 2.0;
+
+    let (ref_lat, ref_lng) = reference.as_tuple();
+    let prefix = encode_degrees(ref_lat, ref_lng, PAIR_CODE_LENGTH);
+    let area = decode(&format!("{}{}", &prefix[..missing], code))?;
+    let (mut lat, mut lng) = area.center().as_tuple();
+
+    // The prefix can put the area a cell away from the reference; move to the nearer neighbour.
+    if ref_lat + half < lat && lat - resolution >= -90.0 {
+        lat -= resolution;
+    } else if ref_lat - half > lat && lat + resolution <= 90.0 {
+        lat += resolution;
+    }
+    if ref_lng + half < lng {
+        lng -= resolution;
+    } else if ref_lng - half > lng {
+        lng += resolution;
+    }
+    Ok(encode_degrees(lat, lng, area.code_length))
+}
+
+/// Splits input such as "GC9M+7V Lagos" or "6FR5GC9M+7V" into an upper-cased code and the
+/// locality after it, or `None` when it does not start with a plus code.
+pub fn split_locality(input: &str) -> Option<(String, Option<&str>)> {
+    let input = input.trim();
+    let (code, locality) = match input.split_once(char::is_whitespace) {
+        Some((code, locality)) => (code.trim_end_matches(','), Some(locality)),
+        None => (input.trim_end_matches(','), None),
+    };
+    if !is_valid(code) {
+        return None;
+    }
+    let locality = locality
+        .map(|locality| locality.trim_start_matches([',', ' ']).trim())
+        .filter(|locality| !locality.is_empty());
+    Some((code.to_uppercase(), locality))
+}
+
+fn digit_value(c: char) -> Option<i64> {
+    let c = c.to_ascii_uppercase();
+    ALPHABET
+        .iter()
+        .position(|&digit| digit as char == c)
+        .map(|value| value as i64)
+}
+
+fn invalid(code: &str) -> MapradarError {
+    MapradarError::InvalidInput(format!("Invalid plus code '{}'", code))
+}
@@ -0,0 +1,37 @@
+//! Progress of long-running client calls, for progress bars and spinners.
+//!
+//! Register a [`ProgressObserver`] with
+//! [`MapradarClientBuilder::progress`](crate::client::MapradarClientBuilder::progress). Batch
+//! geocoding reports one step per row, and
+//! [`fetch_intelligence_async`](crate::client::MapradarClient::fetch_intelligence_async) one
+//! step per service-type search. An observer is shared by every clone of the client, so calls
+//! running at the same time report to it interleaved.
+
+/// Kind of call being reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressTask {
+    /// Geocoding a batch of addresses; each step is a row.
+    BatchGeocode,
+    /// Reverse geocoding a batch of points; each step is a row.
+    BatchReverse,
+    /// Searching several service types around one location; each step is a service type.
+    Nearby,
+}
+
+/// Follows the steps of batch and multi-type calls.
+///
+/// Every method defaults to doing nothing, so implementations override only what they need.
+pub trait ProgressObserver: Send + Sync {
+    /// Called when a call starts, with the number of steps it will take.
+    fn started(&self, _task: ProgressTask, _steps: usize) {}
+
+    /// Called as a step begins, with its row input or service type. Several steps run at once,
+    /// up to the call's concurrency.
+    fn step_started(&self, _label: &str) {}
+
+    /// Called as a step ends, with whether it succeeded.
+    fn step_finished(&self, _label: &str, _ok: bool) {}
+
+    /// Called once every step has ended.
+    fn finished(&self) {}
+}
@@ -0,0 +1,1256 @@
+use std::{collections::HashMap, net::IpAddr, time::Duration};
+
+use futures::future::BoxFuture;
+use reqwest::RequestBuilder;
+use serde_json::Value;
+
+use super::{GeocodingProvider, NearbyPage, check_http_status};
+use crate::transport::Transport;
+use crate::{
+    error::MapradarError,
+    geo::haversine_distance,
+    models::{
+        AddressComponents, AdminArea, AutocompleteBias, BIAS_RADIUS_KM, GeoLocation,
+        GeocodeCandidate, MatchType, MatrixElement, NearbyService, OpeningHours, PlaceCategory,
+        PlaceDetails, PlacePhoto, PostcodeArea, Route, RouteStep, SearchRegion, ServiceType,
+        SnappedPoint, Suggestion, TimeZoneInfo, TravelMode, country_code,
+    },
+    staticmap::{CIRCLE_FILL, CIRCLE_STROKE, StaticMap},
+    utils::{parse_address_components, strip_html_tags},
+};
+
+pub const DEFAULT_BASE_URL: &str = "https://maps.googleapis.com";
+const GEOCODE_PATH: &str = "/maps/api/geocode/json";
+const NEARBY_SEARCH_PATH: &str = "/maps/api/place/nearbysearch/json";
+const DIRECTIONS_PATH: &str = "/maps/api/directions/json";
+const AUTOCOMPLETE_PATH: &str = "/maps/api/place/autocomplete/json";
+const TIMEZONE_PATH: &str = "/maps/api/timezone/json";
+const DETAILS_PATH: &str = "/maps/api/place/details/json";
+const PHOTO_PATH: &str = "/maps/api/place/photo";
+const STATIC_MAP_PATH: &str = "/maps/api/staticmap";
+/// Only these fields are requested, which keeps Place Details in its cheapest billing tiers.
+const DETAILS_FIELDS: &str = "place_id,name,geometry/location,formatted_address,\
+international_phone_number,website,rating,user_ratings_total,opening_hours,photos,types";
+/// Google only honours a `next_page_token` a short while after issuing it.
+const PAGE_TOKEN_DELAY: Duration = Duration::from_secs(2);
+const PAGE_TOKEN_ATTEMPTS: usize = 3;
+const ELEVATION_PATH: &str = "/maps/api/elevation/json";
+const ELEVATION_MAX_LOCATIONS: usize = 512;
+/// The Roads API has a host of its own.
+const ROADS_URL: &str = "https://roads.googleapis.com";
+const SNAP_TO_ROADS_PATH: &str = "/v1/snapToRoads";
+const SNAP_MAX_POINTS: usize = 100;
+const DISTANCE_MATRIX_PATH: &str = "/maps/api/distancematrix/json";
+const MATRIX_MAX_DESTINATIONS: usize = 25;
+const MATRIX_MAX_ELEMENTS: usize = 100;
+/// Google area types accepted as administrative areas, most specific first, with the
+/// OpenStreetMap `admin_level` each is reported as.
+const ADMIN_LEVELS: &[(&str, u8)] = &[
+    ("neighborhood", 10),
+    ("sublocality", 9),
+    ("administrative_area_level_5", 9),
+    ("administrative_area_level_4", 8),
+    ("locality", 8),
+    ("administrative_area_level_3", 7),
+    ("administrative_area_level_2", 6),
+    ("administrative_area_level_1", 4),
+    ("country", 2),
+];
+
+/// Google Maps Platform backend (Geocoding API and Places API).
+#[derive(Clone)]
+pub struct GoogleProvider {
+    api_key: String,
+    transport: Transport,
+    base_url: String,
+    language: Option<String>,
+}
+
+impl GoogleProvider {
+    pub fn new(api_key: String, transport: Transport) -> Self {
+        Self {
+            api_key,
+            transport,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            language: None,
+        }
+    }
+
+    /// Sends every request to `base_url` (scheme and host, optionally a path prefix) instead of
+    /// [`DEFAULT_BASE_URL`], e.g. for a proxy or mock server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Asks for addresses, place names, and directions in `language`, a code such as "fr" or
+    /// "pt-BR". Google falls back to the local language where it has no translation.
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Roads API URL for `path`, sent to [`ROADS_URL`] unless another base URL was set.
+    fn roads_url(&self, path: &str) -> String {
+        if self.base_url == DEFAULT_BASE_URL {
+            format!("{}{}", ROADS_URL, path)
+        } else {
+            self.url(path)
+        }
+    }
+
+    /// Adds the `language` parameter when one was set.
+    fn localized(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.language {
+            Some(language) => request.query(&[("language", language)]),
+            None => request,
+        }
+    }
+
+    /// Reads `periods` (and `weekday_text`) from an `opening_hours` object; `None` when the
+    /// response carries only `open_now`. A lone period with no close is open around the clock.
+    pub(crate) fn parse_opening_hours(value: &Value) -> Option<OpeningHours> {
+        let periods = value["periods"].as_array().filter(|p| !p.is_empty())?;
+        let point = |value: &Value| {
+            let day = value["day"].as_u64()? as u32;
+            let time = value["time"].as_str()?.parse::<u32>().ok()?;
+            Some(day * 24 * 60 + (time / 100) * 60 + time % 100)
+        };
+
+        let mut hours = OpeningHours::default();
+        for period in periods {
+            let Some(open) = point(&period["open"]) else {
+                continue;
+            };
+            match period.get("close").and_then(point) {
+                Some(close) => hours.add_span(open, close),
+                None => {
+                    hours = OpeningHours::always_open();
+                    break;
+                }
+            }
+        }
+        hours.weekday_text = value["weekday_text"]
+            .as_array()
+            .map(|lines| {
+                lines
+                    .iter()
+                    .filter_map(|line| line.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(hours)
+    }
+
+    /// Maps a service type to its Google Places category.
+    pub fn place_type(service_type: ServiceType) -> &'static str {
+        match service_type {
+            ServiceType::BusStop => "bus_station",
+            ServiceType::Market => "supermarket",
+            ServiceType::School => "school",
+            ServiceType::Mall => "shopping_mall",
+            ServiceType::Hospital => "hospital",
+            ServiceType::Bank => "bank",
+            ServiceType::Restaurant => "restaurant",
+            ServiceType::FuelStation => "gas_station",
+            ServiceType::TrainStation => "train_station",
+            ServiceType::TaxiStand => "taxi_stand",
+            ServiceType::Landmark => "tourist_attraction",
+            ServiceType::Pharmacy => "pharmacy",
+            ServiceType::PoliceStation => "police",
+            ServiceType::FireStation => "fire_station",
+            ServiceType::Gym => "gym",
+            ServiceType::Park => "park",
+            ServiceType::PlaceOfWorship => "place_of_worship",
+            ServiceType::Airport => "airport",
+            ServiceType::Hotel => "lodging",
+            ServiceType::Supermarket => "supermarket",
+            ServiceType::Cafe => "cafe",
+            ServiceType::Cinema => "movie_theater",
+            ServiceType::Library => "library",
+            ServiceType::PostOffice => "post_office",
+        }
+    }
+
+    /// Google place type for a built-in or custom category.
+    fn category_type(category: &PlaceCategory) -> &str {
+        match category {
+            PlaceCategory::Known(service_type) => Self::place_type(*service_type),
+            PlaceCategory::Custom(tag) => tag,
+        }
+    }
+
+    /// Translates a non-OK Google status into the matching error variant.
+    fn status_error(status: &str, data: &Value, fallback: &str) -> MapradarError {
+        let message = data["error_message"]
+            .as_str()
+            .unwrap_or(fallback)
+            .to_string();
+        match status {
+            "REQUEST_DENIED" => MapradarError::AuthFailed {
+                provider: "google".to_string(),
+                message,
+            },
+            "OVER_QUERY_LIMIT" | "OVER_DAILY_LIMIT" => MapradarError::QuotaExceeded {
+                provider: "google".to_string(),
+                message,
+            },
+            "ZERO_RESULTS" => MapradarError::ZeroResults,
+            _ => MapradarError::Api {
+                status: status.to_string(),
+                message,
+            },
+        }
+    }
+
+    /// Sends a Geocoding API request and returns its results, failing when there are none.
+    async fn fetch_results(
+        &self,
+        params: &[(&str, String)],
+        failure_message: &str,
+    ) -> Result<Vec<Value>, MapradarError> {
+        let request = self
+            .transport
+            .get(self.url(GEOCODE_PATH))
+            .query(params)
+            .query(&[("key", &self.api_key)]);
+        let response = self.transport.send(self.localized(request)).await?;
+
+        let mut data: Value = response.json().await?;
+        let status = data["status"].as_str().unwrap_or("UNKNOWN");
+
+        if status != "OK" && status != "ZERO_RESULTS" {
+            return Err(Self::status_error(status, &data, failure_message));
+        }
+
+        match data["results"].take() {
+            Value::Array(results) if !results.is_empty() => Ok(results),
+            _ => {
+                let query = params.first().map(|(_, value)| value.clone());
+                Err(MapradarError::AddressNotFound {
+                    query: query.unwrap_or_default(),
+                })
+            }
+        }
+    }
+
+    /// Sends a Geocoding API request and parses the first result.
+    async fn fetch_location(
+        &self,
+        params: &[(&str, String)],
+        failure_message: &str,
+    ) -> Result<GeoLocation, MapradarError> {
+        let results = self.fetch_results(params, failure_message).await?;
+        self.parse_result(&results[0])
+    }
+
+    /// Geocodes `address` within `region`: a lone country becomes a component filter and the bias
+    /// point a `bounds` viewport. Google filters by one country at a time, so matches in other
+    /// countries are dropped here.
+    async fn fetch_in_region(
+        &self,
+        address: &str,
+        region: &SearchRegion,
+    ) -> Result<Vec<Value>, MapradarError> {
+        let mut params = vec![("address", address.to_string())];
+        if let [country] = &region.countries[..] {
+            params.push(("components", format!("country:{}", country)));
+        }
+        if let Some((min_lng, min_lat, max_lng, max_lat)) = region.bias_box() {
+            params.push((
+                "bounds",
+                format!("{},{}|{},{}", min_lat, min_lng, max_lat, max_lng),
+            ));
+        }
+        let results = self.fetch_results(&params, "Geocoding failed").await?;
+        let mut allowed = Vec::with_capacity(results.len());
+        for result in results {
+            if region.allows(&self.parse_result(&result)?) {
+                allowed.push(result);
+            }
+        }
+        if allowed.is_empty() {
+            return Err(MapradarError::AddressNotFound {
+                query: address.to_string(),
+            });
+        }
+        Ok(allowed)
+    }
+
+    /// Geocodes a postcode with a `postal_code` component filter. Google lists the localities of
+    /// a postcode spanning several in `postcode_localities`; otherwise the match's own city is
+    /// used.
+    async fn fetch_postcode(
+        &self,
+        postcode: &str,
+        country: &str,
+    ) -> Result<PostcodeArea, MapradarError> {
+        let country = country_code(country)?;
+        let results = self
+            .fetch_results(
+                &[
+                    ("address", postcode.to_string()),
+                    (
+                        "components",
+                        format!("postal_code:{}|country:{}", postcode.trim(), country),
+                    ),
+                ],
+                "Postcode lookup failed",
+            )
+            .await?;
+        let candidates = results
+            .iter()
+            .map(|result| self.parse_candidate(result))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut area =
+            PostcodeArea::from_candidates(postcode, &country, &candidates).ok_or_else(|| {
+                MapradarError::AddressNotFound {
+                    query: postcode.to_string(),
+                }
+            })?;
+        if let Some(localities) = results[0]["postcode_localities"].as_array() {
+            area.localities = localities
+                .iter()
+                .filter_map(|locality| locality.as_str().map(str::to_string))
+                .collect();
+        }
+        Ok(area)
+    }
+
+    fn parse_result(&self, result: &Value) -> Result<GeoLocation, MapradarError> {
+        let geometry = &result["geometry"]["location"];
+        let (city, state, country) = parse_address_components(&result["address_components"])?;
+
+        Ok(GeoLocation {
+            address: result["formatted_address"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            latitude: geometry["lat"].as_f64().unwrap_or_default(),
+            longitude: geometry["lng"].as_f64().unwrap_or_default(),
+            city,
+            state,
+            country,
+            address_components: Some(Self::parse_components(&result["address_components"])),
+            timezone: None,
+            elevation_m: None,
+            language: self.language.clone(),
+        })
+    }
+
+    /// Maps `address_components` types onto [`AddressComponents`], taking the first match for each.
+    fn parse_components(components: &Value) -> AddressComponents {
+        let find = |types: &[&str], name: &str| {
+            components.as_array()?.iter().find_map(|component| {
+                let matches = component["types"]
+                    .as_array()?
+                    .iter()
+                    .any(|t| t.as_str().is_some_and(|t| types.contains(&t)));
+                matches
+                    .then(|| component[name].as_str().map(str::to_string))
+                    .flatten()
+            })
+        };
+
+        AddressComponents {
+            house_number: find(&["street_number"], "long_name"),
+            street: find(&["route"], "long_name"),
+            suburb: find(
+                &["sublocality", "sublocality_level_1", "neighborhood"],
+                "long_name",
+            ),
+            city: find(&["locality", "postal_town"], "long_name"),
+            state: find(&["administrative_area_level_1"], "long_name"),
+            postcode: find(&["postal_code"], "long_name"),
+            country_code: find(&["country"], "short_name"),
+        }
+    }
+
+    /// Scores a result from its `location_type`, halving the score for partial matches.
+    fn parse_candidate(&self, result: &Value) -> Result<GeocodeCandidate, MapradarError> {
+        let geometry = &result["geometry"];
+        let (match_type, confidence) = match geometry["location_type"].as_str() {
+            Some("ROOFTOP") => (MatchType::Exact, 1.0),
+            Some("RANGE_INTERPOLATED") => (MatchType::Interpolated, 0.8),
+            Some("GEOMETRIC_CENTER") => (MatchType::Approximate, 0.6),
+            _ => (MatchType::Approximate, 0.4),
+        };
+        let partial = result["partial_match"].as_bool().unwrap_or(false);
+
+        let extent = if geometry["bounds"].is_object() {
+            &geometry["bounds"]
+        } else {
+            &geometry["viewport"]
+        };
+        let bounding_box = match (
+            extent["southwest"]["lng"].as_f64(),
+            extent["southwest"]["lat"].as_f64(),
+            extent["northeast"]["lng"].as_f64(),
+            extent["northeast"]["lat"].as_f64(),
+        ) {
+            (Some(min_lng), Some(min_lat), Some(max_lng), Some(max_lat)) => {
+                Some((min_lng, min_lat, max_lng, max_lat))
+            }
+            _ => None,
+        };
+
+        Ok(GeocodeCandidate {
+            location: self.parse_result(result)?,
+            confidence: if partial {
+                confidence / 2.0
+            } else {
+                confidence
+            },
+            match_type,
+            bounding_box,
+        })
+    }
+
+    async fn search_places(
+        &self,
+        lat: f64,
+        lng: f64,
+        category: &PlaceCategory,
+        radius_meters: f64,
+        max_results: usize,
+        page_token: Option<&str>,
+    ) -> Result<NearbyPage, MapradarError> {
+        let mut attempt = 0;
+        let data = loop {
+            // A follow-up page takes only the token; the search parameters travel inside it.
+            let request = match page_token {
+                Some(token) => {
+                    tokio::time::sleep(PAGE_TOKEN_DELAY).await;
+                    self.transport.get(self.url(NEARBY_SEARCH_PATH)).query(&[
+                        ("pagetoken", token.to_string()),
+                        ("key", self.api_key.clone()),
+                    ])
+                }
+                None => self.transport.get(self.url(NEARBY_SEARCH_PATH)).query(&[
+                    ("location", format!("{},{}", lat, lng)),
+                    ("radius", radius_meters.to_string()),
+                    ("type", Self::category_type(category).to_string()),
+                    ("key", self.api_key.clone()),
+                ]),
+            };
+            let data: Value = self
+                .transport
+                .send(self.localized(request))
+                .await?
+                .json()
+                .await?;
+            attempt += 1;
+            // INVALID_REQUEST for a page token usually means it is not active yet.
+            if page_token.is_none()
+                || data["status"] != "INVALID_REQUEST"
+                || attempt == PAGE_TOKEN_ATTEMPTS
+            {
+                break data;
+            }
+        };
+        let status = data["status"].as_str().unwrap_or("UNKNOWN");
+
+        if status != "OK" && status != "ZERO_RESULTS" {
+            return Err(Self::status_error(
+                status,
+                &data,
+                "Places API search failed",
+            ));
+        }
+
+        let mut services = Vec::new();
+        if let Some(results) = data["results"].as_array() {
+            for place in results.iter().take(max_results) {
+                let loc = &place["geometry"]["location"];
+                let p_lat = loc["lat"].as_f64().unwrap_or_default();
+                let p_lng = loc["lng"].as_f64().unwrap_or_default();
+
+                services.push(NearbyService {
+                    name: place["name"].as_str().unwrap_or("Unknown").to_string(),
+                    service_type: category.clone(),
+                    latitude: p_lat,
+                    longitude: p_lng,
+                    distance_km: haversine_distance(lat, lng, p_lat, p_lng),
+                    address: place
+                        .get("vicinity")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string()),
+                    rating: place
+                        .get("rating")
+                        .and_then(|r| r.as_f64())
+                        .map(|f| f as f32),
+                    place_id: place
+                        .get("place_id")
+                        .and_then(|p| p.as_str())
+                        .map(|s| s.to_string()),
+                    phone_number: place
+                        .get("international_phone_number")
+                        .and_then(|p| p.as_str())
+                        .map(|s| s.to_string()),
+                    open_now: place
+                        .get("opening_hours")
+                        .and_then(|p| p.get("open_now"))
+                        .and_then(|p| p.as_bool()),
+                    opening_hours: place
+                        .get("opening_hours")
+                        .and_then(Self::parse_opening_hours),
+                    photos: Vec::new(),
+                    service_types: Vec::new(),
+                    travel_time_min: None,
+                    departures: Vec::new(),
+                    routes: Vec::new(),
+                });
+            }
+        }
+
+        Ok(NearbyPage {
+            services,
+            next_page_token: data["next_page_token"].as_str().map(str::to_string),
+        })
+    }
+
+    async fn suggest(
+        &self,
+        partial: &str,
+        limit: usize,
+        bias: &AutocompleteBias,
+    ) -> Result<Vec<Suggestion>, MapradarError> {
+        let mut params = vec![("input", partial.to_string())];
+        if !bias.countries.is_empty() {
+            let countries: Vec<String> = bias
+                .countries
+                .iter()
+                .map(|country| format!("country:{}", country))
+                .collect();
+            params.push(("components", countries.join("|")));
+        }
+        if let Some(point) = bias.around {
+            params.push((
+                "locationbias",
+                format!("circle:{:.0}@{}", BIAS_RADIUS_KM * 1000.0, point),
+            ));
+        }
+        if let Some((min_lng, min_lat, max_lng, max_lat)) =
+            bias.bounds.as_ref().and_then(|bounds| bounds.envelope())
+        {
+            params.push((
+                "locationbias",
+                format!("rectangle:{},{}|{},{}", min_lat, min_lng, max_lat, max_lng),
+            ));
+        }
+        params.push(("key", self.api_key.clone()));
+
+        let request = self
+            .transport
+            .get(self.url(AUTOCOMPLETE_PATH))
+            .query(&params);
+        let response = self.transport.send(self.localized(request)).await?;
+
+        let data: Value = response.json().await?;
+        let status = data["status"].as_str().unwrap_or("UNKNOWN");
+
+        if status == "ZERO_RESULTS" {
+            return Ok(Vec::new());
+        }
+        if status != "OK" {
+            return Err(Self::status_error(status, &data, "Autocomplete failed"));
+        }
+
+        let predictions = data["predictions"].as_array().map(Vec::as_slice);
+        Ok(predictions
+            .unwrap_or_default()
+            .iter()
+            .take(limit)
+            .map(|prediction| {
+                let description = prediction["description"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string();
+                let formatting = &prediction["structured_formatting"];
+                Suggestion {
+                    main_text: formatting["main_text"]
+                        .as_str()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| description.clone()),
+                    secondary_text: formatting["secondary_text"].as_str().map(str::to_string),
+                    description,
+                    place_id: prediction["place_id"].as_str().map(str::to_string),
+                    types: prediction["types"]
+                        .as_array()
+                        .map(|types| {
+                            types
+                                .iter()
+                                .filter_map(|t| t.as_str().map(str::to_string))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                }
+            })
+            .collect())
+    }
+
+    async fn details(&self, place_id: &str) -> Result<PlaceDetails, MapradarError> {
+        let request = self.transport.get(self.url(DETAILS_PATH)).query(&[
+            ("place_id", place_id),
+            ("fields", DETAILS_FIELDS),
+            ("key", &self.api_key),
+        ]);
+        let response = self.transport.send(self.localized(request)).await?;
+
+        let data: Value = response.json().await?;
+        let status = data["status"].as_str().unwrap_or("UNKNOWN");
+
+        if status != "OK" {
+            return Err(Self::status_error(
+                status,
+                &data,
+                "Place details lookup failed",
+            ));
+        }
+
+        let result = &data["result"];
+        let text = |key: &str| result[key].as_str().map(str::to_string);
+        let strings = |value: &Value| -> Vec<String> {
+            value
+                .as_array()
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|item| item.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        Ok(PlaceDetails {
+            place_id: text("place_id").unwrap_or_else(|| place_id.to_string()),
+            name: text("name").unwrap_or_else(|| "Unknown".to_string()),
+            latitude: result["geometry"]["location"]["lat"]
+                .as_f64()
+                .unwrap_or_default(),
+            longitude: result["geometry"]["location"]["lng"]
+                .as_f64()
+                .unwrap_or_default(),
+            address: text("formatted_address"),
+            phone_number: text("international_phone_number"),
+            website: text("website"),
+            rating: result["rating"].as_f64().map(|f| f as f32),
+            reviews_count: result["user_ratings_total"].as_u64().map(|n| n as u32),
+            open_now: result["opening_hours"]["open_now"].as_bool(),
+            opening_hours: Self::parse_opening_hours(&result["opening_hours"]),
+            photos: result["photos"]
+                .as_array()
+                .map(|photos| {
+                    photos
+                        .iter()
+                        .filter_map(|photo| {
+                            Some(PlacePhoto {
+                                reference: photo["photo_reference"].as_str()?.to_string(),
+                                width: photo["width"].as_u64().unwrap_or_default() as u32,
+                                height: photo["height"].as_u64().unwrap_or_default() as u32,
+                                attributions: strings(&photo["html_attributions"]),
+                                url: None,
+                                path: None,
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            types: strings(&result["types"]),
+        })
+    }
+
+    /// Google answers a photo request with a redirect to the image on a keyless host; the
+    /// redirect target is the URL, so the key never appears in output.
+    async fn resolve_photo(
+        &self,
+        reference: &str,
+        max_width: u32,
+    ) -> Result<String, MapradarError> {
+        let request = self.transport.get(self.url(PHOTO_PATH)).query(&[
+            ("photo_reference", reference.to_string()),
+            ("maxwidth", max_width.to_string()),
+            ("key", self.api_key.clone()),
+        ]);
+        let response = check_http_status(self.name(), self.transport.send(request).await?)?
+            .error_for_status()?;
+
+        if response.url().path().starts_with(PHOTO_PATH) {
+            return Err(MapradarError::Api {
+                status: response.status().to_string(),
+                message: "Places Photo request was not redirected to an image".to_string(),
+            });
+        }
+        Ok(response.url().to_string())
+    }
+
+    async fn fetch_photo(&self, url: &str) -> Result<Vec<u8>, MapradarError> {
+        let response = self.transport.send(self.transport.get(url)).await?;
+        let response = check_http_status(self.name(), response)?.error_for_status()?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn render_static_map(&self, map: &StaticMap) -> Result<Vec<u8>, MapradarError> {
+        let mut params = vec![
+            ("size", format!("{}x{}", map.width, map.height)),
+            ("format", "png".to_string()),
+        ];
+        // Markers sharing a style go in one parameter, keeping the URL short.
+        let mut styles: Vec<(&str, Option<char>, Vec<String>)> = Vec::new();
+        for marker in &map.markers {
+            let point = format!("{:.6},{:.6}", marker.latitude, marker.longitude);
+            match styles
+                .iter_mut()
+                .find(|(color, label, _)| *color == marker.color && *label == marker.label)
+            {
+                Some((_, _, points)) => points.push(point),
+                None => styles.push((&marker.color, marker.label, vec![point])),
+            }
+        }
+        for (color, label, points) in styles {
+            let mut style = format!("color:{}", color);
+            if let Some(label) = label {
+                style.push_str(&format!("|label:{}", label));
+            }
+            params.push(("markers", format!("{}|{}", style, points.join("|"))));
+        }
+        if let Some(circle) = &map.circle {
+            let outline: Vec<String> = circle
+                .outline()
+                .iter()
+                .map(|(lat, lng)| format!("{:.6},{:.6}", lat, lng))
+                .collect();
+            params.push((
+                "path",
+                format!(
+                    "color:{}|weight:2|fillcolor:{}|{}",
+                    CIRCLE_STROKE,
+                    CIRCLE_FILL,
+                    outline.join("|")
+                ),
+            ));
+        }
+        params.push(("key", self.api_key.clone()));
+
+        let request = self.transport.get(self.url(STATIC_MAP_PATH)).query(&params);
+        let response = check_http_status(
+            self.name(),
+            self.transport.send(self.localized(request)).await?,
+        )?;
+        let status = response.status();
+        let is_image = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("image/"));
+        if !status.is_success() || !is_image {
+            return Err(MapradarError::Api {
+                status: status.to_string(),
+                message: response.text().await.unwrap_or_default().trim().to_string(),
+            });
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Reverse geocodes a point restricted to political area types, one result per area. Google
+    /// publishes no boundary polygons, so the areas carry no geometry.
+    async fn admin_hierarchy(&self, lat: f64, lng: f64) -> Result<Vec<AdminArea>, MapradarError> {
+        let result_type = ADMIN_LEVELS
+            .iter()
+            .map(|(place_type, _)| *place_type)
+            .collect::<Vec<_>>()
+            .join("|");
+        let results = self
+            .fetch_results(
+                &[
+                    ("latlng", format!("{},{}", lat, lng)),
+                    ("result_type", result_type),
+                ],
+                "Administrative area lookup failed",
+            )
+            .await?;
+
+        Ok(results
+            .iter()
+            .filter_map(|result| {
+                let types = result["types"].as_array()?;
+                let admin_level = ADMIN_LEVELS.iter().find_map(|(place_type, level)| {
+                    types
+                        .iter()
+                        .any(|t| t.as_str() == Some(place_type))
+                        .then_some(*level)
+                })?;
+                let component = &result["address_components"][0];
+                Some(AdminArea {
+                    id: result["place_id"].as_str()?.to_string(),
+                    name: component["long_name"].as_str()?.to_string(),
+                    admin_level,
+                    code: (admin_level == 2)
+                        .then(|| component["short_name"].as_str().map(str::to_string))
+                        .flatten(),
+                    geometry: None,
+                })
+            })
+            .collect())
+    }
+
+    async fn time_zone(&self, lat: f64, lng: f64) -> Result<TimeZoneInfo, MapradarError> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let request = self.transport.get(self.url(TIMEZONE_PATH)).query(&[
+            ("location", format!("{},{}", lat, lng)),
+            ("timestamp", timestamp.to_string()),
+            ("key", self.api_key.clone()),
+        ]);
+        let response = self.transport.send(request).await?;
+
+        let data: Value = response.json().await?;
+        let status = data["status"].as_str().unwrap_or("UNKNOWN");
+
+        if status != "OK" {
+            return Err(Self::status_error(status, &data, "Time zone lookup failed"));
+        }
+
+        let dst_offset = data["dstOffset"].as_i64().unwrap_or_default();
+        Ok(TimeZoneInfo {
+            id: data["timeZoneId"].as_str().unwrap_or_default().to_string(),
+            utc_offset_secs: (data["rawOffset"].as_i64().unwrap_or_default() + dst_offset) as i32,
+            dst: dst_offset != 0,
+        })
+    }
+
+    async fn elevations(&self, points: &[(f64, f64)]) -> Result<Vec<f64>, MapradarError> {
+        let mut elevations = Vec::with_capacity(points.len());
+        for chunk in points.chunks(ELEVATION_MAX_LOCATIONS) {
+            let locations = chunk
+                .iter()
+                .map(|(lat, lng)| format!("{},{}", lat, lng))
+                .collect::<Vec<_>>()
+                .join("|");
+            let request = self
+                .transport
+                .get(self.url(ELEVATION_PATH))
+                .query(&[("locations", locations), ("key", self.api_key.clone())]);
+            let response = self.transport.send(request).await?;
+
+            let data: Value = response.json().await?;
+            let status = data["status"].as_str().unwrap_or("UNKNOWN");
+
+            if status != "OK" {
+                return Err(Self::status_error(
+                    status,
+                    &data,
+                    "Elevation request failed",
+                ));
+            }
+
+            let results = data["results"].as_array().map(Vec::as_slice);
+            elevations.extend(
+                results
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|result| result["elevation"].as_f64().unwrap_or_default()),
+            );
+        }
+        Ok(elevations)
+    }
+
+    async fn snapped_points(
+        &self,
+        points: &[(f64, f64)],
+    ) -> Result<Vec<SnappedPoint>, MapradarError> {
+        let mut snapped = Vec::with_capacity(points.len());
+        for (chunk_index, chunk) in points.chunks(SNAP_MAX_POINTS).enumerate() {
+            let offset = chunk_index * SNAP_MAX_POINTS;
+            let path = chunk
+                .iter()
+                .map(|(lat, lng)| format!("{},{}", lat, lng))
+                .collect::<Vec<_>>()
+                .join("|");
+            let request = self
+                .transport
+                .get(self.roads_url(SNAP_TO_ROADS_PATH))
+                .query(&[("path", path), ("key", self.api_key.clone())]);
+            let response = check_http_status(self.name(), self.transport.send(request).await?)?;
+
+            let data: Value = response.json().await?;
+            if let Some(error) = data.get("error") {
+                return Err(MapradarError::Api {
+                    status: error["status"].as_str().unwrap_or("UNKNOWN").to_string(),
+                    message: error["message"]
+                        .as_str()
+                        .unwrap_or("Snap to road request failed")
+                        .to_string(),
+                });
+            }
+
+            // Each point snaps at most once without interpolation; points missing from the
+            // response had no road nearby.
+            let mut by_index: HashMap<usize, &Value> = HashMap::new();
+            for point in data["snappedPoints"].as_array().into_iter().flatten() {
+                if let Some(index) = point["originalIndex"].as_u64() {
+                    by_index.entry(index as usize).or_insert(point);
+                }
+            }
+            snapped.extend(chunk.iter().enumerate().map(|(i, &original)| {
+                let location = by_index.get(&i).map(|point| &point["location"]);
+                match location.and_then(|l| l["latitude"].as_f64().zip(l["longitude"].as_f64())) {
+                    Some(position) => SnappedPoint {
+                        place_id: by_index[&i]["placeId"].as_str().map(str::to_string),
+                        ..SnappedPoint::snapped(offset + i, original, position)
+                    },
+                    None => SnappedPoint::unsnapped(offset + i, original),
+                }
+            }));
+        }
+        Ok(snapped)
+    }
+
+    fn mode_param(mode: TravelMode) -> &'static str {
+        match mode {
+            TravelMode::Driving => "driving",
+            TravelMode::Walking => "walking",
+            TravelMode::Cycling => "bicycling",
+        }
+    }
+
+    fn join_points(points: &[(f64, f64)]) -> String {
+        points
+            .iter()
+            .map(|(lat, lng)| format!("{},{}", lat, lng))
+            .collect::<Vec<_>>()
+            .join("|")
+    }
+
+    /// Requests the matrix in chunks that respect the Distance Matrix API element limits.
+    async fn matrix(
+        &self,
+        origins: &[(f64, f64)],
+        destinations: &[(f64, f64)],
+        mode: TravelMode,
+    ) -> Result<Vec<Vec<MatrixElement>>, MapradarError> {
+        let mut rows = vec![Vec::with_capacity(destinations.len()); origins.len()];
+
+        for destination_chunk in destinations.chunks(MATRIX_MAX_DESTINATIONS) {
+            let origins_per_request = (MATRIX_MAX_ELEMENTS / destination_chunk.len()).max(1);
+
+            for (chunk_index, origin_chunk) in origins.chunks(origins_per_request).enumerate() {
+                let chunk_rows = self
+                    .matrix_request(origin_chunk, destination_chunk, mode)
+                    .await?;
+                for (offset, row) in chunk_rows.into_iter().enumerate() {
+                    rows[chunk_index * origins_per_request + offset].extend(row);
+                }
+            }
+        }
+
+        Ok(rows)
+    }
+
+    async fn matrix_request(
+        &self,
+        origins: &[(f64, f64)],
+        destinations: &[(f64, f64)],
+        mode: TravelMode,
+    ) -> Result<Vec<Vec<MatrixElement>>, MapradarError> {
+        let request = self.transport.get(self.url(DISTANCE_MATRIX_PATH)).query(&[
+            ("origins", Self::join_points(origins)),
+            ("destinations", Self::join_points(destinations)),
+            ("mode", Self::mode_param(mode).to_string()),
+            ("key", self.api_key.clone()),
+        ]);
+        let response = self.transport.send(request).await?;
+
+        let data: Value = response.json().await?;
+        let status = data["status"].as_str().unwrap_or("UNKNOWN");
+
+        if status != "OK" {
+            return Err(Self::status_error(
+                status,
+                &data,
+                "Distance Matrix request failed",
+            ));
+        }
+
+        let rows = data["rows"]
+            .as_array()
+            .map(|rows| {
+                rows.iter()
+                    .map(|row| {
+                        row["elements"]
+                            .as_array()
+                            .map(|elements| elements.iter().map(Self::parse_element).collect())
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(rows)
+    }
+
+    fn parse_element(element: &Value) -> MatrixElement {
+        match element["status"].as_str().unwrap_or("UNKNOWN") {
+            "OK" => MatrixElement::ok(
+                element["distance"]["value"].as_f64().unwrap_or_default() / 1000.0,
+                element["duration"]["value"].as_u64(),
+            ),
+            status => MatrixElement::failed(status),
+        }
+    }
+
+    async fn directions(
+        &self,
+        origin: (f64, f64),
+        destination: (f64, f64),
+        mode: TravelMode,
+    ) -> Result<Route, MapradarError> {
+        let request = self
+            .localized(self.transport.get(self.url(DIRECTIONS_PATH)))
+            .query(&[
+                ("origin", format!("{},{}", origin.0, origin.1)),
+                (
+                    "destination",
+                    format!("{},{}", destination.0, destination.1),
+                ),
+                ("mode", Self::mode_param(mode).to_string()),
+                ("key", self.api_key.clone()),
+            ]);
+        let response = self.transport.send(request).await?;
+
+        let data: Value = response.json().await?;
+        let status = data["status"].as_str().unwrap_or("UNKNOWN");
+
+        if status != "OK" {
+            return Err(Self::status_error(
+                status,
+                &data,
+                "Directions request failed",
+            ));
+        }
+
+        let route = &data["routes"][0];
+        let leg = &route["legs"][0];
+        let steps = leg["steps"]
+            .as_array()
+            .map(|steps| {
+                steps
+                    .iter()
+                    .map(|step| RouteStep {
+                        instruction: strip_html_tags(
+                            step["html_instructions"].as_str().unwrap_or_default(),
+                        ),
+                        distance_km: step["distance"]["value"].as_f64().unwrap_or_default()
+                            / 1000.0,
+                        duration_secs: step["duration"]["value"].as_u64().unwrap_or_default(),
+                        start_latitude: step["start_location"]["lat"].as_f64().unwrap_or_default(),
+                        start_longitude: step["start_location"]["lng"].as_f64().unwrap_or_default(),
+                        end_latitude: step["end_location"]["lat"].as_f64().unwrap_or_default(),
+                        end_longitude: step["end_location"]["lng"].as_f64().unwrap_or_default(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Route {
+            mode,
+            distance_km: leg["distance"]["value"].as_f64().unwrap_or_default() / 1000.0,
+            duration_secs: leg["duration"]["value"].as_u64().unwrap_or_default(),
+            polyline: route["overview_polyline"]["points"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            steps,
+        })
+    }
+}
+
+impl GeocodingProvider for GoogleProvider {
+    fn name(&self) -> &'static str {
+        "google"
+    }
+
+    fn geocode<'a>(
+        &'a self,
+        address: &'a str,
+    ) -> BoxFuture<'a, Result<GeoLocation, MapradarError>> {
+        Box::pin(async move {
+            self.fetch_location(&[("address", address.to_string())], "Geocoding failed")
+                .await
+        })
+    }
+
+    fn geocode_all<'a>(
+        &'a self,
+        address: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<GeocodeCandidate>, MapradarError>> {
+        Box::pin(async move {
+            self.fetch_results(&[("address", address.to_string())], "Geocoding failed")
+                .await?
+                .iter()
+                .map(|result| self.parse_candidate(result))
+                .collect()
+        })
+    }
+
+    fn geocode_in<'a>(
+        &'a self,
+        address: &'a str,
+        region: &'a SearchRegion,
+    ) -> BoxFuture<'a, Result<GeoLocation, MapradarError>> {
+        Box::pin(async move { self.parse_result(&self.fetch_in_region(address, region).await?[0]) })
+    }
+
+    fn geocode_all_in<'a>(
+        &'a self,
+        address: &'a str,
+        region: &'a SearchRegion,
+    ) -> BoxFuture<'a, Result<Vec<GeocodeCandidate>, MapradarError>> {
+        Box::pin(async move {
+            self.fetch_in_region(address, region)
+                .await?
+                .iter()
+                .map(|result| self.parse_candidate(result))
+                .collect()
+        })
+    }
+
+    fn postcode_area<'a>(
+        &'a self,
+        postcode: &'a str,
+        country: &'a str,
+    ) -> BoxFuture<'a, Result<PostcodeArea, MapradarError>> {
+        Box::pin(self.fetch_postcode(postcode, country))
+    }
+
+    fn reverse_geocode(
+        &self,
+        lat: f64,
+        lng: f64,
+    ) -> BoxFuture<'_, Result<GeoLocation, MapradarError>> {
+        Box::pin(async move {
+            self.fetch_location(
+                &[("latlng", format!("{},{}", lat, lng))],
+                "Reverse geocoding failed",
+            )
+            .await
+        })
+    }
+
+    fn elevation<'a>(
+        &'a self,
+        points: &'a [(f64, f64)],
+    ) -> BoxFuture<'a, Result<Vec<f64>, MapradarError>> {
+        Box::pin(self.elevations(points))
+    }
+
+    fn snap_to_road<'a>(
+        &'a self,
+        points: &'a [(f64, f64)],
+    ) -> BoxFuture<'a, Result<Vec<SnappedPoint>, MapradarError>> {
+        Box::pin(self.snapped_points(points))
+    }
+
+    fn ip_location(&self, ip: Option<IpAddr>) -> BoxFuture<'_, Result<GeoLocation, MapradarError>> {
+        Box::pin(super::ipapi::locate(&self.transport, ip))
+    }
+
+    fn admin_areas(
+        &self,
+        lat: f64,
+        lng: f64,
+        _with_geometry: bool,
+    ) -> BoxFuture<'_, Result<Vec<AdminArea>, MapradarError>> {
+        Box::pin(self.admin_hierarchy(lat, lng))
+    }
+
+    fn timezone(&self, lat: f64, lng: f64) -> BoxFuture<'_, Result<TimeZoneInfo, MapradarError>> {
+        Box::pin(self.time_zone(lat, lng))
+    }
+
+    fn nearby_search<'a>(
+        &'a self,
+        lat: f64,
+        lng: f64,
+        category: &'a PlaceCategory,
+        radius_meters: f64,
+        max_results: usize,
+    ) -> BoxFuture<'a, Result<Vec<NearbyService>, MapradarError>> {
+        Box::pin(async move {
+            self.search_places(lat, lng, category, radius_meters, max_results, None)
+                .await
+                .map(|page| page.services)
+        })
+    }
+
+    fn nearby_search_page<'a>(
+        &'a self,
+        lat: f64,
+        lng: f64,
+        category: &'a PlaceCategory,
+        radius_meters: f64,
+        max_results: usize,
+        page_token: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<NearbyPage, MapradarError>> {
+        Box::pin(self.search_places(lat, lng, category, radius_meters, max_results, page_token))
+    }
+
+    fn place_details<'a>(
+        &'a self,
+        place_id: &'a str,
+    ) -> BoxFuture<'a, Result<PlaceDetails, MapradarError>> {
+        Box::pin(self.details(place_id))
+    }
+
+    fn photo_url<'a>(
+        &'a self,
+        reference: &'a str,
+        max_width: u32,
+    ) -> BoxFuture<'a, Result<String, MapradarError>> {
+        Box::pin(self.resolve_photo(reference, max_width))
+    }
+
+    fn download_photo<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<Vec<u8>, MapradarError>> {
+        Box::pin(self.fetch_photo(url))
+    }
+
+    fn static_map<'a>(
+        &'a self,
+        map: &'a StaticMap,
+    ) -> BoxFuture<'a, Result<Vec<u8>, MapradarError>> {
+        Box::pin(self.render_static_map(map))
+    }
+
+    fn route(
+        &self,
+        origin: (f64, f64),
+        destination: (f64, f64),
+        mode: TravelMode,
+    ) -> BoxFuture<'_, Result<Route, MapradarError>> {
+        Box::pin(self.directions(origin, destination, mode))
+    }
+
+    fn distance_matrix<'a>(
+        &'a self,
+        origins: &'a [(f64, f64)],
+        destinations: &'a [(f64, f64)],
+        mode: TravelMode,
+    ) -> BoxFuture<'a, Result<Vec<Vec<MatrixElement>>, MapradarError>> {
+        Box::pin(self.matrix(origins, destinations, mode))
+    }
+
+    fn autocomplete<'a>(
+        &'a self,
+        partial: &'a str,
+        limit: usize,
+        bias: &'a AutocompleteBias,
+    ) -> BoxFuture<'a, Result<Vec<Suggestion>, MapradarError>> {
+        Box::pin(self.suggest(partial, limit, bias))
+    }
+}
@@ -0,0 +1,83 @@
+//! IP geolocation through the keyless ipapi.co service, shared by every provider.
+//!
+//! Locations are city-level approximations of where an address block is registered, so they can
+//! be tens of kilometres off and are unreliable for VPN or mobile carrier addresses.
+
+use std::net::IpAddr;
+
+use serde_json::Value;
+
+use super::check_http_status;
+use crate::{
+    error::MapradarError,
+    models::{AddressComponents, GeoLocation},
+    transport::Transport,
+};
+
+const IPAPI_URL: &str = "https://ipapi.co";
+
+/// Locates `ip`, or the caller's public address when `None`.
+pub(crate) async fn locate(
+    transport: &Transport,
+    ip: Option<IpAddr>,
+) -> Result<GeoLocation, MapradarError> {
+    let url = match ip {
+        Some(ip) => format!("{}/{}/json/", IPAPI_URL, ip),
+        None => format!("{}/json/", IPAPI_URL),
+    };
+    let response = check_http_status("ipapi", transport.send(transport.get(url)).await?)?;
+    let response = response.error_for_status()?;
+
+    let data: Value = response.json().await?;
+    let query = data["ip"]
+        .as_str()
+        .map(str::to_string)
+        .or(ip.map(|ip| ip.to_string()))
+        .unwrap_or_default();
+    if data["error"].as_bool() == Some(true) {
+        let reason = data["reason"].as_str().unwrap_or("IP lookup failed");
+        return Err(if data["reserved"].as_bool() == Some(true) {
+            MapradarError::InvalidInput(format!("{} is a {}", query, reason.to_lowercase()))
+        } else {
+            MapradarError::Api {
+                status: "IP_LOOKUP_FAILED".to_string(),
+                message: reason.to_string(),
+            }
+        });
+    }
+
+    let (Some(latitude), Some(longitude)) = (data["latitude"].as_f64(), data["longitude"].as_f64())
+    else {
+        return Err(MapradarError::AddressNotFound { query });
+    };
+    let text = |key: &str| {
+        data[key]
+            .as_str()
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    let components = AddressComponents {
+        city: text("city"),
+        state: text("region"),
+        postcode: text("postal"),
+        country_code: text("country_code"),
+        ..AddressComponents::default()
+    };
+
+    Ok(GeoLocation {
+        address: [text("city"), text("region"), text("country_name")]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(", "),
+        latitude,
+        longitude,
+        city: components.city.clone(),
+        state: components.state.clone(),
+        country: components.country_code.clone().unwrap_or_default(),
+        address_components: Some(components),
+        timezone: None,
+        elevation_m: None,
+        language: None,
+    })
+}
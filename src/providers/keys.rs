@@ -0,0 +1,321 @@
+//! Spreading requests over several API keys.
+//!
+//! A [`KeyPool`] holds one provider per key. Each request goes to a key chosen by the pool's
+//! [`KeyRotation`]; when the provider rejects a key ([`MapradarError::AuthFailed`]) or reports it
+//! out of quota ([`MapradarError::QuotaExceeded`]), the request is retried with the next key and
+//! the rejected one is skipped for [`KEY_COOLDOWN`].
+
+use futures::future::BoxFuture;
+use std::{
+    fmt,
+    net::IpAddr,
+    str::FromStr,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use super::{GeocodingProvider, NearbyPage};
+use crate::{
+    error::MapradarError,
+    models::{
+        AdminArea, AutocompleteBias, GeoLocation, GeocodeCandidate, MatrixElement, NearbyService,
+        PlaceCategory, PlaceDetails, PostcodeArea, Route, SearchRegion, SnappedPoint, Suggestion,
+        TimeZoneInfo, TravelMode,
+    },
+    staticmap::StaticMap,
+};
+
+/// How long a rejected or exhausted key is passed over before it is tried again.
+pub const KEY_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Order in which a [`KeyPool`] uses its keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyRotation {
+    /// Each request starts at the key after the one the previous request started at.
+    #[default]
+    RoundRobin,
+    /// Every request starts at the first key; later keys are used only while earlier ones are
+    /// rejected or out of quota.
+    Failover,
+}
+
+impl FromStr for KeyRotation {
+    type Err = MapradarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "round-robin" | "round_robin" | "roundrobin" => Ok(Self::RoundRobin),
+            "failover" => Ok(Self::Failover),
+            other => Err(MapradarError::Config(format!(
+                "Unknown key rotation '{}' (expected round-robin or failover)",
+                other
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for KeyRotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RoundRobin => write!(f, "round-robin"),
+            Self::Failover => write!(f, "failover"),
+        }
+    }
+}
+
+/// Provider that rotates requests over one inner provider per API key.
+pub struct KeyPool<P> {
+    providers: Vec<P>,
+    rotation: KeyRotation,
+    next: AtomicUsize,
+    /// When each key may be used again after a rejection.
+    cooling_until: Mutex<Vec<Option<Instant>>>,
+}
+
+impl<P: GeocodingProvider> KeyPool<P> {
+    /// Pools `providers`, which should differ only in their key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `providers` is empty.
+    pub fn new(providers: Vec<P>, rotation: KeyRotation) -> Self {
+        assert!(!providers.is_empty(), "a key pool needs at least one key");
+        Self {
+            cooling_until: Mutex::new(vec![None; providers.len()]),
+            providers,
+            rotation,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    pub fn rotation(&self) -> KeyRotation {
+        self.rotation
+    }
+
+    /// Key indices to try for one request, in order. Keys cooling down come last, so a request
+    /// still goes out when every key was recently rejected.
+    fn order(&self) -> Vec<usize> {
+        let len = self.providers.len();
+        let start = match self.rotation {
+            KeyRotation::RoundRobin => self.next.fetch_add(1, Ordering::Relaxed) % len,
+            KeyRotation::Failover => 0,
+        };
+        let now = Instant::now();
+        let cooling = self.cooling_until.lock().map(|until| {
+            until
+                .iter()
+                .map(|until| until.is_some_and(|until| until > now))
+                .collect::<Vec<_>>()
+        });
+        let mut order: Vec<usize> = (0..len).map(|i| (start + i) % len).collect();
+        if let Ok(cooling) = cooling {
+            order.sort_by_key(|&i| cooling[i]);
+        }
+        order
+    }
+
+    fn cool_down(&self, index: usize) {
+        if let Ok(mut until) = self.cooling_until.lock() {
+            until[index] = Some(Instant::now() + KEY_COOLDOWN);
+        }
+    }
+
+    /// Runs `call` with each key in rotation order until one is not rejected.
+    fn with_keys<'a, T: Send + 'a>(
+        &'a self,
+        call: impl Fn(&'a P) -> BoxFuture<'a, Result<T, MapradarError>> + Send + 'a,
+    ) -> BoxFuture<'a, Result<T, MapradarError>> {
+        Box::pin(async move {
+            let order = self.order();
+            let last = order.len() - 1;
+            for (attempt, index) in order.into_iter().enumerate() {
+                match call(&self.providers[index]).await {
+                    Err(
+                        err @ (MapradarError::AuthFailed { .. }
+                        | MapradarError::QuotaExceeded { .. }),
+                    ) => {
+                        self.cool_down(index);
+                        if attempt == last {
+                            return Err(err);
+                        }
+                        tracing::warn!(key = index, error = %err, "API key rejected, trying the next");
+                    }
+                    result => return result,
+                }
+            }
+            unreachable!("a key pool has at least one key")
+        })
+    }
+}
+
+impl<P: GeocodingProvider> GeocodingProvider for KeyPool<P> {
+    fn name(&self) -> &'static str {
+        self.providers[0].name()
+    }
+
+    fn geocode<'a>(
+        &'a self,
+        address: &'a str,
+    ) -> BoxFuture<'a, Result<GeoLocation, MapradarError>> {
+        self.with_keys(move |p| p.geocode(address))
+    }
+
+    fn geocode_all<'a>(
+        &'a self,
+        address: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<GeocodeCandidate>, MapradarError>> {
+        self.with_keys(move |p| p.geocode_all(address))
+    }
+
+    fn geocode_in<'a>(
+        &'a self,
+        address: &'a str,
+        region: &'a SearchRegion,
+    ) -> BoxFuture<'a, Result<GeoLocation, MapradarError>> {
+        self.with_keys(move |p| p.geocode_in(address, region))
+    }
+
+    fn geocode_all_in<'a>(
+        &'a self,
+        address: &'a str,
+        region: &'a SearchRegion,
+    ) -> BoxFuture<'a, Result<Vec<GeocodeCandidate>, MapradarError>> {
+        self.with_keys(move |p| p.geocode_all_in(address, region))
+    }
+
+    fn postcode_area<'a>(
+        &'a self,
+        postcode: &'a str,
+        country: &'a str,
+    ) -> BoxFuture<'a, Result<PostcodeArea, MapradarError>> {
+        self.with_keys(move |p| p.postcode_area(postcode, country))
+    }
+
+    fn reverse_geocode(
+        &self,
+        lat: f64,
+        lng: f64,
+    ) -> BoxFuture<'_, Result<GeoLocation, MapradarError>> {
+        self.with_keys(move |p| p.reverse_geocode(lat, lng))
+    }
+
+    fn ip_location(&self, ip: Option<IpAddr>) -> BoxFuture<'_, Result<GeoLocation, MapradarError>> {
+        self.with_keys(move |p| p.ip_location(ip))
+    }
+
+    fn admin_areas(
+        &self,
+        lat: f64,
+        lng: f64,
+        with_geometry: bool,
+    ) -> BoxFuture<'_, Result<Vec<AdminArea>, MapradarError>> {
+        self.with_keys(move |p| p.admin_areas(lat, lng, with_geometry))
+    }
+
+    fn timezone(&self, lat: f64, lng: f64) -> BoxFuture<'_, Result<TimeZoneInfo, MapradarError>> {
+        self.with_keys(move |p| p.timezone(lat, lng))
+    }
+
+    fn elevation<'a>(
+        &'a self,
+        points: &'a [(f64, f64)],
+    ) -> BoxFuture<'a, Result<Vec<f64>, MapradarError>> {
+        self.with_keys(move |p| p.elevation(points))
+    }
+
+    fn snap_to_road<'a>(
+        &'a self,
+        points: &'a [(f64, f64)],
+    ) -> BoxFuture<'a, Result<Vec<SnappedPoint>, MapradarError>> {
+        self.with_keys(move |p| p.snap_to_road(points))
+    }
+
+    fn nearby_search<'a>(
+        &'a self,
+        lat: f64,
+        lng: f64,
+        category: &'a PlaceCategory,
+        radius_meters: f64,
+        max_results: usize,
+    ) -> BoxFuture<'a, Result<Vec<NearbyService>, MapradarError>> {
+        self.with_keys(move |p| p.nearby_search(lat, lng, category, radius_meters, max_results))
+    }
+
+    fn nearby_search_page<'a>(
+        &'a self,
+        lat: f64,
+        lng: f64,
+        category: &'a PlaceCategory,
+        radius_meters: f64,
+        max_results: usize,
+        page_token: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<NearbyPage, MapradarError>> {
+        self.with_keys(move |p| {
+            p.nearby_search_page(lat, lng, category, radius_meters, max_results, page_token)
+        })
+    }
+
+    fn place_details<'a>(
+        &'a self,
+        place_id: &'a str,
+    ) -> BoxFuture<'a, Result<PlaceDetails, MapradarError>> {
+        self.with_keys(move |p| p.place_details(place_id))
+    }
+
+    fn photo_url<'a>(
+        &'a self,
+        reference: &'a str,
+        max_width: u32,
+    ) -> BoxFuture<'a, Result<String, MapradarError>> {
+        self.with_keys(move |p| p.photo_url(reference, max_width))
+    }
+
+    fn download_photo<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<Vec<u8>, MapradarError>> {
+        self.with_keys(move |p| p.download_photo(url))
+    }
+
+    fn static_map<'a>(
+        &'a self,
+        map: &'a StaticMap,
+    ) -> BoxFuture<'a, Result<Vec<u8>, MapradarError>> {
+        self.with_keys(move |p| p.static_map(map))
+    }
+
+    fn route(
+        &self,
+        origin: (f64, f64),
+        destination: (f64, f64),
+        mode: TravelMode,
+    ) -> BoxFuture<'_, Result<Route, MapradarError>> {
+        self.with_keys(move |p| p.route(origin, destination, mode))
+    }
+
+    fn distance_matrix<'a>(
+        &'a self,
+        origins: &'a [(f64, f64)],
+        destinations: &'a [(f64, f64)],
+        mode: TravelMode,
+    ) -> BoxFuture<'a, Result<Vec<Vec<MatrixElement>>, MapradarError>> {
+        self.with_keys(move |p| p.distance_matrix(origins, destinations, mode))
+    }
+
+    fn autocomplete<'a>(
+        &'a self,
+        partial: &'a str,
+        limit: usize,
+        bias: &'a AutocompleteBias,
+    ) -> BoxFuture<'a, Result<Vec<Suggestion>, MapradarError>> {
+        self.with_keys(move |p| p.autocomplete(partial, limit, bias))
+    }
+}
@@ -0,0 +1,334 @@
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::{fmt, net::IpAddr, str::FromStr};
+
+use crate::{
+    address::postcodes_match,
+    error::MapradarError,
+    models::{
+        AdminArea, AutocompleteBias, GeoLocation, GeocodeCandidate, MatrixElement, NearbyService,
+        PlaceCategory, PlaceDetails, PostcodeArea, Route, SearchRegion, SnappedPoint, Suggestion,
+        TimeZoneInfo, TravelMode,
+    },
+    staticmap::StaticMap,
+};
+
+pub mod google;
+mod ipapi;
+pub mod keys;
+pub mod nominatim;
+#[cfg(feature = "offline")]
+pub mod offline;
+
+pub use google::GoogleProvider;
+pub use keys::{KeyPool, KeyRotation};
+pub use nominatim::NominatimProvider;
+#[cfg(feature = "offline")]
+pub use offline::OfflineProvider;
+
+/// An upstream service that resolves addresses and searches for nearby places.
+///
+/// Implementations return boxed futures so the client can hold any provider as a trait object.
+pub trait GeocodingProvider: Send + Sync {
+    /// Short identifier used in logs and error messages.
+    fn name(&self) -> &'static str;
+
+    /// Converts an address into a location.
+    fn geocode<'a>(&'a self, address: &'a str)
+    -> BoxFuture<'a, Result<GeoLocation, MapradarError>>;
+
+    /// Returns every location the provider considers a match for `address`.
+    fn geocode_all<'a>(
+        &'a self,
+        _address: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<GeocodeCandidate>, MapradarError>> {
+        unsupported(self.name(), "multiple geocoding candidates")
+    }
+
+    /// Converts an address into a location within `region`.
+    ///
+    /// Providers that cannot restrict a search natively need not override this; the default
+    /// geocodes as usual, ignoring the bias, and fails when the match is outside
+    /// `region.countries`.
+    fn geocode_in<'a>(
+        &'a self,
+        address: &'a str,
+        region: &'a SearchRegion,
+    ) -> BoxFuture<'a, Result<GeoLocation, MapradarError>> {
+        Box::pin(async move {
+            let location = self.geocode(address).await?;
+            if region.allows(&location) {
+                Ok(location)
+            } else {
+                Err(MapradarError::AddressNotFound {
+                    query: address.to_string(),
+                })
+            }
+        })
+    }
+
+    /// Returns every match for `address` within `region`; the default keeps the
+    /// [`geocode_all`](Self::geocode_all) candidates in `region.countries`.
+    fn geocode_all_in<'a>(
+        &'a self,
+        address: &'a str,
+        region: &'a SearchRegion,
+    ) -> BoxFuture<'a, Result<Vec<GeocodeCandidate>, MapradarError>> {
+        Box::pin(async move {
+            let mut candidates = self.geocode_all(address).await?;
+            candidates.retain(|candidate| region.allows(&candidate.location));
+            Ok(candidates)
+        })
+    }
+
+    /// Looks up the centre, extent, and localities of a postal code in `country`.
+    ///
+    /// The default geocodes the postcode within the country and merges the matches that report
+    /// it as their postcode.
+    fn postcode_area<'a>(
+        &'a self,
+        postcode: &'a str,
+        country: &'a str,
+    ) -> BoxFuture<'a, Result<PostcodeArea, MapradarError>> {
+        Box::pin(async move {
+            let region = SearchRegion::in_countries([country])?;
+            let mut candidates = self.geocode_all_in(postcode, &region).await?;
+            candidates.retain(|candidate| {
+                candidate
+                    .location
+                    .components()
+                    .postcode
+                    .is_some_and(|found| postcodes_match(postcode, &found))
+            });
+            PostcodeArea::from_candidates(postcode, country, &candidates).ok_or_else(|| {
+                MapradarError::AddressNotFound {
+                    query: postcode.to_string(),
+                }
+            })
+        })
+    }
+
+    /// Converts coordinates into a location with a human-readable address.
+    fn reverse_geocode(
+        &self,
+        lat: f64,
+        lng: f64,
+    ) -> BoxFuture<'_, Result<GeoLocation, MapradarError>>;
+
+    /// Approximates the location of an IP address, or of the caller's public address when `None`.
+    fn ip_location(
+        &self,
+        _ip: Option<IpAddr>,
+    ) -> BoxFuture<'_, Result<GeoLocation, MapradarError>> {
+        unsupported(self.name(), "IP geolocation")
+    }
+
+    /// Lists the administrative areas containing a point, in any order, with their boundary
+    /// polygons when `with_geometry` is set and the provider has them.
+    fn admin_areas(
+        &self,
+        _lat: f64,
+        _lng: f64,
+        _with_geometry: bool,
+    ) -> BoxFuture<'_, Result<Vec<AdminArea>, MapradarError>> {
+        unsupported(self.name(), "administrative boundaries")
+    }
+
+    /// Looks up the time zone currently in effect at a point.
+    fn timezone(&self, _lat: f64, _lng: f64) -> BoxFuture<'_, Result<TimeZoneInfo, MapradarError>> {
+        unsupported(self.name(), "time zone lookup")
+    }
+
+    /// Looks up ground elevation in meters for each `(lat, lng)` point, in input order.
+    fn elevation<'a>(
+        &'a self,
+        _points: &'a [(f64, f64)],
+    ) -> BoxFuture<'a, Result<Vec<f64>, MapradarError>> {
+        unsupported(self.name(), "elevation lookup")
+    }
+
+    /// Moves each `(lat, lng)` GPS point onto the nearest road, in input order. Points with no
+    /// road nearby are returned [unsnapped](SnappedPoint::unsnapped).
+    fn snap_to_road<'a>(
+        &'a self,
+        _points: &'a [(f64, f64)],
+    ) -> BoxFuture<'a, Result<Vec<SnappedPoint>, MapradarError>> {
+        unsupported(self.name(), "snap to road")
+    }
+
+    /// Finds up to `max_results` places of `category` within `radius_meters`.
+    fn nearby_search<'a>(
+        &'a self,
+        lat: f64,
+        lng: f64,
+        category: &'a PlaceCategory,
+        radius_meters: f64,
+        max_results: usize,
+    ) -> BoxFuture<'a, Result<Vec<NearbyService>, MapradarError>>;
+
+    /// Fetches one page of a nearby search: the first when `page_token` is `None`, otherwise
+    /// the page after the one that returned the token.
+    ///
+    /// Providers that return everything in one response need not override this; the default
+    /// answers with [`nearby_search`](Self::nearby_search) and no further pages.
+    fn nearby_search_page<'a>(
+        &'a self,
+        lat: f64,
+        lng: f64,
+        category: &'a PlaceCategory,
+        radius_meters: f64,
+        max_results: usize,
+        _page_token: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<NearbyPage, MapradarError>> {
+        Box::pin(async move {
+            Ok(NearbyPage {
+                services: self
+                    .nearby_search(lat, lng, category, radius_meters, max_results)
+                    .await?,
+                next_page_token: None,
+            })
+        })
+    }
+
+    /// Looks up phone, website, hours, and photos for a place ID from a nearby search.
+    fn place_details<'a>(
+        &'a self,
+        _place_id: &'a str,
+    ) -> BoxFuture<'a, Result<PlaceDetails, MapradarError>> {
+        unsupported(self.name(), "place details")
+    }
+
+    /// Resolves a photo reference from [`PlaceDetails::photos`] to an image URL of at most
+    /// `max_width` pixels that can be fetched without credentials.
+    fn photo_url<'a>(
+        &'a self,
+        _reference: &'a str,
+        _max_width: u32,
+    ) -> BoxFuture<'a, Result<String, MapradarError>> {
+        unsupported(self.name(), "place photos")
+    }
+
+    /// Downloads the image behind a URL from [`photo_url`](Self::photo_url).
+    fn download_photo<'a>(
+        &'a self,
+        _url: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<u8>, MapradarError>> {
+        unsupported(self.name(), "place photos")
+    }
+
+    /// Renders a static map image (PNG) with the map's markers and circle.
+    fn static_map<'a>(
+        &'a self,
+        _map: &'a StaticMap,
+    ) -> BoxFuture<'a, Result<Vec<u8>, MapradarError>> {
+        unsupported(self.name(), "static maps")
+    }
+
+    /// Computes a travel route between two `(lat, lng)` points.
+    fn route(
+        &self,
+        _origin: (f64, f64),
+        _destination: (f64, f64),
+        _mode: TravelMode,
+    ) -> BoxFuture<'_, Result<Route, MapradarError>> {
+        unsupported(self.name(), "routing")
+    }
+
+    /// Computes travel distance and duration for every origin/destination pair.
+    ///
+    /// Returns one row per origin, each with one element per destination.
+    fn distance_matrix<'a>(
+        &'a self,
+        _origins: &'a [(f64, f64)],
+        _destinations: &'a [(f64, f64)],
+        _mode: TravelMode,
+    ) -> BoxFuture<'a, Result<Vec<Vec<MatrixElement>>, MapradarError>> {
+        unsupported(self.name(), "distance matrices")
+    }
+
+    /// Suggests up to `limit` addresses completing `partial`, best match first.
+    fn autocomplete<'a>(
+        &'a self,
+        _partial: &'a str,
+        _limit: usize,
+        _bias: &'a AutocompleteBias,
+    ) -> BoxFuture<'a, Result<Vec<Suggestion>, MapradarError>> {
+        unsupported(self.name(), "autocomplete")
+    }
+}
+
+/// One page of nearby-search results.
+#[derive(Debug, Clone, Default)]
+pub struct NearbyPage {
+    pub services: Vec<NearbyService>,
+    /// Cursor for the next page, or `None` when this is the last.
+    pub next_page_token: Option<String>,
+}
+
+/// Future resolving to [`MapradarError::Unsupported`] for capabilities a provider lacks.
+pub(crate) fn unsupported<T: Send + 'static>(
+    provider: &str,
+    capability: &str,
+) -> BoxFuture<'static, Result<T, MapradarError>> {
+    let err = MapradarError::Unsupported(format!(
+        "the {} provider does not support {}",
+        provider, capability
+    ));
+    Box::pin(async move { Err(err) })
+}
+
+/// Maps HTTP-level auth and quota rejections to their error variants, passing other responses through.
+pub(crate) fn check_http_status(
+    provider: &str,
+    response: reqwest::Response,
+) -> Result<reqwest::Response, MapradarError> {
+    let status = response.status();
+    let message = format!("HTTP {}", status);
+    match status.as_u16() {
+        401 | 403 => Err(MapradarError::AuthFailed {
+            provider: provider.to_string(),
+            message,
+        }),
+        429 => Err(MapradarError::QuotaExceeded {
+            provider: provider.to_string(),
+            message,
+        }),
+        _ => Ok(response),
+    }
+}
+
+/// Built-in providers selectable by name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProviderKind {
+    #[default]
+    Google,
+    Nominatim,
+    /// Local index of imported OpenStreetMap extracts (`offline` feature).
+    Offline,
+}
+
+impl FromStr for ProviderKind {
+    type Err = MapradarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "google" => Ok(Self::Google),
+            "nominatim" | "osm" => Ok(Self::Nominatim),
+            "offline" => Ok(Self::Offline),
+            other => Err(MapradarError::Config(format!(
+                "Unknown provider '{}' (expected google, nominatim, or offline)",
+                other
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ProviderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Google => write!(f, "google"),
+            Self::Nominatim => write!(f, "nominatim"),
+            Self::Offline => write!(f, "offline"),
+        }
+    }
+}
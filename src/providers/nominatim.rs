@@ -0,0 +1,998 @@
+use std::{collections::HashMap, net::IpAddr};
+
+use futures::future::BoxFuture;
+use reqwest::RequestBuilder;
+use serde_json::Value;
+
+use super::{GeocodingProvider, check_http_status};
+use crate::transport::Transport;
+use crate::{
+    error::MapradarError,
+    geo::{Geometry, haversine_distance},
+    models::{
+        AddressComponents, AdminArea, GeoLocation, GeocodeCandidate, MatchType, MatrixElement,
+        NearbyService, OpeningHours, PlaceCategory, PlaceDetails, Polygons, Route, RouteStep,
+        SearchRegion, ServiceType, SnappedPoint, TravelMode,
+    },
+};
+
+pub const DEFAULT_BASE_URL: &str = "https://nominatim.openstreetmap.org";
+const OVERPASS_URL: &str = "https://overpass-api.de/api/interpreter";
+const OSRM_URL: &str = "https://router.project-osrm.org";
+/// Most coordinates the public OSRM server map-matches per request.
+const OSRM_MATCH_MAX_POINTS: usize = 100;
+const ELEVATION_URL: &str = "https://api.open-meteo.com/v1/elevation";
+const ELEVATION_MAX_POINTS: usize = 100;
+const MAX_CANDIDATES: usize = 10;
+/// Most OSM IDs Nominatim's lookup endpoint accepts per request.
+const LOOKUP_MAX_IDS: usize = 50;
+
+/// OpenStreetMap backend: Nominatim for geocoding, Overpass for nearby search, OSRM for routing
+/// and snapping to roads, and Open-Meteo (Copernicus DEM) for elevation.
+///
+/// Requires no API key, but the public instances are rate limited and intended for light usage.
+#[derive(Clone)]
+pub struct NominatimProvider {
+    transport: Transport,
+    base_url: String,
+    language: Option<String>,
+}
+
+impl NominatimProvider {
+    pub fn new(transport: Transport) -> Self {
+        Self {
+            transport,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            language: None,
+        }
+    }
+
+    /// Geocodes against a self-hosted Nominatim at `base_url` instead of the public instance.
+    /// Overpass, OSRM, and Open-Meteo requests are unaffected.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Asks Nominatim for addresses in `language`, a code such as "fr" or "pt-BR", and names
+    /// Overpass results by their `name:<language>` tag where they have one.
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Adds the `accept-language` parameter when a language was set.
+    fn localized(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.language {
+            Some(language) => request.query(&[("accept-language", language)]),
+            None => request,
+        }
+    }
+
+    /// Maps a service type to its OpenStreetMap `key=value` tag.
+    pub fn osm_tag(service_type: ServiceType) -> (&'static str, &'static str) {
+        match service_type {
+            ServiceType::BusStop => ("highway", "bus_stop"),
+            ServiceType::Market => ("amenity", "marketplace"),
+            ServiceType::School => ("amenity", "school"),
+            ServiceType::Mall => ("shop", "mall"),
+            ServiceType::Hospital => ("amenity", "hospital"),
+            ServiceType::Bank => ("amenity", "bank"),
+            ServiceType::Restaurant => ("amenity", "restaurant"),
+            ServiceType::FuelStation => ("amenity", "fuel"),
+            ServiceType::TrainStation => ("railway", "station"),
+            ServiceType::TaxiStand => ("amenity", "taxi"),
+            ServiceType::Landmark => ("tourism", "attraction"),
+            ServiceType::Pharmacy => ("amenity", "pharmacy"),
+            ServiceType::PoliceStation => ("amenity", "police"),
+            ServiceType::FireStation => ("amenity", "fire_station"),
+            ServiceType::Gym => ("leisure", "fitness_centre"),
+            ServiceType::Park => ("leisure", "park"),
+            ServiceType::PlaceOfWorship => ("amenity", "place_of_worship"),
+            ServiceType::Airport => ("aeroway", "aerodrome"),
+            ServiceType::Hotel => ("tourism", "hotel"),
+            ServiceType::Supermarket => ("shop", "supermarket"),
+            ServiceType::Cafe => ("amenity", "cafe"),
+            ServiceType::Cinema => ("amenity", "cinema"),
+            ServiceType::Library => ("amenity", "library"),
+            ServiceType::PostOffice => ("amenity", "post_office"),
+        }
+    }
+
+    /// Parses a Nominatim `jsonv2` place with `addressdetails=1`.
+    fn parse_place(&self, place: &Value) -> GeoLocation {
+        let address = &place["address"];
+        let city = ["city", "town", "village", "municipality"]
+            .iter()
+            .find_map(|key| address[*key].as_str())
+            .map(|s| s.to_string());
+
+        GeoLocation {
+            address: place["display_name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            latitude: parse_coordinate(&place["lat"]),
+            longitude: parse_coordinate(&place["lon"]),
+            city,
+            state: address["state"].as_str().map(|s| s.to_string()),
+            country: address["country_code"]
+                .as_str()
+                .unwrap_or_default()
+                .to_uppercase(),
+            address_components: Some(Self::parse_components(address)),
+            timezone: None,
+            elevation_m: None,
+            language: self.language.clone(),
+        }
+    }
+
+    /// Maps a Nominatim `address` object onto [`AddressComponents`].
+    fn parse_components(address: &Value) -> AddressComponents {
+        let find = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| address[*key].as_str())
+                .map(|s| s.to_string())
+        };
+
+        AddressComponents {
+            house_number: find(&["house_number"]),
+            street: find(&["road", "pedestrian", "footway"]),
+            suburb: find(&["suburb", "neighbourhood", "quarter", "city_district"]),
+            city: find(&["city", "town", "village", "municipality"]),
+            state: find(&["state"]),
+            postcode: find(&["postcode"]),
+            country_code: find(&["country_code"]).map(|code| code.to_uppercase()),
+        }
+    }
+
+    /// Classifies a place by its address rank, scaling confidence by its OSM importance.
+    ///
+    /// Houses synthesized from address interpolation lines come back as `place=house`.
+    fn parse_candidate(&self, place: &Value) -> GeocodeCandidate {
+        let rank = place["place_rank"].as_u64().unwrap_or_default();
+        let (match_type, confidence) =
+            match (place["category"].as_str(), place["type"].as_str(), rank) {
+                (Some("place"), Some("house"), _) => (MatchType::Interpolated, 0.8),
+                (_, _, 30..) => (MatchType::Exact, 1.0),
+                (_, _, 26..) => (MatchType::Approximate, 0.6),
+                _ => (MatchType::Approximate, 0.4),
+            };
+        let importance = place["importance"].as_f64().unwrap_or(0.5).clamp(0.0, 1.0);
+
+        // Nominatim orders the box as [min_lat, max_lat, min_lon, max_lon], as strings.
+        let bounding_box = place["boundingbox"]
+            .as_array()
+            .map(|values| values.iter().map(parse_coordinate).collect::<Vec<_>>())
+            .and_then(|values| match values[..] {
+                [min_lat, max_lat, min_lng, max_lng] => Some((min_lng, min_lat, max_lng, max_lat)),
+                _ => None,
+            });
+
+        GeocodeCandidate {
+            location: self.parse_place(place),
+            confidence: confidence * (0.5 + importance / 2.0),
+            match_type,
+            bounding_box,
+        }
+    }
+
+    /// OSM tag for a built-in or custom category; custom tags without `key=` are amenities.
+    pub(crate) fn category_tag(category: &PlaceCategory) -> (&str, &str) {
+        match category {
+            PlaceCategory::Known(service_type) => Self::osm_tag(*service_type),
+            PlaceCategory::Custom(tag) => tag.split_once('=').unwrap_or(("amenity", tag)),
+        }
+    }
+
+    /// Searches for `address`, restricted to `region.countries` and preferring the viewbox around
+    /// its bias point.
+    async fn search(
+        &self,
+        address: &str,
+        limit: usize,
+        region: &SearchRegion,
+    ) -> Result<Vec<Value>, MapradarError> {
+        let mut params = vec![
+            ("q", address.to_string()),
+            ("format", "jsonv2".to_string()),
+            ("addressdetails", "1".to_string()),
+            ("limit", limit.to_string()),
+        ];
+        if !region.countries.is_empty() {
+            params.push(("countrycodes", region.countries.join(",").to_lowercase()));
+        }
+        if let Some((min_lng, min_lat, max_lng, max_lat)) = region.bias_box() {
+            params.push((
+                "viewbox",
+                format!("{},{},{},{}", min_lng, min_lat, max_lng, max_lat),
+            ));
+        }
+        let request = self
+            .transport
+            .get(format!("{}/search", self.base_url))
+            .query(&params);
+        let response = self.transport.send(self.localized(request)).await?;
+        let response = check_http_status(self.name(), response)?.error_for_status()?;
+
+        match response.json().await? {
+            Value::Array(places) if !places.is_empty() => Ok(places),
+            _ => Err(MapradarError::AddressNotFound {
+                query: address.to_string(),
+            }),
+        }
+    }
+
+    async fn reverse(&self, lat: f64, lng: f64) -> Result<GeoLocation, MapradarError> {
+        let request = self
+            .transport
+            .get(format!("{}/reverse", self.base_url))
+            .query(&[
+                ("lat", lat.to_string()),
+                ("lon", lng.to_string()),
+                ("format", "jsonv2".to_string()),
+                ("addressdetails", "1".to_string()),
+            ]);
+        let response = self.transport.send(self.localized(request)).await?;
+        let response = check_http_status(self.name(), response)?.error_for_status()?;
+
+        let data: Value = response.json().await?;
+        if data.get("error").is_some() {
+            return Err(MapradarError::ZeroResults);
+        }
+
+        Ok(self.parse_place(&data))
+    }
+
+    /// Looks up an `osm:<type>/<id>` place with its extra tags (phone, website, hours).
+    async fn lookup(&self, place_id: &str) -> Result<PlaceDetails, MapradarError> {
+        let request = self
+            .transport
+            .get(format!("{}/lookup", self.base_url))
+            .query(&[
+                ("osm_ids", osm_lookup_id(place_id)?),
+                ("format", "jsonv2".to_string()),
+                ("addressdetails", "1".to_string()),
+                ("extratags", "1".to_string()),
+            ]);
+        let response = self.transport.send(self.localized(request)).await?;
+        let response = check_http_status(self.name(), response)?.error_for_status()?;
+
+        let data: Value = response.json().await?;
+        let Some(place) = data.as_array().and_then(|places| places.first()) else {
+            return Err(MapradarError::ZeroResults);
+        };
+        let tags = &place["extratags"];
+        let tag = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| tags[*key].as_str())
+                .map(str::to_string)
+        };
+        let location = self.parse_place(place);
+        let opening_hours = tags["opening_hours"].as_str().and_then(parse_opening_hours);
+
+        Ok(PlaceDetails {
+            place_id: place_id.to_string(),
+            name: place["name"]
+                .as_str()
+                .filter(|name| !name.is_empty())
+                .unwrap_or("Unknown")
+                .to_string(),
+            latitude: location.latitude,
+            longitude: location.longitude,
+            address: Some(location.address).filter(|address| !address.is_empty()),
+            phone_number: tag(&["phone", "contact:phone"]),
+            website: tag(&["website", "contact:website", "url"]),
+            rating: None,
+            reviews_count: None,
+            open_now: opening_hours
+                .as_ref()
+                .and_then(|hours| local_open_now(hours, location.latitude, location.longitude)),
+            opening_hours,
+            photos: Vec::new(),
+            types: match (place["category"].as_str(), place["type"].as_str()) {
+                (Some(key), Some(value)) => vec![format!("{}={}", key, value)],
+                _ => Vec::new(),
+            },
+        })
+    }
+
+    async fn overpass(
+        &self,
+        lat: f64,
+        lng: f64,
+        category: &PlaceCategory,
+        radius_meters: f64,
+        max_results: usize,
+    ) -> Result<Vec<NearbyService>, MapradarError> {
+        let (key, value) = Self::category_tag(category);
+        let query = format!(
+            "[out:json][timeout:25];nwr(around:{:.0},{},{})[\"{}\"=\"{}\"][name];out center;",
+            radius_meters, lat, lng, key, value
+        );
+
+        let request = self.transport.get(OVERPASS_URL).query(&[("data", query)]);
+        let response = check_http_status(self.name(), self.transport.send(request).await?)?;
+
+        if !response.status().is_success() {
+            return Err(MapradarError::Api {
+                status: response.status().to_string(),
+                message: "Overpass API search failed".to_string(),
+            });
+        }
+
+        let data: Value = response.json().await?;
+        let mut services: Vec<NearbyService> = data["elements"]
+            .as_array()
+            .map(|elements| {
+                elements
+                    .iter()
+                    .map(|element| self.parse_element(element, category, lat, lng))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        services.sort_by(|a, b| {
+            a.distance_km
+                .partial_cmp(&b.distance_km)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        services.truncate(max_results);
+
+        Ok(services)
+    }
+
+    /// Finds the administrative boundary relations containing a point with an Overpass `is_in`
+    /// query, then fetches their polygons from Nominatim when `with_geometry` is set.
+    async fn admin_relations(
+        &self,
+        lat: f64,
+        lng: f64,
+        with_geometry: bool,
+    ) -> Result<Vec<AdminArea>, MapradarError> {
+        let query = format!(
+            "[out:json][timeout:25];is_in({},{})->.a;\
+             rel(pivot.a)[\"boundary\"=\"administrative\"][\"admin_level\"][name];out tags;",
+            lat, lng
+        );
+        let request = self.transport.get(OVERPASS_URL).query(&[("data", query)]);
+        let response = check_http_status(self.name(), self.transport.send(request).await?)?;
+        if !response.status().is_success() {
+            return Err(MapradarError::Api {
+                status: response.status().to_string(),
+                message: "Overpass API boundary lookup failed".to_string(),
+            });
+        }
+
+        let data: Value = response.json().await?;
+        let mut areas: Vec<(u64, AdminArea)> = data["elements"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|element| {
+                let id = element["id"].as_u64()?;
+                let tags = &element["tags"];
+                let name = self
+                    .language
+                    .as_ref()
+                    .and_then(|language| tags[format!("name:{}", language)].as_str())
+                    .or(tags["name"].as_str())?;
+                Some((
+                    id,
+                    AdminArea {
+                        id: format!("osm:relation/{}", id),
+                        name: name.to_string(),
+                        admin_level: tags["admin_level"].as_str()?.parse().ok()?,
+                        code: tags["ISO3166-2"]
+                            .as_str()
+                            .or(tags["ISO3166-1"].as_str())
+                            .map(str::to_string),
+                        geometry: None,
+                    },
+                ))
+            })
+            .collect();
+
+        if with_geometry && !areas.is_empty() {
+            let mut polygons = self
+                .boundary_polygons(areas.iter().map(|(id, _)| *id))
+                .await?;
+            for (id, area) in &mut areas {
+                area.geometry = polygons.remove(id);
+            }
+        }
+        Ok(areas.into_iter().map(|(_, area)| area).collect())
+    }
+
+    /// Fetches the boundary polygons of OpenStreetMap relations from Nominatim, keyed by
+    /// relation ID. Relations Nominatim has no polygon for are missing from the map.
+    async fn boundary_polygons(
+        &self,
+        relation_ids: impl Iterator<Item = u64>,
+    ) -> Result<HashMap<u64, Polygons>, MapradarError> {
+        let osm_ids: Vec<String> = relation_ids.map(|id| format!("R{}", id)).collect();
+        let mut polygons = HashMap::new();
+        for chunk in osm_ids.chunks(LOOKUP_MAX_IDS) {
+            let request = self
+                .transport
+                .get(format!("{}/lookup", self.base_url))
+                .query(&[
+                    ("osm_ids", chunk.join(",")),
+                    ("format", "jsonv2".to_string()),
+                    ("polygon_geojson", "1".to_string()),
+                ]);
+            let response = self.transport.send(request).await?;
+            let response = check_http_status(self.name(), response)?.error_for_status()?;
+
+            let data: Value = response.json().await?;
+            for place in data.as_array().into_iter().flatten() {
+                let Some(id) = place["osm_id"].as_u64() else {
+                    continue;
+                };
+                if let Ok(Geometry::Polygons(rings)) = Geometry::from_geojson(&place["geojson"]) {
+                    polygons.insert(id, rings);
+                }
+            }
+        }
+        Ok(polygons)
+    }
+
+    async fn open_meteo_elevations(
+        &self,
+        points: &[(f64, f64)],
+    ) -> Result<Vec<f64>, MapradarError> {
+        let join = |values: &mut dyn Iterator<Item = f64>| {
+            values.map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+        };
+
+        let mut elevations = Vec::with_capacity(points.len());
+        for chunk in points.chunks(ELEVATION_MAX_POINTS) {
+            let request = self.transport.get(ELEVATION_URL).query(&[
+                ("latitude", join(&mut chunk.iter().map(|p| p.0))),
+                ("longitude", join(&mut chunk.iter().map(|p| p.1))),
+            ]);
+            let response = check_http_status(self.name(), self.transport.send(request).await?)?;
+            if !response.status().is_success() {
+                return Err(MapradarError::Api {
+                    status: response.status().to_string(),
+                    message: "Open-Meteo elevation request failed".to_string(),
+                });
+            }
+
+            let data: Value = response.json().await?;
+            let values = data["elevation"].as_array().map(Vec::as_slice);
+            elevations.extend(
+                values
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|v| v.as_f64().unwrap_or_default()),
+            );
+        }
+        Ok(elevations)
+    }
+
+    fn osrm_profile(mode: TravelMode) -> &'static str {
+        match mode {
+            TravelMode::Driving => "driving",
+            TravelMode::Walking => "foot",
+            TravelMode::Cycling => "bike",
+        }
+    }
+
+    /// Formats points as OSRM's `lng,lat;lng,lat` path segment.
+    fn osrm_coordinates(points: &[(f64, f64)]) -> String {
+        points
+            .iter()
+            .map(|(lat, lng)| format!("{},{}", lng, lat))
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    async fn osrm_table(
+        &self,
+        origins: &[(f64, f64)],
+        destinations: &[(f64, f64)],
+        mode: TravelMode,
+    ) -> Result<Vec<Vec<MatrixElement>>, MapradarError> {
+        let points: Vec<(f64, f64)> = origins.iter().chain(destinations).copied().collect();
+        let indices = |range: std::ops::Range<usize>| {
+            range.map(|i| i.to_string()).collect::<Vec<_>>().join(";")
+        };
+
+        let url = format!(
+            "{}/table/v1/{}/{}",
+            OSRM_URL,
+            Self::osrm_profile(mode),
+            Self::osrm_coordinates(&points)
+        );
+
+        let request = self.transport.get(url).query(&[
+            ("sources", indices(0..origins.len())),
+            ("destinations", indices(origins.len()..points.len())),
+            ("annotations", "distance,duration".to_string()),
+        ]);
+        let response = check_http_status(self.name(), self.transport.send(request).await?)?;
+
+        let data: Value = response.json().await?;
+        let code = data["code"].as_str().unwrap_or("UNKNOWN");
+
+        if code != "Ok" {
+            return Err(MapradarError::Api {
+                status: code.to_string(),
+                message: data["message"]
+                    .as_str()
+                    .unwrap_or("OSRM table request failed")
+                    .to_string(),
+            });
+        }
+
+        let rows = (0..origins.len())
+            .map(|i| {
+                (0..destinations.len())
+                    .map(|j| {
+                        let distance = data["distances"][i][j].as_f64();
+                        let duration = data["durations"][i][j].as_f64();
+                        match distance {
+                            Some(meters) => MatrixElement::ok(
+                                meters / 1000.0,
+                                duration.map(|secs| secs.round() as u64),
+                            ),
+                            None => MatrixElement::failed("NoRoute"),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(rows)
+    }
+
+    /// Snaps GPS points to roads with OSRM: a trace is map-matched, which follows the road the
+    /// points travel along; a lone point moves to the nearest road.
+    async fn osrm_snap(&self, points: &[(f64, f64)]) -> Result<Vec<SnappedPoint>, MapradarError> {
+        let mut snapped = Vec::with_capacity(points.len());
+        for (chunk_index, chunk) in points.chunks(OSRM_MATCH_MAX_POINTS).enumerate() {
+            let offset = chunk_index * OSRM_MATCH_MAX_POINTS;
+            let coordinates = Self::osrm_coordinates(chunk);
+            let (request, key) = if chunk.len() == 1 {
+                let url = format!("{}/nearest/v1/driving/{}", OSRM_URL, coordinates);
+                (self.transport.get(url), "waypoints")
+            } else {
+                let url = format!("{}/match/v1/driving/{}", OSRM_URL, coordinates);
+                let request = self.transport.get(url).query(&[
+                    ("overview", "false"),
+                    ("gaps", "ignore"),
+                    ("tidy", "false"),
+                ]);
+                (request, "tracepoints")
+            };
+            let response = check_http_status(self.name(), self.transport.send(request).await?)?;
+
+            let data: Value = response.json().await?;
+            let code = data["code"].as_str().unwrap_or("UNKNOWN");
+            let matched = match code {
+                "Ok" => data[key].as_array().cloned().unwrap_or_default(),
+                // No road near enough to any point
+                "NoMatch" | "NoSegment" => Vec::new(),
+                _ => {
+                    return Err(MapradarError::Api {
+                        status: code.to_string(),
+                        message: data["message"]
+                            .as_str()
+                            .unwrap_or("OSRM snap to road request failed")
+                            .to_string(),
+                    });
+                }
+            };
+
+            snapped.extend(chunk.iter().enumerate().map(|(i, &original)| {
+                let point = matched.get(i).filter(|point| !point.is_null());
+                let location =
+                    point.and_then(|p| p["location"][1].as_f64().zip(p["location"][0].as_f64()));
+                match (point, location) {
+                    (Some(point), Some(position)) => SnappedPoint {
+                        road: point["name"]
+                            .as_str()
+                            .filter(|name| !name.is_empty())
+                            .map(str::to_string),
+                        ..SnappedPoint::snapped(offset + i, original, position)
+                    },
+                    _ => SnappedPoint::unsnapped(offset + i, original),
+                }
+            }));
+        }
+        Ok(snapped)
+    }
+
+    async fn osrm_route(
+        &self,
+        origin: (f64, f64),
+        destination: (f64, f64),
+        mode: TravelMode,
+    ) -> Result<Route, MapradarError> {
+        let url = format!(
+            "{}/route/v1/{}/{}",
+            OSRM_URL,
+            Self::osrm_profile(mode),
+            Self::osrm_coordinates(&[origin, destination])
+        );
+
+        let request = self.transport.get(url).query(&[
+            ("overview", "full"),
+            ("geometries", "polyline"),
+            ("steps", "true"),
+        ]);
+        let response = check_http_status(self.name(), self.transport.send(request).await?)?;
+
+        let data: Value = response.json().await?;
+        let code = data["code"].as_str().unwrap_or("UNKNOWN");
+
+        if code != "Ok" {
+            if code == "NoRoute" {
+                return Err(MapradarError::ZeroResults);
+            }
+            return Err(MapradarError::Api {
+                status: code.to_string(),
+                message: data["message"]
+                    .as_str()
+                    .unwrap_or("OSRM routing failed")
+                    .to_string(),
+            });
+        }
+
+        let route = &data["routes"][0];
+        let steps = route["legs"][0]["steps"]
+            .as_array()
+            .map(|steps| steps.iter().map(Self::parse_osrm_step).collect())
+            .unwrap_or_default();
+
+        Ok(Route {
+            mode,
+            distance_km: route["distance"].as_f64().unwrap_or_default() / 1000.0,
+            duration_secs: route["duration"].as_f64().unwrap_or_default().round() as u64,
+            polyline: route["geometry"].as_str().unwrap_or_default().to_string(),
+            steps,
+        })
+    }
+
+    /// Builds a readable instruction (e.g., "turn left onto Main Street") from an OSRM step.
+    fn parse_osrm_step(step: &Value) -> RouteStep {
+        let maneuver = &step["maneuver"];
+        let action = match (
+            maneuver["type"].as_str().unwrap_or("continue"),
+            maneuver["modifier"].as_str(),
+        ) {
+            (kind, Some(modifier)) => format!("{} {}", kind, modifier),
+            (kind, None) => kind.to_string(),
+        };
+        let instruction = match step["name"].as_str().filter(|name| !name.is_empty()) {
+            Some(name) => format!("{} onto {}", action, name),
+            None => action,
+        };
+
+        let geometry = crate::utils::decode_polyline(step["geometry"].as_str().unwrap_or_default());
+        let start = geometry.first().copied().unwrap_or_default();
+        let end = geometry.last().copied().unwrap_or_default();
+
+        RouteStep {
+            instruction,
+            distance_km: step["distance"].as_f64().unwrap_or_default() / 1000.0,
+            duration_secs: step["duration"].as_f64().unwrap_or_default().round() as u64,
+            start_latitude: start.0,
+            start_longitude: start.1,
+            end_latitude: end.0,
+            end_longitude: end.1,
+        }
+    }
+
+    /// Parses an Overpass node, way, or relation (ways and relations carry a `center`).
+    fn parse_element(
+        &self,
+        element: &Value,
+        category: &PlaceCategory,
+        origin_lat: f64,
+        origin_lng: f64,
+    ) -> NearbyService {
+        let point = if element.get("center").is_some() {
+            &element["center"]
+        } else {
+            element
+        };
+        let p_lat = point["lat"].as_f64().unwrap_or_default();
+        let p_lng = point["lon"].as_f64().unwrap_or_default();
+        let tags = &element["tags"];
+
+        let address = match (
+            tags["addr:housenumber"].as_str(),
+            tags["addr:street"].as_str(),
+        ) {
+            (Some(number), Some(street)) => Some(format!("{} {}", number, street)),
+            (None, Some(street)) => Some(street.to_string()),
+            _ => None,
+        };
+        let opening_hours = tags["opening_hours"].as_str().and_then(parse_opening_hours);
+
+        NearbyService {
+            name: self
+                .language
+                .as_ref()
+                .and_then(|language| tags[format!("name:{}", language)].as_str())
+                .or(tags["name"].as_str())
+                .unwrap_or("Unknown")
+                .to_string(),
+            service_type: category.clone(),
+            latitude: p_lat,
+            longitude: p_lng,
+            distance_km: haversine_distance(origin_lat, origin_lng, p_lat, p_lng),
+            address,
+            rating: None,
+            place_id: match (element["type"].as_str(), element["id"].as_u64()) {
+                (Some(kind), Some(id)) => Some(format!("osm:{}/{}", kind, id)),
+                _ => None,
+            },
+            phone_number: tags["phone"]
+                .as_str()
+                .or_else(|| tags["contact:phone"].as_str())
+                .map(|s| s.to_string()),
+            open_now: opening_hours
+                .as_ref()
+                .and_then(|hours| local_open_now(hours, p_lat, p_lng)),
+            opening_hours,
+            photos: Vec::new(),
+            service_types: Vec::new(),
+            travel_time_min: None,
+            departures: Vec::new(),
+            routes: Vec::new(),
+        }
+    }
+}
+
+/// Converts an `osm:node/123` place ID (the `osm:` prefix is optional) into the `N123` form
+/// Nominatim's lookup endpoint expects.
+pub(crate) fn osm_lookup_id(place_id: &str) -> Result<String, MapradarError> {
+    let osm_ref = place_id.strip_prefix("osm:").unwrap_or(place_id);
+    let invalid = || {
+        MapradarError::InvalidInput(format!(
+            "Invalid OpenStreetMap place ID '{}' (expected e.g. osm:node/123)",
+            place_id
+        ))
+    };
+    let (kind, id) = osm_ref
+        .split_once('/')
+        .filter(|(_, id)| id.parse::<u64>().is_ok())
+        .ok_or_else(invalid)?;
+    let prefix = match kind {
+        "node" => 'N',
+        "way" => 'W',
+        "relation" => 'R',
+        _ => return Err(invalid()),
+    };
+    Ok(format!("{}{}", prefix, id))
+}
+
+/// OSM day abbreviations, in the Sunday-first order used by [`OpeningHours`].
+const OSM_DAYS: [&str; 7] = ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"];
+
+/// Parses the common subset of the OSM `opening_hours` syntax: `24/7`, and `;`-separated rules
+/// such as `Mo-Fr 08:00-17:00,18:00-22:00`, `Sa 10:00-02:00`, or `Su off`, where later rules
+/// replace earlier ones for the days they name. Returns `None` for anything else (holidays,
+/// months, sunrise), rather than guess.
+pub(crate) fn parse_opening_hours(tag: &str) -> Option<OpeningHours> {
+    let tag = tag.trim();
+    if tag == "24/7" {
+        return Some(OpeningHours::always_open());
+    }
+
+    let mut hours = OpeningHours::default();
+    for rule in tag
+        .split(';')
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty())
+    {
+        let (days, times) = match rule.split_once(' ') {
+            Some((days, times)) if days.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                (parse_osm_days(days)?, times.trim())
+            }
+            _ => ((0..7).collect(), rule),
+        };
+        hours
+            .intervals
+            .retain(|interval| !days.contains(&interval.day));
+        if times == "off" || times == "closed" {
+            continue;
+        }
+        for range in times.split(',') {
+            let (open, close) = range.trim().split_once('-')?;
+            let (open, mut close) = (parse_osm_time(open)?, parse_osm_time(close)?);
+            if close <= open {
+                close += 24 * 60;
+            }
+            for &day in &days {
+                let start = u32::from(day) * 24 * 60;
+                hours.add_span(start + open, start + close);
+            }
+        }
+    }
+    Some(hours)
+}
+
+/// Days named by a rule selector like `Mo-Fr,Su`, as 0 (Sunday) to 6.
+fn parse_osm_days(selector: &str) -> Option<Vec<u8>> {
+    let day = |name: &str| OSM_DAYS.iter().position(|d| *d == name).map(|i| i as u8);
+    let mut days = Vec::new();
+    for part in selector.split(',') {
+        match part.split_once('-') {
+            Some((from, to)) => {
+                let (from, to) = (day(from)?, day(to)?);
+                let mut current = from;
+                loop {
+                    days.push(current);
+                    if current == to {
+                        break;
+                    }
+                    current = (current + 1) % 7;
+                }
+            }
+            None => days.push(day(part)?),
+        }
+    }
+    Some(days)
+}
+
+/// `HH:MM` as minutes after midnight; `24:00` is accepted as the end of the day.
+fn parse_osm_time(time: &str) -> Option<u32> {
+    let (hour, minute) = time.trim().split_once(':')?;
+    let (hour, minute) = (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?);
+    (hour <= 24 && minute < 60 && hour * 60 + minute <= 24 * 60).then_some(hour * 60 + minute)
+}
+
+/// Whether `hours` are open now at the place's local time, looked up offline.
+#[cfg(feature = "offline-tz")]
+fn local_open_now(hours: &OpeningHours, lat: f64, lng: f64) -> Option<bool> {
+    let (day, minute) = crate::timezone::local_weekday_minute(lat, lng).ok()?;
+    Some(hours.is_open_at(day, minute))
+}
+
+/// Without the `offline-tz` feature the place's local time is unknown.
+#[cfg(not(feature = "offline-tz"))]
+fn local_open_now(_hours: &OpeningHours, _lat: f64, _lng: f64) -> Option<bool> {
+    None
+}
+
+/// Nominatim returns coordinates as strings.
+fn parse_coordinate(value: &Value) -> f64 {
+    value
+        .as_str()
+        .and_then(|s| s.parse().ok())
+        .or_else(|| value.as_f64())
+        .unwrap_or_default()
+}
+
+// Autocomplete keeps the unsupported default: the public Nominatim usage policy forbids
+// sending a request per keystroke.
+impl GeocodingProvider for NominatimProvider {
+    fn name(&self) -> &'static str {
+        "nominatim"
+    }
+
+    fn geocode<'a>(
+        &'a self,
+        address: &'a str,
+    ) -> BoxFuture<'a, Result<GeoLocation, MapradarError>> {
+        Box::pin(async move {
+            let places = self.search(address, 1, &SearchRegion::default()).await?;
+            Ok(self.parse_place(&places[0]))
+        })
+    }
+
+    fn geocode_all<'a>(
+        &'a self,
+        address: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<GeocodeCandidate>, MapradarError>> {
+        Box::pin(async move {
+            let places = self
+                .search(address, MAX_CANDIDATES, &SearchRegion::default())
+                .await?;
+            Ok(places
+                .iter()
+                .map(|place| self.parse_candidate(place))
+                .collect())
+        })
+    }
+
+    fn geocode_in<'a>(
+        &'a self,
+        address: &'a str,
+        region: &'a SearchRegion,
+    ) -> BoxFuture<'a, Result<GeoLocation, MapradarError>> {
+        Box::pin(async move {
+            let places = self.search(address, 1, region).await?;
+            Ok(self.parse_place(&places[0]))
+        })
+    }
+
+    fn geocode_all_in<'a>(
+        &'a self,
+        address: &'a str,
+        region: &'a SearchRegion,
+    ) -> BoxFuture<'a, Result<Vec<GeocodeCandidate>, MapradarError>> {
+        Box::pin(async move {
+            let places = self.search(address, MAX_CANDIDATES, region).await?;
+            Ok(places
+                .iter()
+                .map(|place| self.parse_candidate(place))
+                .collect())
+        })
+    }
+
+    fn admin_areas(
+        &self,
+        lat: f64,
+        lng: f64,
+        with_geometry: bool,
+    ) -> BoxFuture<'_, Result<Vec<AdminArea>, MapradarError>> {
+        Box::pin(self.admin_relations(lat, lng, with_geometry))
+    }
+
+    fn reverse_geocode(
+        &self,
+        lat: f64,
+        lng: f64,
+    ) -> BoxFuture<'_, Result<GeoLocation, MapradarError>> {
+        Box::pin(self.reverse(lat, lng))
+    }
+
+    fn nearby_search<'a>(
+        &'a self,
+        lat: f64,
+        lng: f64,
+        category: &'a PlaceCategory,
+        radius_meters: f64,
+        max_results: usize,
+    ) -> BoxFuture<'a, Result<Vec<NearbyService>, MapradarError>> {
+        Box::pin(self.overpass(lat, lng, category, radius_meters, max_results))
+    }
+
+    fn ip_location(&self, ip: Option<IpAddr>) -> BoxFuture<'_, Result<GeoLocation, MapradarError>> {
+        Box::pin(super::ipapi::locate(&self.transport, ip))
+    }
+
+    fn elevation<'a>(
+        &'a self,
+        points: &'a [(f64, f64)],
+    ) -> BoxFuture<'a, Result<Vec<f64>, MapradarError>> {
+        Box::pin(self.open_meteo_elevations(points))
+    }
+
+    fn snap_to_road<'a>(
+        &'a self,
+        points: &'a [(f64, f64)],
+    ) -> BoxFuture<'a, Result<Vec<SnappedPoint>, MapradarError>> {
+        Box::pin(self.osrm_snap(points))
+    }
+
+    fn place_details<'a>(
+        &'a self,
+        place_id: &'a str,
+    ) -> BoxFuture<'a, Result<PlaceDetails, MapradarError>> {
+        Box::pin(self.lookup(place_id))
+    }
+
+    fn route(
+        &self,
+        origin: (f64, f64),
+        destination: (f64, f64),
+        mode: TravelMode,
+    ) -> BoxFuture<'_, Result<Route, MapradarError>> {
+        Box::pin(self.osrm_route(origin, destination, mode))
+    }
+
+    fn distance_matrix<'a>(
+        &'a self,
+        origins: &'a [(f64, f64)],
+        destinations: &'a [(f64, f64)],
+        mode: TravelMode,
+    ) -> BoxFuture<'a, Result<Vec<Vec<MatrixElement>>, MapradarError>> {
+        Box::pin(self.osrm_table(origins, destinations, mode))
+    }
+}
@@ -0,0 +1,195 @@
+use futures::future::BoxFuture;
+
+use super::{GeocodingProvider, NominatimProvider};
+use crate::{
+    error::MapradarError,
+    models::{AddressComponents, GeoLocation, NearbyService, PlaceCategory, PlaceDetails},
+    osm::{OsmIndex, OsmPlace},
+};
+
+/// Address levels used for reverse geocoding: `place` tag values and search radius in km.
+const SUBURB_PLACES: (&[&str], f64) = (&["neighbourhood", "quarter", "suburb", "borough"], 5.0);
+const CITY_PLACES: (&[&str], f64) = (&["hamlet", "village", "town", "city"], 30.0);
+const STATE_PLACES: (&[&str], f64) = (&["state", "province", "region"], 500.0);
+const COUNTRY_PLACES: (&[&str], f64) = (&["country"], 3000.0);
+
+/// Backend answering from a local [`OsmIndex`] built with `mapradar osm import`, with no network
+/// access.
+///
+/// Nearby search covers everything imported. Geocoding matches place names exactly (the part of
+/// the address before the first comma), and reverse geocoding names the nearest suburb, city,
+/// state, and country nodes, so both are coarse compared with online providers.
+#[derive(Clone)]
+pub struct OfflineProvider {
+    index: OsmIndex,
+}
+
+impl OfflineProvider {
+    pub fn new(index: OsmIndex) -> Self {
+        Self { index }
+    }
+
+    fn nearest_name(
+        &self,
+        lat: f64,
+        lng: f64,
+        (kinds, radius_km): (&[&str], f64),
+    ) -> Result<Option<String>, MapradarError> {
+        Ok(self
+            .index
+            .nearest_settlements(lat, lng, kinds, radius_km, 1)?
+            .into_iter()
+            .next()
+            .map(|(place, _)| place.name))
+    }
+
+    /// Describes a point by the settlements around it, leading with `name` when given.
+    fn describe(
+        &self,
+        lat: f64,
+        lng: f64,
+        name: Option<&str>,
+    ) -> Result<GeoLocation, MapradarError> {
+        let suburb = self.nearest_name(lat, lng, SUBURB_PLACES)?;
+        let city = self.nearest_name(lat, lng, CITY_PLACES)?;
+        let state = self.nearest_name(lat, lng, STATE_PLACES)?;
+        let country = self.nearest_name(lat, lng, COUNTRY_PLACES)?;
+        if name.is_none() && suburb.is_none() && city.is_none() {
+            return Err(MapradarError::ZeroResults);
+        }
+
+        let mut parts: Vec<&str> = Vec::new();
+        for part in [
+            name,
+            suburb.as_deref(),
+            city.as_deref(),
+            state.as_deref(),
+            country.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if !parts.contains(&part) {
+                parts.push(part);
+            }
+        }
+
+        Ok(GeoLocation {
+            address: parts.join(", "),
+            latitude: lat,
+            longitude: lng,
+            city: city.clone(),
+            state: state.clone(),
+            country: country.unwrap_or_default(),
+            address_components: Some(AddressComponents {
+                suburb,
+                city,
+                state,
+                ..Default::default()
+            }),
+            timezone: None,
+            elevation_m: None,
+            language: None,
+        })
+    }
+
+    fn to_service(place: OsmPlace, distance_km: f64, category: &PlaceCategory) -> NearbyService {
+        NearbyService {
+            name: place.name,
+            service_type: category.clone(),
+            latitude: place.latitude,
+            longitude: place.longitude,
+            distance_km,
+            address: place.address,
+            rating: None,
+            place_id: Some(format!("osm:{}", place.osm_ref)),
+            phone_number: place.phone,
+            open_now: None,
+            opening_hours: None,
+            photos: Vec::new(),
+            service_types: Vec::new(),
+            travel_time_min: None,
+            departures: Vec::new(),
+            routes: Vec::new(),
+        }
+    }
+}
+
+impl GeocodingProvider for OfflineProvider {
+    fn name(&self) -> &'static str {
+        "offline"
+    }
+
+    fn geocode<'a>(
+        &'a self,
+        address: &'a str,
+    ) -> BoxFuture<'a, Result<GeoLocation, MapradarError>> {
+        Box::pin(async move {
+            let name = address.split(',').next().unwrap_or(address);
+            let place = self
+                .index
+                .find_by_name(name, 1)?
+                .into_iter()
+                .next()
+                .ok_or_else(|| MapradarError::AddressNotFound {
+                    query: address.to_string(),
+                })?;
+            self.describe(place.latitude, place.longitude, Some(&place.name))
+        })
+    }
+
+    fn reverse_geocode(
+        &self,
+        lat: f64,
+        lng: f64,
+    ) -> BoxFuture<'_, Result<GeoLocation, MapradarError>> {
+        Box::pin(async move { self.describe(lat, lng, None) })
+    }
+
+    fn nearby_search<'a>(
+        &'a self,
+        lat: f64,
+        lng: f64,
+        category: &'a PlaceCategory,
+        radius_meters: f64,
+        max_results: usize,
+    ) -> BoxFuture<'a, Result<Vec<NearbyService>, MapradarError>> {
+        Box::pin(async move {
+            let (key, value) = NominatimProvider::category_tag(category);
+            Ok(self
+                .index
+                .nearby(lat, lng, key, value, radius_meters / 1000.0, max_results)?
+                .into_iter()
+                .map(|(place, distance)| Self::to_service(place, distance, category))
+                .collect())
+        })
+    }
+
+    fn place_details<'a>(
+        &'a self,
+        place_id: &'a str,
+    ) -> BoxFuture<'a, Result<PlaceDetails, MapradarError>> {
+        Box::pin(async move {
+            let osm_ref = place_id.strip_prefix("osm:").unwrap_or(place_id);
+            let place = self.index.get(osm_ref)?.ok_or(MapradarError::ZeroResults)?;
+            Ok(PlaceDetails {
+                place_id: format!("osm:{}", place.osm_ref),
+                name: place.name,
+                latitude: place.latitude,
+                longitude: place.longitude,
+                address: place.address,
+                phone_number: place.phone,
+                website: None,
+                rating: None,
+                reviews_count: None,
+                open_now: None,
+                opening_hours: None,
+                photos: Vec::new(),
+                types: place
+                    .place
+                    .map(|place| vec![format!("place={}", place)])
+                    .unwrap_or_default(),
+            })
+        })
+    }
+}
@@ -0,0 +1,181 @@
+//! Shared plumbing for the Python bindings: JSON conversion and the common model methods.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use pyo3::{
+    IntoPyObjectExt,
+    exceptions::PyTypeError,
+    prelude::*,
+    types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+
+use crate::error::MapradarError;
+
+/// Implements a pyclass's `#[pymethods]` block, adding `from_dict`, `to_dict`, `from_json`,
+/// `to_json`, `__eq__`, `__hash__`, and `__str__` to any methods given in the braces.
+///
+/// The model round-trips through its serde representation, so Python dicts and JSON use the
+/// same field names as the CLI's JSON output.
+macro_rules! data_model {
+    ($ty:ident) => {
+        $crate::python::data_model!($ty {});
+    };
+    ($ty:ident { $($methods:tt)* }) => {
+        #[pyo3::pymethods]
+        impl $ty {
+            $($methods)*
+
+            /// Builds the model from a dict shaped like its JSON form.
+            #[staticmethod]
+            #[pyo3(name = "from_dict")]
+            fn py_from_dict(data: &pyo3::Bound<'_, pyo3::PyAny>) -> pyo3::PyResult<Self> {
+                $crate::python::from_dict(data)
+            }
+
+            /// The model as plain dicts, lists, and scalars.
+            #[pyo3(name = "to_dict")]
+            fn py_to_dict<'py>(
+                &self,
+                py: pyo3::Python<'py>,
+            ) -> pyo3::PyResult<pyo3::Bound<'py, pyo3::PyAny>> {
+                $crate::python::to_dict(py, self)
+            }
+
+            /// Parses the model from a JSON string.
+            #[staticmethod]
+            #[pyo3(name = "from_json")]
+            fn py_from_json(json: &str) -> pyo3::PyResult<Self> {
+                $crate::python::from_json(json)
+            }
+
+            /// Serializes the model to JSON, pretty-printed when `indent` is given.
+            #[pyo3(name = "to_json", signature = (indent=None))]
+            fn py_to_json(&self, indent: Option<usize>) -> pyo3::PyResult<String> {
+                $crate::python::to_json(self, indent)
+            }
+
+            fn __eq__(&self, other: &Self) -> pyo3::PyResult<bool> {
+                $crate::python::eq(self, other)
+            }
+
+            fn __hash__(&self) -> pyo3::PyResult<u64> {
+                $crate::python::hash(self)
+            }
+
+            fn __str__(&self) -> pyo3::PyResult<String> {
+                $crate::python::to_json(self, None)
+            }
+        }
+    };
+}
+
+pub(crate) use data_model;
+
+pub(crate) fn from_dict<T: DeserializeOwned>(data: &Bound<'_, PyAny>) -> PyResult<T> {
+    Ok(serde_json::from_value(py_to_json(data)?).map_err(MapradarError::from)?)
+}
+
+pub(crate) fn to_dict<'py, T: Serialize>(
+    py: Python<'py>,
+    model: &T,
+) -> PyResult<Bound<'py, PyAny>> {
+    json_to_py(py, &to_value(model)?)
+}
+
+pub(crate) fn from_json<T: DeserializeOwned>(json: &str) -> PyResult<T> {
+    Ok(serde_json::from_str(json).map_err(MapradarError::from)?)
+}
+
+pub(crate) fn to_json<T: Serialize>(model: &T, indent: Option<usize>) -> PyResult<String> {
+    let Some(indent) = indent else {
+        return Ok(serde_json::to_string(model).map_err(MapradarError::from)?);
+    };
+    let indent = " ".repeat(indent);
+    let mut buffer = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+    model
+        .serialize(&mut serializer)
+        .map_err(MapradarError::from)?;
+    Ok(String::from_utf8(buffer).expect("serde_json writes UTF-8"))
+}
+
+/// Field-by-field equality through the serialized form, since not every model is `PartialEq`.
+pub(crate) fn eq<T: Serialize>(a: &T, b: &T) -> PyResult<bool> {
+    Ok(to_value(a)? == to_value(b)?)
+}
+
+pub(crate) fn hash<T: Serialize>(model: &T) -> PyResult<u64> {
+    let mut hasher = DefaultHasher::new();
+    to_value(model)?.to_string().hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+fn to_value<T: Serialize>(model: &T) -> PyResult<Value> {
+    Ok(serde_json::to_value(model).map_err(MapradarError::from)?)
+}
+
+/// Converts a JSON value into the equivalent Python object: dicts, lists, and scalars.
+pub(crate) fn json_to_py<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    match value {
+        Value::Null => Ok(py.None().into_bound(py)),
+        Value::Bool(b) => b.into_bound_py_any(py),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => i.into_bound_py_any(py),
+            (None, Some(u)) => u.into_bound_py_any(py),
+            (None, None) => n.as_f64().unwrap_or(f64::NAN).into_bound_py_any(py),
+        },
+        Value::String(s) => s.into_bound_py_any(py),
+        Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(json_to_py(py, item)?)?;
+            }
+            Ok(list.into_any())
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, item) in map {
+                dict.set_item(key, json_to_py(py, item)?)?;
+            }
+            Ok(dict.into_any())
+        }
+    }
+}
+
+/// Converts dicts, lists, tuples, and scalars into a JSON value.
+pub(crate) fn py_to_json(object: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if object.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(b) = object.cast::<PyBool>() {
+        Ok(Value::Bool(b.is_true()))
+    } else if object.is_instance_of::<PyInt>() {
+        match object.extract::<i64>() {
+            Ok(i) => Ok(Value::from(i)),
+            Err(_) => Ok(Value::from(object.extract::<u64>()?)),
+        }
+    } else if let Ok(f) = object.cast::<PyFloat>() {
+        Ok(Value::from(f.value()))
+    } else if let Ok(s) = object.cast::<PyString>() {
+        Ok(Value::String(s.to_str()?.to_string()))
+    } else if let Ok(dict) = object.cast::<PyDict>() {
+        let mut map = Map::new();
+        for (key, item) in dict.iter() {
+            map.insert(key.str()?.to_str()?.to_string(), py_to_json(&item)?);
+        }
+        Ok(Value::Object(map))
+    } else if object.is_instance_of::<PyList>() || object.is_instance_of::<PyTuple>() {
+        object
+            .try_iter()?
+            .map(|item| py_to_json(&item?))
+            .collect::<PyResult<Vec<_>>>()
+            .map(Value::Array)
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "Cannot convert {} to JSON",
+            object.get_type().name()?
+        )))
+    }
+}
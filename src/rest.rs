@@ -0,0 +1,266 @@
+//! REST server fronting the upstream provider with `/geocode`, `/reverse`, and `/nearby`.
+//!
+//! Responses use the same JSON models as the library. Callers may send their own provider key in
+//! the `X-Api-Key` header; otherwise the server's configured key is used. Each request is logged
+//! to stderr as `METHOD path status duration`, without the query string or headers.
+
+use std::{net::SocketAddr, time::Instant};
+
+use axum::{
+    Extension, Json, Router,
+    extract::{MatchedPath, Query, Request, State, rejection::QueryRejection},
+    http::{HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use moka::future::Cache;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    client::{MapradarClient, MapradarClientBuilder},
+    coordinates::Coordinates,
+    error::MapradarError,
+    health,
+    metrics::{self, Metrics},
+    models::{GeoLocation, LocationIntelligence, PlaceCategory, SearchQuery},
+    rpc::http::shutdown_signal,
+};
+
+/// Header carrying a caller-supplied provider API key.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Passthrough clients kept alive so their in-memory caches and connection pools are reused.
+const MAX_PASSTHROUGH_CLIENTS: u64 = 64;
+
+#[derive(Clone)]
+struct AppState {
+    builder: MapradarClientBuilder,
+    /// `None` when the builder has no usable key, making the header mandatory.
+    default_client: Option<MapradarClient>,
+    passthrough: Cache<String, MapradarClient>,
+}
+
+impl AppState {
+    async fn client(&self, headers: &HeaderMap) -> Result<MapradarClient, ApiError> {
+        let Some(key) = headers.get(API_KEY_HEADER) else {
+            return self.default_client.clone().ok_or_else(|| ApiError {
+                status: StatusCode::UNAUTHORIZED,
+                error: MapradarError::Config(
+                    "No API key configured on the server; send one in the X-Api-Key header"
+                        .to_string(),
+                ),
+            });
+        };
+        let key = key.to_str().map_err(|_| {
+            ApiError::from(MapradarError::InvalidInput(
+                "X-Api-Key must be visible ASCII".to_string(),
+            ))
+        })?;
+
+        if let Some(client) = self.passthrough.get(key).await {
+            return Ok(client);
+        }
+        let client = self.builder.clone().api_key(key).build()?;
+        self.passthrough
+            .insert(key.to_string(), client.clone())
+            .await;
+        Ok(client)
+    }
+}
+
+/// Serves the REST API on `addr` until Ctrl-C, letting in-flight requests finish.
+///
+/// Clients are built from `builder`; if it cannot build one on its own (e.g. Google without a
+/// key), every request must supply the `X-Api-Key` header. `GET /metrics` reports requests,
+/// upstream calls, and cache use across every client; `/healthz` and `/readyz` report health
+/// (see [`crate::health`]).
+pub async fn serve(builder: MapradarClientBuilder, addr: SocketAddr) -> Result<(), MapradarError> {
+    let metrics = Metrics::new();
+    let builder = builder.metrics(metrics.clone());
+    let default_client = builder.clone().build().ok();
+    let state = AppState {
+        default_client: default_client.clone(),
+        builder,
+        passthrough: Cache::new(MAX_PASSTHROUGH_CLIENTS),
+    };
+
+    let app = Router::new()
+        .route("/geocode", get(geocode))
+        .route("/reverse", get(reverse))
+        .route("/nearby", get(nearby))
+        .layer(middleware::from_fn_with_state(metrics.clone(), log_request))
+        .with_state(state)
+        .merge(metrics::router(metrics))
+        .merge(health::router(default_client));
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+    Ok(())
+}
+
+async fn log_request(State(metrics): State<Metrics>, request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let route = request.extensions().get::<MatchedPath>().cloned();
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+    if let Some(route) = route {
+        let variant = response.extensions().get::<ErrorVariant>().map(|v| v.0);
+        metrics.record_request_variant("rest", route.as_str(), variant);
+    }
+    tracing::info!(
+        method = %method,
+        path,
+        status = response.status().as_u16(),
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "request"
+    );
+    response
+}
+
+#[derive(Deserialize)]
+struct GeocodeParams {
+    address: String,
+}
+
+#[derive(Deserialize)]
+struct ReverseParams {
+    #[serde(alias = "latitude")]
+    lat: f64,
+    #[serde(alias = "lng", alias = "longitude")]
+    lon: f64,
+}
+
+#[derive(Deserialize)]
+struct NearbyParams {
+    address: Option<String>,
+    #[serde(alias = "latitude")]
+    lat: Option<f64>,
+    #[serde(alias = "lng", alias = "longitude")]
+    lon: Option<f64>,
+    /// Comma-separated service types.
+    #[serde(default = "default_types", alias = "types")]
+    r#type: String,
+    #[serde(default = "default_radius_km")]
+    radius_km: f64,
+    #[serde(default = "default_max_results")]
+    max_results: usize,
+}
+
+fn default_types() -> String {
+    "bank".to_string()
+}
+
+fn default_radius_km() -> f64 {
+    1.0
+}
+
+fn default_max_results() -> usize {
+    10
+}
+
+async fn geocode(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    params: Result<Query<GeocodeParams>, QueryRejection>,
+) -> Result<Json<GeoLocation>, ApiError> {
+    let Query(params) = params?;
+    let client = state.client(&headers).await?;
+    Ok(Json(client.geocode_async(&params.address).await?))
+}
+
+async fn reverse(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    params: Result<Query<ReverseParams>, QueryRejection>,
+) -> Result<Json<GeoLocation>, ApiError> {
+    let Query(params) = params?;
+    let point = Coordinates::new(params.lat, params.lon)?;
+    let client = state.client(&headers).await?;
+    Ok(Json(client.reverse_geocode_async(point).await?))
+}
+
+async fn nearby(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    params: Result<Query<NearbyParams>, QueryRejection>,
+) -> Result<Json<LocationIntelligence>, ApiError> {
+    let Query(params) = params?;
+    let query = match (params.address, params.lat, params.lon) {
+        (_, Some(lat), Some(lon)) => SearchQuery::from_coordinates(lat, lon)?,
+        (Some(address), None, None) => SearchQuery::from_address(address),
+        _ => {
+            return Err(MapradarError::InvalidInput(
+                "Provide either address or both lat and lon".to_string(),
+            )
+            .into());
+        }
+    };
+    let categories = params
+        .r#type
+        .split(',')
+        .map(str::parse)
+        .collect::<Result<Vec<PlaceCategory>, MapradarError>>()?;
+
+    let client = state.client(&headers).await?;
+    Ok(Json(
+        client
+            .fetch_intelligence_async(query, categories, params.radius_km, params.max_results)
+            .await?,
+    ))
+}
+
+/// Marks an error response with its [`MapradarError::variant_name`] for the metrics layer.
+#[derive(Clone, Copy)]
+struct ErrorVariant(&'static str);
+
+/// Error body `{"error": {"code", "message"}}`, where `code` matches the JSON-RPC code.
+struct ApiError {
+    status: StatusCode,
+    error: MapradarError,
+}
+
+impl From<MapradarError> for ApiError {
+    fn from(error: MapradarError) -> Self {
+        let status = match &error {
+            MapradarError::InvalidInput(_) | MapradarError::InvalidCoordinates { .. } => {
+                StatusCode::BAD_REQUEST
+            }
+            MapradarError::AuthFailed { .. } => StatusCode::UNAUTHORIZED,
+            MapradarError::AddressNotFound { .. } | MapradarError::ZeroResults => {
+                StatusCode::NOT_FOUND
+            }
+            MapradarError::QuotaExceeded { .. } => StatusCode::TOO_MANY_REQUESTS,
+            MapradarError::Unsupported(_) => StatusCode::NOT_IMPLEMENTED,
+            MapradarError::Network(_)
+            | MapradarError::Api { .. }
+            | MapradarError::Deserialization(_) => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self { status, error }
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        MapradarError::InvalidInput(rejection.body_text()).into()
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = json!({
+            "error": {
+                "code": self.error.json_rpc_code(),
+                "message": self.error.to_string(),
+            }
+        });
+        let variant = ErrorVariant(self.error.variant_name());
+        (self.status, Extension(variant), Json(body)).into_response()
+    }
+}
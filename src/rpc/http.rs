@@ -0,0 +1,43 @@
+//! HTTP transport: JSON-RPC messages are POSTed to `/`.
+
+use std::net::SocketAddr;
+
+use axum::{
+    Json, Router,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+};
+
+use crate::{client::MapradarClient, error::MapradarError, health, metrics};
+
+/// Serves JSON-RPC over HTTP on `addr` until Ctrl-C, letting in-flight requests finish.
+///
+/// `GET /metrics` reports the client's [`Metrics`](crate::metrics::Metrics), or request counts
+/// alone when it has none; `/healthz` and `/readyz` report health (see [`crate::health`]).
+pub async fn serve_http(client: MapradarClient, addr: SocketAddr) -> Result<(), MapradarError> {
+    let metrics = client.metrics().cloned().unwrap_or_default();
+    let app = Router::new()
+        .route("/", post(handle))
+        .with_state(client.clone())
+        .merge(metrics::router(metrics))
+        .merge(health::router(Some(client)));
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+    Ok(())
+}
+
+/// Takes the raw body so malformed JSON is answered with a JSON-RPC parse error.
+async fn handle(State(client): State<MapradarClient>, body: String) -> Response {
+    match super::handle_message(&client, &body).await {
+        Some(reply) => Json(reply).into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    }
+}
+
+pub(crate) async fn shutdown_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
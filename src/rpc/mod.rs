@@ -0,0 +1,311 @@
+//! JSON-RPC 2.0 server: request parsing, method dispatch, and stdio/HTTP transports.
+//!
+//! Methods are `geocode`, `reverse`, and `nearby`, with params passed by name. A batch (an array
+//! of requests) is answered with an array of responses; notifications get no response.
+
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::{
+    client::MapradarClient,
+    coordinates::Coordinates,
+    error::MapradarError,
+    models::{JsonRpcError, PlaceCategory, SearchQuery},
+};
+
+#[cfg(feature = "server")]
+pub mod http;
+
+pub const PARSE_ERROR: i32 = -32700;
+pub const INVALID_REQUEST: i32 = -32600;
+pub const METHOD_NOT_FOUND: i32 = -32601;
+pub const INVALID_PARAMS: i32 = -32602;
+
+/// Wire-format response. Unlike [`JsonRpcResponse`](crate::models::JsonRpcResponse), the result
+/// is embedded as JSON rather than a string, and the id keeps the type the caller sent.
+#[derive(Debug, Clone, Serialize)]
+pub struct RpcResponse {
+    pub jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+    pub id: Value,
+}
+
+impl RpcResponse {
+    pub fn success(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            result: Some(result),
+            error: None,
+            id,
+        }
+    }
+
+    pub fn failure(id: Value, code: i32, message: String) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            result: None,
+            error: Some(JsonRpcError::new(code, message, None)),
+            id,
+        }
+    }
+
+    fn from_result(id: Value, result: Result<Value, MapradarError>) -> Self {
+        match result {
+            Ok(value) => Self::success(id, value),
+            Err(err) => Self::failure(id, err.json_rpc_code(), err.to_string()),
+        }
+    }
+}
+
+/// A request that passed envelope validation.
+pub struct RpcRequest {
+    pub method: String,
+    pub params: Value,
+    /// `None` for notifications.
+    pub id: Option<Value>,
+}
+
+impl RpcRequest {
+    /// Validates the JSON-RPC envelope, returning the error response to send when it is malformed.
+    pub fn parse(message: Value) -> Result<Self, Box<RpcResponse>> {
+        let id = message.get("id").cloned();
+        let invalid = |reason: &str| {
+            Box::new(RpcResponse::failure(
+                id.clone().unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                format!("Invalid request: {}", reason),
+            ))
+        };
+
+        if message.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+            return Err(invalid("jsonrpc must be \"2.0\""));
+        }
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            return Err(invalid("method must be a string"));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        if !matches!(params, Value::Null | Value::Object(_)) {
+            return Err(Box::new(RpcResponse::failure(
+                id.unwrap_or(Value::Null),
+                INVALID_PARAMS,
+                "Params must be passed by name".to_string(),
+            )));
+        }
+
+        Ok(Self {
+            method: method.to_string(),
+            params,
+            id,
+        })
+    }
+}
+
+/// Handles one message (a single request or a batch), returning the JSON to send back, if any.
+pub async fn handle_message(client: &MapradarClient, message: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(message) {
+        Ok(message) => message,
+        Err(err) => {
+            return to_json(RpcResponse::failure(
+                Value::Null,
+                PARSE_ERROR,
+                format!("Parse error: {}", err),
+            ));
+        }
+    };
+
+    match message {
+        Value::Array(batch) if batch.is_empty() => to_json(RpcResponse::failure(
+            Value::Null,
+            INVALID_REQUEST,
+            "Invalid request: empty batch".to_string(),
+        )),
+        Value::Array(batch) => {
+            let responses: Vec<RpcResponse> = futures::future::join_all(
+                batch
+                    .into_iter()
+                    .map(|request| handle_request(client, request)),
+            )
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+            if responses.is_empty() {
+                None
+            } else {
+                serde_json::to_value(responses).ok()
+            }
+        }
+        request => to_json(handle_request(client, request).await?),
+    }
+}
+
+async fn handle_request(client: &MapradarClient, request: Value) -> Option<RpcResponse> {
+    let request = match RpcRequest::parse(request) {
+        Ok(request) => request,
+        Err(response) => return Some(*response),
+    };
+    let result = call(client, &request.method, request.params).await;
+    if let Some(metrics) = client.metrics() {
+        // Unknown method names are caller-controlled, so they share one label.
+        let method = match request.method.as_str() {
+            method @ ("geocode" | "reverse" | "nearby") => method,
+            _ => "unknown",
+        };
+        metrics.record_request("jsonrpc", method, result.as_ref().err());
+    }
+    // Notifications run for their side effects only.
+    let id = request.id?;
+    Some(RpcResponse::from_result(id, result))
+}
+
+fn to_json(response: RpcResponse) -> Option<Value> {
+    serde_json::to_value(response).ok()
+}
+
+/// Runs a method with params given by name.
+pub async fn call(
+    client: &MapradarClient,
+    method: &str,
+    params: Value,
+) -> Result<Value, MapradarError> {
+    match method {
+        "geocode" => {
+            let params: GeocodeParams = parse_params(params)?;
+            to_value(client.geocode_async(&params.address).await)
+        }
+        "reverse" => {
+            let params: ReverseParams = parse_params(params)?;
+            to_value(
+                client
+                    .reverse_geocode_async(Coordinates::new(params.latitude, params.longitude)?)
+                    .await,
+            )
+        }
+        "nearby" => {
+            let params: NearbyParams = parse_params(params)?;
+            let categories = params
+                .types
+                .iter()
+                .map(|t| t.parse())
+                .collect::<Result<Vec<PlaceCategory>, MapradarError>>()?;
+            to_value(
+                client
+                    .fetch_intelligence_async(
+                        params.query()?,
+                        categories,
+                        params.radius_km,
+                        params.max_results,
+                    )
+                    .await,
+            )
+        }
+        other => Err(MapradarError::Unsupported(format!(
+            "unknown method '{}'",
+            other
+        ))),
+    }
+}
+
+#[derive(Deserialize)]
+struct GeocodeParams {
+    address: String,
+}
+
+#[derive(Deserialize)]
+struct ReverseParams {
+    #[serde(alias = "lat")]
+    latitude: f64,
+    #[serde(alias = "lng", alias = "lon")]
+    longitude: f64,
+}
+
+#[derive(Deserialize)]
+struct NearbyParams {
+    address: Option<String>,
+    #[serde(alias = "lat")]
+    latitude: Option<f64>,
+    #[serde(alias = "lng", alias = "lon")]
+    longitude: Option<f64>,
+    #[serde(default = "default_types")]
+    types: Vec<String>,
+    #[serde(default = "default_radius_km")]
+    radius_km: f64,
+    #[serde(default = "default_max_results")]
+    max_results: usize,
+}
+
+fn default_types() -> Vec<String> {
+    vec!["bank".to_string()]
+}
+
+fn default_radius_km() -> f64 {
+    1.0
+}
+
+fn default_max_results() -> usize {
+    10
+}
+
+impl NearbyParams {
+    fn query(&self) -> Result<SearchQuery, MapradarError> {
+        match (&self.address, self.latitude, self.longitude) {
+            (_, Some(latitude), Some(longitude)) => {
+                SearchQuery::from_coordinates(latitude, longitude)
+            }
+            (Some(address), None, None) => Ok(SearchQuery::from_address(address.clone())),
+            _ => Err(MapradarError::InvalidInput(
+                "Provide either address or both latitude and longitude".to_string(),
+            )),
+        }
+    }
+}
+
+fn parse_params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, MapradarError> {
+    let params = match params {
+        Value::Null => Value::Object(Default::default()),
+        params => params,
+    };
+    serde_json::from_value(params).map_err(|err| MapradarError::InvalidInput(err.to_string()))
+}
+
+fn to_value<T: Serialize>(result: Result<T, MapradarError>) -> Result<Value, MapradarError> {
+    Ok(serde_json::to_value(result?)?)
+}
+
+/// Serves JSON-RPC requests from stdin, one message per line, until it closes.
+pub async fn serve_stdio(client: MapradarClient) -> Result<(), MapradarError> {
+    serve_lines(|line| {
+        let client = &client;
+        async move { handle_message(client, &line).await }
+    })
+    .await
+}
+
+/// Reads newline-delimited messages from stdin and writes each reply as one line on stdout.
+pub(crate) async fn serve_lines<F, Fut>(mut handle: F) -> Result<(), MapradarError>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Option<Value>>,
+{
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(reply) = handle(line).await {
+            let mut bytes = serde_json::to_vec(&reply)?;
+            bytes.push(b'\n');
+            stdout.write_all(&bytes).await?;
+            stdout.flush().await?;
+        }
+    }
+    Ok(())
+}
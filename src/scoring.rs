@@ -0,0 +1,304 @@
+//! Walkability / amenity scores: a 0-100 rating of how well a location is served by nearby
+//! services.
+//!
+//! Each category earns full marks when its nearest result is within walking range and nothing
+//! beyond the cut-off distance, decaying linearly in between. Category scores are combined as a
+//! weighted mean, so a nearby supermarket can count for more than a nearby cinema.
+
+use std::{collections::BTreeMap, path::Path};
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::MapradarError,
+    models::{GeoLocation, LocationIntelligence, NearbyService, PlaceCategory, ServiceType},
+    spatial::SpatialIndex,
+};
+
+/// Distance up to which a service earns full marks (about a five-minute walk).
+pub const DEFAULT_FULL_SCORE_KM: f64 = 0.4;
+
+/// Distance beyond which a service earns nothing (about a twenty-minute walk).
+pub const DEFAULT_MAX_DISTANCE_KM: f64 = 1.6;
+
+/// Weight given to searched categories missing from the weight table.
+const DEFAULT_WEIGHT: f64 = 1.0;
+
+/// Per-category weights and distance thresholds used to compute an [`AmenityScore`].
+#[derive(Debug, Clone)]
+pub struct ScoreWeights {
+    weights: Vec<(PlaceCategory, f64)>,
+    full_score_km: f64,
+    max_distance_km: f64,
+}
+
+impl Default for ScoreWeights {
+    /// Everyday errands weigh most: groceries, transit, and healthcare.
+    fn default() -> Self {
+        let weights = [
+            (ServiceType::Supermarket, 3.0),
+            (ServiceType::Market, 2.0),
+            (ServiceType::BusStop, 2.0),
+            (ServiceType::Restaurant, 2.0),
+            (ServiceType::Pharmacy, 1.5),
+            (ServiceType::School, 1.5),
+            (ServiceType::TrainStation, 1.0),
+            (ServiceType::Hospital, 1.0),
+            (ServiceType::Bank, 1.0),
+            (ServiceType::Cafe, 1.0),
+            (ServiceType::Park, 1.0),
+            (ServiceType::Gym, 0.5),
+            (ServiceType::Library, 0.5),
+            (ServiceType::PostOffice, 0.5),
+            (ServiceType::Cinema, 0.5),
+        ];
+        Self {
+            weights: weights
+                .into_iter()
+                .map(|(service_type, weight)| (service_type.into(), weight))
+                .collect(),
+            full_score_km: DEFAULT_FULL_SCORE_KM,
+            max_distance_km: DEFAULT_MAX_DISTANCE_KM,
+        }
+    }
+}
+
+/// On-disk form of [`ScoreWeights`]. A `[weights]` table replaces the default weights entirely.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WeightsFile {
+    full_score_km: Option<f64>,
+    max_distance_km: Option<f64>,
+    weights: Option<BTreeMap<String, f64>>,
+}
+
+impl ScoreWeights {
+    /// Reads weights from a TOML file such as:
+    ///
+    /// ```toml
+    /// full_score_km = 0.4
+    /// max_distance_km = 1.6
+    ///
+    /// [weights]
+    /// supermarket = 3
+    /// bus-stop = 2
+    /// "custom:atm" = 1
+    /// ```
+    pub fn load(path: &Path) -> Result<Self, MapradarError> {
+        let text = std::fs::read_to_string(path)?;
+        Self::from_toml(&text)
+            .map_err(|err| MapradarError::Config(format!("{}: {}", path.display(), err)))
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, MapradarError> {
+        let file: WeightsFile =
+            toml::from_str(text).map_err(|err| MapradarError::Config(err.to_string()))?;
+        let mut weights = Self::default();
+
+        if let Some(table) = file.weights {
+            weights.weights = table
+                .into_iter()
+                .map(|(category, weight)| {
+                    if !(weight >= 0.0 && weight.is_finite()) {
+                        return Err(MapradarError::Config(format!(
+                            "Weight for '{}' must be a non-negative number",
+                            category
+                        )));
+                    }
+                    Ok((category.parse()?, weight))
+                })
+                .collect::<Result<_, MapradarError>>()?;
+        }
+        weights.full_score_km = file.full_score_km.unwrap_or(weights.full_score_km);
+        weights.max_distance_km = file.max_distance_km.unwrap_or(weights.max_distance_km);
+
+        if !(0.0 <= weights.full_score_km && weights.full_score_km < weights.max_distance_km) {
+            return Err(MapradarError::Config(
+                "full_score_km must be at least 0 and less than max_distance_km".to_string(),
+            ));
+        }
+        Ok(weights)
+    }
+
+    /// Categories with a non-zero weight, heaviest first.
+    pub fn categories(&self) -> Vec<PlaceCategory> {
+        let mut weighted: Vec<_> = self.weights.iter().filter(|(_, w)| *w > 0.0).collect();
+        weighted.sort_by(|a, b| b.1.total_cmp(&a.1));
+        weighted.into_iter().map(|(c, _)| c.clone()).collect()
+    }
+
+    /// Search radius that covers every distance the score can reward.
+    pub fn max_distance_km(&self) -> f64 {
+        self.max_distance_km
+    }
+
+    pub fn weight(&self, category: &PlaceCategory) -> f64 {
+        self.weights
+            .iter()
+            .find(|(c, _)| c == category)
+            .map_or(DEFAULT_WEIGHT, |(_, weight)| *weight)
+    }
+
+    /// Share of full marks (0.0 to 1.0) earned by a service `distance_km` away.
+    fn decay(&self, distance_km: f64) -> f64 {
+        let span = self.max_distance_km - self.full_score_km;
+        (1.0 - (distance_km - self.full_score_km) / span).clamp(0.0, 1.0)
+    }
+
+    /// Scores `intel` over the categories that were searched. A searched category with no
+    /// results scores zero rather than being skipped.
+    pub fn score(
+        &self,
+        intel: &LocationIntelligence,
+        categories: &[PlaceCategory],
+    ) -> AmenityScore {
+        let index = SpatialIndex::new(intel.nearby_services.clone());
+        self.score_at(
+            &index,
+            intel.location.latitude,
+            intel.location.longitude,
+            categories,
+        )
+    }
+
+    /// Scores any point against already-fetched results, e.g. another address on the same
+    /// street, with distances measured from that point.
+    pub fn score_at(
+        &self,
+        index: &SpatialIndex,
+        lat: f64,
+        lng: f64,
+        categories: &[PlaceCategory],
+    ) -> AmenityScore {
+        let breakdown: Vec<CategoryScore> = categories
+            .iter()
+            .map(|category| {
+                let in_category = |service: &NearbyService| service.matches_category(category);
+                let nearest_km = index
+                    .nearest_matching(lat, lng, 1, in_category)
+                    .first()
+                    .map(|service| service.distance_km);
+                CategoryScore {
+                    category: category.clone(),
+                    weight: self.weight(category),
+                    count: index
+                        .services()
+                        .filter(|service| in_category(service))
+                        .count(),
+                    nearest_km,
+                    score: nearest_km.map_or(0.0, |d| self.decay(d) * 100.0),
+                }
+            })
+            .collect();
+
+        let total_weight: f64 = breakdown.iter().map(|c| c.weight).sum();
+        let score = if total_weight > 0.0 {
+            breakdown.iter().map(|c| c.score * c.weight).sum::<f64>() / total_weight
+        } else {
+            0.0
+        };
+
+        AmenityScore {
+            score: (score * 10.0).round() / 10.0,
+            categories: breakdown,
+        }
+    }
+}
+
+/// Overall 0-100 amenity score with its per-category breakdown.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmenityScore {
+    pub score: f64,
+    pub categories: Vec<CategoryScore>,
+}
+
+/// Contribution of one service category to an [`AmenityScore`].
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryScore {
+    pub category: PlaceCategory,
+    pub weight: f64,
+    /// Results found for the category.
+    pub count: usize,
+    pub nearest_km: Option<f64>,
+    /// 0-100 score from the nearest result's distance.
+    pub score: f64,
+}
+
+#[cfg(feature = "python")]
+crate::python::data_model!(AmenityScore);
+#[cfg(feature = "python")]
+crate::python::data_model!(CategoryScore);
+
+/// Side-by-side amenity comparison of two locations searched for the same categories.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comparison {
+    pub first: ComparedLocation,
+    pub second: ComparedLocation,
+    /// `first` minus `second` overall score; positive when the first location is better served.
+    pub score_diff: f64,
+    pub categories: Vec<CategoryComparison>,
+}
+
+/// One side of a [`Comparison`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparedLocation {
+    pub location: GeoLocation,
+    pub score: f64,
+}
+
+/// Counts and nearest distances for one category at both locations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryComparison {
+    pub category: PlaceCategory,
+    pub first_count: usize,
+    pub second_count: usize,
+    pub first_nearest_km: Option<f64>,
+    pub second_nearest_km: Option<f64>,
+    /// `first` minus `second` category score.
+    pub score_diff: f64,
+}
+
+impl ScoreWeights {
+    /// Scores both locations over `categories` and pairs up the per-category results.
+    pub fn compare(
+        &self,
+        first: &LocationIntelligence,
+        second: &LocationIntelligence,
+        categories: &[PlaceCategory],
+    ) -> Comparison {
+        let first_score = self.score(first, categories);
+        let second_score = self.score(second, categories);
+        let round = |value: f64| (value * 10.0).round() / 10.0;
+
+        let categories = first_score
+            .categories
+            .iter()
+            .zip(&second_score.categories)
+            .map(|(a, b)| CategoryComparison {
+                category: a.category.clone(),
+                first_count: a.count,
+                second_count: b.count,
+                first_nearest_km: a.nearest_km,
+                second_nearest_km: b.nearest_km,
+                score_diff: round(a.score - b.score),
+            })
+            .collect();
+
+        Comparison {
+            first: ComparedLocation {
+                location: first.location.clone(),
+                score: first_score.score,
+            },
+            second: ComparedLocation {
+                location: second.location.clone(),
+                score: second_score.score,
+            },
+            score_diff: round(first_score.score - second_score.score),
+            categories,
+        }
+    }
+}
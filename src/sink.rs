@@ -0,0 +1,100 @@
+//! Delivery of results to places other than stdout.
+//!
+//! [`WebhookSink`] POSTs a JSON payload to an HTTP endpoint, retrying transient failures and
+//! optionally signing the body so the receiver can verify where it came from.
+
+use hmac::{Hmac, Mac};
+use reqwest::header::{CONTENT_TYPE, HeaderName, HeaderValue};
+use serde::Serialize;
+use sha2::Sha256;
+
+use crate::{
+    error::MapradarError,
+    transport::{RetryPolicy, Transport},
+};
+
+/// Header carrying the HMAC-SHA256 signature of the request body, as `sha256=<hex>`.
+pub const SIGNATURE_HEADER: &str = "X-Mapradar-Signature";
+
+/// Sends results as JSON POST requests to a webhook URL.
+#[derive(Clone)]
+pub struct WebhookSink {
+    url: reqwest::Url,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    secret: Option<Vec<u8>>,
+    transport: Transport,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str) -> Result<Self, MapradarError> {
+        let url = reqwest::Url::parse(url)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+            .ok_or_else(|| MapradarError::InvalidInput(format!("Invalid webhook URL '{}'", url)))?;
+        Ok(Self {
+            url,
+            headers: Vec::new(),
+            secret: None,
+            transport: Transport::default(),
+        })
+    }
+
+    /// Adds a header to every delivery, e.g. `Authorization`.
+    pub fn header(mut self, name: &str, value: &str) -> Result<Self, MapradarError> {
+        let invalid = || MapradarError::InvalidInput(format!("Invalid header '{}'", name));
+        let name = HeaderName::from_bytes(name.trim().as_bytes()).map_err(|_| invalid())?;
+        let value = HeaderValue::from_str(value.trim()).map_err(|_| invalid())?;
+        self.headers.push((name, value));
+        Ok(self)
+    }
+
+    /// Signs each body with HMAC-SHA256 under `secret`, sent in [`SIGNATURE_HEADER`].
+    pub fn secret(mut self, secret: impl Into<Vec<u8>>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    /// Retries for connection failures, `429`, and `5xx` responses.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.transport = Transport::new(reqwest::Client::new(), retry_policy);
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    /// POSTs `payload` as JSON, succeeding once the endpoint answers with a 2xx status.
+    pub async fn deliver<T: Serialize + ?Sized>(&self, payload: &T) -> Result<(), MapradarError> {
+        let body = serde_json::to_vec(payload)?;
+        let mut request = self
+            .transport
+            .post(self.url.clone())
+            .header(CONTENT_TYPE, "application/json");
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        if let Some(secret) = &self.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, &body));
+        }
+
+        let response = self.transport.send(request.body(body)).await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        Err(MapradarError::Api {
+            status: status.as_u16().to_string(),
+            message: format!("Webhook {} rejected the delivery", self.url),
+        })
+    }
+}
+
+/// `sha256=<hex>` HMAC of `body`, in the format GitHub-style receivers verify.
+pub fn sign(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("sha256={}", hex)
+}
@@ -0,0 +1,143 @@
+//! In-memory r-tree over nearby-search results, for re-querying them without new API calls.
+//!
+//! Points are stored as unit vectors on the sphere, where straight-line distance grows with
+//! great-circle distance. Nearest-neighbour and radius queries are therefore exact, including
+//! across the antimeridian and near the poles, and reported distances are haversine km.
+
+use rstar::{RTree, primitives::GeomWithData};
+
+use crate::{
+    geo::{EARTH_RADIUS_KM, haversine_distance},
+    models::NearbyService,
+};
+
+type IndexedService = GeomWithData<[f64; 3], NearbyService>;
+
+/// Spatial index of [`NearbyService`] results.
+///
+/// Query results are copies of the indexed services with `distance_km` measured from the query
+/// point, nearest first.
+///
+/// ```rust,ignore
+/// use mapradar::spatial::SpatialIndex;
+///
+/// let index = SpatialIndex::new(intel.nearby_services);
+/// let closest = index.nearest(6.5095, 3.3711, 3);
+/// let walkable = index.within(6.5095, 3.3711, 0.8);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SpatialIndex {
+    tree: RTree<IndexedService>,
+}
+
+/// Unit vector for a latitude/longitude in degrees.
+pub(crate) fn to_unit_vector(lat: f64, lng: f64) -> [f64; 3] {
+    let (lat, lng) = (lat.to_radians(), lng.to_radians());
+    [lat.cos() * lng.cos(), lat.cos() * lng.sin(), lat.sin()]
+}
+
+/// Latitude and longitude in degrees of the direction of a vector, which need not be unit length.
+pub(crate) fn from_vector(v: [f64; 3]) -> (f64, f64) {
+    (
+        v[2].atan2(v[0].hypot(v[1])).to_degrees(),
+        v[1].atan2(v[0]).to_degrees(),
+    )
+}
+
+/// Squared chord length subtending `radius_km` on the unit sphere, the r-tree's distance metric.
+pub(crate) fn chord_squared(radius_km: f64) -> f64 {
+    let angle = (radius_km.max(0.0) / EARTH_RADIUS_KM).min(std::f64::consts::PI);
+    let chord = 2.0 * (angle / 2.0).sin();
+    chord * chord
+}
+
+impl SpatialIndex {
+    pub fn new(services: Vec<NearbyService>) -> Self {
+        Self {
+            tree: RTree::bulk_load(
+                services
+                    .into_iter()
+                    .map(|service| {
+                        GeomWithData::new(
+                            to_unit_vector(service.latitude, service.longitude),
+                            service,
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.size() == 0
+    }
+
+    pub fn insert(&mut self, service: NearbyService) {
+        self.tree.insert(GeomWithData::new(
+            to_unit_vector(service.latitude, service.longitude),
+            service,
+        ));
+    }
+
+    /// Indexed services, in no particular order.
+    pub fn services(&self) -> impl Iterator<Item = &NearbyService> {
+        self.tree.iter().map(|entry| &entry.data)
+    }
+
+    /// The `k` services closest to the point.
+    pub fn nearest(&self, lat: f64, lng: f64, k: usize) -> Vec<NearbyService> {
+        self.nearest_matching(lat, lng, k, |_| true)
+    }
+
+    /// The `k` services closest to the point among those `predicate` accepts, e.g. one category.
+    pub fn nearest_matching(
+        &self,
+        lat: f64,
+        lng: f64,
+        k: usize,
+        predicate: impl Fn(&NearbyService) -> bool,
+    ) -> Vec<NearbyService> {
+        self.tree
+            .nearest_neighbor_iter(to_unit_vector(lat, lng))
+            .map(|entry| &entry.data)
+            .filter(|service| predicate(service))
+            .take(k)
+            .map(|service| Self::measured(service, lat, lng))
+            .collect()
+    }
+
+    /// Services within `radius_km` of the point.
+    pub fn within(&self, lat: f64, lng: f64, radius_km: f64) -> Vec<NearbyService> {
+        let mut services: Vec<NearbyService> = self
+            .tree
+            .locate_within_distance(to_unit_vector(lat, lng), chord_squared(radius_km))
+            .map(|entry| Self::measured(&entry.data, lat, lng))
+            .filter(|service| service.distance_km <= radius_km)
+            .collect();
+        services.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km));
+        services
+    }
+
+    fn measured(service: &NearbyService, lat: f64, lng: f64) -> NearbyService {
+        NearbyService {
+            distance_km: haversine_distance(lat, lng, service.latitude, service.longitude),
+            ..service.clone()
+        }
+    }
+}
+
+impl From<Vec<NearbyService>> for SpatialIndex {
+    fn from(services: Vec<NearbyService>) -> Self {
+        Self::new(services)
+    }
+}
+
+impl FromIterator<NearbyService> for SpatialIndex {
+    fn from_iter<I: IntoIterator<Item = NearbyService>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::MapradarError,
+    geo::destination_point,
+    models::{LocationIntelligence, PlaceCategory, ServiceType},
+};
+
+/// Largest width or height, in pixels, that static map backends render.
+pub const MAX_SIZE: u32 = 640;
+
+/// Points used to draw the outline of a radius circle.
+const CIRCLE_POINTS: usize = 36;
+
+/// Color of the searched location's marker.
+const ORIGIN_COLOR: &str = "0xd93025";
+/// Color shared by every custom category.
+const CUSTOM_COLOR: &str = "0x5f6368";
+/// Radius circle stroke and fill, as `0xRRGGBBAA`.
+pub const CIRCLE_STROKE: &str = "0x1a73e8cc";
+pub const CIRCLE_FILL: &str = "0x1a73e822";
+
+/// Marker colors as `0xRRGGBB`, cycled across service types in [`ServiceType::ALL`] order.
+const PALETTE: [&str; 12] = [
+    "0x1a73e8", "0xf29900", "0x188038", "0x9334e6", "0xe37400", "0x12b5cb", "0xe52592", "0x795548",
+    "0x3949ab", "0x7cb342", "0x00897b", "0xc0ca33",
+];
+
+/// A pin on a static map.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapMarker {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Fill color as `0xRRGGBB`.
+    pub color: String,
+    /// Single uppercase letter or digit drawn on the pin.
+    pub label: Option<char>,
+}
+
+/// A circle drawn around a point, such as the radius of a nearby search.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MapCircle {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius_m: f64,
+}
+
+impl MapCircle {
+    /// Closed outline of the circle as `(lat, lng)` points.
+    pub fn outline(&self) -> Vec<(f64, f64)> {
+        (0..=CIRCLE_POINTS)
+            .map(|i| {
+                destination_point(
+                    self.latitude,
+                    self.longitude,
+                    360.0 * i as f64 / CIRCLE_POINTS as f64,
+                    self.radius_m / 1000.0,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Everything drawn on a static map image. The backend fits the view to the markers and circle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaticMap {
+    /// Image width in pixels, at most [`MAX_SIZE`].
+    pub width: u32,
+    /// Image height in pixels, at most [`MAX_SIZE`].
+    pub height: u32,
+    pub markers: Vec<MapMarker>,
+    pub circle: Option<MapCircle>,
+}
+
+impl StaticMap {
+    /// An empty map of the given size, clamped to [`MAX_SIZE`].
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width: width.clamp(1, MAX_SIZE),
+            height: height.clamp(1, MAX_SIZE),
+            markers: Vec::new(),
+            circle: None,
+        }
+    }
+
+    /// Maps the searched location as a red pin and each nearby service as a pin colored and
+    /// labelled by its service type.
+    pub fn from_intelligence(intel: &LocationIntelligence, width: u32, height: u32) -> Self {
+        let mut map = Self::new(width, height);
+        map.markers.push(MapMarker {
+            latitude: intel.location.latitude,
+            longitude: intel.location.longitude,
+            color: ORIGIN_COLOR.to_string(),
+            label: None,
+        });
+        map.markers
+            .extend(intel.nearby_services.iter().map(|service| {
+                MapMarker {
+                    latitude: service.latitude,
+                    longitude: service.longitude,
+                    color: marker_color(&service.service_type).to_string(),
+                    label: service
+                        .service_type
+                        .to_string()
+                        .chars()
+                        .next()
+                        .map(|c| c.to_ascii_uppercase())
+                        .filter(char::is_ascii_alphanumeric),
+                }
+            }));
+        map
+    }
+
+    /// Adds a circle of `radius_m` around a point.
+    pub fn with_circle(mut self, latitude: f64, longitude: f64, radius_m: f64) -> Self {
+        self.circle = Some(MapCircle {
+            latitude,
+            longitude,
+            radius_m,
+        });
+        self
+    }
+}
+
+/// Marker color for a category; built-in types keep the same color from map to map.
+pub fn marker_color(category: &PlaceCategory) -> &'static str {
+    match category {
+        PlaceCategory::Known(service_type) => ServiceType::ALL
+            .iter()
+            .position(|t| t == service_type)
+            .map_or(CUSTOM_COLOR, |index| PALETTE[index % PALETTE.len()]),
+        PlaceCategory::Custom(_) => CUSTOM_COLOR,
+    }
+}
+
+/// Parses an image size such as `640x400`.
+pub fn parse_size(s: &str) -> Result<(u32, u32), MapradarError> {
+    let invalid = || MapradarError::InvalidInput(format!("Invalid size '{}' (expected WxH)", s));
+    let (width, height) = s.trim().split_once(['x', 'X']).ok_or_else(invalid)?;
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|n| (1..=MAX_SIZE).contains(n))
+            .ok_or_else(|| {
+                MapradarError::InvalidInput(format!(
+                    "Invalid size '{}' (each side must be 1-{} pixels)",
+                    s, MAX_SIZE
+                ))
+            })
+    };
+    Ok((parse(width)?, parse(height)?))
+}
@@ -0,0 +1,751 @@
+use colored::Colorize;
+use serde::Serialize;
+use std::{cmp::Ordering, collections::BTreeMap, io::Write, str::FromStr};
+
+use crate::{
+    batch::BatchRecord,
+    clustering::ServiceCluster,
+    error::MapradarError,
+    geohash::GeohashCluster,
+    heatmap::{Heatmap, HeatmapCell},
+    models::{NearbyService, SnappedPoint},
+    scoring::Comparison,
+    units::Units,
+    usage::UsageReport,
+};
+
+pub use crate::models::SortKey;
+
+/// Flat, spreadsheet-friendly row for a nearby service.
+#[derive(Debug, Serialize)]
+pub struct ServiceRow<'a> {
+    pub name: &'a str,
+    #[serde(rename = "type")]
+    pub service_type: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub distance_km: f64,
+    /// Set by [`ServiceRow::in_units`] for imperial output.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance_mi: Option<f64>,
+    pub rating: Option<f32>,
+    pub address: Option<&'a str>,
+}
+
+impl ServiceRow<'_> {
+    /// Adds a `distance_mi` column when `units` is imperial.
+    pub fn in_units(mut self, units: Units) -> Self {
+        self.distance_mi =
+            (units == Units::Imperial).then(|| units.distance_from_km(self.distance_km));
+        self
+    }
+}
+
+impl<'a> From<&'a NearbyService> for ServiceRow<'a> {
+    fn from(service: &'a NearbyService) -> Self {
+        Self {
+            name: &service.name,
+            service_type: type_label(service),
+            latitude: service.latitude,
+            longitude: service.longitude,
+            distance_km: service.distance_km,
+            distance_mi: None,
+            rating: service.rating,
+            address: service.address.as_deref(),
+        }
+    }
+}
+
+/// Flat row for a batch geocoding record; location columns are empty for failed rows.
+#[derive(Debug, Serialize)]
+pub struct BatchRow<'a> {
+    pub row: usize,
+    pub input: &'a str,
+    pub address: Option<&'a str>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub city: Option<&'a str>,
+    pub state: Option<&'a str>,
+    pub country: Option<&'a str>,
+    pub error: Option<&'a str>,
+}
+
+impl<'a> From<&'a BatchRecord> for BatchRow<'a> {
+    fn from(record: &'a BatchRecord) -> Self {
+        let location = record.location.as_ref();
+        Self {
+            row: record.row,
+            input: &record.input,
+            address: location.map(|l| l.address.as_str()),
+            latitude: location.map(|l| l.latitude),
+            longitude: location.map(|l| l.longitude),
+            city: location.and_then(|l| l.city.as_deref()),
+            state: location.and_then(|l| l.state.as_deref()),
+            country: location.map(|l| l.country.as_str()),
+            error: record.error.as_deref(),
+        }
+    }
+}
+
+/// Flat row for a point snapped to a road; the road columns are empty when the provider gives
+/// none.
+#[derive(Debug, Serialize)]
+pub struct SnapRow<'a> {
+    pub index: usize,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub original_latitude: f64,
+    pub original_longitude: f64,
+    pub snap_distance_m: Option<f64>,
+    pub road: Option<&'a str>,
+    pub place_id: Option<&'a str>,
+}
+
+impl<'a> From<&'a SnappedPoint> for SnapRow<'a> {
+    fn from(point: &'a SnappedPoint) -> Self {
+        Self {
+            index: point.index,
+            latitude: point.latitude,
+            longitude: point.longitude,
+            original_latitude: point.original_latitude,
+            original_longitude: point.original_longitude,
+            snap_distance_m: point.snap_distance_m,
+            road: point.road.as_deref(),
+            place_id: point.place_id.as_deref(),
+        }
+    }
+}
+
+/// Flat row for a geohash cluster: its cell, centre, and counts.
+#[derive(Debug, Serialize)]
+pub struct GeohashRow<'a> {
+    pub geohash: &'a str,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub count: usize,
+    /// Counts per type, e.g. "atm:2 bank:3".
+    pub types: String,
+}
+
+impl<'a> From<&'a GeohashCluster> for GeohashRow<'a> {
+    fn from(cluster: &'a GeohashCluster) -> Self {
+        Self {
+            geohash: &cluster.geohash,
+            latitude: cluster.latitude,
+            longitude: cluster.longitude,
+            count: cluster.count,
+            types: join_counts(&cluster.by_type),
+        }
+    }
+}
+
+/// Flat row for a DBSCAN cluster: its centroid, size, and counts.
+#[derive(Debug, Serialize)]
+pub struct ClusterRow {
+    pub cluster: usize,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub count: usize,
+    pub radius_m: f64,
+    /// Counts per type, e.g. "atm:2 bank:3".
+    pub types: String,
+}
+
+impl From<&ServiceCluster> for ClusterRow {
+    fn from(cluster: &ServiceCluster) -> Self {
+        Self {
+            cluster: cluster.id,
+            latitude: cluster.latitude,
+            longitude: cluster.longitude,
+            count: cluster.count,
+            radius_m: cluster.radius_m,
+            types: join_counts(&cluster.by_type),
+        }
+    }
+}
+
+/// Flat row for a heatmap cell: its position, centre, and counts.
+#[derive(Debug, Serialize)]
+pub struct HeatmapRow {
+    pub row: usize,
+    pub column: usize,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub count: usize,
+    /// Counts per type, e.g. "atm:2 bank:3".
+    pub types: String,
+}
+
+impl From<&HeatmapCell> for HeatmapRow {
+    fn from(cell: &HeatmapCell) -> Self {
+        let (latitude, longitude) = cell.center().as_tuple();
+        Self {
+            row: cell.row,
+            column: cell.column,
+            latitude,
+            longitude,
+            count: cell.count,
+            types: join_counts(&cell.by_type),
+        }
+    }
+}
+
+/// Delimited-text writer that emits a header before the first row and flushes after each row.
+pub struct RowWriter<W: Write> {
+    inner: csv::Writer<W>,
+}
+
+impl<W: Write> RowWriter<W> {
+    /// Creates a writer using `delimiter` (`b','` for CSV, `b'\t'` for TSV).
+    pub fn new(writer: W, delimiter: u8) -> Self {
+        Self {
+            inner: csv::WriterBuilder::new()
+                .delimiter(delimiter)
+                .from_writer(writer),
+        }
+    }
+
+    /// Writes a single row, flushing so streamed output appears immediately.
+    pub fn write<T: Serialize>(&mut self, row: T) -> Result<(), MapradarError> {
+        self.inner.serialize(row).map_err(std::io::Error::from)?;
+        self.inner.flush()?;
+        Ok(())
+    }
+
+    /// Writes every row in order.
+    pub fn write_all<T: Serialize>(
+        &mut self,
+        rows: impl IntoIterator<Item = T>,
+    ) -> Result<(), MapradarError> {
+        for row in rows {
+            self.write(row)?;
+        }
+        Ok(())
+    }
+}
+
+/// Columns available when rendering nearby services as a terminal table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceColumn {
+    Name,
+    Type,
+    Distance,
+    Rating,
+    Address,
+    Latitude,
+    Longitude,
+    Phone,
+    OpenNow,
+    TravelTime,
+}
+
+impl ServiceColumn {
+    /// Columns shown when none are selected explicitly.
+    pub const DEFAULT: [ServiceColumn; 5] = [
+        Self::Name,
+        Self::Type,
+        Self::Distance,
+        Self::Rating,
+        Self::Address,
+    ];
+
+    /// Default columns with the travel time after the distance, for results with travel times.
+    pub const WITH_TRAVEL_TIME: [ServiceColumn; 6] = [
+        Self::Name,
+        Self::Type,
+        Self::Distance,
+        Self::TravelTime,
+        Self::Rating,
+        Self::Address,
+    ];
+
+    fn header(self) -> &'static str {
+        match self {
+            Self::Name => "NAME",
+            Self::Type => "TYPE",
+            Self::Distance => "DISTANCE",
+            Self::Rating => "RATING",
+            Self::Address => "ADDRESS",
+            Self::Latitude => "LAT",
+            Self::Longitude => "LNG",
+            Self::Phone => "PHONE",
+            Self::OpenNow => "OPEN",
+            Self::TravelTime => "TIME",
+        }
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            Self::Distance | Self::Rating | Self::Latitude | Self::Longitude | Self::TravelTime
+        )
+    }
+
+    fn value(self, service: &NearbyService, units: Units) -> String {
+        let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        match self {
+            Self::Name => service.name.clone(),
+            Self::Type => type_label(service),
+            Self::Distance => units.format_distance(service.distance_km),
+            Self::Rating => or_dash(service.rating.map(|r| format!("{:.1}", r))),
+            Self::Address => or_dash(service.address.clone()),
+            Self::Latitude => format!("{:.5}", service.latitude),
+            Self::Longitude => format!("{:.5}", service.longitude),
+            Self::Phone => or_dash(service.phone_number.clone()),
+            Self::OpenNow => or_dash(
+                service
+                    .open_now
+                    .map(|open| if open { "yes" } else { "no" }.to_string()),
+            ),
+            Self::TravelTime => or_dash(
+                service
+                    .travel_time_min
+                    .map(|minutes| format!("{:.0} min", minutes)),
+            ),
+        }
+    }
+}
+
+impl FromStr for ServiceColumn {
+    type Err = MapradarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "name" => Ok(Self::Name),
+            "type" => Ok(Self::Type),
+            "distance" => Ok(Self::Distance),
+            "rating" => Ok(Self::Rating),
+            "address" => Ok(Self::Address),
+            "lat" | "latitude" => Ok(Self::Latitude),
+            "lng" | "lon" | "longitude" => Ok(Self::Longitude),
+            "phone" => Ok(Self::Phone),
+            "open" | "open-now" => Ok(Self::OpenNow),
+            "time" | "travel-time" => Ok(Self::TravelTime),
+            other => Err(MapradarError::InvalidInput(format!(
+                "Unknown column '{}' (expected name, type, distance, rating, address, lat, lng, phone, open, or time)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Sorts services in place by `key`.
+pub fn sort_services(services: &mut [NearbyService], key: SortKey) {
+    key.sort(services);
+}
+
+/// Renders services as an aligned table with a bold header row.
+///
+/// Distances are shown in `units`. Cells longer than [`MAX_CELL_WIDTH`] characters are truncated
+/// with an ellipsis.
+pub fn render_table(services: &[NearbyService], columns: &[ServiceColumn], units: Units) -> String {
+    let rows: Vec<Vec<String>> = services
+        .iter()
+        .map(|service| {
+            columns
+                .iter()
+                .map(|column| truncate(&column.value(service, units)))
+                .collect()
+        })
+        .collect();
+
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(column.header().len()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let pad = |text: &str, width: usize, numeric: bool| {
+        if numeric {
+            format!("{:>width$}", text, width = width)
+        } else {
+            format!("{:<width$}", text, width = width)
+        }
+    };
+
+    let header = columns
+        .iter()
+        .zip(&widths)
+        .map(|(column, &width)| pad(column.header(), width, column.is_numeric()))
+        .collect::<Vec<_>>()
+        .join("  ");
+
+    let mut lines = vec![header.trim_end().bold().to_string()];
+    for row in &rows {
+        let line = columns
+            .iter()
+            .zip(&widths)
+            .zip(row)
+            .map(|((column, &width), cell)| {
+                let cell = pad(cell, width, column.is_numeric());
+                match column {
+                    ServiceColumn::Name => cell.cyan().to_string(),
+                    ServiceColumn::Distance => cell.yellow().to_string(),
+                    _ => cell,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        lines.push(line.trim_end().to_string());
+    }
+
+    lines.join("\n")
+}
+
+/// Renders a [`Comparison`] as a terminal table, one row per category plus the overall score,
+/// highlighting the better-served location in each row. Distances are shown in `units`.
+pub fn render_comparison(comparison: &Comparison, units: Units) -> String {
+    let nearest =
+        |km: Option<f64>| km.map_or_else(|| "-".to_string(), |km| units.format_distance(km));
+    let better = |diff: f64| match diff.partial_cmp(&0.0) {
+        Some(Ordering::Greater) => "A",
+        Some(Ordering::Less) => "B",
+        _ => "=",
+    };
+
+    let header = [
+        "TYPE",
+        "A COUNT",
+        "B COUNT",
+        "A NEAREST",
+        "B NEAREST",
+        "BETTER",
+    ];
+    let mut rows: Vec<[String; 6]> = comparison
+        .categories
+        .iter()
+        .map(|c| {
+            [
+                c.category.to_string(),
+                c.first_count.to_string(),
+                c.second_count.to_string(),
+                nearest(c.first_nearest_km),
+                nearest(c.second_nearest_km),
+                better(c.score_diff).to_string(),
+            ]
+        })
+        .collect();
+    rows.push([
+        "score".to_string(),
+        format!("{:.1}", comparison.first.score),
+        format!("{:.1}", comparison.second.score),
+        String::new(),
+        String::new(),
+        better(comparison.score_diff).to_string(),
+    ]);
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(header[i].len()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &width))| match i {
+                0 => format!("{:<width$}", cell, width = width),
+                _ => format!("{:>width$}", cell, width = width),
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![
+        format!(
+            "{} {}",
+            "A:".cyan().bold(),
+            truncate(&comparison.first.location.address)
+        ),
+        format!(
+            "{} {}",
+            "B:".cyan().bold(),
+            truncate(&comparison.second.location.address)
+        ),
+        String::new(),
+        line(&header.map(String::from)).bold().to_string(),
+    ];
+    let last = rows.len() - 1;
+    for (i, row) in rows.iter().enumerate() {
+        let text = line(row);
+        lines.push(if i == last {
+            text.yellow().to_string()
+        } else {
+            text
+        });
+    }
+    lines.join("\n")
+}
+
+/// Renders a [`UsageReport`] as a terminal table, one row per day and endpoint plus a total
+/// row, followed by calls per command.
+pub fn render_usage(report: &UsageReport) -> String {
+    let usd = |value: f64| format!("${:.4}", value);
+    let header = ["DAY", "ENDPOINT", "CALLS", "CACHE HITS", "COST", "SAVED"];
+    let mut rows: Vec<[String; 6]> = report
+        .endpoints
+        .iter()
+        .map(|e| {
+            [
+                e.day.clone(),
+                truncate(&e.endpoint),
+                e.calls.to_string(),
+                e.cache_hits.to_string(),
+                usd(e.cost_usd),
+                usd(e.saved_usd),
+            ]
+        })
+        .collect();
+    rows.push([
+        format!("since {}", report.since),
+        "total".to_string(),
+        report.total_calls.to_string(),
+        report.total_cache_hits.to_string(),
+        usd(report.cost_usd),
+        usd(report.saved_usd),
+    ]);
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(header[i].len()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &width))| match i {
+                0 | 1 => format!("{:<width$}", cell, width = width),
+                _ => format!("{:>width$}", cell, width = width),
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![line(&header.map(String::from)).bold().to_string()];
+    let last = rows.len() - 1;
+    for (i, row) in rows.iter().enumerate() {
+        let text = line(row);
+        lines.push(if i == last {
+            text.yellow().to_string()
+        } else {
+            text
+        });
+    }
+
+    if !report.commands.is_empty() {
+        lines.push(String::new());
+        for command in &report.commands {
+            lines.push(format!(
+                "{} {} calls, {} cache hits",
+                format!("{}:", command.command).cyan().bold(),
+                command.calls,
+                command.cache_hits
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Renders geohash clusters as a terminal table, densest cell first as given.
+pub fn render_clusters(clusters: &[GeohashCluster]) -> String {
+    let header = ["GEOHASH", "COUNT", "LAT", "LNG", "TYPES"];
+    let rows: Vec<[String; 5]> = clusters
+        .iter()
+        .map(|cluster| {
+            [
+                cluster.geohash.clone(),
+                cluster.count.to_string(),
+                format!("{:.5}", cluster.latitude),
+                format!("{:.5}", cluster.longitude),
+                truncate(&join_counts(&cluster.by_type)),
+            ]
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(header[i].len()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &width))| match i {
+                0 | 4 => format!("{:<width$}", cell, width = width),
+                _ => format!("{:>width$}", cell, width = width),
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![line(&header.map(String::from)).bold().to_string()];
+    lines.extend(rows.iter().map(|row| line(row)));
+    lines.join("\n")
+}
+
+/// Renders DBSCAN clusters as a terminal table, largest first, with radii in `units`.
+pub fn render_service_clusters(clusters: &[ServiceCluster], units: Units) -> String {
+    let header = ["CLUSTER", "COUNT", "LAT", "LNG", "RADIUS", "TYPES"];
+    let rows: Vec<[String; 6]> = clusters
+        .iter()
+        .map(|cluster| {
+            [
+                cluster.id.to_string(),
+                cluster.count.to_string(),
+                format!("{:.5}", cluster.latitude),
+                format!("{:.5}", cluster.longitude),
+                units.format_radius(cluster.radius_m),
+                truncate(&join_counts(&cluster.by_type)),
+            ]
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(header[i].len()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &width))| match i {
+                5 => format!("{:<width$}", cell, width = width),
+                _ => format!("{:>width$}", cell, width = width),
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![line(&header.map(String::from)).bold().to_string()];
+    lines.extend(rows.iter().map(|row| line(row)));
+    lines.join("\n")
+}
+
+/// Longest cell rendered in a table before truncation.
+pub const MAX_CELL_WIDTH: usize = 48;
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_CELL_WIDTH {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(MAX_CELL_WIDTH - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Category of a result, or every category of a deduplicated one, e.g. `Bank/Mall`.
+fn type_label(service: &NearbyService) -> String {
+    if service.service_types.len() > 1 {
+        let labels: Vec<String> = service
+            .service_types
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        labels.join("/")
+    } else {
+        service.service_type.to_string()
+    }
+}
+
+/// Renders the cells of a heatmap that have results as a terminal table, densest first.
+pub fn render_heatmap(heatmap: &Heatmap) -> String {
+    let mut cells: Vec<&HeatmapCell> = heatmap.cells.iter().filter(|c| c.count > 0).collect();
+    cells.sort_by_key(|cell| std::cmp::Reverse(cell.count));
+    let header = ["ROW", "COLUMN", "COUNT", "LAT", "LNG", "TYPES"];
+    let rows: Vec<[String; 6]> = cells
+        .iter()
+        .map(|cell| {
+            let (lat, lng) = cell.center().as_tuple();
+            [
+                cell.row.to_string(),
+                cell.column.to_string(),
+                cell.count.to_string(),
+                format!("{:.5}", lat),
+                format!("{:.5}", lng),
+                truncate(&join_counts(&cell.by_type)),
+            ]
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(header[i].len()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &width))| match i {
+                5 => format!("{:<width$}", cell, width = width),
+                _ => format!("{:>width$}", cell, width = width),
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![line(&header.map(String::from)).bold().to_string()];
+    lines.extend(rows.iter().map(|row| line(row)));
+    lines.push(format!(
+        "{} results in {} of {} cells ({} by {})",
+        heatmap.total,
+        cells.len(),
+        heatmap.cells.len(),
+        heatmap.rows,
+        heatmap.columns
+    ));
+    lines.join("\n")
+}
+
+/// Counts per type, e.g. "atm:2 bank:3".
+fn join_counts(by_type: &BTreeMap<String, usize>) -> String {
+    by_type
+        .iter()
+        .map(|(service_type, count)| format!("{}:{}", service_type, count))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
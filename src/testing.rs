@@ -0,0 +1,334 @@
+//! Test doubles: an in-memory [`MockProvider`] and on-disk [`Fixtures`] for recording upstream
+//! responses and replaying them later.
+//!
+//! Fixtures work below the providers, so a replayed client parses exactly what the real service
+//! sent. Each response is stored as one JSON file named after the endpoint and a hash of the
+//! request, with credentials redacted from the URL before hashing; fixtures recorded with one API
+//! key replay under any other, and are safe to commit.
+//!
+//! ```rust,ignore
+//! use mapradar::{client::MapradarClient, testing::Fixtures};
+//!
+//! // Once, against the real service:
+//! let client = MapradarClient::builder()
+//!     .api_key(key)
+//!     .fixtures(Fixtures::record("tests/fixtures"))
+//!     .build()?;
+//! client.geocode_async("Yaba, Lagos").await?;
+//!
+//! // In tests, offline:
+//! let client = MapradarClient::builder()
+//!     .fixtures(Fixtures::replay("tests/fixtures"))
+//!     .build()?;
+//! let location = client.geocode_async("Yaba, Lagos").await?;
+//! ```
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use futures::future::BoxFuture;
+use reqwest::{Request, Response};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::MapradarError,
+    geo::haversine_distance,
+    models::{GeoLocation, NearbyService, PlaceCategory, TimeZoneInfo},
+    providers::GeocodingProvider,
+    transport::redact_url,
+};
+
+/// Provider answering from canned data, for tests that should not touch the network.
+///
+/// Geocoding matches addresses case-insensitively, reverse geocoding returns the nearest canned
+/// location, and nearby search filters canned services by category and distance. Every call is
+/// logged and can be read back with [`MockProvider::calls`].
+#[derive(Clone, Default)]
+pub struct MockProvider {
+    locations: HashMap<String, GeoLocation>,
+    services: Vec<NearbyService>,
+    timezones: Vec<TimeZoneInfo>,
+    calls: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers geocoding requests for `address`; the location is also a reverse-geocoding
+    /// candidate.
+    pub fn location(mut self, address: &str, location: GeoLocation) -> Self {
+        self.locations
+            .insert(address.trim().to_lowercase(), location);
+        self
+    }
+
+    /// Adds a service to nearby-search results.
+    pub fn service(mut self, service: NearbyService) -> Self {
+        self.services.push(service);
+        self
+    }
+
+    /// Answers every time zone lookup with `timezone`.
+    pub fn timezone(mut self, timezone: TimeZoneInfo) -> Self {
+        self.timezones = vec![timezone];
+        self
+    }
+
+    /// Calls received so far, oldest first, e.g. `geocode Yaba, Lagos`.
+    pub fn calls(&self) -> Vec<String> {
+        self.calls
+            .lock()
+            .map(|calls| calls.clone())
+            .unwrap_or_default()
+    }
+
+    fn log(&self, call: String) {
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.push(call);
+        }
+    }
+}
+
+impl GeocodingProvider for MockProvider {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn geocode<'a>(
+        &'a self,
+        address: &'a str,
+    ) -> BoxFuture<'a, Result<GeoLocation, MapradarError>> {
+        self.log(format!("geocode {}", address));
+        let result = self
+            .locations
+            .get(&address.trim().to_lowercase())
+            .cloned()
+            .ok_or_else(|| MapradarError::AddressNotFound {
+                query: address.to_string(),
+            });
+        Box::pin(async move { result })
+    }
+
+    fn reverse_geocode(
+        &self,
+        lat: f64,
+        lng: f64,
+    ) -> BoxFuture<'_, Result<GeoLocation, MapradarError>> {
+        self.log(format!("reverse_geocode {},{}", lat, lng));
+        let result = self
+            .locations
+            .values()
+            .min_by(|a, b| {
+                haversine_distance(lat, lng, a.latitude, a.longitude)
+                    .total_cmp(&haversine_distance(lat, lng, b.latitude, b.longitude))
+            })
+            .cloned()
+            .ok_or(MapradarError::ZeroResults);
+        Box::pin(async move { result })
+    }
+
+    fn ip_location(&self, ip: Option<IpAddr>) -> BoxFuture<'_, Result<GeoLocation, MapradarError>> {
+        self.log(match ip {
+            Some(ip) => format!("ip_location {}", ip),
+            None => "ip_location".to_string(),
+        });
+        let result = self
+            .locations
+            .values()
+            .next()
+            .cloned()
+            .ok_or(MapradarError::ZeroResults);
+        Box::pin(async move { result })
+    }
+
+    fn timezone(&self, lat: f64, lng: f64) -> BoxFuture<'_, Result<TimeZoneInfo, MapradarError>> {
+        self.log(format!("timezone {},{}", lat, lng));
+        let result = self
+            .timezones
+            .first()
+            .cloned()
+            .ok_or(MapradarError::ZeroResults);
+        Box::pin(async move { result })
+    }
+
+    fn nearby_search<'a>(
+        &'a self,
+        lat: f64,
+        lng: f64,
+        category: &'a PlaceCategory,
+        radius_meters: f64,
+        max_results: usize,
+    ) -> BoxFuture<'a, Result<Vec<NearbyService>, MapradarError>> {
+        self.log(format!(
+            "nearby_search {},{} {} {:.0}",
+            lat, lng, category, radius_meters
+        ));
+        let services = self
+            .services
+            .iter()
+            .filter(|service| &service.service_type == category)
+            .filter(|service| {
+                haversine_distance(lat, lng, service.latitude, service.longitude) * 1000.0
+                    <= radius_meters
+            })
+            .take(max_results)
+            .cloned()
+            .collect();
+        Box::pin(async move { Ok(services) })
+    }
+}
+
+/// Whether [`Fixtures`] capture live responses or stand in for the network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixtureMode {
+    /// Send requests as usual and save each response.
+    Record,
+    /// Answer requests from saved responses without touching the network.
+    Replay,
+}
+
+/// Directory of recorded upstream responses; see the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct Fixtures {
+    dir: PathBuf,
+    mode: FixtureMode,
+}
+
+/// On-disk form of one recorded response.
+#[derive(Serialize, Deserialize)]
+struct Fixture {
+    method: String,
+    /// Request URL with credentials redacted.
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    request_body: Option<String>,
+    status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>,
+    body: String,
+}
+
+impl Fixtures {
+    pub fn record(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            mode: FixtureMode::Record,
+        }
+    }
+
+    pub fn replay(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            mode: FixtureMode::Replay,
+        }
+    }
+
+    pub fn mode(&self) -> FixtureMode {
+        self.mode
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// File holding the response to `request`: the endpoint host and path for readability,
+    /// then a hash of the method, redacted URL, and body.
+    fn path_for(&self, request: &Request) -> PathBuf {
+        let url = redact_url(request.url());
+        let mut hasher = Sha256::new();
+        hasher.update(request.method().as_str());
+        hasher.update(b" ");
+        hasher.update(url.as_str());
+        if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+            hasher.update(b"\n");
+            hasher.update(body);
+        }
+        let hash: String = hasher.finalize()[..8]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        let endpoint: String = format!("{}{}", url.host_str().unwrap_or_default(), url.path())
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        self.dir
+            .join(format!("{}-{}.json", endpoint.trim_matches('_'), hash))
+    }
+
+    /// Loads the saved response to `request`.
+    pub(crate) fn replay_response(&self, request: &Request) -> Result<Response, MapradarError> {
+        let path = self.path_for(request);
+        let text = std::fs::read_to_string(&path).map_err(|err| {
+            MapradarError::Config(format!(
+                "No recorded response for {} {} ({}: {})",
+                request.method(),
+                redact_url(request.url()),
+                path.display(),
+                err
+            ))
+        })?;
+        let fixture: Fixture = serde_json::from_str(&text)?;
+        tracing::debug!(path = %path.display(), "replayed fixture");
+        to_response(
+            fixture.status,
+            fixture.content_type.as_deref(),
+            fixture.body,
+        )
+    }
+
+    /// Saves `response` as the answer to `request` and returns an equivalent response.
+    pub(crate) async fn record_response(
+        &self,
+        request: &Request,
+        response: Response,
+    ) -> Result<Response, MapradarError> {
+        let status = response.status().as_u16();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response.text().await?;
+
+        let fixture = Fixture {
+            method: request.method().to_string(),
+            url: redact_url(request.url()).to_string(),
+            request_body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(|bytes| String::from_utf8_lossy(bytes).into_owned()),
+            status,
+            content_type: content_type.clone(),
+            body: body.clone(),
+        };
+        let path = self.path_for(request);
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(&path, serde_json::to_string_pretty(&fixture)?)?;
+        tracing::debug!(path = %path.display(), "recorded fixture");
+
+        to_response(status, content_type.as_deref(), body)
+    }
+}
+
+fn to_response(
+    status: u16,
+    content_type: Option<&str>,
+    body: String,
+) -> Result<Response, MapradarError> {
+    let mut builder = http::Response::builder().status(status);
+    if let Some(content_type) = content_type {
+        builder = builder.header(reqwest::header::CONTENT_TYPE, content_type);
+    }
+    builder
+        .body(body)
+        .map(Response::from)
+        .map_err(|err| MapradarError::Unknown(format!("Invalid recorded response: {}", err)))
+}
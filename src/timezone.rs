@@ -0,0 +1,51 @@
+//! Offline time zone lookup from bundled tz boundary data (`offline-tz` feature).
+//!
+//! Boundaries come from timezone-boundary-builder via `tzf-rs`; offsets and DST come from the
+//! IANA database compiled into `chrono-tz`. The finder is built once, on first use.
+
+use std::sync::OnceLock;
+
+use chrono::{Datelike, Offset, TimeZone, Timelike, Utc};
+use chrono_tz::{OffsetComponents, Tz};
+use tzf_rs::DefaultFinder;
+
+use crate::{error::MapradarError, models::TimeZoneInfo};
+
+static FINDER: OnceLock<DefaultFinder> = OnceLock::new();
+
+/// Time zone currently in effect at a point, without any network request.
+pub fn lookup(lat: f64, lng: f64) -> Result<TimeZoneInfo, MapradarError> {
+    let finder = FINDER.get_or_init(DefaultFinder::new);
+    let id = finder.get_tz_name(lng, lat);
+    if id.is_empty() {
+        return Err(MapradarError::ZeroResults);
+    }
+    info(id)
+}
+
+/// Current offset and DST status for an IANA time zone identifier.
+pub fn info(id: &str) -> Result<TimeZoneInfo, MapradarError> {
+    let tz: Tz = id
+        .parse()
+        .map_err(|_| MapradarError::InvalidInput(format!("Unknown time zone '{}'", id)))?;
+    let offset = tz.offset_from_utc_datetime(&Utc::now().naive_utc());
+
+    Ok(TimeZoneInfo {
+        id: id.to_string(),
+        utc_offset_secs: offset.fix().local_minus_utc(),
+        dst: !offset.dst_offset().is_zero(),
+    })
+}
+
+/// Current local weekday (0 = Sunday) and minutes after midnight at a point.
+pub fn local_weekday_minute(lat: f64, lng: f64) -> Result<(u8, u16), MapradarError> {
+    let id = lookup(lat, lng)?.id;
+    let tz: Tz = id
+        .parse()
+        .map_err(|_| MapradarError::InvalidInput(format!("Unknown time zone '{}'", id)))?;
+    let now = Utc::now().with_timezone(&tz);
+    Ok((
+        now.weekday().num_days_from_sunday() as u8,
+        (now.hour() * 60 + now.minute()) as u16,
+    ))
+}
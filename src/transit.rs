@@ -0,0 +1,980 @@
+//! Transit stops, routes, and next scheduled departures from GTFS feeds (`gtfs` feature).
+//!
+//! [`GtfsIndex`] keeps the stops of imported feeds, with the routes serving them, in the local
+//! cache database, so that nearby bus stop and train station searches can be answered or
+//! completed offline. [`GtfsFeed`] reads a feed directly to look up departures.
+//!
+//! A feed is a GTFS zip archive or a directory of its text files. Stops, routes, trips, and
+//! service calendars are read when the feed is opened; stop times are streamed when departures
+//! are looked up, keeping only those of stops near a result. Schedules are read in the time
+//! zone of the feed's first agency, and trips running past midnight are taken from the previous
+//! service day.
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::MapradarError,
+    geo::haversine_distance,
+    models::{Departure, NearbyService, PlaceCategory, ServiceType},
+};
+
+/// Departures attached per stop unless another count is set.
+pub const DEFAULT_DEPARTURES: usize = 3;
+
+/// Bus stops farther than this from every feed stop get no departures.
+const BUS_STOP_MATCH_M: f64 = 100.0;
+
+/// Stations cover more ground than stops, so they are matched from farther away.
+const STATION_MATCH_M: f64 = 300.0;
+
+/// Kilometers per degree of latitude.
+const KM_PER_DEGREE: f64 = 111.32;
+
+struct Stop {
+    id: String,
+    name: Option<String>,
+    latitude: f64,
+    longitude: f64,
+    /// `parent_station` of a platform.
+    parent: Option<String>,
+}
+
+struct Trip {
+    route: String,
+    /// `route_type` of the trip's route.
+    route_type: Option<u32>,
+    service_id: String,
+    headsign: Option<String>,
+}
+
+struct Calendar {
+    /// Monday first.
+    days: [bool; 7],
+    start: NaiveDate,
+    end: NaiveDate,
+}
+
+/// A route's name and `route_type`.
+type RouteRef<'a> = (&'a str, Option<u32>);
+
+/// A GTFS schedule, ready to look up departures from its stops.
+pub struct GtfsFeed {
+    source: PathBuf,
+    timezone: Tz,
+    stops: Vec<Stop>,
+    trips: HashMap<String, Trip>,
+    calendars: HashMap<String, Calendar>,
+    /// Dates a service is added on (`true`) or removed from (`false`), from `calendar_dates.txt`.
+    exceptions: HashMap<(String, NaiveDate), bool>,
+}
+
+impl GtfsFeed {
+    /// Reads a GTFS zip archive, or a directory holding its files.
+    pub fn open(path: &Path) -> Result<Self, MapradarError> {
+        let mut feed = Self {
+            source: path.to_path_buf(),
+            timezone: Tz::UTC,
+            stops: Vec::new(),
+            trips: HashMap::new(),
+            calendars: HashMap::new(),
+            exceptions: HashMap::new(),
+        };
+
+        let timezone = feed
+            .read("agency.txt", |mut rows| {
+                let column = rows.required("agency_timezone")?;
+                Ok(rows
+                    .next()?
+                    .and_then(|row| field(&row, Some(column)).map(str::to_string)))
+            })?
+            .flatten()
+            .ok_or_else(|| invalid(path, "agency.txt has no agency_timezone"))?;
+        feed.timezone = timezone
+            .parse()
+            .map_err(|_| invalid(path, &format!("unknown agency_timezone '{}'", timezone)))?;
+
+        feed.stops = feed
+            .read("stops.txt", |mut rows| {
+                let (id, name) = (rows.required("stop_id")?, rows.optional("stop_name"));
+                let (lat, lon) = (rows.required("stop_lat")?, rows.required("stop_lon")?);
+                let parent = rows.optional("parent_station");
+                let mut stops = Vec::new();
+                while let Some(row) = rows.next()? {
+                    let number = |index| field(&row, Some(index)).and_then(|v| v.parse().ok());
+                    if let (Some(stop_id), Some(latitude), Some(longitude)) =
+                        (field(&row, Some(id)), number(lat), number(lon))
+                    {
+                        stops.push(Stop {
+                            id: stop_id.to_string(),
+                            name: field(&row, name).map(str::to_string),
+                            latitude,
+                            longitude,
+                            parent: field(&row, parent).map(str::to_string),
+                        });
+                    }
+                }
+                Ok(stops)
+            })?
+            .ok_or_else(|| invalid(path, "stops.txt is missing"))?;
+
+        let routes: HashMap<String, (String, Option<u32>)> = feed
+            .read("routes.txt", |mut rows| {
+                let id = rows.required("route_id")?;
+                let (short, long, kind) = (
+                    rows.optional("route_short_name"),
+                    rows.optional("route_long_name"),
+                    rows.optional("route_type"),
+                );
+                let mut routes = HashMap::new();
+                while let Some(row) = rows.next()? {
+                    if let Some(route_id) = field(&row, Some(id)) {
+                        let name = field(&row, short).or(field(&row, long)).unwrap_or(route_id);
+                        let route_type = field(&row, kind).and_then(|v| v.parse().ok());
+                        routes.insert(route_id.to_string(), (name.to_string(), route_type));
+                    }
+                }
+                Ok(routes)
+            })?
+            .ok_or_else(|| invalid(path, "routes.txt is missing"))?;
+
+        feed.trips = feed
+            .read("trips.txt", |mut rows| {
+                let (id, route, service) = (
+                    rows.required("trip_id")?,
+                    rows.required("route_id")?,
+                    rows.required("service_id")?,
+                );
+                let headsign = rows.optional("trip_headsign");
+                let mut trips = HashMap::new();
+                while let Some(row) = rows.next()? {
+                    if let (Some(trip_id), Some(route_id), Some(service_id)) = (
+                        field(&row, Some(id)),
+                        field(&row, Some(route)),
+                        field(&row, Some(service)),
+                    ) {
+                        let (route, route_type) = routes
+                            .get(route_id)
+                            .map_or((route_id, None), |(name, kind)| (name.as_str(), *kind));
+                        let trip = Trip {
+                            route: route.to_string(),
+                            route_type,
+                            service_id: service_id.to_string(),
+                            headsign: field(&row, headsign).map(str::to_string),
+                        };
+                        trips.insert(trip_id.to_string(), trip);
+                    }
+                }
+                Ok(trips)
+            })?
+            .ok_or_else(|| invalid(path, "trips.txt is missing"))?;
+
+        // Either calendar file may be absent, but not both.
+        let calendars = feed.read("calendar.txt", |mut rows| {
+            let service = rows.required("service_id")?;
+            let days = [
+                "monday",
+                "tuesday",
+                "wednesday",
+                "thursday",
+                "friday",
+                "saturday",
+                "sunday",
+            ]
+            .map(|day| rows.required(day));
+            let days = days.into_iter().collect::<Result<Vec<_>, _>>()?;
+            let (start, end) = (rows.required("start_date")?, rows.required("end_date")?);
+            let mut calendars = HashMap::new();
+            while let Some(row) = rows.next()? {
+                let date = |index| field(&row, Some(index)).and_then(parse_date);
+                if let (Some(service_id), Some(start), Some(end)) =
+                    (field(&row, Some(service)), date(start), date(end))
+                {
+                    let mut runs = [false; 7];
+                    for (day, &index) in runs.iter_mut().zip(&days) {
+                        *day = field(&row, Some(index)) == Some("1");
+                    }
+                    let calendar = Calendar {
+                        days: runs,
+                        start,
+                        end,
+                    };
+                    calendars.insert(service_id.to_string(), calendar);
+                }
+            }
+            Ok(calendars)
+        })?;
+        let exceptions = feed.read("calendar_dates.txt", |mut rows| {
+            let (service, date, kind) = (
+                rows.required("service_id")?,
+                rows.required("date")?,
+                rows.required("exception_type")?,
+            );
+            let mut exceptions = HashMap::new();
+            while let Some(row) = rows.next()? {
+                if let (Some(service_id), Some(day), Some(kind)) = (
+                    field(&row, Some(service)),
+                    field(&row, Some(date)).and_then(parse_date),
+                    field(&row, Some(kind)),
+                ) {
+                    exceptions.insert((service_id.to_string(), day), kind == "1");
+                }
+            }
+            Ok(exceptions)
+        })?;
+        if calendars.is_none() && exceptions.is_none() {
+            return Err(invalid(
+                path,
+                "neither calendar.txt nor calendar_dates.txt is present",
+            ));
+        }
+        feed.calendars = calendars.unwrap_or_default();
+        feed.exceptions = exceptions.unwrap_or_default();
+        Ok(feed)
+    }
+
+    /// Attaches up to `limit` upcoming departures to each bus stop and train station in
+    /// `services` that lies near a stop of the feed.
+    pub fn attach_departures(
+        &self,
+        services: &mut [NearbyService],
+        limit: usize,
+    ) -> Result<(), MapradarError> {
+        self.attach_departures_at(services, Utc::now(), limit)
+    }
+
+    /// Like [`attach_departures`](Self::attach_departures), counting from `now` instead of the
+    /// current time.
+    pub fn attach_departures_at(
+        &self,
+        services: &mut [NearbyService],
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<(), MapradarError> {
+        let matched: Vec<(usize, HashSet<&str>)> = services
+            .iter()
+            .enumerate()
+            .filter_map(|(index, service)| {
+                let stops = self.stops_for(service);
+                (!stops.is_empty()).then_some((index, stops))
+            })
+            .collect();
+        if matched.is_empty() || limit == 0 {
+            return Ok(());
+        }
+        let wanted: HashSet<&str> = matched
+            .iter()
+            .flat_map(|(_, stops)| stops)
+            .copied()
+            .collect();
+
+        // Service days whose trips can still depart: yesterday's run past midnight.
+        let today = now.with_timezone(&self.timezone).date_naive();
+        let days: Vec<(NaiveDate, DateTime<Tz>)> =
+            [today.pred_opt(), Some(today), today.succ_opt()]
+                .into_iter()
+                .flatten()
+                .filter_map(|day| Some((day, self.service_day_start(day)?)))
+                .collect();
+        let mut active: HashMap<(&str, NaiveDate), bool> = HashMap::new();
+
+        let mut by_stop: HashMap<String, Vec<(DateTime<Tz>, &Trip)>> = HashMap::new();
+        self.read("stop_times.txt", |mut rows| {
+            let (trip, stop, time) = (
+                rows.required("trip_id")?,
+                rows.required("stop_id")?,
+                rows.required("departure_time")?,
+            );
+            while let Some(row) = rows.next()? {
+                let Some(stop_id) = field(&row, Some(stop)).filter(|id| wanted.contains(id)) else {
+                    continue;
+                };
+                let (Some(trip), Some(seconds)) = (
+                    field(&row, Some(trip)).and_then(|id| self.trips.get(id)),
+                    field(&row, Some(time)).and_then(parse_time),
+                ) else {
+                    continue;
+                };
+                for (day, start) in &days {
+                    let runs = *active
+                        .entry((trip.service_id.as_str(), *day))
+                        .or_insert_with(|| self.runs_on(&trip.service_id, *day));
+                    let departs = *start + Duration::seconds(seconds);
+                    if runs && departs >= now {
+                        by_stop
+                            .entry(stop_id.to_string())
+                            .or_default()
+                            .push((departs, trip));
+                    }
+                }
+            }
+            Ok(())
+        })?
+        .ok_or_else(|| invalid(&self.source, "stop_times.txt is missing"))?;
+
+        for (index, stops) in matched {
+            let mut departures: Vec<(DateTime<Tz>, &Trip, &str)> = stops
+                .iter()
+                .flat_map(|&stop| {
+                    by_stop
+                        .get(stop)
+                        .into_iter()
+                        .flatten()
+                        .map(move |&(departs, trip)| (departs, trip, stop))
+                })
+                .collect();
+            departures.sort_by_key(|&(departs, _, _)| departs);
+            services[index].departures = departures
+                .into_iter()
+                .take(limit)
+                .map(|(departs, trip, stop)| Departure {
+                    route: trip.route.clone(),
+                    headsign: trip.headsign.clone(),
+                    departs_at: departs.to_rfc3339(),
+                    minutes_until: ((departs.with_timezone(&Utc) - now).num_seconds() / 60) as u32,
+                    stop_id: stop.to_string(),
+                })
+                .collect();
+        }
+        Ok(())
+    }
+
+    /// IDs of the feed stops serving a result: the nearest stop to a bus stop, or every
+    /// platform of the station nearest a train station. Other results have none.
+    fn stops_for(&self, service: &NearbyService) -> HashSet<&str> {
+        let Some(radius_m) = match_radius_m(&service.service_type) else {
+            return HashSet::new();
+        };
+        let nearest = self
+            .stops
+            .iter()
+            .map(|stop| {
+                let distance_m = haversine_distance(
+                    service.latitude,
+                    service.longitude,
+                    stop.latitude,
+                    stop.longitude,
+                ) * 1000.0;
+                (stop, distance_m)
+            })
+            .filter(|&(_, distance_m)| distance_m <= radius_m)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((stop, _)) = nearest else {
+            return HashSet::new();
+        };
+        if radius_m == BUS_STOP_MATCH_M {
+            return HashSet::from([stop.id.as_str()]);
+        }
+
+        let station = stop.parent.as_deref().unwrap_or(&stop.id);
+        self.stops
+            .iter()
+            .filter(|s| s.id == station || s.parent.as_deref() == Some(station))
+            .map(|s| s.id.as_str())
+            .collect()
+    }
+
+    /// Routes calling at each stop, from the feed's stop times.
+    fn routes_by_stop(&self) -> Result<HashMap<String, HashSet<RouteRef<'_>>>, MapradarError> {
+        let mut by_stop: HashMap<String, HashSet<RouteRef<'_>>> = HashMap::new();
+        self.read("stop_times.txt", |mut rows| {
+            let (trip, stop) = (rows.required("trip_id")?, rows.required("stop_id")?);
+            while let Some(row) = rows.next()? {
+                if let (Some(trip), Some(stop_id)) = (
+                    field(&row, Some(trip)).and_then(|id| self.trips.get(id)),
+                    field(&row, Some(stop)),
+                ) {
+                    let route = (trip.route.as_str(), trip.route_type);
+                    if let Some(routes) = by_stop.get_mut(stop_id) {
+                        routes.insert(route);
+                    } else {
+                        by_stop.insert(stop_id.to_string(), HashSet::from([route]));
+                    }
+                }
+            }
+            Ok(())
+        })?
+        .ok_or_else(|| invalid(&self.source, "stop_times.txt is missing"))?;
+        Ok(by_stop)
+    }
+
+    /// Whether a service runs on a date, with `calendar_dates.txt` overriding `calendar.txt`.
+    fn runs_on(&self, service_id: &str, day: NaiveDate) -> bool {
+        if let Some(&added) = self.exceptions.get(&(service_id.to_string(), day)) {
+            return added;
+        }
+        self.calendars.get(service_id).is_some_and(|calendar| {
+            (calendar.start..=calendar.end).contains(&day)
+                && calendar.days[day.weekday().num_days_from_monday() as usize]
+        })
+    }
+
+    /// Time GTFS counts a service day's times from: noon less twelve hours, which is midnight
+    /// except on days the clocks change.
+    fn service_day_start(&self, day: NaiveDate) -> Option<DateTime<Tz>> {
+        let noon = self
+            .timezone
+            .from_local_datetime(&day.and_hms_opt(12, 0, 0)?)
+            .earliest()?;
+        Some(noon - Duration::hours(12))
+    }
+
+    /// Runs `read` over one file of the feed, or returns `None` when the feed lacks it.
+    fn read<T>(
+        &self,
+        name: &str,
+        read: impl FnOnce(Rows<'_>) -> Result<T, MapradarError>,
+    ) -> Result<Option<T>, MapradarError> {
+        if self.source.is_dir() {
+            let path = self.source.join(name);
+            if !path.exists() {
+                return Ok(None);
+            }
+            return Rows::new(&mut File::open(path)?).and_then(read).map(Some);
+        }
+
+        let mut archive = zip::ZipArchive::new(File::open(&self.source)?).map_err(zip_error)?;
+        let mut file = match archive.by_name(name) {
+            Ok(file) => file,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(err) => return Err(zip_error(err)),
+        };
+        Rows::new(&mut file).and_then(read).map(Some)
+    }
+}
+
+/// Counts reported after importing a feed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GtfsImportStats {
+    pub source: String,
+    pub bus_stops: usize,
+    pub train_stations: usize,
+    pub routes: usize,
+    /// Stops no trip calls at, or only ferries, cable cars, and the like; not imported.
+    pub skipped: usize,
+}
+
+/// An imported feed and the area its stops span.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GtfsFeedSummary {
+    pub source: String,
+    /// Unix time of the import.
+    pub imported_at: i64,
+    pub stops: usize,
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+}
+
+/// Feeds in the index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GtfsIndexStats {
+    pub path: String,
+    pub feeds: Vec<GtfsFeedSummary>,
+}
+
+/// Stops and the routes serving them from imported GTFS feeds, kept in SQLite with an R*Tree
+/// over their coordinates; clones share one connection.
+///
+/// Platforms are folded into their parent station. A stop is a train station when a rail,
+/// metro, or tram route calls there, and a bus stop when only buses, coaches, or trolleybuses
+/// do. Only feeds whose stops span a searched point are consulted.
+#[derive(Clone)]
+pub struct GtfsIndex {
+    conn: Arc<Mutex<Connection>>,
+    path: PathBuf,
+}
+
+impl GtfsIndex {
+    /// Opens (or creates) the index tables in the database at `path`.
+    pub fn open(path: &Path) -> Result<Self, MapradarError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS gtfs_feeds (
+                id INTEGER PRIMARY KEY,
+                source TEXT NOT NULL UNIQUE,
+                imported_at INTEGER NOT NULL,
+                min_lat REAL NOT NULL,
+                max_lat REAL NOT NULL,
+                min_lng REAL NOT NULL,
+                max_lng REAL NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS gtfs_stops (
+                id INTEGER PRIMARY KEY,
+                feed_id INTEGER NOT NULL,
+                stop_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                latitude REAL NOT NULL,
+                longitude REAL NOT NULL,
+                service_type TEXT NOT NULL,
+                routes TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS gtfs_stops_feed ON gtfs_stops (feed_id);
+            CREATE VIRTUAL TABLE IF NOT EXISTS gtfs_stops_rtree
+                USING rtree(id, min_lat, max_lat, min_lng, max_lng);",
+        )?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Opens the index at `path` only if a feed has been imported into it, without creating the
+    /// database or its tables otherwise.
+    pub fn open_imported(path: &Path) -> Result<Option<Self>, MapradarError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let conn = Connection::open(path)?;
+        let imported: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'gtfs_feeds')",
+            [],
+            |row| row.get(0),
+        )?;
+        if !imported
+            || !conn.query_row("SELECT EXISTS(SELECT 1 FROM gtfs_feeds)", [], |row| {
+                row.get(0)
+            })?
+        {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            path: path.to_path_buf(),
+        }))
+    }
+
+    /// Default location: the on-disk response cache,
+    /// [`DiskCache::default_path`](crate::cache::DiskCache::default_path). Clearing the cache
+    /// leaves imported feeds in place.
+    pub fn default_path() -> PathBuf {
+        crate::cache::DiskCache::default_path()
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, MapradarError> {
+        self.conn
+            .lock()
+            .map_err(|_| MapradarError::Unknown("GTFS index lock poisoned".to_string()))
+    }
+
+    /// Adds the stops of a GTFS zip archive or directory, replacing those of an earlier import
+    /// from the same path.
+    pub fn import(&self, feed: &Path) -> Result<GtfsImportStats, MapradarError> {
+        let source = std::fs::canonicalize(feed)?.display().to_string();
+        let gtfs = GtfsFeed::open(feed)?;
+        let served = gtfs.routes_by_stop()?;
+
+        // Routes per stop, with platforms counted at their station.
+        let by_id: HashMap<&str, &Stop> = gtfs.stops.iter().map(|s| (s.id.as_str(), s)).collect();
+        let mut stations: HashMap<&str, HashSet<RouteRef<'_>>> = HashMap::new();
+        for stop in &gtfs.stops {
+            let station = stop
+                .parent
+                .as_deref()
+                .filter(|parent| by_id.contains_key(parent))
+                .unwrap_or(&stop.id);
+            stations
+                .entry(station)
+                .or_default()
+                .extend(served.get(&stop.id).into_iter().flatten());
+        }
+
+        let mut stats = GtfsImportStats {
+            source: source.clone(),
+            bus_stops: 0,
+            train_stations: 0,
+            routes: gtfs
+                .trips
+                .values()
+                .map(|trip| trip.route.as_str())
+                .collect::<HashSet<_>>()
+                .len(),
+            skipped: 0,
+        };
+        let mut rows = Vec::new();
+        for (id, routes) in stations {
+            let kinds: Vec<ServiceType> =
+                routes.iter().filter_map(|&(_, kind)| mode(kind)).collect();
+            let service_type = if kinds.contains(&ServiceType::TrainStation) {
+                stats.train_stations += 1;
+                ServiceType::TrainStation
+            } else if kinds.contains(&ServiceType::BusStop) {
+                stats.bus_stops += 1;
+                ServiceType::BusStop
+            } else {
+                stats.skipped += 1;
+                continue;
+            };
+            let names: BTreeSet<&str> = routes
+                .iter()
+                .filter(|&&(_, kind)| mode(kind).is_some())
+                .map(|&(name, _)| name)
+                .collect();
+            rows.push((by_id[id], service_type, names));
+        }
+
+        let mut conn = self.lock()?;
+        let tx = conn.transaction()?;
+        let previous: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM gtfs_feeds WHERE source = ?1",
+                params![source],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(feed_id) = previous {
+            tx.execute(
+                "DELETE FROM gtfs_stops_rtree WHERE id IN
+                     (SELECT id FROM gtfs_stops WHERE feed_id = ?1)",
+                params![feed_id],
+            )?;
+            tx.execute(
+                "DELETE FROM gtfs_stops WHERE feed_id = ?1",
+                params![feed_id],
+            )?;
+            tx.execute("DELETE FROM gtfs_feeds WHERE id = ?1", params![feed_id])?;
+        }
+        if rows.is_empty() {
+            tx.commit()?;
+            return Ok(stats);
+        }
+
+        let bounds = rows.iter().fold(
+            (f64::MAX, f64::MIN, f64::MAX, f64::MIN),
+            |(south, north, west, east), (stop, _, _)| {
+                (
+                    south.min(stop.latitude),
+                    north.max(stop.latitude),
+                    west.min(stop.longitude),
+                    east.max(stop.longitude),
+                )
+            },
+        );
+        tx.execute(
+            "INSERT INTO gtfs_feeds (source, imported_at, min_lat, max_lat, min_lng, max_lng)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                source,
+                Utc::now().timestamp(),
+                bounds.0,
+                bounds.1,
+                bounds.2,
+                bounds.3
+            ],
+        )?;
+        let feed_id = tx.last_insert_rowid();
+        for (stop, service_type, routes) in rows {
+            tx.execute(
+                "INSERT INTO gtfs_stops
+                     (feed_id, stop_id, name, latitude, longitude, service_type, routes)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    feed_id,
+                    stop.id,
+                    stop.name.as_deref().unwrap_or(&stop.id),
+                    stop.latitude,
+                    stop.longitude,
+                    service_type.slug(),
+                    serde_json::to_string(&routes)?,
+                ],
+            )?;
+            tx.execute(
+                "INSERT INTO gtfs_stops_rtree (id, min_lat, max_lat, min_lng, max_lng)
+                 VALUES (?1, ?2, ?2, ?3, ?3)",
+                params![tx.last_insert_rowid(), stop.latitude, stop.longitude],
+            )?;
+        }
+        tx.commit()?;
+        Ok(stats)
+    }
+
+    pub fn stats(&self) -> Result<GtfsIndexStats, MapradarError> {
+        let conn = self.lock()?;
+        let mut statement = conn.prepare(
+            "SELECT f.source, f.imported_at, f.min_lat, f.min_lng, f.max_lat, f.max_lng,
+                    (SELECT COUNT(*) FROM gtfs_stops s WHERE s.feed_id = f.id)
+             FROM gtfs_feeds f ORDER BY f.source",
+        )?;
+        let feeds = statement
+            .query_map([], |row| {
+                Ok(GtfsFeedSummary {
+                    source: row.get(0)?,
+                    imported_at: row.get(1)?,
+                    south: row.get(2)?,
+                    west: row.get(3)?,
+                    north: row.get(4)?,
+                    east: row.get(5)?,
+                    stops: row.get::<_, i64>(6)? as usize,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(GtfsIndexStats {
+            path: self.path.display().to_string(),
+            feeds,
+        })
+    }
+
+    /// Bus stops or train stations within `radius_km` of a point, nearest first, from the
+    /// feeds spanning it. Each result's `place_id` is `gtfs:<stop_id>`.
+    pub fn nearby(
+        &self,
+        lat: f64,
+        lng: f64,
+        service_type: ServiceType,
+        radius_km: f64,
+        limit: usize,
+    ) -> Result<Vec<NearbyService>, MapradarError> {
+        let dlat = radius_km / KM_PER_DEGREE;
+        let dlng = radius_km / (KM_PER_DEGREE * lat.to_radians().cos().max(0.01));
+        let conn = self.lock()?;
+        let mut statement = conn.prepare(
+            "SELECT s.stop_id, s.name, s.latitude, s.longitude, s.routes
+             FROM gtfs_stops_rtree r
+             JOIN gtfs_stops s ON s.id = r.id
+             JOIN gtfs_feeds f ON f.id = s.feed_id
+             WHERE r.min_lat >= ?1 AND r.max_lat <= ?2 AND r.min_lng >= ?3 AND r.max_lng <= ?4
+               AND s.service_type = ?5
+               AND ?6 BETWEEN f.min_lat AND f.max_lat AND ?7 BETWEEN f.min_lng AND f.max_lng",
+        )?;
+        let rows = statement
+            .query_map(
+                params![
+                    lat - dlat,
+                    lat + dlat,
+                    lng - dlng,
+                    lng + dlng,
+                    service_type.slug(),
+                    lat,
+                    lng
+                ],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, f64>(2)?,
+                        row.get::<_, f64>(3)?,
+                        row.get::<_, String>(4)?,
+                    ))
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stops = Vec::new();
+        for (stop_id, name, latitude, longitude, routes) in rows {
+            let distance_km = haversine_distance(lat, lng, latitude, longitude);
+            if distance_km > radius_km {
+                continue;
+            }
+            stops.push(NearbyService {
+                name,
+                service_type: service_type.into(),
+                latitude,
+                longitude,
+                distance_km,
+                address: None,
+                rating: None,
+                place_id: Some(format!("gtfs:{}", stop_id)),
+                phone_number: None,
+                open_now: None,
+                opening_hours: None,
+                photos: Vec::new(),
+                service_types: Vec::new(),
+                travel_time_min: None,
+                departures: Vec::new(),
+                routes: serde_json::from_str(&routes)?,
+            });
+        }
+        stops.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km));
+        stops.truncate(limit);
+        Ok(stops)
+    }
+
+    /// Merges the nearest imported stops into results searched around a point: a result of
+    /// the same kind standing at a stop gains the stop's routes, and other stops are added, so
+    /// a stop found in overlapping feeds is listed once.
+    /// Each bus stop and train station category in `categories` keeps its `limit` nearest.
+    pub fn merge_into(
+        &self,
+        lat: f64,
+        lng: f64,
+        categories: &[PlaceCategory],
+        radius_km: f64,
+        limit: usize,
+        services: &mut Vec<NearbyService>,
+    ) -> Result<(), MapradarError> {
+        for category in categories {
+            let (PlaceCategory::Known(service_type), Some(radius_m)) =
+                (category, match_radius_m(category))
+            else {
+                continue;
+            };
+            let stops = self.nearby(lat, lng, *service_type, radius_km, limit)?;
+            if stops.is_empty() {
+                continue;
+            }
+            for stop in stops {
+                let existing = services
+                    .iter_mut()
+                    .filter(|s| &s.service_type == category)
+                    .map(|s| {
+                        let distance_m = haversine_distance(
+                            s.latitude,
+                            s.longitude,
+                            stop.latitude,
+                            stop.longitude,
+                        ) * 1000.0;
+                        (s, distance_m)
+                    })
+                    .filter(|&(_, distance_m)| distance_m <= radius_m)
+                    .min_by(|a, b| a.1.total_cmp(&b.1));
+                match existing {
+                    Some((service, _)) => {
+                        for route in stop.routes {
+                            if !service.routes.contains(&route) {
+                                service.routes.push(route);
+                            }
+                        }
+                    }
+                    None => services.push(stop),
+                }
+            }
+
+            services.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km));
+            let mut kept = 0;
+            services.retain(|s| {
+                if &s.service_type != category {
+                    return true;
+                }
+                kept += 1;
+                kept <= limit
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Kind of stop a GTFS `route_type` serves, for both the basic and the extended route types;
+/// ferries, cable cars, and other modes have none.
+fn mode(route_type: Option<u32>) -> Option<ServiceType> {
+    match route_type? {
+        0 | 1 | 2 | 5 | 7 | 12 | 100..=199 | 400..=499 | 900..=999 | 1400..=1499 => {
+            Some(ServiceType::TrainStation)
+        }
+        3 | 11 | 200..=299 | 700..=799 | 800 => Some(ServiceType::BusStop),
+        _ => None,
+    }
+}
+
+/// How far a bus stop or train station result may be from a feed stop and still be taken for
+/// it; other categories are never matched.
+fn match_radius_m(category: &PlaceCategory) -> Option<f64> {
+    match category {
+        PlaceCategory::Known(ServiceType::BusStop) => Some(BUS_STOP_MATCH_M),
+        PlaceCategory::Known(ServiceType::TrainStation) => Some(STATION_MATCH_M),
+        _ => None,
+    }
+}
+
+/// Records of one feed file, with its columns looked up by name.
+struct Rows<'a> {
+    reader: csv::Reader<&'a mut dyn Read>,
+    headers: csv::StringRecord,
+}
+
+impl<'a> Rows<'a> {
+    fn new(file: &'a mut dyn Read) -> Result<Self, MapradarError> {
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(file);
+        let headers = reader.headers().map_err(std::io::Error::from)?.clone();
+        Ok(Self { reader, headers })
+    }
+
+    fn optional(&self, column: &str) -> Option<usize> {
+        self.headers
+            .iter()
+            .position(|header| header.trim_start_matches('\u{feff}').trim() == column)
+    }
+
+    fn required(&self, column: &str) -> Result<usize, MapradarError> {
+        self.optional(column).ok_or_else(|| {
+            MapradarError::InvalidInput(format!("GTFS column '{}' not found", column))
+        })
+    }
+
+    fn next(&mut self) -> Result<Option<csv::StringRecord>, MapradarError> {
+        let mut record = csv::StringRecord::new();
+        let more = self
+            .reader
+            .read_record(&mut record)
+            .map_err(std::io::Error::from)?;
+        Ok(more.then_some(record))
+    }
+}
+
+/// A trimmed, non-empty value from a record.
+fn field(record: &csv::StringRecord, index: Option<usize>) -> Option<&str> {
+    record
+        .get(index?)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// Parses a GTFS `YYYYMMDD` date.
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    if value.len() != 8 || !value.is_ascii() {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(
+        value[..4].parse().ok()?,
+        value[4..6].parse().ok()?,
+        value[6..].parse().ok()?,
+    )
+}
+
+/// Parses a GTFS `H:MM:SS` time into seconds from the start of the service day; hours may
+/// pass 24 for trips running past midnight.
+fn parse_time(value: &str) -> Option<i64> {
+    let mut parts = value.split(':').map(|part| part.parse::<i64>().ok());
+    let (hours, minutes, seconds) = (parts.next()??, parts.next()??, parts.next()??);
+    Some(hours * 3600 + minutes * 60 + seconds)
+}
+
+fn invalid(path: &Path, problem: &str) -> MapradarError {
+    MapradarError::InvalidInput(format!("Invalid GTFS feed {}: {}", path.display(), problem))
+}
+
+fn zip_error(err: zip::result::ZipError) -> MapradarError {
+    MapradarError::Io(std::io::Error::other(err))
+}
+
+/// Returns a copy of `intel` with up to `limit` upcoming departures from a GTFS feed (a zip
+/// archive or directory) on each bus stop and train station near one of its stops.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (intel, gtfs, limit=DEFAULT_DEPARTURES))]
+pub fn attach_departures(
+    intel: crate::models::LocationIntelligence,
+    gtfs: PathBuf,
+    limit: usize,
+) -> PyResult<crate::models::LocationIntelligence> {
+    let mut intel = intel;
+    GtfsFeed::open(&gtfs)?.attach_departures(&mut intel.nearby_services, limit)?;
+    Ok(intel)
+}
@@ -0,0 +1,51 @@
+//! Hooks around upstream requests, for enterprise gateways that need extra headers or request
+//! signatures, for logging payloads, or for answering requests from a cache of your own.
+//!
+//! Register a [`RequestHook`] with
+//! [`MapradarClientBuilder::middleware`](crate::client::MapradarClientBuilder::middleware).
+//! Hooks run in the order they were added. They see requests with their real credentials, and
+//! are skipped by dry runs and fixture replays, which never reach the network.
+
+use reqwest::{Request, Response, ResponseBuilderExt};
+
+use crate::error::MapradarError;
+
+/// A buffered upstream response, as seen and returned by hooks.
+pub type HookResponse = http::Response<Vec<u8>>;
+
+/// Intercepts the requests a client sends upstream and the responses it receives.
+///
+/// Both methods default to doing nothing, so implementations override only what they need.
+pub trait RequestHook: Send + Sync {
+    /// Called once per request, before it is rate limited, counted, or sent. It can change the
+    /// request (headers, URL, body), fail it with an error, or answer it without sending by
+    /// returning a response; later hooks are then skipped.
+    fn on_request(&self, _request: &mut Request) -> Result<Option<HookResponse>, MapradarError> {
+        Ok(None)
+    }
+
+    /// Called with every response received upstream, retries included, before the provider
+    /// reads it. The body is fully buffered and can be inspected or replaced.
+    fn on_response(
+        &self,
+        _request: &Request,
+        _response: &mut HookResponse,
+    ) -> Result<(), MapradarError> {
+        Ok(())
+    }
+}
+
+/// Buffers `response` into a [`HookResponse`], keeping its final URL.
+pub(crate) async fn buffer(response: Response) -> Result<HookResponse, MapradarError> {
+    let mut builder = http::Response::builder()
+        .status(response.status())
+        .version(response.version())
+        .url(response.url().clone());
+    if let Some(headers) = builder.headers_mut() {
+        *headers = response.headers().clone();
+    }
+    let body = response.bytes().await?.to_vec();
+    builder
+        .body(body)
+        .map_err(|err| MapradarError::Unknown(format!("Failed to buffer response: {}", err)))
+}
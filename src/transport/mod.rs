@@ -0,0 +1,317 @@
+use reqwest::{IntoUrl, Request, RequestBuilder, Response, Url};
+use std::{sync::Arc, time::Instant};
+use tracing::Instrument;
+
+use crate::{
+    error::MapradarError,
+    metrics::Metrics,
+    plan::RequestRecorder,
+    testing::{FixtureMode, Fixtures},
+    usage::UsageLedger,
+};
+
+pub mod middleware;
+pub mod rate_limit;
+pub mod retry;
+
+pub use middleware::{HookResponse, RequestHook};
+pub use rate_limit::{RateLimit, RateLimiter};
+pub use retry::RetryPolicy;
+pub use tokio_util::sync::CancellationToken;
+
+/// Shared HTTP layer used by every provider request.
+///
+/// Wraps the underlying `reqwest` client and applies the client-wide retry policy and rate limit.
+#[derive(Clone)]
+pub struct Transport {
+    http_client: reqwest::Client,
+    retry: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    recorder: Option<RequestRecorder>,
+    ledger: Option<UsageLedger>,
+    fixtures: Option<Fixtures>,
+    metrics: Option<Metrics>,
+    hooks: Vec<Arc<dyn RequestHook>>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl Transport {
+    pub fn new(http_client: reqwest::Client, retry: RetryPolicy) -> Self {
+        Self {
+            http_client,
+            retry,
+            rate_limiter: None,
+            recorder: None,
+            ledger: None,
+            fixtures: None,
+            metrics: None,
+            hooks: Vec::new(),
+            cancellation: None,
+        }
+    }
+
+    /// Throttles every request sent through this transport (and its clones).
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Records requests in `recorder` instead of sending them; see [`crate::plan`].
+    pub fn with_recorder(mut self, recorder: RequestRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Counts every request sent, and enforces the ledger's daily budget; see [`crate::usage`].
+    pub fn with_ledger(mut self, ledger: UsageLedger) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// Records upstream latency and rate-limit waits in `metrics`; see [`crate::metrics`].
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Passes every request and response through `hook`, after any hooks added before it; see
+    /// [`middleware`].
+    pub fn with_middleware(mut self, hook: Arc<dyn RequestHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    /// Records responses to, or replays them from, `fixtures`; see [`crate::testing`].
+    pub fn with_fixtures(mut self, fixtures: Fixtures) -> Self {
+        self.fixtures = Some(fixtures);
+        self
+    }
+
+    /// Abandons requests, including their retries and rate-limit waits, once `token` is
+    /// cancelled; they fail with [`MapradarError::Cancelled`].
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Starts a GET request; send it with [`Transport::send`].
+    pub fn get(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.http_client.get(url)
+    }
+
+    /// Starts a POST request; send it with [`Transport::send`].
+    pub fn post(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.http_client.post(url)
+    }
+
+    /// Sends a request, retrying transient failures according to the retry policy.
+    ///
+    /// Requests with streaming bodies cannot be cloned and are sent once. Each call runs in an
+    /// `upstream` tracing span carrying the method and the URL with credentials redacted.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, MapradarError> {
+        match &self.cancellation {
+            Some(token) => token
+                .run_until_cancelled(self.send_uncancelled(request))
+                .await
+                .unwrap_or(Err(MapradarError::Cancelled)),
+            None => self.send_uncancelled(request).await,
+        }
+    }
+
+    async fn send_uncancelled(&self, request: RequestBuilder) -> Result<Response, MapradarError> {
+        if let Some(recorder) = &self.recorder {
+            if let Some(built) = request.try_clone().and_then(|r| r.build().ok()) {
+                tracing::info!(method = %built.method(), url = %redact_url(built.url()), "dry run");
+                recorder.record(&built);
+            }
+            return Err(MapradarError::DryRun);
+        }
+
+        let built = request.try_clone().and_then(|r| r.build().ok());
+        let span = match &built {
+            Some(built) => tracing::info_span!(
+                "upstream",
+                method = %built.method(),
+                url = %redact_url(built.url()),
+            ),
+            None => tracing::info_span!("upstream"),
+        };
+
+        let (Some(fixtures), Some(built)) = (&self.fixtures, &built) else {
+            let url = built.map(|built| built.url().clone());
+            return self.send_with_retries(request, url).instrument(span).await;
+        };
+        match fixtures.mode() {
+            FixtureMode::Replay => span.in_scope(|| fixtures.replay_response(built)),
+            FixtureMode::Record => {
+                let response = self
+                    .send_with_retries(request, Some(built.url().clone()))
+                    .instrument(span)
+                    .await?;
+                fixtures.record_response(built, response).await
+            }
+        }
+    }
+
+    async fn send_with_retries(
+        &self,
+        request: RequestBuilder,
+        url: Option<Url>,
+    ) -> Result<Response, MapradarError> {
+        let (request, hooked) = match self.run_request_hooks(request)? {
+            Hooked::Send(request, hooked) => (request, hooked),
+            Hooked::Answered(response) => return Ok(response),
+        };
+        let mut attempt = 1;
+        loop {
+            if let (Some(ledger), Some(url)) = (&self.ledger, &url) {
+                ledger.charge(url)?;
+            }
+            if let Some(limiter) = &self.rate_limiter {
+                let wait_started = Instant::now();
+                limiter.acquire().await;
+                let waited = wait_started.elapsed();
+                tracing::trace!(
+                    wait_ms = waited.as_millis() as u64,
+                    "rate limiter slot acquired"
+                );
+                if let Some(metrics) = &self.metrics {
+                    metrics.observe_rate_limit_wait(waited);
+                }
+            }
+
+            let Some(current) = request.try_clone() else {
+                return Ok(request.send().await.map_err(redact_error)?);
+            };
+
+            let started = Instant::now();
+            let outcome = current.send().await.map_err(redact_error);
+            let elapsed = started.elapsed();
+            let elapsed_ms = elapsed.as_millis() as u64;
+            if let Some(metrics) = &self.metrics {
+                let host = url.as_ref().and_then(Url::host_str).unwrap_or("unknown");
+                let status = outcome.as_ref().ok().map(|r| r.status().as_u16());
+                metrics.observe_upstream(host, status, elapsed);
+            }
+            match &outcome {
+                Ok(response) => {
+                    tracing::info!(
+                        attempt,
+                        status = response.status().as_u16(),
+                        elapsed_ms,
+                        "response"
+                    )
+                }
+                Err(err) => tracing::info!(attempt, elapsed_ms, error = %err, "request failed"),
+            }
+            if let (Some(limiter), Ok(response)) = (&self.rate_limiter, &outcome) {
+                limiter.observe(response, self.retry.delay_cap()).await;
+            }
+            // A hook's error ends the request; it is not a transient failure to retry.
+            let outcome = match (&hooked, outcome) {
+                (Some(hooked), Ok(response)) => {
+                    Ok(self.run_response_hooks(hooked, response).await?)
+                }
+                (_, outcome) => outcome,
+            };
+
+            if !self.retry.should_retry(attempt, &outcome) {
+                return Ok(outcome?);
+            }
+
+            let delay = self.retry.delay_for(attempt);
+            tracing::info!(
+                attempt,
+                delay_ms = delay.as_millis() as u64,
+                "retrying transient failure"
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Runs the `on_request` hooks. The request to send comes back with a copy of it for the
+    /// `on_response` hooks, when there are hooks and the request can be copied.
+    fn run_request_hooks(&self, request: RequestBuilder) -> Result<Hooked, MapradarError> {
+        if self.hooks.is_empty() {
+            return Ok(Hooked::Send(request, None));
+        }
+        let (client, built) = request.build_split();
+        let mut built = built.map_err(redact_error)?;
+        for hook in &self.hooks {
+            if let Some(response) = hook.on_request(&mut built)? {
+                tracing::debug!(url = %redact_url(built.url()), "answered by middleware");
+                return Ok(Hooked::Answered(response.into()));
+            }
+        }
+        let hooked = built.try_clone().map(Box::new);
+        Ok(Hooked::Send(
+            RequestBuilder::from_parts(client, built),
+            hooked,
+        ))
+    }
+
+    async fn run_response_hooks(
+        &self,
+        request: &Request,
+        response: Response,
+    ) -> Result<Response, MapradarError> {
+        let mut response = middleware::buffer(response).await?;
+        for hook in &self.hooks {
+            hook.on_response(request, &mut response)?;
+        }
+        Ok(response.into())
+    }
+}
+
+/// Outcome of the `on_request` hooks.
+enum Hooked {
+    Send(RequestBuilder, Option<Box<Request>>),
+    Answered(Response),
+}
+
+/// Query parameters whose values are credentials and never appear in logs.
+const SECRET_PARAMS: &[&str] = &[
+    "key",
+    "api_key",
+    "apikey",
+    "access_token",
+    "token",
+    "signature",
+];
+
+/// Copy of `url` with the values of credential query parameters replaced by `REDACTED`.
+pub(crate) fn redact_url(url: &Url) -> Url {
+    let is_secret = |name: &str| SECRET_PARAMS.contains(&name.to_lowercase().as_str());
+    let mut redacted = url.clone();
+    if url.query_pairs().any(|(name, _)| is_secret(&name)) {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(name, value)| {
+                let value = if is_secret(&name) {
+                    "REDACTED".into()
+                } else {
+                    value
+                };
+                (name.into_owned(), value.into_owned())
+            })
+            .collect();
+        redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    redacted
+}
+
+/// Strips credentials from the URL carried by a request error, so they never reach logs or
+/// error messages.
+fn redact_error(err: reqwest::Error) -> reqwest::Error {
+    match err.url().map(redact_url) {
+        Some(url) => err.with_url(url),
+        None => err,
+    }
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Self::new(reqwest::Client::new(), RetryPolicy::default())
+    }
+}
@@ -0,0 +1,168 @@
+use reqwest::{Response, StatusCode, header::HeaderMap};
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::Mutex;
+
+use crate::error::MapradarError;
+
+/// Requests allowed per time window, parsed from strings like `10/s`, `600/min`, or `5`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+impl RateLimit {
+    pub fn per_second(requests: u32) -> Self {
+        Self {
+            requests,
+            per: Duration::from_secs(1),
+        }
+    }
+
+    pub fn per_minute(requests: u32) -> Self {
+        Self {
+            requests,
+            per: Duration::from_secs(60),
+        }
+    }
+}
+
+impl FromStr for RateLimit {
+    type Err = MapradarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (count, unit) = s.trim().split_once('/').unwrap_or((s.trim(), "s"));
+        let requests: u32 = count
+            .trim()
+            .parse()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| MapradarError::Config(format!("Invalid rate limit '{}'", s)))?;
+
+        match unit.trim() {
+            "s" | "sec" | "second" => Ok(Self::per_second(requests)),
+            "m" | "min" | "minute" => Ok(Self::per_minute(requests)),
+            other => Err(MapradarError::Config(format!(
+                "Unknown rate limit unit '{}' (expected s or min)",
+                other
+            ))),
+        }
+    }
+}
+
+struct Bucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+    paused_until: Option<Instant>,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+}
+
+/// Token-bucket limiter shared by every clone, so concurrent tasks draw from the same budget.
+#[derive(Clone)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        let capacity = limit.requests.max(1) as f64;
+        Self {
+            bucket: Arc::new(Mutex::new(Bucket {
+                capacity,
+                tokens: capacity,
+                refill_per_sec: capacity / limit.per.as_secs_f64().max(f64::EPSILON),
+                last_refill: Instant::now(),
+                paused_until: None,
+            })),
+        }
+    }
+
+    /// Waits until a request may be sent, then consumes one token.
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().await;
+                let now = Instant::now();
+
+                match bucket.paused_until {
+                    Some(until) if until > now => until - now,
+                    _ => {
+                        bucket.paused_until = None;
+                        bucket.refill(now);
+                        if bucket.tokens >= 1.0 {
+                            bucket.tokens -= 1.0;
+                            return;
+                        }
+                        Duration::from_secs_f64((1.0 - bucket.tokens) / bucket.refill_per_sec)
+                    }
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Blocks all callers for `duration`, e.g., after the upstream reports a rate limit.
+    pub async fn pause_for(&self, duration: Duration) {
+        let mut bucket = self.bucket.lock().await;
+        let now = Instant::now();
+        let until = now.checked_add(duration).unwrap_or(now);
+        if bucket.paused_until.is_none_or(|current| current < until) {
+            bucket.paused_until = Some(until);
+        }
+        bucket.tokens = 0.0;
+    }
+
+    /// Backs off when a response carries rate-limit signals, for at most `max_pause`.
+    pub(crate) async fn observe(&self, response: &Response, max_pause: Duration) {
+        if let Some(delay) = backoff_from_headers(response.status(), response.headers()) {
+            self.pause_for(delay.min(max_pause)).await;
+        }
+    }
+}
+
+/// Reads `Retry-After` on 429/503, or an exhausted `X-RateLimit-Remaining` with its reset time.
+///
+/// A reset later than the current Unix time is taken as the epoch second it happens at, as many
+/// APIs send it, rather than a number of seconds to wait.
+fn backoff_from_headers(status: StatusCode, headers: &HeaderMap) -> Option<Duration> {
+    let seconds = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|v| v.is_finite() && *v >= 0.0)
+    };
+
+    if matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+    ) {
+        let delay = seconds("retry-after").unwrap_or(1.0);
+        return Some(Duration::try_from_secs_f64(delay).unwrap_or(Duration::MAX));
+    }
+
+    if seconds("x-ratelimit-remaining") == Some(0.0) {
+        let reset = seconds("x-ratelimit-reset")?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let delay = if reset > now { reset - now } else { reset };
+        return Some(Duration::try_from_secs_f64(delay).unwrap_or(Duration::MAX));
+    }
+
+    None
+}
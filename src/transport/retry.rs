@@ -0,0 +1,133 @@
+use reqwest::{Response, StatusCode};
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::Arc,
+    time::Duration,
+};
+
+/// Decides whether a finished attempt should be retried.
+pub type RetryPredicate = Arc<dyn Fn(&Result<Response, reqwest::Error>) -> bool + Send + Sync>;
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_BASE_DELAY_MS: u64 = 200;
+const DEFAULT_MAX_DELAY_MS: u64 = 5_000;
+
+/// Exponential backoff with optional full jitter for transient upstream failures.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    retry_on: RetryPredicate,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: Duration::from_millis(DEFAULT_BASE_DELAY_MS),
+            max_delay: Duration::from_millis(DEFAULT_MAX_DELAY_MS),
+            jitter: true,
+            retry_on: Arc::new(is_transient),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that sends every request exactly once.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Total attempts including the first one (minimum 1).
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry; doubles on each subsequent retry.
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Upper bound for any single backoff delay.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Randomizes each delay between zero and its exponential value to avoid retry storms.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Replaces the default transient-failure check.
+    pub fn retry_on(
+        mut self,
+        predicate: impl Fn(&Result<Response, reqwest::Error>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.retry_on = Arc::new(predicate);
+        self
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Longest single delay, also the most an upstream rate-limit signal may pause requests.
+    pub(crate) fn delay_cap(&self) -> Duration {
+        self.max_delay
+    }
+
+    pub(crate) fn should_retry(
+        &self,
+        attempt: u32,
+        outcome: &Result<Response, reqwest::Error>,
+    ) -> bool {
+        attempt < self.max_attempts && (self.retry_on)(outcome)
+    }
+
+    /// Backoff before retrying after the given (1-based) failed attempt.
+    pub(crate) fn delay_for(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+
+        if self.jitter {
+            exponential.mul_f64(random_fraction())
+        } else {
+            exponential
+        }
+    }
+}
+
+/// Default predicate: network failures, timeouts, HTTP 429, and 5xx responses.
+pub fn is_transient(outcome: &Result<Response, reqwest::Error>) -> bool {
+    match outcome {
+        Ok(response) => {
+            let status = response.status();
+            status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+        }
+        Err(err) => err.is_timeout() || err.is_connect() || err.is_request(),
+    }
+}
+
+/// Uniform value in `[0, 1)` from the standard library's randomly seeded hasher.
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default(),
+    );
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,132 @@
+//! Metric and imperial units for radii and distances.
+//!
+//! The library measures distances in kilometres and radii in metres; these helpers convert at
+//! the edges, where users type a radius or read a distance.
+
+use std::{fmt, str::FromStr};
+
+use crate::error::MapradarError;
+
+/// Kilometres in one international mile.
+pub const KM_PER_MILE: f64 = 1.609344;
+
+/// Metres in one international foot.
+pub const METERS_PER_FOOT: f64 = 0.3048;
+
+/// Feet in one mile.
+const FEET_PER_MILE: f64 = 5280.0;
+
+/// Converts kilometres to miles.
+pub fn km_to_miles(km: f64) -> f64 {
+    km / KM_PER_MILE
+}
+
+/// Converts miles to kilometres.
+pub fn miles_to_km(miles: f64) -> f64 {
+    miles * KM_PER_MILE
+}
+
+/// Converts metres to feet.
+pub fn meters_to_feet(meters: f64) -> f64 {
+    meters / METERS_PER_FOOT
+}
+
+/// Converts feet to metres.
+pub fn feet_to_meters(feet: f64) -> f64 {
+    feet * METERS_PER_FOOT
+}
+
+/// Unit system for radius input and distance display.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Units {
+    /// Radii in metres, distances in kilometres.
+    #[default]
+    Metric,
+    /// Radii in feet, distances in miles.
+    Imperial,
+}
+
+impl Units {
+    /// Metres in a radius typed in these units.
+    pub fn radius_to_meters(self, radius: f64) -> f64 {
+        match self {
+            Self::Metric => radius,
+            Self::Imperial => feet_to_meters(radius),
+        }
+    }
+
+    /// A radius in metres, expressed in these units.
+    pub fn radius_from_meters(self, meters: f64) -> f64 {
+        match self {
+            Self::Metric => meters,
+            Self::Imperial => meters_to_feet(meters),
+        }
+    }
+
+    /// A distance in kilometres, expressed in these units.
+    pub fn distance_from_km(self, km: f64) -> f64 {
+        match self {
+            Self::Metric => km,
+            Self::Imperial => km_to_miles(km),
+        }
+    }
+
+    /// Symbol for radii, "m" or "ft".
+    pub fn radius_symbol(self) -> &'static str {
+        match self {
+            Self::Metric => "m",
+            Self::Imperial => "ft",
+        }
+    }
+
+    /// Symbol for distances, "km" or "mi".
+    pub fn distance_symbol(self) -> &'static str {
+        match self {
+            Self::Metric => "km",
+            Self::Imperial => "mi",
+        }
+    }
+
+    /// A distance in kilometres with two decimals and its symbol, e.g. "1.25 km" or "0.78 mi".
+    pub fn format_distance(self, km: f64) -> String {
+        format!(
+            "{:.2} {}",
+            self.distance_from_km(km),
+            self.distance_symbol()
+        )
+    }
+
+    /// A radius in metres, whole and with its symbol, e.g. "800 m" or "2625 ft". Imperial radii
+    /// of a mile or more are given in miles.
+    pub fn format_radius(self, meters: f64) -> String {
+        let radius = self.radius_from_meters(meters);
+        if self == Self::Imperial && radius >= FEET_PER_MILE {
+            return format!("{:.1} mi", radius / FEET_PER_MILE);
+        }
+        format!("{:.0} {}", radius, self.radius_symbol())
+    }
+}
+
+impl FromStr for Units {
+    type Err = MapradarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "metric" | "si" => Ok(Self::Metric),
+            "imperial" | "us" => Ok(Self::Imperial),
+            other => Err(MapradarError::InvalidInput(format!(
+                "Unknown units '{}' (expected metric or imperial)",
+                other
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Units {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Metric => write!(f, "metric"),
+            Self::Imperial => write!(f, "imperial"),
+        }
+    }
+}
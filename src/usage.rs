@@ -0,0 +1,274 @@
+//! Local ledger of upstream calls and cache hits, with an optional daily call budget.
+//!
+//! Every request a [`Transport`](crate::transport::Transport) sends (each retry included) is
+//! counted against the command that made it and the endpoint it hit, and every cache hit is
+//! counted as a call saved. Days are UTC dates.
+
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::Url;
+
+use crate::{error::MapradarError, plan, providers::ProviderKind};
+
+/// Shared handle to the usage database; clones record into the same ledger.
+#[derive(Clone)]
+pub struct UsageLedger {
+    conn: Arc<Mutex<Connection>>,
+    command: String,
+    provider: ProviderKind,
+    max_calls_per_day: Option<u64>,
+}
+
+/// Calls and cache hits for one day and endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointUsage {
+    /// UTC date, as `YYYY-MM-DD`.
+    pub day: String,
+    pub endpoint: String,
+    pub calls: u64,
+    pub cache_hits: u64,
+    pub cost_usd: f64,
+    /// List price of the calls that cache hits avoided.
+    pub saved_usd: f64,
+}
+
+/// Calls and cache hits made by one CLI command (or library caller label).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandUsage {
+    pub command: String,
+    pub calls: u64,
+    pub cache_hits: u64,
+}
+
+/// Usage over a range of days, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub since: String,
+    pub endpoints: Vec<EndpointUsage>,
+    pub commands: Vec<CommandUsage>,
+    pub total_calls: u64,
+    pub total_cache_hits: u64,
+    pub cost_usd: f64,
+    pub saved_usd: f64,
+    pub calls_today: u64,
+}
+
+/// Request a cached result would otherwise have needed, for pricing cache hits. `None` for the
+/// offline provider, which makes no requests.
+fn cached_request_url(namespace: &str, provider: ProviderKind) -> Option<&'static str> {
+    match (provider, namespace) {
+        (ProviderKind::Google, "nearby") => {
+            Some("https://maps.googleapis.com/maps/api/place/nearbysearch/json")
+        }
+        (ProviderKind::Google, _) => Some("https://maps.googleapis.com/maps/api/geocode/json"),
+        (ProviderKind::Nominatim, "nearby") => Some("https://overpass-api.de/api/interpreter"),
+        (ProviderKind::Nominatim, _) => Some("https://nominatim.openstreetmap.org"),
+        (ProviderKind::Offline, _) => None,
+    }
+}
+
+impl UsageLedger {
+    /// Opens (or creates) the ledger database at `path`.
+    pub fn open(path: &Path) -> Result<Self, MapradarError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS usage (
+                day TEXT NOT NULL,
+                command TEXT NOT NULL,
+                endpoint TEXT NOT NULL,
+                calls INTEGER NOT NULL DEFAULT 0,
+                cache_hits INTEGER NOT NULL DEFAULT 0,
+                cost_usd REAL NOT NULL DEFAULT 0,
+                saved_usd REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, command, endpoint)
+            );",
+        )?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            command: "library".to_string(),
+            provider: ProviderKind::default(),
+            max_calls_per_day: None,
+        })
+    }
+
+    /// Default location: `$XDG_DATA_HOME/mapradar/usage.sqlite`, falling back to
+    /// `~/.local/share`. Kept apart from the cache so clearing the cache keeps the history.
+    pub fn default_path() -> PathBuf {
+        let base = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })
+            .unwrap_or_else(std::env::temp_dir);
+        base.join("mapradar").join("usage.sqlite")
+    }
+
+    /// Labels subsequent records with `command`.
+    pub fn command(mut self, command: impl Into<String>) -> Self {
+        self.command = command.into();
+        self
+    }
+
+    /// Fails requests with [`MapradarError::QuotaExceeded`] once `max` calls (across all
+    /// commands) have been made today.
+    pub fn max_calls_per_day(mut self, max: u64) -> Self {
+        self.max_calls_per_day = Some(max);
+        self
+    }
+
+    /// Provider whose prices apply to cache hits.
+    pub(crate) fn provider(mut self, provider: ProviderKind) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, MapradarError> {
+        self.conn
+            .lock()
+            .map_err(|_| MapradarError::Unknown("Usage ledger lock poisoned".to_string()))
+    }
+
+    /// Checks the daily budget, then records one call to `url`.
+    pub(crate) fn charge(&self, url: &Url) -> Result<(), MapradarError> {
+        let (endpoint, cost) = plan::estimate(url);
+        let day = utc_day(now_secs());
+        let conn = self.lock()?;
+
+        if let Some(max) = self.max_calls_per_day {
+            let calls: i64 = conn.query_row(
+                "SELECT COALESCE(SUM(calls), 0) FROM usage WHERE day = ?1",
+                params![day],
+                |row| row.get(0),
+            )?;
+            if calls as u64 >= max {
+                return Err(MapradarError::QuotaExceeded {
+                    provider: "usage budget".to_string(),
+                    message: format!("{} of {} upstream calls already made today", calls, max),
+                });
+            }
+        }
+
+        conn.execute(
+            "INSERT INTO usage (day, command, endpoint, calls, cost_usd) VALUES (?1, ?2, ?3, 1, ?4)
+             ON CONFLICT (day, command, endpoint)
+             DO UPDATE SET calls = calls + 1, cost_usd = cost_usd + excluded.cost_usd",
+            params![day, self.command, endpoint, cost],
+        )?;
+        Ok(())
+    }
+
+    /// Records a cache hit in `namespace` as a call saved. Failures are ignored; a lost hit only
+    /// understates the savings.
+    pub(crate) fn record_cache_hit(&self, namespace: &str) {
+        let Some(Ok(url)) = cached_request_url(namespace, self.provider).map(Url::parse) else {
+            return;
+        };
+        let (endpoint, cost) = plan::estimate(&url);
+        if let Ok(conn) = self.conn.lock() {
+            let _ = conn.execute(
+                "INSERT INTO usage (day, command, endpoint, cache_hits, saved_usd)
+                 VALUES (?1, ?2, ?3, 1, ?4)
+                 ON CONFLICT (day, command, endpoint)
+                 DO UPDATE SET cache_hits = cache_hits + 1, saved_usd = saved_usd + excluded.saved_usd",
+                params![utc_day(now_secs()), self.command, endpoint, cost],
+            );
+        }
+    }
+
+    /// Summarizes the last `days` days, today included.
+    pub fn report(&self, days: u32) -> Result<UsageReport, MapradarError> {
+        let now = now_secs();
+        let today = utc_day(now);
+        let since = utc_day(now.saturating_sub(u64::from(days.max(1) - 1) * 86_400));
+        let conn = self.lock()?;
+
+        let mut statement = conn.prepare(
+            "SELECT day, endpoint, SUM(calls), SUM(cache_hits), SUM(cost_usd), SUM(saved_usd)
+             FROM usage WHERE day >= ?1
+             GROUP BY day, endpoint ORDER BY day DESC, SUM(calls) DESC, endpoint",
+        )?;
+        let endpoints = statement
+            .query_map(params![since], |row| {
+                Ok(EndpointUsage {
+                    day: row.get(0)?,
+                    endpoint: row.get(1)?,
+                    calls: row.get::<_, i64>(2)? as u64,
+                    cache_hits: row.get::<_, i64>(3)? as u64,
+                    cost_usd: round_usd(row.get(4)?),
+                    saved_usd: round_usd(row.get(5)?),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut statement = conn.prepare(
+            "SELECT command, SUM(calls), SUM(cache_hits) FROM usage WHERE day >= ?1
+             GROUP BY command ORDER BY SUM(calls) DESC, command",
+        )?;
+        let commands = statement
+            .query_map(params![since], |row| {
+                Ok(CommandUsage {
+                    command: row.get(0)?,
+                    calls: row.get::<_, i64>(1)? as u64,
+                    cache_hits: row.get::<_, i64>(2)? as u64,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(UsageReport {
+            since,
+            total_calls: endpoints.iter().map(|e| e.calls).sum(),
+            total_cache_hits: endpoints.iter().map(|e| e.cache_hits).sum(),
+            cost_usd: round_usd(endpoints.iter().fold(0.0, |total, e| total + e.cost_usd)),
+            saved_usd: round_usd(endpoints.iter().fold(0.0, |total, e| total + e.saved_usd)),
+            calls_today: endpoints
+                .iter()
+                .filter(|e| e.day == today)
+                .map(|e| e.calls)
+                .sum(),
+            endpoints,
+            commands,
+        })
+    }
+
+    /// Deletes every record, returning how many rows were removed.
+    pub fn clear(&self) -> Result<usize, MapradarError> {
+        Ok(self.lock()?.execute("DELETE FROM usage", [])?)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn round_usd(value: f64) -> f64 {
+    (value * 100_000.0).round() / 100_000.0
+}
+
+/// UTC calendar date of a Unix timestamp, as `YYYY-MM-DD`.
+fn utc_day(secs: u64) -> String {
+    // Days since 1970-01-01 to civil date (Howard Hinnant's algorithm).
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
@@ -1,38 +1,52 @@
 use serde_json::Value;
 
-use crate::error::GeoError;
+use crate::error::MapradarError;
+
+/// Ensures latitude is within ±90 and longitude within ±180.
+pub fn validate_coordinates(latitude: f64, longitude: f64) -> Result<(), MapradarError> {
+    if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) {
+        Ok(())
+    } else {
+        Err(MapradarError::InvalidCoordinates {
+            latitude,
+            longitude,
+        })
+    }
+}
 
 /// Calculate Haversine distance between two points in km.
+#[deprecated(note = "use geo::haversine_distance")]
 pub fn calculate_distance(
     origin_latitude: f64,
     origin_longitude: f64,
     destination_latitude: f64,
     destination_longitude: f64,
 ) -> f64 {
-    let earth_radius = 6371.0;
-
-    let lat1_rad = origin_latitude.to_radians();
-    let lat2_rad = destination_latitude.to_radians();
-    let long1_rad = origin_longitude.to_radians();
-    let long2_rad = destination_longitude.to_radians();
-
-    let latitude_difference = lat2_rad - lat1_rad;
-    let longitude_difference = long2_rad - long1_rad;
-
-    let a = (latitude_difference / 2.0).sin().powi(2)
-        + lat1_rad.cos() * lat2_rad.cos() * (longitude_difference / 2.0).sin().powi(2);
-
-    let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
+    crate::geo::haversine_distance(
+        origin_latitude,
+        origin_longitude,
+        destination_latitude,
+        destination_longitude,
+    )
+}
 
-    earth_radius * c
+/// Point reached by travelling `distance_km` from an origin along an initial bearing (degrees).
+#[deprecated(note = "use geo::destination_point")]
+pub fn destination_point(
+    latitude: f64,
+    longitude: f64,
+    bearing_degrees: f64,
+    distance_km: f64,
+) -> (f64, f64) {
+    crate::geo::destination_point(latitude, longitude, bearing_degrees, distance_km)
 }
 
 /// Parse address components to find city, state, and country.
 pub fn parse_address_components(
     address: &Value,
-) -> Result<(Option<String>, Option<String>, String), GeoError> {
+) -> Result<(Option<String>, Option<String>, String), MapradarError> {
     let components = address.as_array().ok_or_else(|| {
-        GeoError::Unknown("Missing address components in API response".to_string())
+        MapradarError::Unknown("Missing address components in API response".to_string())
     })?;
 
     let mut city = None;
@@ -41,7 +55,7 @@ pub fn parse_address_components(
 
     for component in components {
         let types = component["types"].as_array().ok_or_else(|| {
-            GeoError::Unknown("Missing component types in API response".to_string())
+            MapradarError::Unknown("Missing component types in API response".to_string())
         })?;
 
         if types.iter().any(|t| t == "locality") {
@@ -68,3 +82,59 @@ pub fn parse_address_components(
 
     Ok((city, state, country))
 }
+
+/// Removes HTML tags from provider-formatted text (e.g., direction instructions).
+pub fn strip_html_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+
+    for ch in html.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                if !text.ends_with(' ') && !text.is_empty() {
+                    text.push(' ');
+                }
+            }
+            _ if !in_tag => text.push(ch),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Decodes an encoded polyline (precision 5) into `(lat, lng)` pairs.
+pub fn decode_polyline(encoded: &str) -> Vec<(f64, f64)> {
+    let bytes = encoded.as_bytes();
+    let mut points = Vec::new();
+    let (mut index, mut lat, mut lng) = (0, 0i64, 0i64);
+
+    while index < bytes.len() {
+        let mut deltas = [0i64; 2];
+        for delta in deltas.iter_mut() {
+            let (mut shift, mut result) = (0, 0i64);
+            while index < bytes.len() {
+                let byte = bytes[index] as i64 - 63;
+                index += 1;
+                result |= (byte & 0x1f) << shift;
+                shift += 5;
+                if byte < 0x20 {
+                    break;
+                }
+            }
+            *delta = if result & 1 != 0 {
+                !(result >> 1)
+            } else {
+                result >> 1
+            };
+        }
+
+        lat += deltas[0];
+        lng += deltas[1];
+        points.push((lat as f64 / 1e5, lng as f64 / 1e5));
+    }
+
+    points
+}
@@ -0,0 +1,219 @@
+//! Address verification: geocode an address, reverse-geocode the match, and compare components.
+//!
+//! The components parsed from the input are compared field by field with the geocoded match,
+//! ignoring case, punctuation, and abbreviated street types. Reverse-geocoding the match confirms
+//! that the provider places the same street at that point; a match whose street the reverse
+//! lookup disagrees with is not counted as deliverable.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    address::{parse_address, postcodes_match},
+    models::{AddressComponents, GeoLocation, GeocodeCandidate, MatchType},
+};
+
+/// Candidates within this much confidence of the best make an address ambiguous.
+pub const AMBIGUITY_MARGIN: f64 = 0.1;
+
+/// Overall outcome of verifying an address.
+#[cfg_attr(feature = "python", pyclass(eq, eq_int))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// Every component written in the address matches the location found.
+    Exact,
+    /// The address was found, but the provider changed or could not confirm some components.
+    Corrected,
+    /// Several locations match about equally well.
+    Ambiguous,
+    /// Nothing matches the address.
+    NotFound,
+}
+
+/// How a component of the input compares with the geocoded match.
+#[cfg_attr(feature = "python", pyclass(eq, eq_int))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    Match,
+    /// The match has a different value.
+    Corrected,
+    /// Missing from the input and filled in by the match.
+    Added,
+    /// In the input, but the match has no value to compare it with.
+    Unconfirmed,
+}
+
+/// One address component as written, as geocoded, and as reverse-geocoded.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentDiff {
+    /// `house_number`, `street`, `suburb`, `city`, `state`, `postcode`, or `country_code`.
+    pub component: String,
+    pub input: Option<String>,
+    pub geocoded: Option<String>,
+    /// The value at the match's coordinates, from reverse geocoding.
+    pub reverse: Option<String>,
+    pub status: ComponentStatus,
+}
+
+/// Result of verifying an address.
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressVerification {
+    pub address: String,
+    pub verdict: Verdict,
+    /// Whether the match is precise enough to deliver to: found without ambiguity, located to a
+    /// building or house-number range, with a street the reverse lookup confirms.
+    pub deliverable: bool,
+    /// The best match, unless nothing was found.
+    pub location: Option<GeoLocation>,
+    pub confidence: Option<f64>,
+    pub match_type: Option<MatchType>,
+    /// Components with a value in the input or the match; empty when nothing was found.
+    pub components: Vec<ComponentDiff>,
+    /// The matches competing with the best one when the verdict is `ambiguous`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternatives: Vec<GeocodeCandidate>,
+}
+
+#[cfg(feature = "python")]
+crate::python::data_model!(ComponentDiff);
+#[cfg(feature = "python")]
+crate::python::data_model!(AddressVerification);
+
+impl AddressVerification {
+    /// Verification of an address nothing matched.
+    pub fn not_found(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            verdict: Verdict::NotFound,
+            deliverable: false,
+            location: None,
+            confidence: None,
+            match_type: None,
+            components: Vec::new(),
+            alternatives: Vec::new(),
+        }
+    }
+
+    /// Compares `address` with its geocoding matches, most confident first, and with the
+    /// reverse-geocoded location of the best one (`None` when the reverse lookup failed).
+    pub fn compare(
+        address: &str,
+        mut candidates: Vec<GeocodeCandidate>,
+        reverse: Option<&GeoLocation>,
+    ) -> Self {
+        if candidates.is_empty() {
+            return Self::not_found(address);
+        }
+        let best = candidates.remove(0);
+        let alternatives: Vec<GeocodeCandidate> = candidates
+            .into_iter()
+            .filter(|candidate| best.confidence - candidate.confidence <= AMBIGUITY_MARGIN)
+            .collect();
+
+        let input = parse_address(address);
+        let geocoded = best.location.components();
+        let reverse = reverse.map(GeoLocation::components).unwrap_or_default();
+        let components = diff_components(&input, &geocoded, &reverse);
+
+        let verdict = if !alternatives.is_empty() {
+            Verdict::Ambiguous
+        } else if components
+            .iter()
+            .all(|diff| matches!(diff.status, ComponentStatus::Match | ComponentStatus::Added))
+        {
+            Verdict::Exact
+        } else {
+            Verdict::Corrected
+        };
+        let street_confirmed = components
+            .iter()
+            .find(|diff| diff.component == "street")
+            .is_some_and(|diff| match (&diff.geocoded, &diff.reverse) {
+                (Some(geocoded), Some(reverse)) => same_text(geocoded, reverse),
+                _ => false,
+            });
+
+        Self {
+            address: address.to_string(),
+            verdict,
+            deliverable: verdict != Verdict::Ambiguous
+                && best.match_type != MatchType::Approximate
+                && street_confirmed,
+            confidence: Some(best.confidence),
+            match_type: Some(best.match_type),
+            location: Some(best.location),
+            components,
+            alternatives: if verdict == Verdict::Ambiguous {
+                alternatives
+            } else {
+                Vec::new()
+            },
+        }
+    }
+}
+
+/// Reads one component out of a set.
+type Field = fn(&AddressComponents) -> &Option<String>;
+
+fn diff_components(
+    input: &AddressComponents,
+    geocoded: &AddressComponents,
+    reverse: &AddressComponents,
+) -> Vec<ComponentDiff> {
+    let fields: [(&str, Field); 7] = [
+        ("house_number", |c| &c.house_number),
+        ("street", |c| &c.street),
+        ("suburb", |c| &c.suburb),
+        ("city", |c| &c.city),
+        ("state", |c| &c.state),
+        ("postcode", |c| &c.postcode),
+        ("country_code", |c| &c.country_code),
+    ];
+    fields
+        .into_iter()
+        .filter_map(|(component, field)| {
+            let (written, found) = (field(input), field(geocoded));
+            let status = match (written, found) {
+                (None, None) => return None,
+                (Some(_), None) => ComponentStatus::Unconfirmed,
+                (None, Some(_)) => ComponentStatus::Added,
+                (Some(a), Some(b)) if component == "postcode" && postcodes_match(a, b) => {
+                    ComponentStatus::Match
+                }
+                (Some(a), Some(b)) if same_text(a, b) => ComponentStatus::Match,
+                (Some(_), Some(_)) => ComponentStatus::Corrected,
+            };
+            Some(ComponentDiff {
+                component: component.to_string(),
+                input: written.clone(),
+                geocoded: found.clone(),
+                reverse: field(reverse).clone(),
+                status,
+            })
+        })
+        .collect()
+}
+
+/// Compares two values ignoring case, punctuation, spacing, and abbreviated street types.
+fn same_text(a: &str, b: &str) -> bool {
+    let normalize = |value: &str| {
+        let words: Vec<String> = value
+            .split_whitespace()
+            .map(|word| {
+                word.chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .flat_map(char::to_lowercase)
+                    .collect()
+            })
+            .filter(|word: &String| !word.is_empty())
+            .collect();
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        crate::address::expand_street_type(&words).to_lowercase()
+    };
+    normalize(a) == normalize(b)
+}
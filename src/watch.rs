@@ -0,0 +1,109 @@
+//! Change detection between two nearby-search snapshots of the same location.
+
+use std::{collections::HashMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::MapradarError,
+    models::{GeoLocation, NearbyService},
+};
+
+/// Difference between two snapshots for a single service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum ServiceChange {
+    Added {
+        service: NearbyService,
+    },
+    Removed {
+        service: NearbyService,
+    },
+    RatingChanged {
+        service: NearbyService,
+        previous_rating: Option<f32>,
+    },
+}
+
+/// Changes found by one watch check, as printed and sent to webhooks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    pub location: GeoLocation,
+    /// Unix timestamp of the check, in seconds.
+    pub checked_at: u64,
+    pub changes: Vec<ServiceChange>,
+}
+
+/// Identifies a service across snapshots: its place ID, or its name, type, and rounded position
+/// when the provider gives none.
+fn identity(service: &NearbyService) -> String {
+    match &service.place_id {
+        Some(id) => id.clone(),
+        None => format!(
+            "{}|{}|{:.4},{:.4}",
+            service.name, service.service_type, service.latitude, service.longitude
+        ),
+    }
+}
+
+/// Compares two snapshots, listing additions and rating changes in `current` order followed by
+/// removals in `previous` order.
+pub fn diff(previous: &[NearbyService], current: &[NearbyService]) -> Vec<ServiceChange> {
+    let before: HashMap<String, &NearbyService> = previous
+        .iter()
+        .map(|service| (identity(service), service))
+        .collect();
+    let after: HashMap<String, &NearbyService> = current
+        .iter()
+        .map(|service| (identity(service), service))
+        .collect();
+
+    let mut changes: Vec<ServiceChange> = current
+        .iter()
+        .filter_map(|service| match before.get(&identity(service)) {
+            None => Some(ServiceChange::Added {
+                service: service.clone(),
+            }),
+            Some(old) if old.rating != service.rating => Some(ServiceChange::RatingChanged {
+                service: service.clone(),
+                previous_rating: old.rating,
+            }),
+            Some(_) => None,
+        })
+        .collect();
+    changes.extend(
+        previous
+            .iter()
+            .filter(|service| !after.contains_key(&identity(service)))
+            .map(|service| ServiceChange::Removed {
+                service: service.clone(),
+            }),
+    );
+    changes
+}
+
+/// Parses an interval such as `90s`, `15m`, `1h`, or `1d`. A bare number is seconds.
+pub fn parse_interval(s: &str) -> Result<Duration, MapradarError> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (count, unit) = s.split_at(split);
+    let count: u64 = count
+        .parse()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or_else(|| MapradarError::InvalidInput(format!("Invalid interval '{}'", s)))?;
+
+    let seconds = match unit.trim() {
+        "" | "s" | "sec" => 1,
+        "m" | "min" => 60,
+        "h" | "hr" => 3600,
+        "d" | "day" => 86_400,
+        other => {
+            return Err(MapradarError::InvalidInput(format!(
+                "Unknown interval unit '{}' (expected s, m, h, or d)",
+                other
+            )));
+        }
+    };
+    Ok(Duration::from_secs(count * seconds))
+}
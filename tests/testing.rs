@@ -0,0 +1,152 @@
+use mapradar::{
+    client::MapradarClient,
+    coordinates::Coordinates,
+    models::{GeoLocation, NearbyService, ServiceType},
+    testing::{Fixtures, MockProvider},
+};
+use serde_json::json;
+
+fn yaba() -> GeoLocation {
+    GeoLocation {
+        address: "Yaba, Lagos, Nigeria".into(),
+        latitude: 6.5095,
+        longitude: 3.3711,
+        city: Some("Lagos".into()),
+        state: Some("Lagos".into()),
+        country: "Nigeria".into(),
+        address_components: None,
+        timezone: None,
+        elevation_m: None,
+        language: None,
+    }
+}
+
+fn hospital(name: &str, latitude: f64, longitude: f64) -> NearbyService {
+    NearbyService {
+        name: name.into(),
+        service_type: ServiceType::Hospital.into(),
+        latitude,
+        longitude,
+        distance_km: 0.0,
+        address: None,
+        rating: None,
+        place_id: None,
+        phone_number: None,
+        open_now: None,
+        opening_hours: None,
+        photos: Vec::new(),
+        service_types: Vec::new(),
+        travel_time_min: None,
+        departures: Vec::new(),
+        routes: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn mock_provider_backs_geocode_nearby_and_cache() {
+    let mock = MockProvider::new()
+        .location("Yaba, Lagos", yaba())
+        .service(hospital("Yaba Hospital", 6.5101, 3.3720))
+        .service(hospital("Ikeja Hospital", 6.6018, 3.3515));
+    let client = MapradarClient::builder()
+        .custom_provider(mock.clone())
+        .build()
+        .unwrap();
+
+    let location = client.geocode_async("Yaba, Lagos").await.unwrap();
+    assert_eq!(location.city.as_deref(), Some("Lagos"));
+    let again = client.geocode_async("Yaba, Lagos").await.unwrap();
+    assert_eq!(again.address, location.address);
+    assert_eq!(mock.calls(), ["geocode Yaba, Lagos"]);
+
+    let point = Coordinates::new(location.latitude, location.longitude).unwrap();
+    let services = client
+        .search_nearby_async(point, ServiceType::Hospital, 2000.0, 10)
+        .await
+        .unwrap();
+    let names: Vec<&str> = services
+        .iter()
+        .map(|service| service.name.as_str())
+        .collect();
+    assert_eq!(names, ["Yaba Hospital"]);
+    client
+        .search_nearby_async(point, ServiceType::Hospital, 2000.0, 10)
+        .await
+        .unwrap();
+    assert_eq!(mock.calls().len(), 2);
+}
+
+#[tokio::test]
+async fn replays_recorded_geocode_under_another_key() {
+    let client = MapradarClient::builder()
+        .api_key("not-the-recording-key")
+        .fixtures(Fixtures::replay(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures"
+        )))
+        .no_cache()
+        .build()
+        .unwrap();
+
+    let location = client.geocode_async("Yaba, Lagos").await.unwrap();
+    assert_eq!(location.city.as_deref(), Some("Lagos"));
+    assert_eq!(location.country, "NG");
+    assert!((location.latitude - 6.5095).abs() < 1e-6);
+}
+
+#[tokio::test]
+async fn recorded_fixtures_leave_out_the_key() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = [0; 4096];
+        let _ = socket.read(&mut request).await.unwrap();
+        let body = json!({
+            "results": [{
+                "formatted_address": "Yaba, Lagos, Nigeria",
+                "geometry": { "location": { "lat": 6.5095, "lng": 3.3711 } },
+                "address_components": [],
+            }],
+            "status": "OK",
+        })
+        .to_string();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+    });
+
+    let dir = std::env::temp_dir().join(format!("mapradar-fixtures-{}", std::process::id()));
+    let recorder = MapradarClient::builder()
+        .api_key("recording-secret")
+        .base_url(&base_url)
+        .fixtures(Fixtures::record(&dir))
+        .no_cache()
+        .build()
+        .unwrap();
+    recorder.geocode_async("Yaba, Lagos").await.unwrap();
+
+    let saved: Vec<String> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect();
+    assert_eq!(saved.len(), 1);
+    assert!(!saved[0].contains("recording-secret"));
+    assert!(saved[0].contains("key=REDACTED"));
+
+    let replayer = MapradarClient::builder()
+        .api_key("another-key")
+        .base_url(&base_url)
+        .fixtures(Fixtures::replay(&dir))
+        .no_cache()
+        .build()
+        .unwrap();
+    let location = replayer.geocode_async("Yaba, Lagos").await.unwrap();
+    assert_eq!(location.address, "Yaba, Lagos, Nigeria");
+    std::fs::remove_dir_all(&dir).unwrap();
+}